@@ -14,8 +14,12 @@ use tokio::time::timeout;
 
 use crate::backend::events::{AppServerEvent, EventSink};
 use crate::codex::args::parse_codex_args;
+use crate::shared::approval_policy_core::{
+    approval_request_facts, file_change_item_paths, is_approval_request_method,
+    ApprovalPolicyEngine,
+};
 use crate::shared::process_core::{kill_child_process_tree, tokio_command};
-use crate::types::WorkspaceEntry;
+use crate::types::{ApprovalPolicyAction, WorkspaceEntry};
 
 #[cfg(target_os = "windows")]
 use crate::shared::process_core::{build_cmd_c_command, resolve_windows_executable};
@@ -445,6 +449,12 @@ pub(crate) struct WorkspaceSession {
     pub(crate) owner_workspace_id: String,
     pub(crate) workspace_ids: Mutex<HashSet<String>>,
     pub(crate) workspace_roots: Mutex<HashMap<String, String>>,
+    /// Answers approval requests before they reach the UI; `None` sends them all through.
+    pub(crate) approval_policy: Option<ApprovalPolicyEngine>,
+    /// Access mode of the last turn started on each thread, for approval rules.
+    pub(crate) thread_access_modes: Mutex<HashMap<String, String>>,
+    /// Paths of in-flight `fileChange` items, since their approval requests omit them.
+    pub(crate) item_file_changes: Mutex<HashMap<String, Vec<String>>>,
 }
 
 impl WorkspaceSession {
@@ -558,6 +568,68 @@ impl WorkspaceSession {
         self.write_message(json!({ "id": id, "result": result }))
             .await
     }
+
+    /// Answers an approval request from the workspace's policy rules. Returns `false` when
+    /// the request escalates and should be shown to the user as usual.
+    async fn apply_approval_policy<E: EventSink>(
+        &self,
+        workspace_id: &str,
+        value: &Value,
+        event_sink: &E,
+    ) -> bool {
+        let Some(policy) = self.approval_policy.as_ref() else {
+            return false;
+        };
+        let (Some(request_id), Some(method)) = (
+            value.get("id").cloned(),
+            value.get("method").and_then(Value::as_str),
+        ) else {
+            return false;
+        };
+        let params = value.get("params").cloned().unwrap_or(Value::Null);
+        let thread_id = extract_thread_id(value);
+        let access_mode = match thread_id.as_ref() {
+            Some(thread_id) => self
+                .thread_access_modes
+                .lock()
+                .await
+                .get(thread_id)
+                .cloned(),
+            None => None,
+        };
+        let file_changes = match params.get("itemId").and_then(Value::as_str) {
+            Some(item_id) => self.item_file_changes.lock().await.get(item_id).cloned(),
+            None => None,
+        };
+        let facts = approval_request_facts(method, &params, file_changes.as_deref(), access_mode);
+        let decision = policy.decide(workspace_id, facts).await;
+        let answer = match decision.action {
+            ApprovalPolicyAction::Approve => "accept",
+            ApprovalPolicyAction::Deny => "decline",
+            ApprovalPolicyAction::Escalate => return false,
+        };
+        if let Err(err) = self
+            .send_response(request_id.clone(), json!({ "decision": answer }))
+            .await
+        {
+            eprintln!("approval policy: failed to answer {method}: {err}");
+            return false;
+        }
+        event_sink.emit_app_server_event(AppServerEvent {
+            workspace_id: workspace_id.to_string(),
+            message: json!({
+                "method": "codex/approvalPolicyDecision",
+                "params": {
+                    "threadId": thread_id,
+                    "requestId": request_id,
+                    "method": method,
+                    "action": decision.action,
+                    "ruleId": decision.rule_id,
+                }
+            }),
+        });
+        true
+    }
 }
 
 pub(crate) fn build_codex_path_env(codex_bin: Option<&str>) -> Option<String> {
@@ -753,6 +825,7 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
     codex_home: Option<PathBuf>,
     client_version: String,
     event_sink: E,
+    approval_policy: Option<ApprovalPolicyEngine>,
 ) -> Result<Arc<WorkspaceSession>, String> {
     let codex_bin = default_codex_bin;
     let _ = check_codex_installation(codex_bin.clone()).await?;
@@ -791,6 +864,9 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
             entry.id.clone(),
             normalize_root_path(&entry.path),
         )])),
+        approval_policy,
+        thread_access_modes: Mutex::new(HashMap::new()),
+        item_file_changes: Mutex::new(HashMap::new()),
     });
 
     let session_clone = Arc::clone(&session);
@@ -946,6 +1022,21 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
                 }
             }
 
+            if let Some(item) = value.get("params").and_then(|params| params.get("item")) {
+                if let Some((item_id, paths)) = file_change_item_paths(item) {
+                    let mut item_file_changes = session_clone.item_file_changes.lock().await;
+                    match method_name {
+                        Some("item/started") => {
+                            item_file_changes.insert(item_id, paths);
+                        }
+                        Some("item/completed") => {
+                            item_file_changes.remove(&item_id);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
             if method_name == Some("thread/archived") {
                 if let Some(ref tid) = thread_id {
                     session_clone.thread_workspace.lock().await.remove(tid);
@@ -968,8 +1059,13 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
                             sent_to_background = true;
                         }
                     }
+                    let answered_by_policy = !sent_to_background
+                        && method_name.is_some_and(is_approval_request_method)
+                        && session_clone
+                            .apply_approval_policy(&routed_workspace_id, &value, &event_sink_clone)
+                            .await;
                     // Don't emit to frontend if this is a background thread event
-                    if !sent_to_background {
+                    if !sent_to_background && !answered_by_policy {
                         if should_broadcast_global_workspace_notification(
                             method_name,
                             thread_id.as_ref(),
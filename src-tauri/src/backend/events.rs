@@ -1,6 +1,8 @@
 use serde::Serialize;
 use serde_json::Value;
 
+use crate::types::{LocalUsageSnapshot, UsageBudgetAlert};
+
 #[derive(Serialize, Clone)]
 pub(crate) struct AppServerEvent {
     pub(crate) workspace_id: String,
@@ -24,8 +26,19 @@ pub(crate) struct TerminalExit {
     pub(crate) terminal_id: String,
 }
 
+/// A fresh usage snapshot for one watched window, sent when its session logs change.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LocalUsageUpdated {
+    pub(crate) days: u32,
+    pub(crate) workspace_path: Option<String>,
+    pub(crate) snapshot: LocalUsageSnapshot,
+}
+
 pub(crate) trait EventSink: Clone + Send + Sync + 'static {
     fn emit_app_server_event(&self, event: AppServerEvent);
     fn emit_terminal_output(&self, event: TerminalOutput);
     fn emit_terminal_exit(&self, event: TerminalExit);
+    fn emit_local_usage_updated(&self, event: LocalUsageUpdated);
+    fn emit_usage_budget_alert(&self, event: UsageBudgetAlert);
 }
@@ -1,4 +1,8 @@
 #[allow(dead_code)]
+#[path = "codex_monitor_daemon/access.rs"]
+mod access;
+#[path = "codex_monitor_daemon/audit.rs"]
+mod audit;
 #[path = "../backend/mod.rs"]
 mod backend;
 #[path = "../codex/args.rs"]
@@ -7,6 +11,11 @@ mod codex_args;
 mod codex_config;
 #[path = "../codex/home.rs"]
 mod codex_home;
+#[allow(dead_code)]
+#[path = "../daemon_tokens.rs"]
+mod daemon_tokens;
+#[path = "codex_monitor_daemon/events.rs"]
+mod events;
 #[path = "../files/io.rs"]
 mod file_io;
 #[path = "../files/ops.rs"]
@@ -15,14 +24,20 @@ mod file_ops;
 mod file_policy;
 #[path = "../git_utils.rs"]
 mod git_utils;
+#[path = "codex_monitor_daemon/metrics.rs"]
+mod metrics;
 #[path = "codex_monitor_daemon/rpc.rs"]
 mod rpc;
 #[path = "../rules.rs"]
 mod rules;
+#[path = "codex_monitor_daemon/schema.rs"]
+mod schema;
 #[path = "../shared/mod.rs"]
 mod shared;
 #[path = "../storage.rs"]
 mod storage;
+#[path = "codex_monitor_daemon/tls.rs"]
+mod tls;
 #[path = "codex_monitor_daemon/transport.rs"]
 mod transport;
 #[allow(dead_code)]
@@ -71,33 +86,47 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use ignore::WalkBuilder;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::net::{TcpListener, TcpStream};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
 use tokio::sync::{broadcast, mpsc, Mutex, Semaphore};
 
+use access::ClientAccess;
 use backend::app_server::{spawn_workspace_session, WorkspaceSession};
-use backend::events::{AppServerEvent, EventSink, TerminalExit, TerminalOutput};
+use backend::events::{AppServerEvent, EventSink, LocalUsageUpdated, TerminalExit, TerminalOutput};
+use events::{EventHub, SequencedEvent};
+use rules::PrefixRule;
+use shared::approval_policy_core::{self, ApprovalPolicyEngine, ApprovalPolicyExplanation};
 use shared::codex_core::CodexLoginCancelState;
 use shared::process_core::kill_child_process_tree;
 use shared::prompts_core::{self, CustomPromptEntry};
+use shared::rpc_error::{RpcError, RpcErrorCode};
+use shared::terminal_core::{self, TerminalSessionInfo, TerminalSessionMap};
+use shared::usage_budget_core::{self, UsageBudgetTracker};
+use shared::usage_export_core::{self, LocalUsageExport, LocalUsageExportFormat};
 use shared::{
     agents_config_core, codex_aux_core, codex_core, files_core, git_core, git_ui_core,
-    local_usage_core, settings_core, workspaces_core, worktree_core,
+    local_usage_core, settings_core, thread_export_core, thread_search_core, workspaces_core,
+    worktree_core,
 };
 use storage::{read_settings, read_workspaces};
 use types::{
-    AppSettings, GitCommitDiff, GitFileDiff, GitHubIssuesResponse, GitHubPullRequestComment,
-    GitHubPullRequestDiff, GitHubPullRequestsResponse, GitLogResponse, LocalUsageSnapshot,
-    WorkspaceEntry, WorkspaceInfo, WorkspaceSettings, WorktreeSetupStatus,
+    AppSettings, ApprovalPolicyRule, GitCommitDiff, GitConflictDetail, GitConflictResolution,
+    GitDiffSelection, GitFileDiff, GitHubIssuesResponse, GitHubPullRequestComment,
+    GitHubPullRequestDiff, GitHubPullRequestsResponse, GitLogFilter, GitLogResponse,
+    GitOperationStatus, GitStashEntry, LocalUsageSnapshot, LocalUsageThreadTimeline,
+    ThreadSearchResponse, UsageBudgetAlert, UsageBudgetStatus, WorkspaceEntry, WorkspaceInfo,
+    WorkspaceSettings, WorktreeLandPreview, WorktreeLandResult, WorktreeLandStrategy,
+    WorktreeSetupStatus,
 };
 use workspace_settings::apply_workspace_settings_update;
 
 const DEFAULT_LISTEN_ADDR: &str = "127.0.0.1:4732";
 const MAX_IN_FLIGHT_RPC_PER_CONNECTION: usize = 32;
 const DAEMON_NAME: &str = "codex-monitor-daemon";
+const TLS_HANDSHAKE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
 
 fn spawn_with_client(
-    event_sink: DaemonEventSink,
+    state: &DaemonState,
     client_version: String,
     entry: WorkspaceEntry,
     default_bin: Option<String>,
@@ -110,54 +139,73 @@ fn spawn_with_client(
         codex_args,
         codex_home,
         client_version,
-        event_sink,
+        state.event_sink.clone(),
+        Some(state.approval_policy.clone()),
     )
 }
 
 #[derive(Clone)]
 struct DaemonEventSink {
-    tx: broadcast::Sender<DaemonEvent>,
+    events: Arc<EventHub>,
 }
 
 #[derive(Clone)]
 enum DaemonEvent {
     AppServer(AppServerEvent),
-    #[allow(dead_code)]
     TerminalOutput(TerminalOutput),
-    #[allow(dead_code)]
     TerminalExit(TerminalExit),
+    LocalUsageUpdated(LocalUsageUpdated),
+    UsageBudgetAlert(UsageBudgetAlert),
 }
 
 impl EventSink for DaemonEventSink {
     fn emit_app_server_event(&self, event: AppServerEvent) {
-        let _ = self.tx.send(DaemonEvent::AppServer(event));
+        self.events.publish(DaemonEvent::AppServer(event));
     }
 
     fn emit_terminal_output(&self, event: TerminalOutput) {
-        let _ = self.tx.send(DaemonEvent::TerminalOutput(event));
+        self.events.publish(DaemonEvent::TerminalOutput(event));
     }
 
     fn emit_terminal_exit(&self, event: TerminalExit) {
-        let _ = self.tx.send(DaemonEvent::TerminalExit(event));
+        self.events.publish(DaemonEvent::TerminalExit(event));
+    }
+
+    fn emit_local_usage_updated(&self, event: LocalUsageUpdated) {
+        self.events.publish(DaemonEvent::LocalUsageUpdated(event));
+    }
+
+    fn emit_usage_budget_alert(&self, event: UsageBudgetAlert) {
+        self.events.publish(DaemonEvent::UsageBudgetAlert(event));
     }
 }
 
 struct DaemonConfig {
     listen: SocketAddr,
     token: Option<String>,
+    insecure_no_auth: bool,
     data_dir: PathBuf,
+    tls: Option<tls::DaemonTlsConfig>,
+    ws_listen: Option<SocketAddr>,
+    metrics_listen: Option<SocketAddr>,
 }
 
 struct DaemonState {
     data_dir: PathBuf,
-    workspaces: Mutex<HashMap<String, WorkspaceEntry>>,
+    workspaces: Arc<Mutex<HashMap<String, WorkspaceEntry>>>,
     sessions: Mutex<HashMap<String, Arc<WorkspaceSession>>>,
+    terminal_sessions: Arc<Mutex<TerminalSessionMap>>,
     storage_path: PathBuf,
     settings_path: PathBuf,
     app_settings: Mutex<AppSettings>,
     event_sink: DaemonEventSink,
     codex_login_cancels: Mutex<HashMap<String, CodexLoginCancelState>>,
     daemon_binary_path: Option<String>,
+    tls_fingerprint: Option<String>,
+    audit: audit::AuditLog,
+    approval_policy: ApprovalPolicyEngine,
+    local_usage_watchers: local_usage_core::LocalUsageWatchers,
+    usage_budgets: Arc<UsageBudgetTracker>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -170,21 +218,30 @@ impl DaemonState {
     fn load(config: &DaemonConfig, event_sink: DaemonEventSink) -> Self {
         let storage_path = config.data_dir.join("workspaces.json");
         let settings_path = config.data_dir.join("settings.json");
-        let workspaces = read_workspaces(&storage_path).unwrap_or_default();
+        let workspaces = Arc::new(Mutex::new(
+            read_workspaces(&storage_path).unwrap_or_default(),
+        ));
         let app_settings = read_settings(&settings_path).unwrap_or_default();
         let daemon_binary_path = std::env::current_exe()
             .ok()
             .and_then(|path| path.to_str().map(str::to_string));
+        let approval_policy = ApprovalPolicyEngine::new(&config.data_dir, Arc::clone(&workspaces));
         Self {
             data_dir: config.data_dir.clone(),
-            workspaces: Mutex::new(workspaces),
+            workspaces,
             sessions: Mutex::new(HashMap::new()),
+            terminal_sessions: Arc::new(Mutex::new(HashMap::new())),
             storage_path,
             settings_path,
             app_settings: Mutex::new(app_settings),
             event_sink,
             codex_login_cancels: Mutex::new(HashMap::new()),
             daemon_binary_path,
+            tls_fingerprint: None,
+            audit: audit::AuditLog::new(&config.data_dir),
+            approval_policy,
+            local_usage_watchers: Default::default(),
+            usage_budgets: Arc::new(UsageBudgetTracker::new(&config.data_dir)),
         }
     }
 
@@ -195,6 +252,8 @@ impl DaemonState {
             "pid": std::process::id(),
             "mode": "tcp",
             "binaryPath": self.daemon_binary_path,
+            "tls": self.tls_fingerprint.is_some(),
+            "tlsFingerprint": self.tls_fingerprint,
         })
     }
 
@@ -261,7 +320,7 @@ impl DaemonState {
             &self.storage_path,
             move |entry, default_bin, codex_args, codex_home| {
                 spawn_with_client(
-                    self.event_sink.clone(),
+                    self,
                     client_version.clone(),
                     entry,
                     default_bin,
@@ -291,7 +350,7 @@ impl DaemonState {
             &self.storage_path,
             move |entry, default_bin, codex_args, codex_home| {
                 spawn_with_client(
-                    self.event_sink.clone(),
+                    self,
                     client_version.clone(),
                     entry,
                     default_bin,
@@ -339,7 +398,7 @@ impl DaemonState {
             },
             move |entry, default_bin, codex_args, codex_home| {
                 spawn_with_client(
-                    self.event_sink.clone(),
+                    self,
                     client_version.clone(),
                     entry,
                     default_bin,
@@ -440,7 +499,7 @@ impl DaemonState {
             },
             move |entry, default_bin, codex_args, codex_home| {
                 spawn_with_client(
-                    self.event_sink.clone(),
+                    self,
                     client_version.clone(),
                     entry,
                     default_bin,
@@ -513,7 +572,7 @@ impl DaemonState {
             },
             move |entry, default_bin, codex_args, codex_home| {
                 spawn_with_client(
-                    self.event_sink.clone(),
+                    self,
                     client_version.clone(),
                     entry,
                     default_bin,
@@ -541,7 +600,7 @@ impl DaemonState {
             &self.app_settings,
             move |entry, default_bin, codex_args, codex_home| {
                 spawn_with_client(
-                    self.event_sink.clone(),
+                    self,
                     client_version.clone(),
                     entry,
                     default_bin,
@@ -567,7 +626,7 @@ impl DaemonState {
             &self.app_settings,
             move |entry, default_bin, next_args, codex_home| {
                 spawn_with_client(
-                    self.event_sink.clone(),
+                    self,
                     client_version.clone(),
                     entry,
                     default_bin,
@@ -680,7 +739,7 @@ impl DaemonState {
         files_core::file_write_core(&self.workspaces, scope, kind, workspace_id, content).await
     }
 
-    async fn start_thread(&self, workspace_id: String) -> Result<Value, String> {
+    async fn start_thread(&self, workspace_id: String) -> Result<Value, RpcError> {
         codex_core::start_thread_core(&self.sessions, &self.workspaces, workspace_id).await
     }
 
@@ -688,7 +747,7 @@ impl DaemonState {
         &self,
         workspace_id: String,
         thread_id: String,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, RpcError> {
         codex_core::resume_thread_core(&self.sessions, workspace_id, thread_id).await
     }
 
@@ -696,7 +755,7 @@ impl DaemonState {
         &self,
         workspace_id: String,
         thread_id: String,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, RpcError> {
         codex_core::thread_live_subscribe_core(
             &self.sessions,
             workspace_id.clone(),
@@ -725,7 +784,7 @@ impl DaemonState {
         &self,
         workspace_id: String,
         thread_id: String,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, RpcError> {
         codex_core::thread_live_unsubscribe_core(
             &self.sessions,
             workspace_id.clone(),
@@ -746,7 +805,11 @@ impl DaemonState {
         Ok(json!({ "ok": true }))
     }
 
-    async fn fork_thread(&self, workspace_id: String, thread_id: String) -> Result<Value, String> {
+    async fn fork_thread(
+        &self,
+        workspace_id: String,
+        thread_id: String,
+    ) -> Result<Value, RpcError> {
         codex_core::fork_thread_core(&self.sessions, workspace_id, thread_id).await
     }
 
@@ -756,7 +819,7 @@ impl DaemonState {
         cursor: Option<String>,
         limit: Option<u32>,
         sort_key: Option<String>,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, RpcError> {
         codex_core::list_threads_core(&self.sessions, workspace_id, cursor, limit, sort_key)
             .await
     }
@@ -766,7 +829,7 @@ impl DaemonState {
         workspace_id: String,
         cursor: Option<String>,
         limit: Option<u32>,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, RpcError> {
         codex_core::list_mcp_server_status_core(&self.sessions, workspace_id, cursor, limit).await
     }
 
@@ -774,7 +837,7 @@ impl DaemonState {
         &self,
         workspace_id: String,
         thread_id: String,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, RpcError> {
         codex_core::archive_thread_core(&self.sessions, workspace_id, thread_id).await
     }
 
@@ -782,7 +845,7 @@ impl DaemonState {
         &self,
         workspace_id: String,
         thread_id: String,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, RpcError> {
         codex_core::compact_thread_core(&self.sessions, workspace_id, thread_id).await
     }
 
@@ -791,7 +854,7 @@ impl DaemonState {
         workspace_id: String,
         thread_id: String,
         name: String,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, RpcError> {
         codex_core::set_thread_name_core(&self.sessions, workspace_id, thread_id, name).await
     }
 
@@ -806,7 +869,13 @@ impl DaemonState {
         images: Option<Vec<String>>,
         app_mentions: Option<Vec<Value>>,
         collaboration_mode: Option<Value>,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, RpcError> {
+        usage_budget_core::ensure_send_allowed(
+            &self.usage_budgets,
+            &workspace_id,
+            self.event_sink.clone(),
+        )
+        .await?;
         codex_core::send_user_message_core(
             &self.sessions,
             &self.workspaces,
@@ -831,7 +900,7 @@ impl DaemonState {
         text: String,
         images: Option<Vec<String>>,
         app_mentions: Option<Vec<Value>>,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, RpcError> {
         codex_core::turn_steer_core(
             &self.sessions,
             workspace_id,
@@ -849,7 +918,7 @@ impl DaemonState {
         workspace_id: String,
         thread_id: String,
         turn_id: String,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, RpcError> {
         codex_core::turn_interrupt_core(&self.sessions, workspace_id, thread_id, turn_id).await
     }
 
@@ -859,12 +928,12 @@ impl DaemonState {
         thread_id: String,
         target: Value,
         delivery: Option<String>,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, RpcError> {
         codex_core::start_review_core(&self.sessions, workspace_id, thread_id, target, delivery)
             .await
     }
 
-    async fn model_list(&self, workspace_id: String) -> Result<Value, String> {
+    async fn model_list(&self, workspace_id: String) -> Result<Value, RpcError> {
         codex_core::model_list_core(&self.sessions, workspace_id).await
     }
 
@@ -873,16 +942,16 @@ impl DaemonState {
         workspace_id: String,
         cursor: Option<String>,
         limit: Option<u32>,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, RpcError> {
         codex_core::experimental_feature_list_core(&self.sessions, workspace_id, cursor, limit)
             .await
     }
 
-    async fn collaboration_mode_list(&self, workspace_id: String) -> Result<Value, String> {
+    async fn collaboration_mode_list(&self, workspace_id: String) -> Result<Value, RpcError> {
         codex_core::collaboration_mode_list_core(&self.sessions, workspace_id).await
     }
 
-    async fn account_rate_limits(&self, workspace_id: String) -> Result<Value, String> {
+    async fn account_rate_limits(&self, workspace_id: String) -> Result<Value, RpcError> {
         codex_core::account_rate_limits_core(&self.sessions, workspace_id).await
     }
 
@@ -890,16 +959,16 @@ impl DaemonState {
         codex_core::account_read_core(&self.sessions, &self.workspaces, workspace_id).await
     }
 
-    async fn codex_login(&self, workspace_id: String) -> Result<Value, String> {
+    async fn codex_login(&self, workspace_id: String) -> Result<Value, RpcError> {
         codex_core::codex_login_core(&self.sessions, &self.codex_login_cancels, workspace_id).await
     }
 
-    async fn codex_login_cancel(&self, workspace_id: String) -> Result<Value, String> {
+    async fn codex_login_cancel(&self, workspace_id: String) -> Result<Value, RpcError> {
         codex_core::codex_login_cancel_core(&self.sessions, &self.codex_login_cancels, workspace_id)
             .await
     }
 
-    async fn skills_list(&self, workspace_id: String) -> Result<Value, String> {
+    async fn skills_list(&self, workspace_id: String) -> Result<Value, RpcError> {
         codex_core::skills_list_core(&self.sessions, &self.workspaces, workspace_id).await
     }
 
@@ -909,7 +978,7 @@ impl DaemonState {
         cursor: Option<String>,
         limit: Option<u32>,
         thread_id: Option<String>,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, RpcError> {
         codex_core::apps_list_core(&self.sessions, workspace_id, cursor, limit, thread_id).await
     }
 
@@ -918,7 +987,7 @@ impl DaemonState {
         workspace_id: String,
         request_id: Value,
         result: Value,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, RpcError> {
         codex_core::respond_to_server_request_core(
             &self.sessions,
             workspace_id,
@@ -937,6 +1006,81 @@ impl DaemonState {
         codex_core::remember_approval_rule_core(&self.workspaces, workspace_id, command).await
     }
 
+    async fn export_thread(
+        &self,
+        workspace_id: String,
+        thread_id: String,
+        format: thread_export_core::ThreadExportFormat,
+        redact: bool,
+    ) -> Result<Value, String> {
+        let export = thread_export_core::export_thread_core(
+            &self.workspaces,
+            workspace_id,
+            thread_id,
+            format,
+            redact,
+        )
+        .await?;
+        serde_json::to_value(export).map_err(|err| err.to_string())
+    }
+
+    async fn import_thread(
+        &self,
+        workspace_id: String,
+        bundle: thread_export_core::ThreadBundle,
+    ) -> Result<Value, String> {
+        let imported =
+            thread_export_core::import_thread_core(&self.workspaces, workspace_id, bundle).await?;
+        serde_json::to_value(imported).map_err(|err| err.to_string())
+    }
+
+    async fn list_codex_rules(&self, workspace_id: String) -> Result<Value, String> {
+        let listing = codex_core::list_codex_rules_core(&self.workspaces, workspace_id).await?;
+        serde_json::to_value(listing).map_err(|err| err.to_string())
+    }
+
+    async fn update_codex_rule(
+        &self,
+        workspace_id: String,
+        rule_id: String,
+        rule: PrefixRule,
+    ) -> Result<Value, String> {
+        let entry =
+            codex_core::update_codex_rule_core(&self.workspaces, workspace_id, rule_id, rule)
+                .await?;
+        serde_json::to_value(entry).map_err(|err| err.to_string())
+    }
+
+    async fn delete_codex_rule(
+        &self,
+        workspace_id: String,
+        rule_id: String,
+    ) -> Result<Value, String> {
+        codex_core::delete_codex_rule_core(&self.workspaces, workspace_id, rule_id).await?;
+        Ok(json!({ "ok": true }))
+    }
+
+    async fn explain_approval_policy(
+        &self,
+        workspace_id: String,
+        method: String,
+        params: Value,
+        access_mode: Option<String>,
+        rules: Option<Vec<ApprovalPolicyRule>>,
+    ) -> Result<Value, String> {
+        let explanation: ApprovalPolicyExplanation =
+            approval_policy_core::explain_approval_policy_core(
+                &self.workspaces,
+                workspace_id,
+                method,
+                params,
+                access_mode,
+                rules,
+            )
+            .await?;
+        serde_json::to_value(explanation).map_err(|err| err.to_string())
+    }
+
     async fn get_config_model(&self, workspace_id: String) -> Result<Value, String> {
         codex_core::get_config_model_core(&self.workspaces, workspace_id).await
     }
@@ -958,7 +1102,7 @@ impl DaemonState {
             &self.storage_path,
             |entry, default_bin, codex_args, codex_home| {
                 spawn_with_client(
-                    self.event_sink.clone(),
+                    self,
                     client_version.clone(),
                     entry,
                     default_bin,
@@ -970,10 +1114,47 @@ impl DaemonState {
         .await
     }
 
-    async fn apply_worktree_changes(&self, workspace_id: String) -> Result<(), String> {
+    async fn apply_worktree_changes(&self, workspace_id: String) -> Result<(), RpcError> {
         workspaces_core::apply_worktree_changes_core(&self.workspaces, workspace_id).await
     }
 
+    async fn preview_land_worktree(
+        &self,
+        workspace_id: String,
+    ) -> Result<WorktreeLandPreview, String> {
+        workspaces_core::preview_land_worktree_core(
+            &self.workspaces,
+            &self.app_settings,
+            workspace_id,
+        )
+        .await
+    }
+
+    async fn land_worktree(
+        &self,
+        workspace_id: String,
+        strategy: WorktreeLandStrategy,
+        message: Option<String>,
+        remove_worktree: bool,
+    ) -> Result<WorktreeLandResult, RpcError> {
+        let mut result = workspaces_core::land_worktree_core(
+            &self.workspaces,
+            workspace_id.clone(),
+            strategy,
+            message,
+            remove_worktree,
+        )
+        .await?;
+        // The branch has landed by now, so a failed removal is reported rather than an error.
+        if remove_worktree {
+            match self.remove_worktree(workspace_id).await {
+                Ok(()) => result.worktree_removed = true,
+                Err(error) => result.worktree_removal_error = Some(error),
+            }
+        }
+        Ok(result)
+    }
+
     async fn open_workspace_in(
         &self,
         path: String,
@@ -1047,8 +1228,10 @@ impl DaemonState {
         &self,
         workspace_id: String,
         limit: Option<usize>,
+        cursor: Option<String>,
+        filter: Option<GitLogFilter>,
     ) -> Result<GitLogResponse, String> {
-        git_ui_core::get_git_log_core(&self.workspaces, workspace_id, limit).await
+        git_ui_core::get_git_log_core(&self.workspaces, workspace_id, limit, cursor, filter).await
     }
 
     async fn get_git_commit_diff(
@@ -1085,6 +1268,54 @@ impl DaemonState {
         git_ui_core::revert_git_file_core(&self.workspaces, workspace_id, path).await
     }
 
+    async fn stage_git_selection(
+        &self,
+        workspace_id: String,
+        path: String,
+        selection: GitDiffSelection,
+    ) -> Result<(), String> {
+        git_ui_core::stage_git_selection_core(
+            &self.workspaces,
+            &self.app_settings,
+            workspace_id,
+            path,
+            selection,
+        )
+        .await
+    }
+
+    async fn unstage_git_selection(
+        &self,
+        workspace_id: String,
+        path: String,
+        selection: GitDiffSelection,
+    ) -> Result<(), String> {
+        git_ui_core::unstage_git_selection_core(
+            &self.workspaces,
+            &self.app_settings,
+            workspace_id,
+            path,
+            selection,
+        )
+        .await
+    }
+
+    async fn revert_git_selection(
+        &self,
+        workspace_id: String,
+        path: String,
+        selection: GitDiffSelection,
+    ) -> Result<(), String> {
+        git_ui_core::revert_git_selection_core(
+            &self.workspaces,
+            &self.app_settings,
+            workspace_id,
+            path,
+            selection,
+        )
+        .await
+    }
+
     async fn revert_git_all(&self, workspace_id: String) -> Result<(), String> {
         git_ui_core::revert_git_all_core(&self.workspaces, workspace_id).await
     }
@@ -1109,6 +1340,127 @@ impl DaemonState {
         git_ui_core::sync_git_core(&self.workspaces, workspace_id).await
     }
 
+    async fn list_git_stashes(&self, workspace_id: String) -> Result<Vec<GitStashEntry>, String> {
+        git_ui_core::list_git_stashes_core(&self.workspaces, workspace_id).await
+    }
+
+    async fn get_git_stash_diff(
+        &self,
+        workspace_id: String,
+        index: usize,
+        sha: String,
+    ) -> Result<Vec<GitFileDiff>, RpcError> {
+        git_ui_core::get_git_stash_diff_core(
+            &self.workspaces,
+            &self.app_settings,
+            workspace_id,
+            index,
+            sha,
+        )
+        .await
+    }
+
+    async fn create_git_stash(
+        &self,
+        workspace_id: String,
+        message: Option<String>,
+        include_untracked: bool,
+    ) -> Result<GitStashEntry, String> {
+        git_ui_core::create_git_stash_core(
+            &self.workspaces,
+            workspace_id,
+            message,
+            include_untracked,
+        )
+        .await
+    }
+
+    async fn apply_git_stash(
+        &self,
+        workspace_id: String,
+        index: usize,
+        sha: String,
+    ) -> Result<(), RpcError> {
+        git_ui_core::apply_git_stash_core(&self.workspaces, workspace_id, index, sha).await
+    }
+
+    async fn pop_git_stash(
+        &self,
+        workspace_id: String,
+        index: usize,
+        sha: String,
+    ) -> Result<(), RpcError> {
+        git_ui_core::pop_git_stash_core(&self.workspaces, workspace_id, index, sha).await
+    }
+
+    async fn drop_git_stash(
+        &self,
+        workspace_id: String,
+        index: usize,
+        sha: String,
+    ) -> Result<(), RpcError> {
+        git_ui_core::drop_git_stash_core(&self.workspaces, workspace_id, index, sha).await
+    }
+
+    async fn merge_git_branch(
+        &self,
+        workspace_id: String,
+        name: String,
+    ) -> Result<GitOperationStatus, String> {
+        git_ui_core::merge_git_branch_core(&self.workspaces, workspace_id, name).await
+    }
+
+    async fn rebase_git_branch(
+        &self,
+        workspace_id: String,
+        name: String,
+    ) -> Result<GitOperationStatus, String> {
+        git_ui_core::rebase_git_branch_core(&self.workspaces, workspace_id, name).await
+    }
+
+    async fn continue_git_operation(
+        &self,
+        workspace_id: String,
+    ) -> Result<GitOperationStatus, String> {
+        git_ui_core::continue_git_operation_core(&self.workspaces, workspace_id).await
+    }
+
+    async fn skip_git_operation(&self, workspace_id: String) -> Result<GitOperationStatus, String> {
+        git_ui_core::skip_git_operation_core(&self.workspaces, workspace_id).await
+    }
+
+    async fn abort_git_operation(
+        &self,
+        workspace_id: String,
+    ) -> Result<GitOperationStatus, String> {
+        git_ui_core::abort_git_operation_core(&self.workspaces, workspace_id).await
+    }
+
+    async fn get_git_conflict(
+        &self,
+        workspace_id: String,
+        path: String,
+    ) -> Result<GitConflictDetail, String> {
+        git_ui_core::get_git_conflict_core(&self.workspaces, workspace_id, path).await
+    }
+
+    async fn resolve_git_conflict(
+        &self,
+        workspace_id: String,
+        path: String,
+        resolution: GitConflictResolution,
+        content: Option<String>,
+    ) -> Result<(), String> {
+        git_ui_core::resolve_git_conflict_core(
+            &self.workspaces,
+            workspace_id,
+            path,
+            resolution,
+            content,
+        )
+        .await
+    }
+
     async fn get_github_issues(
         &self,
         workspace_id: String,
@@ -1260,7 +1612,7 @@ impl DaemonState {
         &self,
         workspace_id: String,
         commit_message_model_id: Option<String>,
-    ) -> Result<String, String> {
+    ) -> Result<String, RpcError> {
         let repo_root = git_ui_core::resolve_repo_root_for_workspace_core(
             &self.workspaces,
             workspace_id.clone(),
@@ -1289,7 +1641,7 @@ impl DaemonState {
         &self,
         workspace_id: String,
         prompt: String,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, RpcError> {
         codex_aux_core::generate_run_metadata_core(
             &self.sessions,
             &self.workspaces,
@@ -1306,7 +1658,7 @@ impl DaemonState {
         &self,
         workspace_id: String,
         description: String,
-    ) -> Result<codex_aux_core::GeneratedAgentConfiguration, String> {
+    ) -> Result<codex_aux_core::GeneratedAgentConfiguration, RpcError> {
         codex_aux_core::generate_agent_description_core(
             &self.sessions,
             &self.workspaces,
@@ -1324,46 +1676,208 @@ impl DaemonState {
         days: Option<u32>,
         workspace_path: Option<String>,
     ) -> Result<LocalUsageSnapshot, String> {
-        local_usage_core::local_usage_snapshot_core(&self.workspaces, days, workspace_path).await
+        let model_prices = self.app_settings.lock().await.model_prices.clone();
+        let snapshot = local_usage_core::local_usage_snapshot_core(
+            &self.workspaces,
+            &self.data_dir,
+            days,
+            workspace_path,
+            model_prices,
+        )
+        .await?;
+        usage_budget_core::spawn_usage_budget_check(&self.usage_budgets, self.event_sink.clone());
+        Ok(snapshot)
     }
 
-    async fn menu_set_accelerators(&self, _updates: Vec<Value>) -> Result<(), String> {
-        // Daemon has no native menu runtime; treat as no-op for remote parity.
-        Ok(())
+    async fn local_usage_export(
+        &self,
+        format: LocalUsageExportFormat,
+        days: Option<u32>,
+        workspace_path: Option<String>,
+    ) -> Result<LocalUsageExport, String> {
+        let model_prices = self.app_settings.lock().await.model_prices.clone();
+        usage_export_core::local_usage_export_core(
+            &self.workspaces,
+            &self.data_dir,
+            format,
+            days,
+            workspace_path,
+            model_prices,
+        )
+        .await
     }
 
-    async fn is_macos_debug_build(&self) -> bool {
-        cfg!(all(target_os = "macos", debug_assertions))
+    async fn local_usage_watch(
+        &self,
+        connection_id: u64,
+        days: Option<u32>,
+        workspace_path: Option<String>,
+    ) -> Result<(), String> {
+        local_usage_core::local_usage_watch_core(
+            &self.local_usage_watchers,
+            connection_id,
+            &self.workspaces,
+            &self.data_dir,
+            days,
+            workspace_path,
+            self.usage_budgets.clone(),
+            self.event_sink.clone(),
+        )
+        .await
     }
 
-    async fn send_notification_fallback(&self, title: String, body: String) -> Result<(), String> {
-        send_notification_fallback_inner(title, body)
+    fn local_usage_unwatch(
+        &self,
+        connection_id: u64,
+        days: Option<u32>,
+        workspace_path: Option<String>,
+    ) -> Result<(), String> {
+        local_usage_core::local_usage_unwatch_core(
+            &self.local_usage_watchers,
+            connection_id,
+            days,
+            workspace_path,
+        )
     }
-}
 
-fn should_skip_dir(name: &str) -> bool {
-    matches!(
-        name,
-        ".git" | "node_modules" | "dist" | "target" | "release-artifacts"
-    )
-}
+    async fn local_usage_thread_timeline(
+        &self,
+        thread_id: String,
+    ) -> Result<LocalUsageThreadTimeline, String> {
+        let model_prices = self.app_settings.lock().await.model_prices.clone();
+        local_usage_core::local_usage_thread_timeline_core(
+            &self.workspaces,
+            thread_id,
+            model_prices,
+        )
+        .await
+    }
 
-fn normalize_git_path(path: &str) -> String {
-    path.replace('\\', "/")
-}
+    async fn usage_budget_status(&self) -> Result<Vec<UsageBudgetStatus>, String> {
+        usage_budget_core::usage_budget_status_core(&self.usage_budgets, self.event_sink.clone())
+            .await
+    }
 
-fn emit_background_thread_hide(event_sink: &DaemonEventSink, workspace_id: &str, thread_id: &str) {
-    event_sink.emit_app_server_event(AppServerEvent {
-        workspace_id: workspace_id.to_string(),
-        message: json!({
-            "method": "codex/backgroundThread",
-            "params": {
-                "threadId": thread_id,
-                "action": "hide"
-            }
-        }),
-    });
-}
+    async fn override_usage_budget(&self, budget_id: String) -> Result<(), String> {
+        usage_budget_core::override_usage_budget_core(&self.usage_budgets, budget_id).await
+    }
+
+    async fn search_threads(
+        &self,
+        query: String,
+        workspace_id: Option<String>,
+        limit: Option<u32>,
+        allowed_workspaces: Option<HashSet<String>>,
+    ) -> Result<ThreadSearchResponse, String> {
+        thread_search_core::search_threads_core(
+            &self.workspaces,
+            &self.data_dir,
+            query,
+            workspace_id,
+            limit,
+            allowed_workspaces,
+        )
+        .await
+    }
+
+    async fn terminal_open(
+        &self,
+        workspace_id: String,
+        terminal_id: String,
+        cols: u16,
+        rows: u16,
+    ) -> Result<TerminalSessionInfo, String> {
+        let sessions = Arc::clone(&self.terminal_sessions);
+        let runtime = tokio::runtime::Handle::current();
+        terminal_core::terminal_open_core(
+            &self.terminal_sessions,
+            &self.workspaces,
+            workspace_id,
+            terminal_id,
+            cols,
+            rows,
+            self.event_sink.clone(),
+            move |key, session| {
+                runtime.spawn(async move {
+                    terminal_core::remove_exited_terminal_session(&sessions, &key, &session).await;
+                });
+            },
+        )
+        .await
+    }
+
+    async fn terminal_write(
+        &self,
+        workspace_id: String,
+        terminal_id: String,
+        data: String,
+    ) -> Result<(), String> {
+        terminal_core::terminal_write_core(&self.terminal_sessions, workspace_id, terminal_id, data)
+            .await
+    }
+
+    async fn terminal_resize(
+        &self,
+        workspace_id: String,
+        terminal_id: String,
+        cols: u16,
+        rows: u16,
+    ) -> Result<(), String> {
+        terminal_core::terminal_resize_core(
+            &self.terminal_sessions,
+            workspace_id,
+            terminal_id,
+            cols,
+            rows,
+        )
+        .await
+    }
+
+    async fn terminal_close(
+        &self,
+        workspace_id: String,
+        terminal_id: String,
+    ) -> Result<(), String> {
+        terminal_core::terminal_close_core(&self.terminal_sessions, workspace_id, terminal_id).await
+    }
+
+    async fn menu_set_accelerators(&self, _updates: Vec<Value>) -> Result<(), String> {
+        // Daemon has no native menu runtime; treat as no-op for remote parity.
+        Ok(())
+    }
+
+    async fn is_macos_debug_build(&self) -> bool {
+        cfg!(all(target_os = "macos", debug_assertions))
+    }
+
+    async fn send_notification_fallback(&self, title: String, body: String) -> Result<(), String> {
+        send_notification_fallback_inner(title, body)
+    }
+}
+
+fn should_skip_dir(name: &str) -> bool {
+    matches!(
+        name,
+        ".git" | "node_modules" | "dist" | "target" | "release-artifacts"
+    )
+}
+
+fn normalize_git_path(path: &str) -> String {
+    path.replace('\\', "/")
+}
+
+fn emit_background_thread_hide(event_sink: &DaemonEventSink, workspace_id: &str, thread_id: &str) {
+    event_sink.emit_app_server_event(AppServerEvent {
+        workspace_id: workspace_id.to_string(),
+        message: json!({
+            "method": "codex/backgroundThread",
+            "params": {
+                "threadId": thread_id,
+                "action": "hide"
+            }
+        }),
+    });
+}
 
 fn send_notification_fallback_inner(title: String, body: String) -> Result<(), String> {
     #[cfg(all(target_os = "macos", debug_assertions))]
@@ -1489,8 +2003,8 @@ fn default_data_dir() -> PathBuf {
 fn usage() -> String {
     format!(
         "\
-USAGE:\n  codex-monitor-daemon [--listen <addr>] [--data-dir <path>] [--token <token> | --insecure-no-auth]\n\n\
-OPTIONS:\n  --listen <addr>          Bind address (default: {DEFAULT_LISTEN_ADDR})\n  --data-dir <path>        Data dir holding workspaces.json/settings.json\n  --token <token>          Shared token required by TCP clients\n  --insecure-no-auth       Disable TCP auth (dev only)\n  -h, --help               Show this help\n"
+USAGE:\n  codex-monitor-daemon [--listen <addr>] [--data-dir <path>] [--token <token> | --insecure-no-auth]\n                       [--ws-listen <addr>] [--metrics-listen <addr>] [--tls-cert <path> --tls-key <path> [--tls-client-ca <path>]]\n\n\
+OPTIONS:\n  --listen <addr>          Bind address (default: {DEFAULT_LISTEN_ADDR})\n  --ws-listen <addr>       Also serve the protocol over WebSocket on this address\n  --metrics-listen <addr>  Serve GET /metrics (Prometheus) and /usage.csv over HTTP on this address\n  --data-dir <path>        Data dir holding workspaces.json/settings.json/daemon-tokens.json\n  --token <token>          Shared admin token required by clients\n  --insecure-no-auth       Disable TCP auth (dev only)\n  --tls-cert <path>        PEM certificate chain; enables TLS on the listener\n  --tls-key <path>         PEM private key for --tls-cert\n  --tls-client-ca <path>   PEM CA bundle; require client certificates signed by it\n  -h, --help               Show this help\n"
     )
 }
 
@@ -1504,6 +2018,11 @@ fn parse_args() -> Result<DaemonConfig, String> {
         .filter(|value| !value.is_empty());
     let mut insecure_no_auth = false;
     let mut data_dir: Option<PathBuf> = None;
+    let mut tls_cert: Option<PathBuf> = None;
+    let mut tls_key: Option<PathBuf> = None;
+    let mut tls_client_ca: Option<PathBuf> = None;
+    let mut ws_listen: Option<SocketAddr> = None;
+    let mut metrics_listen: Option<SocketAddr> = None;
 
     let mut args = env::args().skip(1);
     while let Some(arg) = args.next() {
@@ -1516,6 +2035,14 @@ fn parse_args() -> Result<DaemonConfig, String> {
                 let value = args.next().ok_or("--listen requires a value")?;
                 listen = value.parse::<SocketAddr>().map_err(|err| err.to_string())?;
             }
+            "--ws-listen" => {
+                let value = args.next().ok_or("--ws-listen requires a value")?;
+                ws_listen = Some(value.parse::<SocketAddr>().map_err(|err| err.to_string())?);
+            }
+            "--metrics-listen" => {
+                let value = args.next().ok_or("--metrics-listen requires a value")?;
+                metrics_listen = Some(value.parse::<SocketAddr>().map_err(|err| err.to_string())?);
+            }
             "--token" => {
                 let value = args.next().ok_or("--token requires a value")?;
                 let trimmed = value.trim();
@@ -1536,21 +2063,55 @@ fn parse_args() -> Result<DaemonConfig, String> {
                 insecure_no_auth = true;
                 token = None;
             }
+            "--tls-cert" | "--tls-key" | "--tls-client-ca" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("{arg} requires a value"))?;
+                let trimmed = value.trim();
+                if trimmed.is_empty() {
+                    return Err(format!("{arg} requires a non-empty value"));
+                }
+                let path = Some(PathBuf::from(trimmed));
+                match arg.as_str() {
+                    "--tls-cert" => tls_cert = path,
+                    "--tls-key" => tls_key = path,
+                    _ => tls_client_ca = path,
+                }
+            }
             _ => return Err(format!("Unknown argument: {arg}")),
         }
     }
 
+    let data_dir = data_dir.unwrap_or_else(default_data_dir);
     if token.is_none() && !insecure_no_auth {
-        return Err(
-            "Missing --token (or set CODEX_MONITOR_DAEMON_TOKEN). Use --insecure-no-auth for local dev only."
-                .to_string(),
-        );
+        let named_tokens = daemon_tokens::read_tokens(&daemon_tokens::tokens_path(&data_dir))?;
+        if named_tokens.is_empty() {
+            return Err(
+                "Missing --token (or set CODEX_MONITOR_DAEMON_TOKEN) and no named tokens in the data dir. Use --insecure-no-auth for local dev only."
+                    .to_string(),
+            );
+        }
     }
 
+    let tls = match (tls_cert, tls_key) {
+        (Some(cert_path), Some(key_path)) => Some(tls::DaemonTlsConfig {
+            cert_path,
+            key_path,
+            client_ca_path: tls_client_ca,
+        }),
+        (None, None) if tls_client_ca.is_none() => None,
+        (None, None) => return Err("--tls-client-ca requires --tls-cert and --tls-key".to_string()),
+        _ => return Err("--tls-cert and --tls-key must be provided together".to_string()),
+    };
+
     Ok(DaemonConfig {
         listen,
         token,
-        data_dir: data_dir.unwrap_or_else(default_data_dir),
+        insecure_no_auth,
+        data_dir,
+        tls,
+        ws_listen,
+        metrics_listen,
     })
 }
 
@@ -1594,17 +2155,26 @@ mod tests {
     }
 
     fn test_state(data_dir: &std::path::Path) -> DaemonState {
-        let (tx, _rx) = broadcast::channel::<DaemonEvent>(32);
+        let workspaces = Arc::new(Mutex::new(HashMap::new()));
+        let approval_policy = ApprovalPolicyEngine::new(data_dir, Arc::clone(&workspaces));
         DaemonState {
             data_dir: data_dir.to_path_buf(),
-            workspaces: Mutex::new(HashMap::new()),
+            workspaces,
             sessions: Mutex::new(HashMap::new()),
+            terminal_sessions: Arc::new(Mutex::new(HashMap::new())),
             storage_path: data_dir.join("workspaces.json"),
             settings_path: data_dir.join("settings.json"),
             app_settings: Mutex::new(AppSettings::default()),
-            event_sink: DaemonEventSink { tx },
+            event_sink: DaemonEventSink {
+                events: Arc::new(EventHub::new()),
+            },
             codex_login_cancels: Mutex::new(HashMap::new()),
             daemon_binary_path: Some("/tmp/codex-monitor-daemon".to_string()),
+            tls_fingerprint: None,
+            audit: audit::AuditLog::new(data_dir),
+            approval_policy,
+            local_usage_watchers: Default::default(),
+            usage_budgets: Arc::new(UsageBudgetTracker::new(data_dir)),
         }
     }
 
@@ -1670,6 +2240,9 @@ mod tests {
             background_thread_callbacks: Mutex::new(HashMap::new()),
             workspace_ids: Mutex::new(HashSet::from([owner_workspace_id.clone()])),
             workspace_roots: Mutex::new(HashMap::new()),
+            approval_policy: None,
+            thread_access_modes: Mutex::new(HashMap::new()),
+            item_file_changes: Mutex::new(HashMap::new()),
             owner_workspace_id,
         })
     }
@@ -1781,9 +2354,1198 @@ mod tests {
                 result.get("version").and_then(Value::as_str),
                 Some(env!("CARGO_PKG_VERSION"))
             );
+            assert_eq!(result.get("tls").and_then(Value::as_bool), Some(false));
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn websocket_listener_requires_auth_before_rpc() {
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+
+        run_async_test(async {
+            let tmp = make_temp_dir("ws-listener");
+            let state = Arc::new(test_state(&tmp));
+            let config = Arc::new(DaemonConfig {
+                listen: DEFAULT_LISTEN_ADDR.parse().expect("listen addr"),
+                token: Some("secret".to_string()),
+                insecure_no_auth: false,
+                data_dir: tmp.clone(),
+                tls: None,
+                ws_listen: None,
+                metrics_listen: None,
+            });
+            let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+            let addr = listener.local_addr().expect("local addr");
+            tokio::spawn(transport::accept_loop(
+                listener,
+                transport::ListenerKind::WebSocket,
+                None,
+                config,
+                state,
+            ));
+
+            let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{addr}"))
+                .await
+                .expect("connect websocket");
+            for (request, pointer, expected) in [
+                (
+                    json!({ "id": 1, "method": "ping" }),
+                    "/error/code",
+                    json!(RpcErrorCode::Unauthorized.code()),
+                ),
+                (
+                    json!({ "id": 2, "method": "auth", "params": { "token": "secret" } }),
+                    "/result/ok",
+                    json!(true),
+                ),
+                (
+                    json!({ "id": 3, "method": "ping" }),
+                    "/result/ok",
+                    json!(true),
+                ),
+            ] {
+                socket
+                    .send(Message::Text(request.to_string()))
+                    .await
+                    .expect("send request");
+                let response = match socket.next().await {
+                    Some(Ok(Message::Text(text))) => text,
+                    other => panic!("unexpected websocket message: {other:?}"),
+                };
+                let response: Value = serde_json::from_str(&response).expect("json response");
+                assert_eq!(response["id"], request["id"]);
+                assert_eq!(response.pointer(pointer), Some(&expected));
+            }
+
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    fn app_server_event(workspace_id: &str, method: &str) -> AppServerEvent {
+        AppServerEvent {
+            workspace_id: workspace_id.to_string(),
+            message: json!({ "method": method }),
+        }
+    }
+
+    #[test]
+    fn event_hub_replays_buffered_events_in_sequence_order() {
+        let hub = EventHub::new();
+        hub.publish(DaemonEvent::AppServer(app_server_event("ws-1", "a")));
+        hub.publish(DaemonEvent::AppServer(app_server_event("ws-2", "b")));
+        hub.publish(DaemonEvent::TerminalOutput(TerminalOutput {
+            workspace_id: "ws-1".to_string(),
+            terminal_id: "term-1".to_string(),
+            data: "ls\n".to_string(),
+        }));
+        hub.publish(DaemonEvent::AppServer(app_server_event("ws-1", "c")));
+
+        assert_eq!(hub.last_seq(), 4);
+        let (missed, _rx, evicted) = hub.subscribe_after(1, |_| true);
+        let seqs: Vec<u64> = missed.iter().map(|event| event.seq).collect();
+        assert_eq!(seqs, vec![2, 4]);
+        assert!(!evicted);
+        let (missed, _rx, _) = hub.subscribe_after(1, |workspace_id| workspace_id == "ws-1");
+        let seqs: Vec<u64> = missed.iter().map(|event| event.seq).collect();
+        assert_eq!(seqs, vec![4]);
+
+        let (missed, _rx, _) = hub.subscribe_after(4, |_| true);
+        assert!(missed.is_empty());
+    }
+
+    #[test]
+    fn lagged_event_forwarding_replays_from_the_buffer_or_reports_a_gap() {
+        run_async_test(async {
+            async fn forward_after_lag(workspace_ids: &[&str], events: usize) -> Vec<Value> {
+                let hub = Arc::new(EventHub::new());
+                let (after_seq, rx) = hub.subscribe();
+                // More than the broadcast channel holds, so the receiver lags.
+                for index in 0..events {
+                    let workspace_id = workspace_ids[index % workspace_ids.len()];
+                    hub.publish(DaemonEvent::AppServer(app_server_event(
+                        workspace_id,
+                        "tick",
+                    )));
+                }
+                let (out_tx, mut out_rx) = mpsc::unbounded_channel();
+                let caller = Arc::new(access::RpcCaller {
+                    access: ClientAccess::unrestricted(),
+                    peer: None,
+                    client_version: "daemon-test".to_string(),
+                    connection_id: 1,
+                });
+                let task = tokio::spawn(rpc::forward_events(
+                    Arc::clone(&hub),
+                    after_seq,
+                    Vec::new(),
+                    rx,
+                    out_tx,
+                    Arc::new(rpc::ForwardedEvents::default()),
+                    caller,
+                ));
+                let mut messages = Vec::new();
+                while let Ok(Some(line)) =
+                    tokio::time::timeout(Duration::from_millis(200), out_rx.recv()).await
+                {
+                    messages.push(serde_json::from_str::<Value>(&line).expect("json"));
+                }
+                task.abort();
+                messages
+            }
+
+            // Three workspaces keep every event in the replay buffers.
+            let messages = forward_after_lag(&["ws-1", "ws-2", "ws-3"], 2_100).await;
+            let seqs: Vec<u64> = messages
+                .iter()
+                .map(|message| message["seq"].as_u64().expect("seq"))
+                .collect();
+            assert_eq!(seqs, (1..=2_100).collect::<Vec<u64>>());
+
+            // One workspace overflows its buffer: the client is told about the gap, then
+            // gets what is still buffered.
+            let messages = forward_after_lag(&["ws-1"], 2_100).await;
+            assert_eq!(messages[0]["method"], json!("event-gap"));
+            assert_eq!(messages[0]["params"]["afterSeq"], json!(0));
+            let seqs: Vec<u64> = messages[1..]
+                .iter()
+                .map(|message| message["seq"].as_u64().expect("seq"))
+                .collect();
+            assert_eq!(seqs, (1_077..=2_100).collect::<Vec<u64>>());
+        });
+    }
+
+    async fn next_json_line<R>(lines: &mut tokio::io::Lines<BufReader<R>>) -> Value
+    where
+        R: AsyncRead + Unpin,
+    {
+        let line = lines.next_line().await.expect("read line").expect("line");
+        serde_json::from_str(&line).expect("json line")
+    }
+
+    #[test]
+    fn auth_with_last_event_seq_replays_missed_events_before_live_ones() {
+        run_async_test(async {
+            let tmp = make_temp_dir("event-resume");
+            let state = Arc::new(test_state(&tmp));
+            let config = Arc::new(DaemonConfig {
+                listen: DEFAULT_LISTEN_ADDR.parse().expect("listen addr"),
+                token: Some("secret".to_string()),
+                insecure_no_auth: false,
+                data_dir: tmp.clone(),
+                tls: None,
+                ws_listen: None,
+                metrics_listen: None,
+            });
+            let events = Arc::clone(&state.event_sink.events);
+            state
+                .event_sink
+                .emit_app_server_event(app_server_event("ws-1", "seen"));
+            state
+                .event_sink
+                .emit_app_server_event(app_server_event("ws-1", "missed"));
+
+            let (client, server) = tokio::io::duplex(64 * 1024);
+            tokio::spawn(transport::handle_client(
+                server,
+                None,
+                config,
+                Arc::clone(&state),
+            ));
+            let (reader, mut writer) = tokio::io::split(client);
+            let mut lines = BufReader::new(reader).lines();
+
+            let auth = json!({
+                "id": 1,
+                "method": "auth",
+                "params": {
+                    "token": "secret",
+                    "eventStreamId": events.stream_id(),
+                    "lastEventSeq": 1,
+                },
+            });
+            writer
+                .write_all(format!("{auth}\n").as_bytes())
+                .await
+                .expect("write auth");
+
+            let response = next_json_line(&mut lines).await;
+            assert_eq!(response["result"]["replayedEvents"], json!(1));
+            assert_eq!(
+                response["result"]["eventStreamId"],
+                json!(events.stream_id())
+            );
+
+            let replayed = next_json_line(&mut lines).await;
+            assert_eq!(replayed["seq"], json!(2));
+            assert_eq!(replayed["params"]["message"]["method"], json!("missed"));
+
+            state
+                .event_sink
+                .emit_app_server_event(app_server_event("ws-1", "live"));
+            let live = next_json_line(&mut lines).await;
+            assert_eq!(live["seq"], json!(3));
+
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn auth_reports_a_gap_when_missed_events_are_gone() {
+        run_async_test(async {
+            async fn resume(
+                state: &Arc<DaemonState>,
+                config: &Arc<DaemonConfig>,
+                stream_id: &str,
+            ) -> (Value, Value) {
+                let (client, server) = tokio::io::duplex(64 * 1024);
+                tokio::spawn(transport::handle_client(
+                    server,
+                    None,
+                    Arc::clone(config),
+                    Arc::clone(state),
+                ));
+                let (reader, mut writer) = tokio::io::split(client);
+                let mut lines = BufReader::new(reader).lines();
+                let auth = json!({
+                    "id": 1,
+                    "method": "auth",
+                    "params": {
+                        "token": "secret",
+                        "eventStreamId": stream_id,
+                        "lastEventSeq": 1,
+                    },
+                });
+                writer
+                    .write_all(format!("{auth}\n").as_bytes())
+                    .await
+                    .expect("write auth");
+                let response = next_json_line(&mut lines).await;
+                (response, next_json_line(&mut lines).await)
+            }
+
+            let tmp = make_temp_dir("event-gap-resume");
+            let state = Arc::new(test_state(&tmp));
+            let config = Arc::new(DaemonConfig {
+                listen: DEFAULT_LISTEN_ADDR.parse().expect("listen addr"),
+                token: Some("secret".to_string()),
+                insecure_no_auth: false,
+                data_dir: tmp.clone(),
+                tls: None,
+                ws_listen: None,
+                metrics_listen: None,
+            });
+            let events = Arc::clone(&state.event_sink.events);
+            // More than one workspace buffer holds, so events after seq 1 were evicted.
+            for _ in 0..1_100 {
+                state
+                    .event_sink
+                    .emit_app_server_event(app_server_event("ws-1", "tick"));
+            }
+
+            let (response, gap) = resume(&state, &config, events.stream_id()).await;
+            assert_eq!(response["id"], json!(1));
+            assert_eq!(gap["method"], json!("event-gap"));
+            assert_eq!(gap["params"], json!({ "afterSeq": 1, "reason": "evicted" }));
+
+            let (response, gap) = resume(&state, &config, "previous-daemon").await;
+            assert_eq!(response["result"]["replayedEvents"], json!(0));
+            assert_eq!(gap["method"], json!("event-gap"));
+            assert_eq!(
+                gap["params"],
+                json!({ "afterSeq": 1_100, "reason": "restarted" })
+            );
+
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn metrics_listener_serves_usage_to_authorized_scrapers() {
+        use tokio::io::AsyncReadExt;
+
+        async fn http_get(
+            config: &Arc<DaemonConfig>,
+            state: &Arc<DaemonState>,
+            request: &str,
+        ) -> String {
+            let (mut client, server) = tokio::io::duplex(64 * 1024);
+            tokio::spawn(metrics::handle_http_client(
+                server,
+                None,
+                Arc::clone(config),
+                Arc::clone(state),
+            ));
+            client
+                .write_all(request.as_bytes())
+                .await
+                .expect("write request");
+            let mut response = String::new();
+            client
+                .read_to_string(&mut response)
+                .await
+                .expect("read response");
+            response
+        }
+
+        run_async_test(async {
+            let tmp = make_temp_dir("metrics-listener");
+            let state = Arc::new(test_state(&tmp));
+            let config = Arc::new(DaemonConfig {
+                listen: DEFAULT_LISTEN_ADDR.parse().expect("listen addr"),
+                token: Some("secret".to_string()),
+                insecure_no_auth: false,
+                data_dir: tmp.clone(),
+                tls: None,
+                ws_listen: None,
+                metrics_listen: None,
+            });
+
+            let response = http_get(&config, &state, "GET /metrics HTTP/1.1\r\n\r\n").await;
+            assert!(response.starts_with("HTTP/1.1 401 "), "{response}");
+
+            let response = http_get(
+                &config,
+                &state,
+                "GET /metrics?days=7 HTTP/1.1\r\nAuthorization: Bearer secret\r\n\r\n",
+            )
+            .await;
+            assert!(response.starts_with("HTTP/1.1 200 "), "{response}");
+            assert!(response.contains("Content-Type: text/plain; version=0.0.4"));
+            assert!(response.contains("codex_monitor_usage_window_days 7\n"));
+            assert!(response.contains("# TYPE codex_monitor_agent_runs gauge\n"));
+
+            let response = http_get(
+                &config,
+                &state,
+                "GET /usage.csv HTTP/1.1\r\nauthorization: bearer secret\r\n\r\n",
+            )
+            .await;
+            assert!(response.starts_with("HTTP/1.1 200 "), "{response}");
+            assert!(response.contains("\r\n\r\nday,model,workspace_id,"));
+
+            let (_, scoped_secret) = daemon_tokens::mint_token(
+                &daemon_tokens::tokens_path(&tmp),
+                "scraper",
+                daemon_tokens::DaemonRole::Admin,
+                Some(vec!["ws-1".to_string()]),
+            )
+            .expect("mint token");
+            let response = http_get(
+                &config,
+                &state,
+                &format!("GET /metrics HTTP/1.1\r\nAuthorization: Bearer {scoped_secret}\r\n\r\n"),
+            )
+            .await;
+            assert!(response.starts_with("HTTP/1.1 403 "), "{response}");
+            assert!(!response.contains("codex_monitor_"), "{response}");
+
+            let response = http_get(
+                &config,
+                &state,
+                "GET /other HTTP/1.1\r\nAuthorization: Bearer secret\r\n\r\n",
+            )
+            .await;
+            assert!(response.starts_with("HTTP/1.1 404 "), "{response}");
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn named_tokens_are_limited_by_role_and_workspace_scope() {
+        run_async_test(async {
+            let tmp = make_temp_dir("scoped-tokens");
+            let state = Arc::new(test_state(&tmp));
+            let config = Arc::new(DaemonConfig {
+                listen: DEFAULT_LISTEN_ADDR.parse().expect("listen addr"),
+                token: None,
+                insecure_no_auth: false,
+                data_dir: tmp.clone(),
+                tls: None,
+                ws_listen: None,
+                metrics_listen: None,
+            });
+            let (_, secret) = daemon_tokens::mint_token(
+                &daemon_tokens::tokens_path(&tmp),
+                "phone",
+                daemon_tokens::DaemonRole::Observer,
+                Some(vec!["ws-1".to_string()]),
+            )
+            .expect("mint token");
+
+            let (client, server) = tokio::io::duplex(64 * 1024);
+            tokio::spawn(transport::handle_client(
+                server,
+                None,
+                config,
+                Arc::clone(&state),
+            ));
+            let (reader, mut writer) = tokio::io::split(client);
+            let mut lines = BufReader::new(reader).lines();
+
+            let requests = [
+                json!({ "id": 1, "method": "auth", "params": { "token": secret } }),
+                json!({ "id": 2, "method": "daemon_shutdown" }),
+                json!({ "id": 3, "method": "list_threads", "params": { "workspaceId": "ws-2" } }),
+            ];
+            for request in &requests {
+                writer
+                    .write_all(format!("{request}\n").as_bytes())
+                    .await
+                    .expect("write request");
+            }
+
+            let mut responses = HashMap::new();
+            for _ in 0..requests.len() {
+                let response = next_json_line(&mut lines).await;
+                responses.insert(response["id"].as_u64().expect("id"), response);
+            }
+            assert_eq!(responses[&1]["result"]["role"], json!("observer"));
+            assert_eq!(responses[&1]["result"]["tokenName"], json!("phone"));
+            assert_eq!(
+                responses[&2]["error"]["code"],
+                json!(RpcErrorCode::Forbidden.code())
+            );
+            assert_eq!(
+                responses[&2]["error"]["data"]["requiredRole"],
+                json!("admin")
+            );
+            assert_eq!(responses[&3]["error"]["data"]["kind"], json!("forbidden"));
+            assert_eq!(responses[&3]["error"]["data"]["workspaceId"], json!("ws-2"));
+
+            state
+                .event_sink
+                .emit_app_server_event(app_server_event("ws-2", "hidden"));
+            state
+                .event_sink
+                .emit_app_server_event(app_server_event("ws-1", "visible"));
+            let event = next_json_line(&mut lines).await;
+            assert_eq!(event["params"]["message"]["method"], json!("visible"));
+
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn mutating_calls_are_audited_with_caller_identity() {
+        run_async_test(async {
+            let tmp = make_temp_dir("audit-log");
+            let state = test_state(&tmp);
+            let record = daemon_tokens::DaemonTokenRecord {
+                name: "ci".to_string(),
+                role: daemon_tokens::DaemonRole::Operator,
+                workspace_ids: None,
+                token_sha256: String::new(),
+                created_at_ms: 0,
+            };
+            let caller = access::RpcCaller {
+                access: ClientAccess::from_record(&record),
+                peer: Some("100.64.0.7:51234".to_string()),
+                client_version: "daemon-test".to_string(),
+                connection_id: 1,
+            };
+
+            let _ = rpc::handle_client_rpc_request(
+                &state,
+                &caller,
+                "stage_git_all",
+                json!({ "workspaceId": "missing" }),
+            )
+            .await
+            .expect_err("unknown workspace");
+            let _ = rpc::handle_client_rpc_request(
+                &state,
+                &caller,
+                "file_write",
+                json!({ "workspaceId": "missing" }),
+            )
+            .await
+            .expect_err("operator cannot write files");
+            rpc::handle_client_rpc_request(&state, &caller, "ping", json!({}))
+                .await
+                .expect("ping");
+
+            let admin = access::RpcCaller {
+                access: ClientAccess::unrestricted(),
+                peer: None,
+                client_version: "daemon-test".to_string(),
+                connection_id: 1,
+            };
+            let result = rpc::handle_client_rpc_request(
+                &state,
+                &admin,
+                "daemon_audit_query",
+                json!({ "workspaceId": "missing" }),
+            )
+            .await
+            .expect("audit query");
+            let entries = result["entries"].as_array().expect("entries");
+            let summary: Vec<(&str, &str)> = entries
+                .iter()
+                .map(|entry| {
+                    (
+                        entry["method"].as_str().unwrap_or_default(),
+                        entry["outcome"].as_str().unwrap_or_default(),
+                    )
+                })
+                .collect();
+            assert_eq!(
+                summary,
+                vec![("stage_git_all", "error"), ("file_write", "denied")]
+            );
+            assert_eq!(entries[0]["tokenName"], json!("ci"));
+            assert_eq!(entries[0]["role"], json!("operator"));
+            assert_eq!(entries[0]["peer"], json!("100.64.0.7:51234"));
+            assert!(entries[0]["timestampMs"].as_i64().is_some());
+
+            let result = rpc::handle_client_rpc_request(
+                &state,
+                &admin,
+                "daemon_audit_query",
+                json!({ "sinceMs": i64::MAX }),
+            )
+            .await
+            .expect("audit query");
+            assert_eq!(result["entries"], json!([]));
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn terminal_writes_are_audited_as_per_terminal_summaries() {
+        run_async_test(async {
+            let tmp = make_temp_dir("audit-terminal");
+            let state = test_state(&tmp);
+            let caller = access::RpcCaller {
+                access: ClientAccess::unrestricted(),
+                peer: Some("100.64.0.7:51234".to_string()),
+                client_version: "daemon-test".to_string(),
+                connection_id: 3,
+            };
+            let write = json!({ "workspaceId": "ws-1", "terminalId": "t-1", "data": "ls\n" });
+            let terminal = json!({ "workspaceId": "ws-1", "terminalId": "t-1" });
+            let denied = RpcError::new(RpcErrorCode::Forbidden, "nope");
+            for (method, params, result, at) in [
+                ("terminal_write", &write, Ok(()), 1_000),
+                ("terminal_write", &write, Ok(()), 2_000),
+                ("terminal_resize", &terminal, Ok(()), 2_500),
+                ("terminal_write", &write, Ok(()), 3_000),
+                ("terminal_write", &write, Ok(()), 61_000),
+                ("terminal_write", &write, Ok(()), 62_000),
+                ("terminal_write", &write, Err(&denied), 62_500),
+                ("terminal_close", &terminal, Ok(()), 63_000),
+            ] {
+                state
+                    .audit
+                    .record_at(&caller, method, params, result, at)
+                    .await
+                    .expect("record");
+            }
+
+            let entries = state
+                .audit
+                .query(&audit::AuditQuery {
+                    limit: 10,
+                    ..Default::default()
+                })
+                .await
+                .expect("query");
+            let summary: Vec<_> = entries
+                .iter()
+                .map(|entry| {
+                    (
+                        entry.method.as_str(),
+                        entry.outcome.as_str(),
+                        entry.timestamp_ms,
+                        entry.writes,
+                        entry.bytes,
+                    )
+                })
+                .collect();
+            assert_eq!(
+                summary,
+                vec![
+                    ("terminal_write", "ok", 1_000, Some(1), Some(3)),
+                    ("terminal_write", "ok", 61_000, Some(3), Some(9)),
+                    ("terminal_write", "denied", 62_500, None, None),
+                    ("terminal_write", "ok", 63_000, Some(1), Some(3)),
+                    ("terminal_close", "ok", 63_000, None, None),
+                ]
+            );
+            assert!(entries
+                .iter()
+                .all(|entry| entry.terminal_id.as_deref() == Some("t-1")));
             let _ = std::fs::remove_dir_all(&tmp);
         });
     }
+
+    #[test]
+    fn rpc_errors_carry_json_rpc_codes() {
+        run_async_test(async {
+            let tmp = make_temp_dir("rpc-error-codes");
+            let state = test_state(&tmp);
+            let caller = access::RpcCaller {
+                access: ClientAccess::unrestricted(),
+                peer: None,
+                client_version: "daemon-test".to_string(),
+                connection_id: 1,
+            };
+
+            insert_workspace(&state, "ws-1", &tmp.to_string_lossy()).await;
+
+            for (method, params, expected) in [
+                ("no_such_method", json!({}), RpcErrorCode::MethodNotFound),
+                (
+                    "get_git_status",
+                    json!({ "workspaceId": "missing" }),
+                    RpcErrorCode::WorkspaceNotFound,
+                ),
+                (
+                    "terminal_resize",
+                    json!({ "workspaceId": "ws-1", "terminalId": "term-1", "cols": 80 }),
+                    RpcErrorCode::InvalidParams,
+                ),
+            ] {
+                let err = rpc::handle_client_rpc_request(&state, &caller, method, params)
+                    .await
+                    .expect_err("expected error");
+                assert_eq!(err.code, expected, "{method}: {}", err.message);
+            }
+
+            let err = rpc::handle_client_rpc_request(
+                &state,
+                &caller,
+                "add_worktree",
+                json!({ "parentId": "gone", "branch": "feature", "copyAgentsMd": false }),
+            )
+            .await
+            .expect_err("expected missing parent");
+            assert_eq!(err.code, RpcErrorCode::WorkspaceNotFound);
+            assert_eq!(
+                err.data,
+                Some(json!({ "method": "add_worktree", "workspaceId": "gone" }))
+            );
+
+            // `ws-1` is registered but has no codex session.
+            let err = rpc::handle_client_rpc_request(
+                &state,
+                &caller,
+                "list_threads",
+                json!({ "workspaceId": "ws-1" }),
+            )
+            .await
+            .expect_err("expected disconnected workspace");
+            assert_eq!(err.code, RpcErrorCode::WorkspaceNotConnected);
+            assert_eq!(err.data, Some(json!({ "workspaceId": "ws-1" })));
+            let background = rpc::handle_client_rpc_request(
+                &state,
+                &caller,
+                "generate_run_metadata",
+                json!({ "workspaceId": "ws-1", "prompt": "fix the build" }),
+            )
+            .await
+            .expect_err("expected disconnected workspace");
+            assert_eq!(background.code, RpcErrorCode::WorkspaceNotConnected);
+
+            let response = rpc::build_error_response(Some(7), &err).expect("response");
+            let response: Value = serde_json::from_str(&response).expect("json");
+            assert_eq!(response["jsonrpc"], json!("2.0"));
+            assert_eq!(response["error"]["code"], json!(-32004));
+            assert_eq!(
+                response["error"]["data"]["kind"],
+                json!("workspace_not_connected")
+            );
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    /// Method names matched by the rpc handler modules, read from their source.
+    /// Smallest value matching `schema`, filling in required properties only.
+    fn sample_params(schema: &Value) -> Value {
+        if let Some(variant) = schema
+            .get("enum")
+            .and_then(Value::as_array)
+            .and_then(|variants| variants.first())
+        {
+            return variant.clone();
+        }
+        match schema.get("type").and_then(Value::as_str) {
+            Some("integer") => json!(0),
+            Some("number") => json!(0.0),
+            Some("boolean") => json!(false),
+            Some("array") => json!([]),
+            Some("null") => Value::Null,
+            Some("object") => {
+                let required: Vec<&str> = schema
+                    .get("required")
+                    .and_then(Value::as_array)
+                    .map(|fields| fields.iter().filter_map(Value::as_str).collect())
+                    .unwrap_or_default();
+                let mut object = serde_json::Map::new();
+                if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+                    for (name, property) in properties {
+                        if required.contains(&name.as_str()) {
+                            object.insert(name.clone(), sample_params(property));
+                        }
+                    }
+                }
+                Value::Object(object)
+            }
+            _ => json!("schema-probe"),
+        }
+    }
+
+    #[test]
+    fn rpc_describe_lists_methods_that_reach_their_handlers() {
+        run_async_test(async {
+            let tmp = make_temp_dir("rpc-describe-drift");
+            let state = test_state(&tmp);
+            let document = rpc::handle_rpc_request(
+                &state,
+                "rpc_describe",
+                Value::Null,
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect("rpc_describe");
+            assert_eq!(document["openrpc"], json!("1.2.6"));
+            let methods = document["methods"].as_array().expect("methods");
+
+            let caller = access::RpcCaller {
+                access: ClientAccess::unrestricted(),
+                peer: None,
+                client_version: "daemon-test".to_string(),
+                connection_id: 1,
+            };
+
+            // The document lists the registry the dispatcher routes by, so every method in it
+            // must reach a handler. A string is never valid params: handlers that take params
+            // reject it before doing anything, and the rest answer outright.
+            for method in methods {
+                let name = method["name"].as_str().expect("name");
+                // `auth` is the transport's, and `daemon_shutdown` would stop the test process.
+                if name == "auth" || name == "daemon_shutdown" {
+                    continue;
+                }
+                let takes_params = !method["params"].as_array().expect("params").is_empty();
+                let result =
+                    rpc::handle_client_rpc_request(&state, &caller, name, json!("probe")).await;
+                if takes_params {
+                    let err = result.expect_err("expected invalid params");
+                    assert_eq!(
+                        err.code,
+                        RpcErrorCode::InvalidParams,
+                        "{name}: {}",
+                        err.message
+                    );
+                } else if let Err(err) = result {
+                    panic!("{name} was not answered: {}", err.message);
+                }
+            }
+
+            // Dropping any required param must be rejected before the handler runs, which
+            // also keeps this probe free of side effects. The probe's workspace ids must name
+            // a known workspace, or the call stops at `WorkspaceNotFound` first.
+            insert_workspace(&state, "schema-probe", &tmp.to_string_lossy()).await;
+            for method in methods {
+                let name = method["name"].as_str().expect("name");
+                if name == "auth" {
+                    continue;
+                }
+                let params = method["params"].as_array().expect("params");
+                let mut sample = serde_json::Map::new();
+                for param in params
+                    .iter()
+                    .filter(|param| param["required"] == json!(true))
+                {
+                    let key = param["name"].as_str().expect("param name");
+                    sample.insert(key.to_string(), sample_params(&param["schema"]));
+                }
+                for key in sample.keys() {
+                    let mut partial = sample.clone();
+                    partial.remove(key);
+                    let err = rpc::handle_client_rpc_request(
+                        &state,
+                        &caller,
+                        name,
+                        Value::Object(partial),
+                    )
+                    .await
+                    .expect_err("expected missing param error");
+                    assert_eq!(
+                        err.code,
+                        RpcErrorCode::InvalidParams,
+                        "{name} without `{key}`: {}",
+                        err.message
+                    );
+                    assert!(
+                        err.message.contains(key.as_str()),
+                        "{name}: {}",
+                        err.message
+                    );
+                }
+            }
+
+            let send = methods
+                .iter()
+                .find(|method| method["name"] == json!("send_user_message"))
+                .expect("send_user_message");
+            let mut required: Vec<&str> = send["params"]
+                .as_array()
+                .expect("params")
+                .iter()
+                .filter(|param| param["required"] == json!(true))
+                .filter_map(|param| param["name"].as_str())
+                .collect();
+            required.sort_unstable();
+            assert_eq!(required, vec!["text", "threadId", "workspaceId"]);
+            assert_eq!(send["x-requiredRole"], json!("operator"));
+            assert_eq!(
+                document["components"]["errors"]["invalid_params"]["code"],
+                json!(-32602)
+            );
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn scoped_access_filters_listed_workspaces() {
+        let record = daemon_tokens::DaemonTokenRecord {
+            name: "ci".to_string(),
+            role: daemon_tokens::DaemonRole::Operator,
+            workspace_ids: Some(vec!["ws-1".to_string()]),
+            token_sha256: String::new(),
+            created_at_ms: 0,
+        };
+        let access = ClientAccess::from_record(&record);
+        assert!(access
+            .authorize("send_user_message", &json!({ "workspaceId": "ws-1" }))
+            .is_ok());
+        assert!(access
+            .authorize("remove_workspace", &json!({ "id": "ws-1" }))
+            .is_err());
+        assert!(access
+            .authorize(
+                "land_worktree",
+                &json!({ "workspaceId": "ws-1", "strategy": "squash" })
+            )
+            .is_ok());
+        assert!(access
+            .authorize(
+                "land_worktree",
+                &json!({ "workspaceId": "ws-1", "strategy": "squash", "removeWorktree": true })
+            )
+            .is_err());
+
+        let listed = access.filter_result(
+            "list_workspaces",
+            json!([{ "id": "ws-1" }, { "id": "ws-2" }]),
+        );
+        assert_eq!(listed, json!([{ "id": "ws-1" }]));
+        assert_eq!(
+            access::required_role("daemon_shutdown"),
+            daemon_tokens::DaemonRole::Admin
+        );
+        assert_eq!(
+            access::required_role("get_git_status"),
+            daemon_tokens::DaemonRole::Observer
+        );
+    }
+
+    #[test]
+    fn terminals_need_an_unscoped_admin_token() {
+        let token = |role, workspace_ids| daemon_tokens::DaemonTokenRecord {
+            name: "shell".to_string(),
+            role,
+            workspace_ids,
+            token_sha256: String::new(),
+            created_at_ms: 0,
+        };
+        let params = json!({ "workspaceId": "ws-1", "terminalId": "term-1" });
+        let operator = ClientAccess::from_record(&token(daemon_tokens::DaemonRole::Operator, None));
+        let err = operator
+            .authorize("terminal_open", &params)
+            .expect_err("operator shell");
+        assert!(err.is(RpcErrorCode::Forbidden), "{err:?}");
+        assert_eq!(err.to_value()["data"]["requiredRole"], json!("admin"));
+
+        let scoped_admin = ClientAccess::from_record(&token(
+            daemon_tokens::DaemonRole::Admin,
+            Some(vec!["ws-1".to_string()]),
+        ));
+        for method in [
+            "terminal_open",
+            "terminal_write",
+            "terminal_resize",
+            "terminal_close",
+        ] {
+            let err = scoped_admin
+                .authorize(method, &params)
+                .expect_err("scoped shell");
+            assert!(err.is(RpcErrorCode::Forbidden), "{method}");
+        }
+        let admin = ClientAccess::from_record(&token(daemon_tokens::DaemonRole::Admin, None));
+        assert!(admin.authorize("terminal_open", &params).is_ok());
+    }
+
+    #[test]
+    fn scoped_access_hides_cross_workspace_usage_and_settings() {
+        let record = daemon_tokens::DaemonTokenRecord {
+            name: "phone".to_string(),
+            role: daemon_tokens::DaemonRole::Observer,
+            workspace_ids: Some(vec!["ws-1".to_string()]),
+            token_sha256: String::new(),
+            created_at_ms: 0,
+        };
+        let scoped = ClientAccess::from_record(&record);
+        for method in [
+            "get_app_settings",
+            "local_usage_snapshot",
+            "local_usage_thread_timeline",
+            "local_usage_export",
+            "local_usage_watch",
+        ] {
+            let err = scoped
+                .authorize(method, &json!({}))
+                .expect_err("scoped token must be refused");
+            assert!(err.is(RpcErrorCode::Forbidden), "{method}");
+        }
+        let unscoped = ClientAccess::from_record(&daemon_tokens::DaemonTokenRecord {
+            workspace_ids: None,
+            ..record
+        });
+        assert!(unscoped
+            .authorize("local_usage_snapshot", &json!({}))
+            .is_ok());
+        assert!(unscoped.authorize("get_app_settings", &json!({})).is_ok());
+
+        let budgets = json!([
+            { "budgetId": "global", "workspaceId": null },
+            { "budgetId": "mine", "workspaceId": "ws-1" },
+            { "budgetId": "theirs", "workspaceId": "ws-2" },
+        ]);
+        assert!(scoped.authorize("usage_budget_status", &json!({})).is_ok());
+        assert_eq!(
+            scoped.filter_result("usage_budget_status", budgets.clone()),
+            json!([{ "budgetId": "mine", "workspaceId": "ws-1" }])
+        );
+        assert_eq!(
+            unscoped.filter_result("usage_budget_status", budgets.clone()),
+            budgets
+        );
+    }
+
+    #[test]
+    fn scoped_budget_overrides_are_checked_against_the_budget_workspace() {
+        run_async_test(async {
+            let tmp = make_temp_dir("budget-override-scope");
+            let budget = |id: &str, workspace_id: Option<&str>| types::UsageBudget {
+                id: id.to_string(),
+                workspace_id: workspace_id.map(str::to_string),
+                period: types::UsageBudgetPeriod::Daily,
+                metric: types::UsageBudgetMetric::Tokens,
+                limit: 1000.0,
+                thresholds: vec![100],
+                block_at_limit: true,
+            };
+            let settings = AppSettings {
+                usage_budgets: vec![
+                    budget("global", None),
+                    budget("mine", Some("ws-1")),
+                    budget("theirs", Some("ws-2")),
+                ],
+                ..AppSettings::default()
+            };
+            crate::storage::write_settings(&tmp.join("settings.json"), &settings)
+                .expect("write settings");
+            let state = test_state(&tmp);
+            let caller = access::RpcCaller {
+                access: ClientAccess::from_record(&daemon_tokens::DaemonTokenRecord {
+                    name: "phone".to_string(),
+                    role: daemon_tokens::DaemonRole::Operator,
+                    workspace_ids: Some(vec!["ws-1".to_string()]),
+                    token_sha256: String::new(),
+                    created_at_ms: 0,
+                }),
+                peer: None,
+                client_version: "daemon-test".to_string(),
+                connection_id: 1,
+            };
+            let override_budget = |budget_id: &str| {
+                rpc::handle_client_rpc_request(
+                    &state,
+                    &caller,
+                    "override_usage_budget",
+                    json!({ "budgetId": budget_id }),
+                )
+            };
+
+            for budget_id in ["global", "theirs"] {
+                let err = override_budget(budget_id)
+                    .await
+                    .expect_err("budget outside the token scope");
+                assert!(err.is(RpcErrorCode::Forbidden), "{budget_id}");
+            }
+            override_budget("mine").await.expect("own workspace budget");
+
+            let admin = access::RpcCaller {
+                access: ClientAccess::unrestricted(),
+                peer: None,
+                client_version: "daemon-test".to_string(),
+                connection_id: 1,
+            };
+            rpc::handle_client_rpc_request(
+                &state,
+                &admin,
+                "override_usage_budget",
+                json!({ "budgetId": "global" }),
+            )
+            .await
+            .expect("unscoped token overrides global budgets");
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn land_worktree_reports_a_failed_removal_after_landing() {
+        run_async_test(async {
+            let tmp = make_temp_dir("land-worktree-removal");
+            let parent_root = tmp.join("parent");
+            let worktree_root = tmp.join("feature");
+            std::fs::create_dir_all(&parent_root).expect("create parent");
+            let git = |root: &std::path::Path, args: &[&str]| {
+                let output = std::process::Command::new("git")
+                    .args(args)
+                    .current_dir(root)
+                    .output()
+                    .expect("run git");
+                assert!(output.status.success(), "git {args:?} failed");
+                String::from_utf8_lossy(&output.stdout).trim().to_string()
+            };
+            git(&parent_root, &["init", "-q"]);
+            git(&parent_root, &["config", "user.name", "Test"]);
+            git(&parent_root, &["config", "user.email", "test@example.com"]);
+            std::fs::write(parent_root.join("base.txt"), "base\n").expect("write base");
+            git(&parent_root, &["add", "base.txt"]);
+            git(&parent_root, &["commit", "-q", "-m", "base"]);
+            let worktree_path = worktree_root.to_string_lossy().to_string();
+            git(
+                &parent_root,
+                &["worktree", "add", "-q", "-b", "feature", &worktree_path],
+            );
+            std::fs::write(worktree_root.join("one.txt"), "one\n").expect("write one");
+            git(&worktree_root, &["add", "one.txt"]);
+            git(&worktree_root, &["commit", "-q", "-m", "add one"]);
+            // `git worktree remove --force` refuses locked worktrees.
+            git(&parent_root, &["worktree", "lock", &worktree_path]);
+
+            let state = test_state(&tmp);
+            insert_workspace(&state, "parent", &parent_root.to_string_lossy()).await;
+            let mut worktree = make_workspace_entry("wt", &worktree_path);
+            worktree.kind = WorkspaceKind::Worktree;
+            worktree.parent_id = Some("parent".to_string());
+            worktree.worktree = Some(types::WorktreeInfo {
+                branch: "feature".to_string(),
+            });
+            state
+                .workspaces
+                .lock()
+                .await
+                .insert("wt".to_string(), worktree);
+
+            let admin = access::RpcCaller {
+                access: ClientAccess::unrestricted(),
+                peer: None,
+                client_version: "daemon-test".to_string(),
+                connection_id: 1,
+            };
+            let result = rpc::handle_client_rpc_request(
+                &state,
+                &admin,
+                "land_worktree",
+                json!({
+                    "workspaceId": "wt",
+                    "strategy": "fast_forward",
+                    "removeWorktree": true,
+                }),
+            )
+            .await
+            .expect("landing succeeds even though removal fails");
+            assert_eq!(
+                result["sha"],
+                json!(git(&parent_root, &["rev-parse", "feature"]))
+            );
+            assert_eq!(result["worktreeRemoved"], json!(false));
+            assert!(result["worktreeRemovalError"]
+                .as_str()
+                .is_some_and(|error| error.contains("locked")));
+            assert!(state.workspaces.lock().await.contains_key("wt"));
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn auth_token_matches_requires_exact_token() {
+        assert!(rpc::auth_token_matches("secret", "secret"));
+        assert!(!rpc::auth_token_matches("secret", "secreT"));
+        assert!(!rpc::auth_token_matches("secret", "secret-longer"));
+        assert!(!rpc::auth_token_matches("secret", ""));
+        assert!(rpc::auth_token_matches("", ""));
+    }
+
+    #[test]
+    fn rpc_terminal_methods_validate_workspace_and_session() {
+        run_async_test(async {
+            let tmp = make_temp_dir("rpc-terminal");
+            let state = test_state(&tmp);
+
+            let open_err = rpc::handle_rpc_request(
+                &state,
+                "terminal_open",
+                json!({
+                    "workspaceId": "missing",
+                    "terminalId": "term-1",
+                    "cols": 80,
+                    "rows": 24
+                }),
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect_err("expected unknown workspace error");
+            assert_eq!(open_err, "Unknown workspace");
+
+            let write_err = rpc::handle_rpc_request(
+                &state,
+                "terminal_write",
+                json!({
+                    "workspaceId": "missing",
+                    "terminalId": "term-1",
+                    "data": "ls\n"
+                }),
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect_err("expected missing session error");
+            assert_eq!(write_err, "Terminal session not found");
+
+            let resize_err = rpc::handle_rpc_request(
+                &state,
+                "terminal_resize",
+                json!({
+                    "workspaceId": "missing",
+                    "terminalId": "term-1",
+                    "cols": 80
+                }),
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect_err("expected missing rows error");
+            assert_eq!(resize_err, "missing or invalid `rows`");
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
     #[test]
     fn list_workspaces_syncs_from_storage_file() {
         run_async_test(async {
@@ -1915,11 +3677,21 @@ fn main() {
         .expect("failed to build tokio runtime");
 
     runtime.block_on(async move {
-        let (events_tx, _events_rx) = broadcast::channel::<DaemonEvent>(2048);
         let event_sink = DaemonEventSink {
-            tx: events_tx.clone(),
+            events: Arc::new(EventHub::new()),
+        };
+        let daemon_tls = match config.tls.as_ref().map(tls::build_daemon_tls).transpose() {
+            Ok(daemon_tls) => daemon_tls,
+            Err(err) => {
+                eprintln!("failed to configure TLS: {err}");
+                std::process::exit(2);
+            }
         };
-        let state = Arc::new(DaemonState::load(&config, event_sink));
+        let mut state = DaemonState::load(&config, event_sink);
+        state.tls_fingerprint = daemon_tls
+            .as_ref()
+            .map(|daemon_tls| daemon_tls.fingerprint.clone());
+        let state = Arc::new(state);
         let config = Arc::new(config);
 
         let listener = match TcpListener::bind(config.listen).await {
@@ -1938,19 +3710,62 @@ fn main() {
                 .unwrap_or(&state.storage_path)
                 .display()
         );
+        if let Some(daemon_tls) = daemon_tls.as_ref() {
+            eprintln!(
+                "TLS enabled{} (certificate SHA-256 fingerprint: {})",
+                if daemon_tls.client_auth {
+                    ", client certificates required"
+                } else {
+                    ""
+                },
+                daemon_tls.fingerprint
+            );
+        }
+        let acceptor = daemon_tls.map(|daemon_tls| daemon_tls.acceptor);
+
+        if let Some(ws_listen) = config.ws_listen {
+            let ws_listener = match TcpListener::bind(ws_listen).await {
+                Ok(listener) => listener,
+                Err(err) => {
+                    eprintln!("failed to bind {ws_listen}: {err}");
+                    std::process::exit(2);
+                }
+            };
+            eprintln!("codex-monitor-daemon WebSocket listening on {ws_listen}");
+            tokio::spawn(transport::accept_loop(
+                ws_listener,
+                transport::ListenerKind::WebSocket,
+                acceptor.clone(),
+                Arc::clone(&config),
+                Arc::clone(&state),
+            ));
+        }
 
-        loop {
-            match listener.accept().await {
-                Ok((socket, _addr)) => {
-                    let config = Arc::clone(&config);
-                    let state = Arc::clone(&state);
-                    let events = events_tx.clone();
-                    tokio::spawn(async move {
-                        transport::handle_client(socket, config, state, events).await;
-                    });
+        if let Some(metrics_listen) = config.metrics_listen {
+            let metrics_listener = match TcpListener::bind(metrics_listen).await {
+                Ok(listener) => listener,
+                Err(err) => {
+                    eprintln!("failed to bind {metrics_listen}: {err}");
+                    std::process::exit(2);
                 }
-                Err(_) => continue,
-            }
+            };
+            eprintln!("codex-monitor-daemon metrics listening on {metrics_listen}");
+            tokio::spawn(transport::accept_loop(
+                metrics_listener,
+                transport::ListenerKind::Http,
+                acceptor.clone(),
+                Arc::clone(&config),
+                Arc::clone(&state),
+            ));
         }
+
+        transport::accept_loop(
+            listener,
+            transport::ListenerKind::Tcp,
+            acceptor,
+            config,
+            state,
+        )
+        .await;
     });
 }
@@ -0,0 +1,220 @@
+use crate::daemon_tokens::{self, DaemonRole, DaemonTokenRecord};
+
+use super::rpc::registry::{find_method, WorkspaceParams};
+use super::rpc::{auth_token_matches, parse_optional_string};
+use super::*;
+
+/// Lowest role allowed to call `method`. Unknown methods require admin.
+pub(super) fn required_role(method: &str) -> DaemonRole {
+    find_method(method).map_or(DaemonRole::Admin, |(_, spec)| spec.role)
+}
+
+fn workspace_params(method: &str) -> WorkspaceParams {
+    find_method(method).map_or(WorkspaceParams::Named, |(_, spec)| spec.workspaces)
+}
+
+/// `required_role` for a concrete call. Landing a worktree can also remove it, which on
+/// its own (`remove_worktree`) needs admin.
+fn required_role_for_call(method: &str, params: &Value) -> DaemonRole {
+    let removes_worktree = params.get("removeWorktree").and_then(Value::as_bool) == Some(true);
+    if method == "land_worktree" && removes_worktree {
+        return DaemonRole::Admin;
+    }
+    required_role(method)
+}
+
+pub(super) fn referenced_workspace_ids(method: &str, params: &Value) -> Vec<String> {
+    let mut keys = vec!["workspaceId", "sourceWorkspaceId", "parentId"];
+    if workspace_params(method) == WorkspaceParams::Id {
+        keys.push("id");
+    }
+    keys.into_iter()
+        .filter_map(|key| parse_optional_string(params, key))
+        .collect()
+}
+
+/// The authenticated client behind a request: used for authorization and auditing.
+pub(super) struct RpcCaller {
+    pub(super) access: ClientAccess,
+    pub(super) peer: Option<String>,
+    pub(super) client_version: String,
+    /// Identifies the connection for per-connection state such as usage watches.
+    pub(super) connection_id: u64,
+}
+
+/// What an authenticated connection may do.
+#[derive(Clone, Debug)]
+pub(super) struct ClientAccess {
+    token_name: Option<String>,
+    role: DaemonRole,
+    workspace_ids: Option<HashSet<String>>,
+}
+
+impl ClientAccess {
+    /// Full access, used for the `--token` shared secret and `--insecure-no-auth`.
+    pub(super) fn unrestricted() -> Self {
+        Self {
+            token_name: None,
+            role: DaemonRole::Admin,
+            workspace_ids: None,
+        }
+    }
+
+    pub(super) fn from_record(record: &DaemonTokenRecord) -> Self {
+        Self {
+            token_name: Some(record.name.clone()),
+            role: record.role,
+            workspace_ids: record
+                .workspace_ids
+                .as_ref()
+                .map(|ids| ids.iter().cloned().collect()),
+        }
+    }
+
+    /// Resolves a presented token against the shared token and the named tokens on disk.
+    pub(super) fn authenticate(config: &DaemonConfig, provided: &str) -> Option<Self> {
+        if let Some(expected) = config.token.as_deref() {
+            if auth_token_matches(expected, provided) {
+                return Some(Self::unrestricted());
+            }
+        }
+        let path = daemon_tokens::tokens_path(&config.data_dir);
+        let tokens = match daemon_tokens::read_tokens(&path) {
+            Ok(tokens) => tokens,
+            Err(err) => {
+                eprintln!("daemon: failed to read {}: {err}", path.display());
+                return None;
+            }
+        };
+        daemon_tokens::find_token(&tokens, provided).map(Self::from_record)
+    }
+
+    pub(super) fn token_name(&self) -> Option<&str> {
+        self.token_name.as_deref()
+    }
+
+    pub(super) fn role(&self) -> DaemonRole {
+        self.role
+    }
+
+    /// `false` when the token is limited to some workspaces.
+    pub(super) fn is_unscoped(&self) -> bool {
+        self.workspace_ids.is_none()
+    }
+
+    /// The workspaces a scoped token is limited to; `None` when unscoped.
+    pub(super) fn workspace_scope(&self) -> Option<&HashSet<String>> {
+        self.workspace_ids.as_ref()
+    }
+
+    pub(super) fn allows_workspace(&self, workspace_id: &str) -> bool {
+        match self.workspace_ids.as_ref() {
+            Some(ids) => ids.contains(workspace_id),
+            None => true,
+        }
+    }
+
+    pub(super) fn authorize(&self, method: &str, params: &Value) -> Result<(), RpcError> {
+        let required = required_role_for_call(method, params);
+        if self.role < required {
+            return Err(RpcError::new(
+                RpcErrorCode::Forbidden,
+                format!(
+                    "`{method}` requires the {} role (token has {})",
+                    required.as_str(),
+                    self.role.as_str()
+                ),
+            )
+            .with_data(json!({
+                "method": method,
+                "role": self.role.as_str(),
+                "requiredRole": required.as_str(),
+            })));
+        }
+        let reach = match workspace_params(method) {
+            WorkspaceParams::Every => Some("covers every workspace"),
+            WorkspaceParams::Host => Some("runs a shell on the daemon host"),
+            _ => None,
+        };
+        if let Some(reach) = reach.filter(|_| !self.is_unscoped()) {
+            return Err(RpcError::new(
+                RpcErrorCode::Forbidden,
+                format!("`{method}` {reach} and needs a token without a workspace scope"),
+            )
+            .with_data(json!({ "method": method })));
+        }
+        if let Some(denied) = referenced_workspace_ids(method, params)
+            .into_iter()
+            .find(|workspace_id| !self.allows_workspace(workspace_id))
+        {
+            return Err(RpcError::new(
+                RpcErrorCode::Forbidden,
+                format!("workspace `{denied}` is outside this token's scope"),
+            )
+            .with_data(json!({
+                "method": method,
+                "workspaceId": denied,
+            })));
+        }
+        Ok(())
+    }
+
+    /// Authorizes `override_usage_budget` once the budget is resolved: a scoped token may
+    /// only override budgets of its own workspaces, never a global budget.
+    pub(super) fn authorize_budget(
+        &self,
+        budget_id: &str,
+        budget_workspace_id: Option<&str>,
+    ) -> Result<(), RpcError> {
+        let allowed = match budget_workspace_id {
+            Some(workspace_id) => self.allows_workspace(workspace_id),
+            None => self.is_unscoped(),
+        };
+        if allowed {
+            return Ok(());
+        }
+        let message = match budget_workspace_id {
+            Some(workspace_id) => format!("workspace `{workspace_id}` is outside this token's scope"),
+            None => format!(
+                "usage budget `{budget_id}` covers every workspace and needs a token without a workspace scope"
+            ),
+        };
+        Err(
+            RpcError::new(RpcErrorCode::Forbidden, message).with_data(json!({
+                "method": "override_usage_budget",
+                "budgetId": budget_id,
+                "workspaceId": budget_workspace_id,
+            })),
+        )
+    }
+
+    /// Hides workspaces outside the allowlist from listing results.
+    pub(super) fn filter_result(&self, method: &str, result: Value) -> Value {
+        if self.is_unscoped() {
+            return result;
+        }
+        match method {
+            "list_workspaces" => self.filter_entries(result, "id"),
+            // Global budgets count every workspace's usage, so only workspace budgets show.
+            "usage_budget_status" => self.filter_entries(result, "workspaceId"),
+            _ => result,
+        }
+    }
+
+    fn filter_entries(&self, result: Value, key: &str) -> Value {
+        match result {
+            Value::Array(entries) => Value::Array(
+                entries
+                    .into_iter()
+                    .filter(|entry| {
+                        entry
+                            .get(key)
+                            .and_then(Value::as_str)
+                            .is_some_and(|id| self.allows_workspace(id))
+                    })
+                    .collect(),
+            ),
+            other => other,
+        }
+    }
+}
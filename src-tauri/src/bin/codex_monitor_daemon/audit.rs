@@ -0,0 +1,272 @@
+use std::path::Path;
+
+use crate::daemon_tokens::DaemonRole;
+use crate::shared::rotating_jsonl::RotatingJsonlLog;
+
+use super::access::{referenced_workspace_ids, required_role, RpcCaller};
+use super::*;
+
+const AUDIT_LOG_NAME: &str = "audit";
+const AUDIT_LOG_MAX_BYTES: u64 = 16 * 1024 * 1024;
+const AUDIT_QUERY_DEFAULT_LIMIT: usize = 200;
+const AUDIT_QUERY_MAX_LIMIT: usize = 2000;
+
+/// Resizes change nothing worth auditing and would drown out everything else in the log.
+const UNAUDITED_METHODS: &[&str] = &["terminal_resize"];
+/// A busy terminal gets at most one `terminal_write` entry per interval.
+const TERMINAL_WRITE_SUMMARY_INTERVAL_MS: i64 = 60_000;
+
+/// Every method that can change state (anything above the observer role) is audited.
+fn is_audited_method(method: &str) -> bool {
+    required_role(method) != DaemonRole::Observer && !UNAUDITED_METHODS.contains(&method)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct AuditEntry {
+    pub(super) timestamp_ms: i64,
+    pub(super) method: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) workspace_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) terminal_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) token_name: Option<String>,
+    pub(super) role: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) peer: Option<String>,
+    /// `ok`, `error` or `denied`.
+    pub(super) outcome: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) error: Option<String>,
+    /// On `terminal_write` summaries: the calls, and the bytes they wrote, since the
+    /// previous entry for the same terminal and connection.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) writes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) bytes: Option<u64>,
+}
+
+impl AuditEntry {
+    pub(super) fn new(
+        caller: &RpcCaller,
+        method: &str,
+        params: &Value,
+        result: Result<(), &RpcError>,
+    ) -> Self {
+        let (outcome, error) = match result {
+            Ok(()) => ("ok", None),
+            Err(err) if err.is(RpcErrorCode::Forbidden) => ("denied", Some(err.message.clone())),
+            Err(err) => ("error", Some(err.message.clone())),
+        };
+        Self {
+            timestamp_ms: now_unix_ms(),
+            method: method.to_string(),
+            workspace_id: referenced_workspace_ids(method, params).into_iter().next(),
+            terminal_id: params
+                .get("terminalId")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            token_name: caller.access.token_name().map(str::to_string),
+            role: caller.access.role().as_str().to_string(),
+            peer: caller.peer.clone(),
+            outcome: outcome.to_string(),
+            error,
+            writes: None,
+            bytes: None,
+        }
+    }
+}
+
+/// Params of `daemon_audit_query`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct AuditQueryRequest {
+    since_ms: Option<i64>,
+    until_ms: Option<i64>,
+    workspace_id: Option<String>,
+    method: Option<String>,
+    limit: Option<u32>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(super) struct AuditQuery {
+    pub(super) since_ms: Option<i64>,
+    pub(super) until_ms: Option<i64>,
+    pub(super) workspace_id: Option<String>,
+    pub(super) method: Option<String>,
+    pub(super) limit: usize,
+}
+
+impl AuditQuery {
+    pub(super) fn from_request(request: AuditQueryRequest) -> Self {
+        Self {
+            since_ms: request.since_ms,
+            until_ms: request.until_ms,
+            workspace_id: request.workspace_id,
+            method: request.method,
+            limit: request
+                .limit
+                .map(|value| value as usize)
+                .unwrap_or(AUDIT_QUERY_DEFAULT_LIMIT)
+                .clamp(1, AUDIT_QUERY_MAX_LIMIT),
+        }
+    }
+
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        if matches!(self.since_ms, Some(since) if entry.timestamp_ms < since) {
+            return false;
+        }
+        if matches!(self.until_ms, Some(until) if entry.timestamp_ms > until) {
+            return false;
+        }
+        if let Some(workspace_id) = self.workspace_id.as_deref() {
+            if entry.workspace_id.as_deref() != Some(workspace_id) {
+                return false;
+            }
+        }
+        if let Some(method) = self.method.as_deref() {
+            if entry.method != method {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Successful writes to one terminal from one connection since its last audit entry.
+struct TerminalWriteTally {
+    /// The latest write, logged as the summary.
+    entry: AuditEntry,
+    writes: u64,
+    bytes: u64,
+    logged_at_ms: Option<i64>,
+}
+
+/// `audit.jsonl` in the daemon data dir, rotated past `AUDIT_LOG_MAX_BYTES`.
+pub(super) struct AuditLog {
+    log: RotatingJsonlLog,
+    /// Keyed by connection id, workspace id and terminal id.
+    terminal_writes: Mutex<HashMap<(u64, String, String), TerminalWriteTally>>,
+}
+
+impl AuditLog {
+    pub(super) fn new(data_dir: &Path) -> Self {
+        Self {
+            log: RotatingJsonlLog::new(data_dir, AUDIT_LOG_NAME, AUDIT_LOG_MAX_BYTES),
+            terminal_writes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Logs one call if its method is audited. Successful `terminal_write` calls are
+    /// summarised per terminal and connection: the first is logged, later ones at most once
+    /// per `TERMINAL_WRITE_SUMMARY_INTERVAL_MS` and when the terminal is closed. The written
+    /// data is never logged.
+    pub(super) async fn record(
+        &self,
+        caller: &RpcCaller,
+        method: &str,
+        params: &Value,
+        result: Result<(), &RpcError>,
+    ) -> Result<(), String> {
+        self.record_at(caller, method, params, result, now_unix_ms())
+            .await
+    }
+
+    pub(super) async fn record_at(
+        &self,
+        caller: &RpcCaller,
+        method: &str,
+        params: &Value,
+        result: Result<(), &RpcError>,
+        now_ms: i64,
+    ) -> Result<(), String> {
+        if !is_audited_method(method) {
+            return Ok(());
+        }
+        let mut entry = AuditEntry::new(caller, method, params, result);
+        entry.timestamp_ms = now_ms;
+        match method {
+            "terminal_write" if result.is_ok() => {
+                let bytes = params
+                    .get("data")
+                    .and_then(Value::as_str)
+                    .map_or(0, |data| data.len() as u64);
+                let key = (
+                    caller.connection_id,
+                    entry.workspace_id.clone().unwrap_or_default(),
+                    entry.terminal_id.clone().unwrap_or_default(),
+                );
+                let mut tallies = self.terminal_writes.lock().await;
+                let tally = tallies.entry(key).or_insert_with(|| TerminalWriteTally {
+                    entry: entry.clone(),
+                    writes: 0,
+                    bytes: 0,
+                    logged_at_ms: None,
+                });
+                tally.entry = entry;
+                tally.writes += 1;
+                tally.bytes += bytes;
+                if tally.logged_at_ms.is_some_and(|logged_at| {
+                    now_ms - logged_at < TERMINAL_WRITE_SUMMARY_INTERVAL_MS
+                }) {
+                    return Ok(());
+                }
+                tally.logged_at_ms = Some(now_ms);
+                let summary = take_summary(tally, now_ms);
+                drop(tallies);
+                self.log.append(&summary).await
+            }
+            "terminal_close" => {
+                let pending: Vec<AuditEntry> = {
+                    let mut tallies = self.terminal_writes.lock().await;
+                    let closed: Vec<_> = tallies
+                        .keys()
+                        .filter(|(_, workspace_id, terminal_id)| {
+                            entry.workspace_id.as_deref() == Some(workspace_id.as_str())
+                                && entry.terminal_id.as_deref() == Some(terminal_id.as_str())
+                        })
+                        .cloned()
+                        .collect();
+                    closed
+                        .into_iter()
+                        .filter_map(|key| tallies.remove(&key))
+                        .filter(|tally| tally.writes > 0)
+                        .map(|mut tally| take_summary(&mut tally, now_ms))
+                        .collect()
+                };
+                for summary in &pending {
+                    self.log.append(summary).await?;
+                }
+                self.log.append(&entry).await
+            }
+            _ => self.log.append(&entry).await,
+        }
+    }
+
+    /// Matching entries in chronological order, keeping the most recent `query.limit`.
+    pub(super) async fn query(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>, String> {
+        let mut entries = self
+            .log
+            .read(|entry: &AuditEntry| query.matches(entry))
+            .await?;
+        let skip = entries.len().saturating_sub(query.limit);
+        Ok(entries.split_off(skip))
+    }
+}
+
+/// The tally's latest write, carrying the counts since the previous entry, which restart.
+fn take_summary(tally: &mut TerminalWriteTally, now_ms: i64) -> AuditEntry {
+    let mut summary = tally.entry.clone();
+    summary.timestamp_ms = now_ms;
+    summary.writes = Some(std::mem::take(&mut tally.writes));
+    summary.bytes = Some(std::mem::take(&mut tally.bytes));
+    summary
+}
+
+fn now_unix_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0)
+}
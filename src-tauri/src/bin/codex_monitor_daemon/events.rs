@@ -0,0 +1,144 @@
+use std::collections::VecDeque;
+
+use super::*;
+
+const EVENT_CHANNEL_CAPACITY: usize = 2048;
+const REPLAY_BUFFER_PER_WORKSPACE: usize = 1024;
+
+#[derive(Clone)]
+pub(super) struct SequencedEvent {
+    pub(super) seq: u64,
+    pub(super) event: DaemonEvent,
+}
+
+impl DaemonEvent {
+    pub(super) fn workspace_id(&self) -> &str {
+        match self {
+            DaemonEvent::AppServer(event) => &event.workspace_id,
+            DaemonEvent::TerminalOutput(event) => &event.workspace_id,
+            DaemonEvent::TerminalExit(event) => &event.workspace_id,
+            // Usage spans workspaces, so only unscoped tokens receive it.
+            DaemonEvent::LocalUsageUpdated(_) => "",
+            DaemonEvent::UsageBudgetAlert(event) => event.workspace_id.as_deref().unwrap_or(""),
+        }
+    }
+
+    /// Raw PTY output is too chatty to buffer without evicting agent events, and a usage
+    /// snapshot is superseded by the next one.
+    fn is_replayable(&self) -> bool {
+        !matches!(
+            self,
+            DaemonEvent::TerminalOutput(_) | DaemonEvent::LocalUsageUpdated(_)
+        )
+    }
+}
+
+#[derive(Default)]
+struct EventHubInner {
+    last_seq: u64,
+    buffers: HashMap<String, VecDeque<SequencedEvent>>,
+    /// Per workspace, the newest sequence dropped from its full buffer.
+    evicted_through: HashMap<String, u64>,
+}
+
+/// Fans events out to connected clients and keeps a bounded per-workspace history so
+/// reconnecting clients can catch up on what they missed.
+pub(super) struct EventHub {
+    stream_id: String,
+    tx: broadcast::Sender<SequencedEvent>,
+    inner: std::sync::Mutex<EventHubInner>,
+}
+
+impl EventHub {
+    pub(super) fn new() -> Self {
+        let (tx, _rx) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self {
+            stream_id: uuid::Uuid::new_v4().to_string(),
+            tx,
+            inner: std::sync::Mutex::new(EventHubInner::default()),
+        }
+    }
+
+    /// Identifies this daemon process; sequence numbers are only comparable within one stream.
+    pub(super) fn stream_id(&self) -> &str {
+        &self.stream_id
+    }
+
+    pub(super) fn last_seq(&self) -> u64 {
+        self.lock_inner().last_seq
+    }
+
+    pub(super) fn publish(&self, event: DaemonEvent) {
+        let mut inner = self.lock_inner();
+        inner.last_seq += 1;
+        let sequenced = SequencedEvent {
+            seq: inner.last_seq,
+            event,
+        };
+        if sequenced.event.is_replayable() {
+            let workspace_id = sequenced.event.workspace_id().to_string();
+            let buffer = inner.buffers.entry(workspace_id.clone()).or_default();
+            let evicted = if buffer.len() >= REPLAY_BUFFER_PER_WORKSPACE {
+                buffer.pop_front()
+            } else {
+                None
+            };
+            buffer.push_back(sequenced.clone());
+            if let Some(evicted) = evicted {
+                inner.evicted_through.insert(workspace_id, evicted.seq);
+            }
+        }
+        // Sending while holding the lock keeps the buffer and the channel consistent for
+        // `subscribe_after`: every event is either in the snapshot or in the receiver.
+        let _ = self.tx.send(sequenced);
+    }
+
+    /// Returns buffered events with `seq > after_seq` of the workspaces accepted by
+    /// `include` (oldest first) and a receiver for everything published afterwards. Also
+    /// reports whether some of those events were already evicted from their buffer, in
+    /// which case the replay is incomplete.
+    pub(super) fn subscribe_after(
+        &self,
+        after_seq: u64,
+        include: impl Fn(&str) -> bool,
+    ) -> (
+        Vec<SequencedEvent>,
+        broadcast::Receiver<SequencedEvent>,
+        bool,
+    ) {
+        let inner = self.lock_inner();
+        let rx = self.tx.subscribe();
+        let evicted = inner
+            .evicted_through
+            .iter()
+            .any(|(workspace_id, seq)| *seq > after_seq && include(workspace_id));
+        let missed = buffered_after(&inner, after_seq)
+            .into_iter()
+            .filter(|event| include(event.event.workspace_id()))
+            .collect();
+        (missed, rx, evicted)
+    }
+
+    /// A receiver for new events, with the sequence of the last event it will not see.
+    pub(super) fn subscribe(&self) -> (u64, broadcast::Receiver<SequencedEvent>) {
+        let inner = self.lock_inner();
+        (inner.last_seq, self.tx.subscribe())
+    }
+
+    fn lock_inner(&self) -> std::sync::MutexGuard<'_, EventHubInner> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn buffered_after(inner: &EventHubInner, after_seq: u64) -> Vec<SequencedEvent> {
+    let mut missed: Vec<SequencedEvent> = inner
+        .buffers
+        .values()
+        .flat_map(|buffer| buffer.iter().filter(|event| event.seq > after_seq))
+        .cloned()
+        .collect();
+    missed.sort_by_key(|event| event.seq);
+    missed
+}
@@ -0,0 +1,211 @@
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+
+use super::access::ClientAccess;
+use super::*;
+
+/// Requests are a request line plus headers; anything larger is not a scraper.
+const MAX_REQUEST_HEAD_BYTES: usize = 16 * 1024;
+const REQUEST_HEAD_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    query: Vec<(String, String)>,
+    bearer_token: Option<String>,
+}
+
+struct HttpResponse {
+    status: u16,
+    reason: &'static str,
+    content_type: String,
+    extra_headers: Vec<(&'static str, String)>,
+    body: String,
+}
+
+impl HttpResponse {
+    fn text(status: u16, reason: &'static str, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            reason,
+            content_type: "text/plain; charset=utf-8".to_string(),
+            extra_headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    fn into_bytes(self, include_body: bool) -> Vec<u8> {
+        let mut head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+            self.status,
+            self.reason,
+            self.content_type,
+            self.body.len()
+        );
+        for (name, value) in &self.extra_headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        head.push_str("\r\n");
+        let mut bytes = head.into_bytes();
+        if include_body {
+            bytes.extend_from_slice(self.body.as_bytes());
+        }
+        bytes
+    }
+}
+
+/// Serves one plain HTTP request on the `--metrics-listen` listener: `GET /metrics` returns
+/// Prometheus text and `GET /usage.csv` the CSV export, both with an optional `?days=N`.
+/// Unless the daemon runs with `--insecure-no-auth`, callers send the shared or a named
+/// token as `Authorization: Bearer <token>`.
+pub(super) async fn handle_http_client<S>(
+    stream: S,
+    peer: Option<SocketAddr>,
+    config: Arc<DaemonConfig>,
+    state: Arc<DaemonState>,
+) where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut reader = BufReader::new(stream);
+    let request =
+        match tokio::time::timeout(REQUEST_HEAD_TIMEOUT, read_request_head(&mut reader)).await {
+            Ok(Ok(request)) => request,
+            Ok(Err(response)) => {
+                let mut stream = reader.into_inner();
+                let _ = stream.write_all(&response.into_bytes(true)).await;
+                let _ = stream.shutdown().await;
+                return;
+            }
+            Err(_) => return,
+        };
+    let include_body = request.method != "HEAD";
+    let response = respond(&request, &config, &state).await;
+    if response.status >= 500 {
+        let peer = peer.map_or_else(|| "unknown".to_string(), |peer| peer.to_string());
+        eprintln!(
+            "daemon: {} {} from {peer} failed: {}",
+            request.method, request.path, response.body
+        );
+    }
+    let mut stream = reader.into_inner();
+    let _ = stream.write_all(&response.into_bytes(include_body)).await;
+    let _ = stream.shutdown().await;
+}
+
+async fn read_request_head<R>(reader: &mut R) -> Result<HttpRequest, HttpResponse>
+where
+    R: AsyncBufReadExt + Unpin,
+{
+    let bad_request = || HttpResponse::text(400, "Bad Request", "malformed request\n");
+    let mut total = 0;
+    let mut lines = Vec::new();
+    loop {
+        let mut line = String::new();
+        let read = reader
+            .read_line(&mut line)
+            .await
+            .map_err(|_| bad_request())?;
+        total += read;
+        if total > MAX_REQUEST_HEAD_BYTES {
+            return Err(HttpResponse::text(
+                431,
+                "Request Header Fields Too Large",
+                "request head too large\n",
+            ));
+        }
+        let line = line.trim_end_matches(['\r', '\n']).to_string();
+        if read == 0 || line.is_empty() {
+            break;
+        }
+        lines.push(line);
+    }
+
+    let mut request_line = lines.first().ok_or_else(bad_request)?.split_whitespace();
+    let method = request_line.next().ok_or_else(bad_request)?.to_string();
+    let target = request_line.next().ok_or_else(bad_request)?;
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (key.to_string(), value.to_string())
+        })
+        .collect();
+    let bearer_token = lines.iter().skip(1).find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        if !name.trim().eq_ignore_ascii_case("authorization") {
+            return None;
+        }
+        let (scheme, token) = value.trim().split_once(' ')?;
+        scheme
+            .eq_ignore_ascii_case("bearer")
+            .then(|| token.trim().to_string())
+    });
+    Ok(HttpRequest {
+        method,
+        path: path.to_string(),
+        query,
+        bearer_token,
+    })
+}
+
+async fn respond(
+    request: &HttpRequest,
+    config: &DaemonConfig,
+    state: &DaemonState,
+) -> HttpResponse {
+    let format = match request.path.as_str() {
+        "/metrics" => LocalUsageExportFormat::Prometheus,
+        "/usage.csv" => LocalUsageExportFormat::Csv,
+        _ => return HttpResponse::text(404, "Not Found", "not found\n"),
+    };
+    if request.method != "GET" && request.method != "HEAD" {
+        let mut response = HttpResponse::text(405, "Method Not Allowed", "use GET\n");
+        response
+            .extra_headers
+            .push(("Allow", "GET, HEAD".to_string()));
+        return response;
+    }
+
+    if !config.insecure_no_auth {
+        let access = request
+            .bearer_token
+            .as_deref()
+            .and_then(|token| ClientAccess::authenticate(config, token));
+        let Some(access) = access else {
+            let mut response =
+                HttpResponse::text(401, "Unauthorized", "missing or invalid token\n");
+            response
+                .extra_headers
+                .push(("WWW-Authenticate", "Bearer".to_string()));
+            return response;
+        };
+        if let Err(err) = access.authorize("local_usage_export", &json!({})) {
+            return HttpResponse::text(403, "Forbidden", format!("{}\n", err.message));
+        }
+    }
+
+    let days = match request.query.iter().find(|(key, _)| key == "days") {
+        Some((_, value)) => match value.parse::<u32>() {
+            Ok(days) => Some(days),
+            Err(_) => return HttpResponse::text(400, "Bad Request", "`days` must be a number\n"),
+        },
+        None => None,
+    };
+    match state.local_usage_export(format, days, None).await {
+        Ok(export) => HttpResponse {
+            status: 200,
+            reason: "OK",
+            content_type: export.mime_type,
+            extra_headers: match format {
+                LocalUsageExportFormat::Csv => vec![(
+                    "Content-Disposition",
+                    format!("attachment; filename=\"{}\"", export.file_name),
+                )],
+                LocalUsageExportFormat::Prometheus => Vec::new(),
+            },
+            body: export.content,
+        },
+        Err(err) => HttpResponse::text(500, "Internal Server Error", format!("{err}\n")),
+    }
+}
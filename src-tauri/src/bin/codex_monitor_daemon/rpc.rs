@@ -1,27 +1,51 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::de::DeserializeOwned;
+
+use super::access::RpcCaller;
 use super::*;
 
+/// Parses `params` into a handler's request type, returning the error from the handler.
+macro_rules! parse_request_or_err {
+    ($params:expr, $ty:ty) => {
+        match parse_request::<$ty>($params) {
+            Ok(value) => value,
+            Err(err) => return Some(Err(err)),
+        }
+    };
+}
+
 #[path = "rpc/codex.rs"]
 mod codex;
 #[path = "rpc/daemon.rs"]
 mod daemon;
+#[path = "rpc/describe.rs"]
+mod describe;
 #[path = "rpc/dispatcher.rs"]
 mod dispatcher;
 #[path = "rpc/git.rs"]
 mod git;
 #[path = "rpc/prompts.rs"]
 mod prompts;
+#[path = "rpc/registry.rs"]
+pub(super) mod registry;
+#[path = "rpc/terminal.rs"]
+mod terminal;
 #[path = "rpc/workspace.rs"]
 mod workspace;
 
-pub(super) fn build_error_response(id: Option<u64>, message: &str) -> Option<String> {
+pub(super) fn build_error_response(id: Option<u64>, error: &RpcError) -> Option<String> {
     let id = id?;
     Some(
         serde_json::to_string(&json!({
+            "jsonrpc": "2.0",
             "id": id,
-            "error": { "message": message }
+            "error": error.to_value()
         }))
         .unwrap_or_else(|_| {
-            "{\"id\":0,\"error\":{\"message\":\"serialization failed\"}}".to_string()
+            "{\"id\":0,\"error\":{\"code\":-32603,\"message\":\"serialization failed\"}}"
+                .to_string()
         }),
     )
 }
@@ -29,25 +53,41 @@ pub(super) fn build_error_response(id: Option<u64>, message: &str) -> Option<Str
 pub(super) fn build_result_response(id: Option<u64>, result: Value) -> Option<String> {
     let id = id?;
     Some(
-        serde_json::to_string(&json!({ "id": id, "result": result })).unwrap_or_else(|_| {
-            "{\"id\":0,\"error\":{\"message\":\"serialization failed\"}}".to_string()
-        }),
+        serde_json::to_string(&json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+            .unwrap_or_else(|_| {
+                "{\"id\":0,\"error\":{\"code\":-32603,\"message\":\"serialization failed\"}}"
+                    .to_string()
+            }),
     )
 }
 
-fn build_event_notification(event: DaemonEvent) -> Option<String> {
-    let payload = match event {
+fn build_event_notification(event: SequencedEvent) -> Option<String> {
+    let seq = event.seq;
+    let payload = match event.event {
         DaemonEvent::AppServer(payload) => json!({
             "method": "app-server-event",
             "params": payload,
+            "seq": seq,
         }),
         DaemonEvent::TerminalOutput(payload) => json!({
             "method": "terminal-output",
             "params": payload,
+            "seq": seq,
         }),
         DaemonEvent::TerminalExit(payload) => json!({
             "method": "terminal-exit",
             "params": payload,
+            "seq": seq,
+        }),
+        DaemonEvent::LocalUsageUpdated(payload) => json!({
+            "method": "local-usage-updated",
+            "params": payload,
+            "seq": seq,
+        }),
+        DaemonEvent::UsageBudgetAlert(payload) => json!({
+            "method": "usage-budget-alert",
+            "params": payload,
+            "seq": seq,
         }),
     };
     serde_json::to_string(&payload).ok()
@@ -64,15 +104,34 @@ pub(super) fn parse_auth_token(params: &Value) -> Option<String> {
     }
 }
 
-pub(super) fn parse_string(value: &Value, key: &str) -> Result<String, String> {
-    match value {
-        Value::Object(map) => map
-            .get(key)
-            .and_then(|value| value.as_str())
-            .map(|value| value.to_string())
-            .ok_or_else(|| format!("missing or invalid `{key}`")),
-        _ => Err(format!("missing `{key}`")),
+/// Compares tokens without short-circuiting on the first mismatched byte.
+pub(super) fn auth_token_matches(expected: &str, provided: &str) -> bool {
+    let expected = expected.as_bytes();
+    let provided = provided.as_bytes();
+    let mut diff = expected.len() ^ provided.len();
+    for (index, byte) in expected.iter().enumerate() {
+        let other = provided.get(index).copied().unwrap_or(0);
+        diff |= usize::from(byte ^ other);
     }
+    diff == 0
+}
+
+/// Deserializes request params; a missing `params` counts as `{}`. Missing fields are
+/// reported as "missing or invalid `field`".
+pub(super) fn parse_request<T: DeserializeOwned>(params: &Value) -> Result<T, RpcError> {
+    let params = if params.is_null() {
+        json!({})
+    } else {
+        params.clone()
+    };
+    serde_json::from_value(params).map_err(|err| {
+        let message = err.to_string();
+        let message = match message.strip_prefix("missing field ") {
+            Some(key) => format!("missing or invalid {key}"),
+            None => message,
+        };
+        RpcError::new(RpcErrorCode::InvalidParams, message)
+    })
 }
 
 pub(super) fn parse_optional_string(value: &Value, key: &str) -> Option<String> {
@@ -85,72 +144,99 @@ pub(super) fn parse_optional_string(value: &Value, key: &str) -> Option<String>
     }
 }
 
-pub(super) fn parse_optional_u32(value: &Value, key: &str) -> Option<u32> {
-    match value {
-        Value::Object(map) => map.get(key).and_then(|value| value.as_u64()).and_then(|v| {
-            if v > u32::MAX as u64 {
-                None
-            } else {
-                Some(v as u32)
-            }
-        }),
-        _ => None,
-    }
-}
-
-pub(super) fn parse_optional_bool(value: &Value, key: &str) -> Option<bool> {
-    match value {
-        Value::Object(map) => map.get(key).and_then(|value| value.as_bool()),
-        _ => None,
-    }
+#[cfg(test)]
+pub(super) async fn handle_rpc_request(
+    state: &DaemonState,
+    method: &str,
+    params: Value,
+    client_version: String,
+) -> Result<Value, String> {
+    dispatcher::dispatch_rpc_request(state, method, &params, &client_version).await
 }
 
-pub(super) fn parse_optional_string_array(value: &Value, key: &str) -> Option<Vec<String>> {
-    match value {
-        Value::Object(map) => map
-            .get(key)
-            .and_then(|value| value.as_array())
-            .map(|items| {
-                items
-                    .iter()
-                    .filter_map(|item| item.as_str().map(|value| value.to_string()))
-                    .collect::<Vec<_>>()
-            }),
-        _ => None,
-    }
+/// Like [`handle_rpc_request`], but limited to what the caller's token allows and audited.
+pub(super) async fn handle_client_rpc_request(
+    state: &DaemonState,
+    caller: &RpcCaller,
+    method: &str,
+    params: Value,
+) -> Result<Value, RpcError> {
+    dispatcher::dispatch_client_rpc_request(state, caller, method, &params).await
 }
 
-pub(super) fn parse_string_array(value: &Value, key: &str) -> Result<Vec<String>, String> {
-    parse_optional_string_array(value, key).ok_or_else(|| format!("missing `{key}`"))
+/// Sequence numbers already delivered on one connection, so a resubscription can skip them.
+#[derive(Default)]
+pub(super) struct ForwardedEvents {
+    first_seq: AtomicU64,
+    last_seq: AtomicU64,
 }
 
-pub(super) fn parse_optional_value(value: &Value, key: &str) -> Option<Value> {
-    match value {
-        Value::Object(map) => map.get(key).cloned(),
-        _ => None,
+impl ForwardedEvents {
+    fn record(&self, seq: u64) {
+        let _ = self
+            .first_seq
+            .compare_exchange(0, seq, Ordering::SeqCst, Ordering::SeqCst);
+        self.last_seq.store(seq, Ordering::SeqCst);
     }
-}
 
-pub(super) async fn handle_rpc_request(
-    state: &DaemonState,
-    method: &str,
-    params: Value,
-    client_version: String,
-) -> Result<Value, String> {
-    dispatcher::dispatch_rpc_request(state, method, &params, &client_version).await
+    pub(super) fn contains(&self, seq: u64) -> bool {
+        let first = self.first_seq.load(Ordering::SeqCst);
+        first != 0 && first <= seq && seq <= self.last_seq.load(Ordering::SeqCst)
+    }
 }
 
+/// Forwards `replay`, then live events, to one connection. A receiver that falls behind
+/// the channel is caught up from the hub's replay buffer; if the buffer no longer covers
+/// the gap, the client gets an `event-gap` notification so it can reload its state.
 pub(super) async fn forward_events(
-    mut rx: broadcast::Receiver<DaemonEvent>,
+    events: Arc<EventHub>,
+    after_seq: u64,
+    replay: Vec<SequencedEvent>,
+    mut rx: broadcast::Receiver<SequencedEvent>,
     out_tx_events: mpsc::UnboundedSender<String>,
+    forwarded: Arc<ForwardedEvents>,
+    caller: Arc<RpcCaller>,
 ) {
+    let access = &caller.access;
+    // Last sequence handled (sent or filtered out) on this connection.
+    let mut last_seq = after_seq;
+    let mut pending: VecDeque<SequencedEvent> = replay.into();
+
     loop {
-        let event = match rx.recv().await {
-            Ok(event) => event,
-            Err(broadcast::error::RecvError::Lagged(_)) => continue,
-            Err(broadcast::error::RecvError::Closed) => break,
+        let event = match pending.pop_front() {
+            Some(event) => event,
+            None => match rx.recv().await {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(_)) => {
+                    let (missed, resubscribed, evicted) = events
+                        .subscribe_after(last_seq, |workspace_id| {
+                            access.allows_workspace(workspace_id)
+                        });
+                    rx = resubscribed;
+                    if evicted {
+                        let Some(payload) = build_event_gap_notification(last_seq, "evicted")
+                        else {
+                            continue;
+                        };
+                        if out_tx_events.send(payload).is_err() {
+                            return;
+                        }
+                    }
+                    pending.extend(missed);
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
         };
+        if event.seq <= last_seq {
+            continue;
+        }
+        last_seq = event.seq;
 
+        if !access.allows_workspace(event.event.workspace_id()) {
+            continue;
+        }
+        let seq = event.seq;
         let Some(payload) = build_event_notification(event) else {
             continue;
         };
@@ -158,26 +244,37 @@ pub(super) async fn forward_events(
         if out_tx_events.send(payload).is_err() {
             break;
         }
+        forwarded.record(seq);
     }
 }
 
+/// Tells a client that events after `after_seq` were lost: `reason` is `evicted` when the
+/// replay buffer dropped them, `restarted` when they belong to a previous daemon process.
+pub(super) fn build_event_gap_notification(after_seq: u64, reason: &str) -> Option<String> {
+    serde_json::to_string(&json!({
+        "method": "event-gap",
+        "params": { "afterSeq": after_seq, "reason": reason },
+    }))
+    .ok()
+}
+
 pub(super) fn spawn_rpc_response_task(
     state: Arc<DaemonState>,
     out_tx: mpsc::UnboundedSender<String>,
+    caller: Arc<RpcCaller>,
     id: Option<u64>,
     method: String,
     params: Value,
-    client_version: String,
     request_limiter: Arc<Semaphore>,
 ) {
     tokio::spawn(async move {
         let Ok(_permit) = request_limiter.acquire_owned().await else {
             return;
         };
-        let result = handle_rpc_request(&state, &method, params, client_version).await;
+        let result = handle_client_rpc_request(&state, &caller, &method, params).await;
         let response = match result {
             Ok(result) => build_result_response(id, result),
-            Err(message) => build_error_response(id, &message),
+            Err(error) => build_error_response(id, &error),
         };
         if let Some(response) = response {
             let _ = out_tx.send(response);
@@ -1,495 +1,617 @@
 use super::*;
-use serde::de::DeserializeOwned;
+use crate::rules::PrefixRule;
+use crate::shared::thread_export_core::{ThreadBundle, ThreadExportFormat};
+use crate::shared::workspace_rpc::WorkspaceIdRequest;
+use crate::types::ApprovalPolicyRule;
 
-fn parse_input<T: DeserializeOwned>(params: &Value) -> Result<T, String> {
-    let input_value = params
-        .as_object()
-        .and_then(|map| map.get("input"))
-        .cloned()
-        .ok_or_else(|| "missing `input`".to_string())?;
-    serde_json::from_value(input_value).map_err(|err| err.to_string())
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ThreadRequest {
+    workspace_id: String,
+    thread_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ExportThreadRequest {
+    workspace_id: String,
+    thread_id: String,
+    format: ThreadExportFormat,
+    /// Replace secret-looking tokens (API keys, bearer tokens, private keys) with `[REDACTED]`.
+    redact: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ImportThreadRequest {
+    workspace_id: String,
+    /// A JSON bundle as returned by `export_thread` with `format: "json"`.
+    bundle: Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ListThreadsRequest {
+    workspace_id: String,
+    cursor: Option<String>,
+    limit: Option<u32>,
+    sort_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct PageRequest {
+    workspace_id: String,
+    cursor: Option<String>,
+    limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct SetThreadNameRequest {
+    workspace_id: String,
+    thread_id: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct SendUserMessageRequest {
+    workspace_id: String,
+    thread_id: String,
+    text: String,
+    model: Option<String>,
+    effort: Option<String>,
+    access_mode: Option<String>,
+    images: Option<Vec<String>>,
+    app_mentions: Option<Vec<Value>>,
+    collaboration_mode: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct TurnRequest {
+    workspace_id: String,
+    thread_id: String,
+    turn_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct TurnSteerRequest {
+    workspace_id: String,
+    thread_id: String,
+    turn_id: String,
+    text: String,
+    images: Option<Vec<String>>,
+    app_mentions: Option<Vec<Value>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct StartReviewRequest {
+    workspace_id: String,
+    thread_id: String,
+    target: Value,
+    delivery: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct FeatureFlagRequest {
+    feature_key: String,
+    enabled: bool,
+}
+
+/// Agent settings calls wrap their payload in `input`.
+#[derive(Debug, Deserialize)]
+pub(super) struct InputRequest<T> {
+    input: T,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct AgentNameRequest {
+    agent_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct AgentConfigRequest {
+    agent_name: String,
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct AppsListRequest {
+    workspace_id: String,
+    cursor: Option<String>,
+    limit: Option<u32>,
+    thread_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ServerRequestResponse {
+    workspace_id: String,
+    /// The app-server request id, a number or a string.
+    request_id: Value,
+    result: Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ApprovalRuleRequest {
+    workspace_id: String,
+    command: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct CodexRuleRequest {
+    workspace_id: String,
+    /// Rule id from `list_codex_rules`; rejected once the rule changed on disk.
+    rule_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct UpdateCodexRuleRequest {
+    workspace_id: String,
+    rule_id: String,
+    /// A `prefix_rule` shaped like the `rule` field of `list_codex_rules` entries.
+    rule: Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ExplainApprovalPolicyRequest {
+    workspace_id: String,
+    /// The app-server request method, e.g. `item/commandExecution/requestApproval`.
+    method: String,
+    #[serde(default)]
+    params: Value,
+    access_mode: Option<String>,
+    /// Rules to try instead of the workspace's saved ones.
+    rules: Option<Vec<ApprovalPolicyRule>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct CodexDoctorRequest {
+    codex_bin: Option<String>,
+    codex_args: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct RunMetadataRequest {
+    workspace_id: String,
+    prompt: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct AgentDescriptionRequest {
+    workspace_id: String,
+    description: String,
+}
+
+fn serialize_value<T: Serialize>(value: T) -> Result<Value, String> {
+    serde_json::to_value(value).map_err(|err| err.to_string())
 }
 
 pub(super) async fn try_handle(
     state: &DaemonState,
     method: &str,
     params: &Value,
-) -> Option<Result<Value, String>> {
+) -> Option<Result<Value, RpcError>> {
     match method {
         "get_codex_config_path" => {
             let path = match settings_core::get_codex_config_path_core() {
                 Ok(value) => value,
-                Err(err) => return Some(Err(err)),
+                Err(err) => return Some(Err(err.into())),
             };
             Some(Ok(Value::String(path)))
         }
         "get_config_model" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.get_config_model(workspace_id).await)
+            let request = parse_request_or_err!(params, WorkspaceIdRequest);
+            Some(
+                state
+                    .get_config_model(request.workspace_id)
+                    .await
+                    .map_err(RpcError::from),
+            )
         }
         "start_thread" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.start_thread(workspace_id).await)
+            let request = parse_request_or_err!(params, WorkspaceIdRequest);
+            Some(state.start_thread(request.workspace_id).await)
         }
         "resume_thread" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let thread_id = match parse_string(params, "threadId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.resume_thread(workspace_id, thread_id).await)
+            let request = parse_request_or_err!(params, ThreadRequest);
+            Some(
+                state
+                    .resume_thread(request.workspace_id, request.thread_id)
+                    .await,
+            )
+        }
+        "export_thread" => {
+            let request = parse_request_or_err!(params, ExportThreadRequest);
+            Some(
+                state
+                    .export_thread(
+                        request.workspace_id,
+                        request.thread_id,
+                        request.format,
+                        request.redact.unwrap_or(false),
+                    )
+                    .await
+                    .map_err(RpcError::from),
+            )
+        }
+        "import_thread" => {
+            let request = parse_request_or_err!(params, ImportThreadRequest);
+            let bundle = parse_request_or_err!(&request.bundle, ThreadBundle);
+            Some(
+                state
+                    .import_thread(request.workspace_id, bundle)
+                    .await
+                    .map_err(RpcError::from),
+            )
         }
         "thread_live_subscribe" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let thread_id = match parse_string(params, "threadId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.thread_live_subscribe(workspace_id, thread_id).await)
+            let request = parse_request_or_err!(params, ThreadRequest);
+            Some(
+                state
+                    .thread_live_subscribe(request.workspace_id, request.thread_id)
+                    .await,
+            )
         }
         "thread_live_unsubscribe" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let thread_id = match parse_string(params, "threadId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.thread_live_unsubscribe(workspace_id, thread_id).await)
+            let request = parse_request_or_err!(params, ThreadRequest);
+            Some(
+                state
+                    .thread_live_unsubscribe(request.workspace_id, request.thread_id)
+                    .await,
+            )
         }
         "fork_thread" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let thread_id = match parse_string(params, "threadId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.fork_thread(workspace_id, thread_id).await)
+            let request = parse_request_or_err!(params, ThreadRequest);
+            Some(
+                state
+                    .fork_thread(request.workspace_id, request.thread_id)
+                    .await,
+            )
         }
         "list_threads" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let cursor = parse_optional_string(params, "cursor");
-            let limit = parse_optional_u32(params, "limit");
-            let sort_key = parse_optional_string(params, "sortKey");
+            let request = parse_request_or_err!(params, ListThreadsRequest);
             Some(
                 state
-                    .list_threads(workspace_id, cursor, limit, sort_key)
+                    .list_threads(
+                        request.workspace_id,
+                        request.cursor,
+                        request.limit,
+                        request.sort_key,
+                    )
                     .await,
             )
         }
         "list_mcp_server_status" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let cursor = parse_optional_string(params, "cursor");
-            let limit = parse_optional_u32(params, "limit");
+            let request = parse_request_or_err!(params, PageRequest);
             Some(
                 state
-                    .list_mcp_server_status(workspace_id, cursor, limit)
+                    .list_mcp_server_status(request.workspace_id, request.cursor, request.limit)
                     .await,
             )
         }
         "archive_thread" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let thread_id = match parse_string(params, "threadId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.archive_thread(workspace_id, thread_id).await)
+            let request = parse_request_or_err!(params, ThreadRequest);
+            Some(
+                state
+                    .archive_thread(request.workspace_id, request.thread_id)
+                    .await,
+            )
         }
         "compact_thread" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let thread_id = match parse_string(params, "threadId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.compact_thread(workspace_id, thread_id).await)
+            let request = parse_request_or_err!(params, ThreadRequest);
+            Some(
+                state
+                    .compact_thread(request.workspace_id, request.thread_id)
+                    .await,
+            )
         }
         "set_thread_name" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let thread_id = match parse_string(params, "threadId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let name = match parse_string(params, "name") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.set_thread_name(workspace_id, thread_id, name).await)
+            let request = parse_request_or_err!(params, SetThreadNameRequest);
+            Some(
+                state
+                    .set_thread_name(request.workspace_id, request.thread_id, request.name)
+                    .await,
+            )
         }
         "send_user_message" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let thread_id = match parse_string(params, "threadId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let text = match parse_string(params, "text") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let model = parse_optional_string(params, "model");
-            let effort = parse_optional_string(params, "effort");
-            let access_mode = parse_optional_string(params, "accessMode");
-            let images = parse_optional_string_array(params, "images");
-            let app_mentions = parse_optional_value(params, "appMentions")
-                .and_then(|value| value.as_array().cloned());
-            let collaboration_mode = parse_optional_value(params, "collaborationMode");
+            let request = parse_request_or_err!(params, SendUserMessageRequest);
             Some(
                 state
                     .send_user_message(
-                        workspace_id,
-                        thread_id,
-                        text,
-                        model,
-                        effort,
-                        access_mode,
-                        images,
-                        app_mentions,
-                        collaboration_mode,
+                        request.workspace_id,
+                        request.thread_id,
+                        request.text,
+                        request.model,
+                        request.effort,
+                        request.access_mode,
+                        request.images,
+                        request.app_mentions,
+                        request.collaboration_mode,
                     )
                     .await,
             )
         }
         "turn_interrupt" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let thread_id = match parse_string(params, "threadId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let turn_id = match parse_string(params, "turnId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.turn_interrupt(workspace_id, thread_id, turn_id).await)
+            let request = parse_request_or_err!(params, TurnRequest);
+            Some(
+                state
+                    .turn_interrupt(request.workspace_id, request.thread_id, request.turn_id)
+                    .await,
+            )
         }
         "turn_steer" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let thread_id = match parse_string(params, "threadId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let turn_id = match parse_string(params, "turnId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let text = match parse_string(params, "text") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let images = parse_optional_string_array(params, "images");
-            let app_mentions = parse_optional_value(params, "appMentions")
-                .and_then(|value| value.as_array().cloned());
+            let request = parse_request_or_err!(params, TurnSteerRequest);
             Some(
                 state
-                    .turn_steer(workspace_id, thread_id, turn_id, text, images, app_mentions)
+                    .turn_steer(
+                        request.workspace_id,
+                        request.thread_id,
+                        request.turn_id,
+                        request.text,
+                        request.images,
+                        request.app_mentions,
+                    )
                     .await,
             )
         }
         "start_review" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let thread_id = match parse_string(params, "threadId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let target = match params
-                .as_object()
-                .and_then(|map| map.get("target"))
-                .cloned()
-                .ok_or("missing `target`")
-            {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err.to_string())),
-            };
-            let delivery = parse_optional_string(params, "delivery");
+            let request = parse_request_or_err!(params, StartReviewRequest);
             Some(
                 state
-                    .start_review(workspace_id, thread_id, target, delivery)
+                    .start_review(
+                        request.workspace_id,
+                        request.thread_id,
+                        request.target,
+                        request.delivery,
+                    )
                     .await,
             )
         }
         "model_list" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.model_list(workspace_id).await)
+            let request = parse_request_or_err!(params, WorkspaceIdRequest);
+            Some(state.model_list(request.workspace_id).await)
         }
         "experimental_feature_list" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let cursor = parse_optional_string(params, "cursor");
-            let limit = parse_optional_u32(params, "limit");
+            let request = parse_request_or_err!(params, PageRequest);
             Some(
                 state
-                    .experimental_feature_list(workspace_id, cursor, limit)
+                    .experimental_feature_list(request.workspace_id, request.cursor, request.limit)
                     .await,
             )
         }
         "collaboration_mode_list" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.collaboration_mode_list(workspace_id).await)
+            let request = parse_request_or_err!(params, WorkspaceIdRequest);
+            Some(state.collaboration_mode_list(request.workspace_id).await)
         }
         "set_codex_feature_flag" => {
-            let feature_key = match parse_string(params, "featureKey") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let enabled = match parse_optional_bool(params, "enabled") {
-                Some(value) => value,
-                None => return Some(Err("missing or invalid `enabled`".to_string())),
-            };
+            let request = parse_request_or_err!(params, FeatureFlagRequest);
             Some(
                 state
-                    .set_codex_feature_flag(feature_key, enabled)
+                    .set_codex_feature_flag(request.feature_key, request.enabled)
                     .await
-                    .map(|_| json!({ "ok": true })),
+                    .map(|_| json!({ "ok": true }))
+                    .map_err(RpcError::from),
             )
         }
         "get_agents_settings" => Some(
             state
                 .get_agents_settings()
                 .await
-                .and_then(|value| serde_json::to_value(value).map_err(|err| err.to_string())),
+                .and_then(serialize_value)
+                .map_err(RpcError::from),
         ),
         "set_agents_core_settings" => {
-            let input = match parse_input::<agents_config_core::SetAgentsCoreInput>(params) {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
+            let request =
+                parse_request_or_err!(params, InputRequest<agents_config_core::SetAgentsCoreInput>);
             Some(
                 state
-                    .set_agents_core_settings(input)
+                    .set_agents_core_settings(request.input)
                     .await
-                    .and_then(|value| serde_json::to_value(value).map_err(|err| err.to_string())),
+                    .and_then(serialize_value)
+                    .map_err(RpcError::from),
             )
         }
         "create_agent" => {
-            let input = match parse_input::<agents_config_core::CreateAgentInput>(params) {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
+            let request =
+                parse_request_or_err!(params, InputRequest<agents_config_core::CreateAgentInput>);
             Some(
                 state
-                    .create_agent(input)
+                    .create_agent(request.input)
                     .await
-                    .and_then(|value| serde_json::to_value(value).map_err(|err| err.to_string())),
+                    .and_then(serialize_value)
+                    .map_err(RpcError::from),
             )
         }
         "update_agent" => {
-            let input = match parse_input::<agents_config_core::UpdateAgentInput>(params) {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
+            let request =
+                parse_request_or_err!(params, InputRequest<agents_config_core::UpdateAgentInput>);
             Some(
                 state
-                    .update_agent(input)
+                    .update_agent(request.input)
                     .await
-                    .and_then(|value| serde_json::to_value(value).map_err(|err| err.to_string())),
+                    .and_then(serialize_value)
+                    .map_err(RpcError::from),
             )
         }
         "delete_agent" => {
-            let input = match parse_input::<agents_config_core::DeleteAgentInput>(params) {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
+            let request =
+                parse_request_or_err!(params, InputRequest<agents_config_core::DeleteAgentInput>);
             Some(
                 state
-                    .delete_agent(input)
+                    .delete_agent(request.input)
                     .await
-                    .and_then(|value| serde_json::to_value(value).map_err(|err| err.to_string())),
+                    .and_then(serialize_value)
+                    .map_err(RpcError::from),
             )
         }
         "read_agent_config_toml" => {
-            let agent_name = match parse_string(params, "agentName") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
+            let request = parse_request_or_err!(params, AgentNameRequest);
             Some(
                 state
-                    .read_agent_config_toml(agent_name)
+                    .read_agent_config_toml(request.agent_name)
                     .await
-                    .and_then(|value| serde_json::to_value(value).map_err(|err| err.to_string())),
+                    .and_then(serialize_value)
+                    .map_err(RpcError::from),
             )
         }
         "write_agent_config_toml" => {
-            let agent_name = match parse_string(params, "agentName") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let content = match parse_string(params, "content") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
+            let request = parse_request_or_err!(params, AgentConfigRequest);
             Some(
                 state
-                    .write_agent_config_toml(agent_name, content)
+                    .write_agent_config_toml(request.agent_name, request.content)
                     .await
-                    .map(|_| json!({ "ok": true })),
+                    .map(|_| json!({ "ok": true }))
+                    .map_err(RpcError::from),
             )
         }
         "account_rate_limits" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.account_rate_limits(workspace_id).await)
+            let request = parse_request_or_err!(params, WorkspaceIdRequest);
+            Some(state.account_rate_limits(request.workspace_id).await)
         }
         "account_read" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.account_read(workspace_id).await)
+            let request = parse_request_or_err!(params, WorkspaceIdRequest);
+            Some(
+                state
+                    .account_read(request.workspace_id)
+                    .await
+                    .map_err(RpcError::from),
+            )
         }
         "codex_login" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.codex_login(workspace_id).await)
+            let request = parse_request_or_err!(params, WorkspaceIdRequest);
+            Some(state.codex_login(request.workspace_id).await)
         }
         "codex_login_cancel" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.codex_login_cancel(workspace_id).await)
+            let request = parse_request_or_err!(params, WorkspaceIdRequest);
+            Some(state.codex_login_cancel(request.workspace_id).await)
         }
         "skills_list" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.skills_list(workspace_id).await)
+            let request = parse_request_or_err!(params, WorkspaceIdRequest);
+            Some(state.skills_list(request.workspace_id).await)
         }
         "apps_list" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let cursor = parse_optional_string(params, "cursor");
-            let limit = parse_optional_u32(params, "limit");
-            let thread_id = parse_optional_string(params, "threadId");
+            let request = parse_request_or_err!(params, AppsListRequest);
             Some(
                 state
-                    .apps_list(workspace_id, cursor, limit, thread_id)
+                    .apps_list(
+                        request.workspace_id,
+                        request.cursor,
+                        request.limit,
+                        request.thread_id,
+                    )
                     .await,
             )
         }
         "respond_to_server_request" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let map = match params.as_object().ok_or("missing requestId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err.to_string())),
-            };
-            let request_id = match map
-                .get("requestId")
-                .cloned()
-                .filter(|value| value.is_number() || value.is_string())
-                .ok_or("missing requestId")
-            {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err.to_string())),
-            };
-            let result = match map.get("result").cloned().ok_or("missing `result`") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err.to_string())),
-            };
+            let request = parse_request_or_err!(params, ServerRequestResponse);
+            if !(request.request_id.is_number() || request.request_id.is_string()) {
+                return Some(Err(RpcError::new(
+                    RpcErrorCode::InvalidParams,
+                    "missing or invalid `requestId`",
+                )));
+            }
             Some(
                 state
-                    .respond_to_server_request(workspace_id, request_id, result)
+                    .respond_to_server_request(
+                        request.workspace_id,
+                        request.request_id,
+                        request.result,
+                    )
                     .await,
             )
         }
         "remember_approval_rule" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let command = match parse_string_array(params, "command") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.remember_approval_rule(workspace_id, command).await)
+            let request = parse_request_or_err!(params, ApprovalRuleRequest);
+            Some(
+                state
+                    .remember_approval_rule(request.workspace_id, request.command)
+                    .await
+                    .map_err(RpcError::from),
+            )
+        }
+        "list_codex_rules" => {
+            let request = parse_request_or_err!(params, WorkspaceIdRequest);
+            Some(
+                state
+                    .list_codex_rules(request.workspace_id)
+                    .await
+                    .map_err(RpcError::from),
+            )
+        }
+        "update_codex_rule" => {
+            let request = parse_request_or_err!(params, UpdateCodexRuleRequest);
+            let rule = parse_request_or_err!(&request.rule, PrefixRule);
+            Some(
+                state
+                    .update_codex_rule(request.workspace_id, request.rule_id, rule)
+                    .await
+                    .map_err(RpcError::from),
+            )
+        }
+        "delete_codex_rule" => {
+            let request = parse_request_or_err!(params, CodexRuleRequest);
+            Some(
+                state
+                    .delete_codex_rule(request.workspace_id, request.rule_id)
+                    .await
+                    .map_err(RpcError::from),
+            )
+        }
+        "explain_approval_policy" => {
+            let request = parse_request_or_err!(params, ExplainApprovalPolicyRequest);
+            Some(
+                state
+                    .explain_approval_policy(
+                        request.workspace_id,
+                        request.method,
+                        request.params,
+                        request.access_mode,
+                        request.rules,
+                    )
+                    .await
+                    .map_err(RpcError::from),
+            )
         }
         "codex_doctor" => {
-            let codex_bin = parse_optional_string(params, "codexBin");
-            let codex_args = parse_optional_string(params, "codexArgs");
-            Some(state.codex_doctor(codex_bin, codex_args).await)
+            let request = parse_request_or_err!(params, CodexDoctorRequest);
+            Some(
+                state
+                    .codex_doctor(request.codex_bin, request.codex_args)
+                    .await
+                    .map_err(RpcError::from),
+            )
         }
         "generate_run_metadata" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let prompt = match parse_string(params, "prompt") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.generate_run_metadata(workspace_id, prompt).await)
+            let request = parse_request_or_err!(params, RunMetadataRequest);
+            Some(
+                state
+                    .generate_run_metadata(request.workspace_id, request.prompt)
+                    .await,
+            )
         }
         "generate_agent_description" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let description = match parse_string(params, "description") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
+            let request = parse_request_or_err!(params, AgentDescriptionRequest);
             Some(
                 state
-                    .generate_agent_description(workspace_id, description)
+                    .generate_agent_description(request.workspace_id, request.description)
                     .await
-                    .and_then(|value| serde_json::to_value(value).map_err(|err| err.to_string())),
+                    .and_then(|config| serialize_value(config).map_err(RpcError::from)),
             )
         }
         _ => None,
@@ -1,10 +1,23 @@
 use super::*;
+use crate::audit::{AuditQuery, AuditQueryRequest};
+
+#[derive(Debug, Deserialize)]
+pub(super) struct MenuAcceleratorsRequest {
+    #[serde(default)]
+    updates: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct NotificationRequest {
+    title: String,
+    body: String,
+}
 
 pub(super) async fn try_handle(
     state: &DaemonState,
     method: &str,
     params: &Value,
-) -> Option<Result<Value, String>> {
+) -> Option<Result<Value, RpcError>> {
     match method {
         "ping" => Some(Ok(json!({ "ok": true }))),
         "daemon_info" => Some(Ok(state.daemon_info())),
@@ -16,43 +29,40 @@ pub(super) async fn try_handle(
             Some(Ok(json!({ "ok": true })))
         }
         "menu_set_accelerators" => {
-            let updates: Vec<Value> = match params {
-                Value::Object(map) => match map
-                    .get("updates")
-                    .cloned()
-                    .map(serde_json::from_value)
-                    .transpose()
-                {
-                    Ok(value) => value.unwrap_or_default(),
-                    Err(err) => return Some(Err(err.to_string())),
-                },
-                _ => Vec::new(),
-            };
+            let request = parse_request_or_err!(params, MenuAcceleratorsRequest);
+            Some(
+                state
+                    .menu_set_accelerators(request.updates)
+                    .await
+                    .map(|_| json!({ "ok": true }))
+                    .map_err(RpcError::from),
+            )
+        }
+        "daemon_audit_query" => {
+            let request = parse_request_or_err!(params, AuditQueryRequest);
+            let query = AuditQuery::from_request(request);
             Some(
                 state
-                    .menu_set_accelerators(updates)
+                    .audit
+                    .query(&query)
                     .await
-                    .map(|_| json!({ "ok": true })),
+                    .map(|entries| json!({ "entries": entries }))
+                    .map_err(RpcError::from),
             )
         }
+        "rpc_describe" => Some(super::describe::describe().map_err(RpcError::from)),
         "is_macos_debug_build" => {
             let is_debug = state.is_macos_debug_build().await;
             Some(Ok(Value::Bool(is_debug)))
         }
         "send_notification_fallback" => {
-            let title = match parse_string(params, "title") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let body = match parse_string(params, "body") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
+            let request = parse_request_or_err!(params, NotificationRequest);
             Some(
                 state
-                    .send_notification_fallback(title, body)
+                    .send_notification_fallback(request.title, request.body)
                     .await
-                    .map(|_| json!({ "ok": true })),
+                    .map(|_| json!({ "ok": true }))
+                    .map_err(RpcError::from),
             )
         }
         _ => None,
@@ -0,0 +1,78 @@
+use super::registry::{methods, Handler, MethodSpec};
+use super::*;
+
+const OPENRPC_VERSION: &str = "1.2.6";
+
+/// Splits a params schema into OpenRPC by-name content descriptors.
+fn content_descriptors(schema: &Value) -> Vec<Value> {
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|fields| fields.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .map(|properties| {
+            properties
+                .iter()
+                .map(|(name, schema)| {
+                    json!({
+                        "name": name,
+                        "required": required.contains(&name.as_str()),
+                        "schema": schema,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn describe_method(handler: Handler, spec: &MethodSpec) -> Result<Value, String> {
+    let schema = spec
+        .params_schema()
+        .map_err(|err| format!("failed to describe `{}`: {err}", spec.name))?;
+    let mut method = json!({
+        "name": spec.name,
+        "summary": spec.summary,
+        "paramStructure": "by-name",
+        "params": content_descriptors(&schema),
+        "result": { "name": "result", "schema": {} },
+    });
+    // `auth` is answered before a role is known.
+    if handler != Handler::Transport {
+        method["x-requiredRole"] = json!(spec.role.as_str());
+    }
+    Ok(method)
+}
+
+/// Error objects the daemon can return, keyed by `data.kind`.
+fn describe_errors() -> Value {
+    let errors: serde_json::Map<String, Value> = RpcErrorCode::ALL
+        .into_iter()
+        .filter(|code| *code != RpcErrorCode::Disconnected)
+        .map(|code| {
+            (
+                code.name().to_string(),
+                json!({ "code": code.code(), "message": code.name() }),
+            )
+        })
+        .collect();
+    Value::Object(errors)
+}
+
+/// The OpenRPC document served by `rpc_describe` and printed by `daemonctl schema`.
+pub(super) fn describe() -> Result<Value, String> {
+    let methods = methods()
+        .map(|(handler, spec)| describe_method(handler, spec))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(json!({
+        "openrpc": OPENRPC_VERSION,
+        "info": {
+            "title": "codex-monitor-daemon",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "methods": methods,
+        "components": { "errors": describe_errors() },
+    }))
+}
@@ -1,30 +1,128 @@
+use super::registry::{find_method, Handler, WorkspaceParams};
 use super::*;
+use crate::access::{referenced_workspace_ids, RpcCaller};
 
-pub(super) async fn dispatch_rpc_request(
+pub(super) async fn dispatch_client_rpc_request(
     state: &DaemonState,
+    caller: &RpcCaller,
     method: &str,
     params: &Value,
-    client_version: &str,
-) -> Result<Value, String> {
-    if let Some(result) = daemon::try_handle(state, method, params).await {
-        return result;
+) -> Result<Value, RpcError> {
+    let result = async {
+        authorize_call(state, &caller.access, method, params).await?;
+        ensure_workspaces_exist(state, method, params).await?;
+        dispatch_typed_rpc_request(state, method, params, caller).await
     }
-
-    if let Some(result) = workspace::try_handle(state, method, params, client_version).await {
-        return result;
+    .await;
+    if let Err(err) = state
+        .audit
+        .record(caller, method, params, result.as_ref().map(|_| ()))
+        .await
+    {
+        eprintln!("daemon: failed to write audit log entry for {method}: {err}");
     }
+    result.map(|value| caller.access.filter_result(method, value))
+}
 
-    if let Some(result) = codex::try_handle(state, method, params).await {
-        return result;
+/// `ClientAccess::authorize` only sees the params; budget overrides name a budget, so the
+/// budget's workspace is looked up before the call is allowed.
+async fn authorize_call(
+    state: &DaemonState,
+    access: &ClientAccess,
+    method: &str,
+    params: &Value,
+) -> Result<(), RpcError> {
+    access.authorize(method, params)?;
+    if method != "override_usage_budget" || access.is_unscoped() {
+        return Ok(());
     }
+    let budget_id = parse_optional_string(params, "budgetId").unwrap_or_default();
+    let budget_workspace_id =
+        usage_budget_core::usage_budget_workspace_core(&state.usage_budgets, budget_id.clone())
+            .await
+            .map_err(RpcError::from)?;
+    access.authorize_budget(&budget_id, budget_workspace_id.as_deref())
+}
 
-    if let Some(result) = git::try_handle(state, method, params).await {
-        return result;
+/// Rejects calls naming a workspace the daemon does not know, before any handler runs.
+async fn ensure_workspaces_exist(
+    state: &DaemonState,
+    method: &str,
+    params: &Value,
+) -> Result<(), RpcError> {
+    let filters_history =
+        find_method(method).is_some_and(|(_, spec)| spec.workspaces == WorkspaceParams::Filter);
+    if filters_history {
+        return Ok(());
     }
-
-    if let Some(result) = prompts::try_handle(state, method, params).await {
-        return result;
+    let workspace_ids = referenced_workspace_ids(method, params);
+    if workspace_ids.is_empty() {
+        return Ok(());
     }
+    let workspaces = state.workspaces.lock().await;
+    match workspace_ids
+        .into_iter()
+        .find(|workspace_id| !workspaces.contains_key(workspace_id))
+    {
+        Some(missing) => Err(RpcError::new(
+            RpcErrorCode::WorkspaceNotFound,
+            format!("workspace `{missing}` not found"),
+        )
+        .with_data(json!({
+            "method": method,
+            "workspaceId": missing,
+        }))),
+        None => Ok(()),
+    }
+}
 
-    Err(format!("unknown method: {method}"))
+#[cfg(test)]
+pub(super) async fn dispatch_rpc_request(
+    state: &DaemonState,
+    method: &str,
+    params: &Value,
+    client_version: &str,
+) -> Result<Value, String> {
+    let caller = RpcCaller {
+        access: ClientAccess::unrestricted(),
+        peer: None,
+        client_version: client_version.to_string(),
+        connection_id: 0,
+    };
+    dispatch_typed_rpc_request(state, method, params, &caller)
+        .await
+        .map_err(String::from)
+}
+
+/// Routes to the handler module the registry names; handlers type their own errors.
+async fn dispatch_typed_rpc_request(
+    state: &DaemonState,
+    method: &str,
+    params: &Value,
+    caller: &RpcCaller,
+) -> Result<Value, RpcError> {
+    let handler = match find_method(method) {
+        Some((handler, _)) if handler != Handler::Transport => handler,
+        _ => {
+            return Err(RpcError::new(
+                RpcErrorCode::MethodNotFound,
+                format!("unknown method: {method}"),
+            ))
+        }
+    };
+    let result = match handler {
+        Handler::Daemon => daemon::try_handle(state, method, params).await,
+        Handler::Workspace => workspace::try_handle(state, method, params, caller).await,
+        Handler::Codex => codex::try_handle(state, method, params).await,
+        Handler::Git => git::try_handle(state, method, params).await,
+        Handler::Prompts => prompts::try_handle(state, method, params).await,
+        Handler::Terminal => terminal::try_handle(state, method, params).await,
+        Handler::Transport => None,
+    };
+    result.unwrap_or_else(|| {
+        Err(RpcError::new(
+            RpcErrorCode::Internal,
+            format!("`{method}` is registered but its handler does not answer it"),
+        ))
+    })
 }
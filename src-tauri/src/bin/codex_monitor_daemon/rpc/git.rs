@@ -1,77 +1,98 @@
 use super::*;
 use crate::shared::git_rpc;
-use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::future::Future;
 
-fn parse_git_request<T: DeserializeOwned>(params: &Value) -> Result<T, String> {
-    git_rpc::from_params(params)
+/// Git state methods report failed git commands as plain strings; those are `GitFailure`.
+/// Errors the git cores already typed (a stale stash, say) pass through unchanged.
+trait IntoGitError {
+    fn into_git_error(self) -> RpcError;
 }
 
-fn serialize_value<T: Serialize>(value: T) -> Result<Value, String> {
-    serde_json::to_value(value).map_err(|err| err.to_string())
+impl IntoGitError for String {
+    fn into_git_error(self) -> RpcError {
+        RpcError::new(RpcErrorCode::GitFailure, self)
+    }
+}
+
+impl IntoGitError for RpcError {
+    fn into_git_error(self) -> RpcError {
+        self
+    }
+}
+
+fn serialize_value<T: Serialize>(value: T) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|err| RpcError::from(err.to_string()))
 }
 
-async fn serialize_result<T, Fut>(future: Fut) -> Result<Value, String>
+async fn serialize_result<T, E, Fut>(future: Fut) -> Result<Value, RpcError>
 where
     T: Serialize,
-    Fut: Future<Output = Result<T, String>>,
+    E: IntoGitError,
+    Fut: Future<Output = Result<T, E>>,
 {
-    future.await.and_then(serialize_value)
+    future
+        .await
+        .map_err(IntoGitError::into_git_error)
+        .and_then(serialize_value)
 }
 
-async fn serialize_ok<Fut>(future: Fut) -> Result<Value, String>
+async fn serialize_ok<E, Fut>(future: Fut) -> Result<Value, RpcError>
 where
-    Fut: Future<Output = Result<(), String>>,
+    E: IntoGitError,
+    Fut: Future<Output = Result<(), E>>,
 {
-    future.await.map(|_| json!({ "ok": true }))
-}
-
-macro_rules! parse_request_or_err {
-    ($params:expr, $ty:ty) => {
-        match parse_git_request::<$ty>($params) {
-            Ok(value) => value,
-            Err(err) => return Some(Err(err)),
-        }
-    };
+    future
+        .await
+        .map(|_| json!({ "ok": true }))
+        .map_err(IntoGitError::into_git_error)
 }
 
 pub(super) async fn try_handle(
     state: &DaemonState,
     method: &str,
     params: &Value,
-) -> Option<Result<Value, String>> {
+) -> Option<Result<Value, RpcError>> {
     match method {
         git_rpc::METHOD_GET_GIT_STATUS => {
             let request = parse_request_or_err!(params, git_rpc::WorkspaceIdRequest);
-            Some(state.get_git_status(request.workspace_id).await)
+            Some(
+                state
+                    .get_git_status(request.workspace_id)
+                    .await
+                    .map_err(IntoGitError::into_git_error),
+            )
         }
         git_rpc::METHOD_INIT_GIT_REPO => {
-            let request = parse_request_or_err!(params, git_rpc::InitGitRepoRequiredRequest);
-            let force = parse_optional_bool(params, "force").unwrap_or(false);
+            let request = parse_request_or_err!(params, git_rpc::InitGitRepoRequest);
             Some(
                 state
-                    .init_git_repo(request.workspace_id, request.branch, force)
-                    .await,
+                    .init_git_repo(
+                        request.workspace_id,
+                        request.branch,
+                        request.force.unwrap_or(false),
+                    )
+                    .await
+                    .map_err(IntoGitError::into_git_error),
             )
         }
         git_rpc::METHOD_CREATE_GITHUB_REPO => {
-            let request = parse_request_or_err!(params, git_rpc::CreateGitHubRepoRequiredRequest);
-            let branch = parse_optional_string(params, "branch");
+            let request = parse_request_or_err!(params, git_rpc::CreateGitHubRepoRequest);
             Some(
                 state
                     .create_github_repo(
                         request.workspace_id,
                         request.repo,
                         request.visibility,
-                        branch,
+                        request.branch,
                     )
-                    .await,
+                    .await
+                    .map_err(IntoGitError::into_git_error),
             )
         }
         git_rpc::METHOD_LIST_GIT_ROOTS => {
-            let request = parse_request_or_err!(params, git_rpc::WorkspaceIdRequest);
-            let depth = parse_optional_u32(params, "depth").map(|value| value as usize);
+            let request = parse_request_or_err!(params, git_rpc::ListGitRootsRequest);
+            let depth = request.depth.map(|value| value as usize);
             Some(serialize_result(state.list_git_roots(request.workspace_id, depth)).await)
         }
         git_rpc::METHOD_GET_GIT_DIFFS => {
@@ -79,9 +100,17 @@ pub(super) async fn try_handle(
             Some(serialize_result(state.get_git_diffs(request.workspace_id)).await)
         }
         git_rpc::METHOD_GET_GIT_LOG => {
-            let request = parse_request_or_err!(params, git_rpc::WorkspaceIdRequest);
-            let limit = parse_optional_u32(params, "limit").map(|value| value as usize);
-            Some(serialize_result(state.get_git_log(request.workspace_id, limit)).await)
+            let request = parse_request_or_err!(params, git_rpc::GetGitLogRequest);
+            let limit = request.limit.map(|value| value as usize);
+            Some(
+                serialize_result(state.get_git_log(
+                    request.workspace_id,
+                    limit,
+                    request.cursor,
+                    request.filter,
+                ))
+                .await,
+            )
         }
         git_rpc::METHOD_GET_GIT_COMMIT_DIFF => {
             let request = parse_request_or_err!(params, git_rpc::WorkspaceShaRequest);
@@ -110,6 +139,39 @@ pub(super) async fn try_handle(
             let request = parse_request_or_err!(params, git_rpc::WorkspacePathRequest);
             Some(serialize_ok(state.revert_git_file(request.workspace_id, request.path)).await)
         }
+        git_rpc::METHOD_STAGE_GIT_SELECTION => {
+            let request = parse_request_or_err!(params, git_rpc::WorkspaceSelectionRequest);
+            Some(
+                serialize_ok(state.stage_git_selection(
+                    request.workspace_id,
+                    request.path,
+                    request.selection,
+                ))
+                .await,
+            )
+        }
+        git_rpc::METHOD_UNSTAGE_GIT_SELECTION => {
+            let request = parse_request_or_err!(params, git_rpc::WorkspaceSelectionRequest);
+            Some(
+                serialize_ok(state.unstage_git_selection(
+                    request.workspace_id,
+                    request.path,
+                    request.selection,
+                ))
+                .await,
+            )
+        }
+        git_rpc::METHOD_REVERT_GIT_SELECTION => {
+            let request = parse_request_or_err!(params, git_rpc::WorkspaceSelectionRequest);
+            Some(
+                serialize_ok(state.revert_git_selection(
+                    request.workspace_id,
+                    request.path,
+                    request.selection,
+                ))
+                .await,
+            )
+        }
         git_rpc::METHOD_REVERT_GIT_ALL => {
             let request = parse_request_or_err!(params, git_rpc::WorkspaceIdRequest);
             Some(serialize_ok(state.revert_git_all(request.workspace_id)).await)
@@ -134,6 +196,99 @@ pub(super) async fn try_handle(
             let request = parse_request_or_err!(params, git_rpc::WorkspaceIdRequest);
             Some(serialize_ok(state.sync_git(request.workspace_id)).await)
         }
+        git_rpc::METHOD_LIST_GIT_STASHES => {
+            let request = parse_request_or_err!(params, git_rpc::WorkspaceIdRequest);
+            Some(serialize_result(state.list_git_stashes(request.workspace_id)).await)
+        }
+        git_rpc::METHOD_GET_GIT_STASH_DIFF => {
+            let request = parse_request_or_err!(params, git_rpc::WorkspaceStashActionRequest);
+            Some(
+                serialize_result(state.get_git_stash_diff(
+                    request.workspace_id,
+                    request.index,
+                    request.sha,
+                ))
+                .await,
+            )
+        }
+        git_rpc::METHOD_CREATE_GIT_STASH => {
+            let request = parse_request_or_err!(params, git_rpc::CreateGitStashRequest);
+            Some(
+                serialize_result(state.create_git_stash(
+                    request.workspace_id,
+                    request.message,
+                    request.include_untracked.unwrap_or(false),
+                ))
+                .await,
+            )
+        }
+        git_rpc::METHOD_APPLY_GIT_STASH => {
+            let request = parse_request_or_err!(params, git_rpc::WorkspaceStashActionRequest);
+            Some(
+                serialize_ok(state.apply_git_stash(
+                    request.workspace_id,
+                    request.index,
+                    request.sha,
+                ))
+                .await,
+            )
+        }
+        git_rpc::METHOD_POP_GIT_STASH => {
+            let request = parse_request_or_err!(params, git_rpc::WorkspaceStashActionRequest);
+            Some(
+                serialize_ok(state.pop_git_stash(request.workspace_id, request.index, request.sha))
+                    .await,
+            )
+        }
+        git_rpc::METHOD_DROP_GIT_STASH => {
+            let request = parse_request_or_err!(params, git_rpc::WorkspaceStashActionRequest);
+            Some(
+                serialize_ok(state.drop_git_stash(
+                    request.workspace_id,
+                    request.index,
+                    request.sha,
+                ))
+                .await,
+            )
+        }
+        git_rpc::METHOD_MERGE_GIT_BRANCH => {
+            let request = parse_request_or_err!(params, git_rpc::WorkspaceNameRequest);
+            Some(serialize_result(state.merge_git_branch(request.workspace_id, request.name)).await)
+        }
+        git_rpc::METHOD_REBASE_GIT_BRANCH => {
+            let request = parse_request_or_err!(params, git_rpc::WorkspaceNameRequest);
+            Some(
+                serialize_result(state.rebase_git_branch(request.workspace_id, request.name)).await,
+            )
+        }
+        git_rpc::METHOD_CONTINUE_GIT_OPERATION => {
+            let request = parse_request_or_err!(params, git_rpc::WorkspaceIdRequest);
+            Some(serialize_result(state.continue_git_operation(request.workspace_id)).await)
+        }
+        git_rpc::METHOD_SKIP_GIT_OPERATION => {
+            let request = parse_request_or_err!(params, git_rpc::WorkspaceIdRequest);
+            Some(serialize_result(state.skip_git_operation(request.workspace_id)).await)
+        }
+        git_rpc::METHOD_ABORT_GIT_OPERATION => {
+            let request = parse_request_or_err!(params, git_rpc::WorkspaceIdRequest);
+            Some(serialize_result(state.abort_git_operation(request.workspace_id)).await)
+        }
+        git_rpc::METHOD_GET_GIT_CONFLICT => {
+            let request = parse_request_or_err!(params, git_rpc::WorkspacePathRequest);
+            Some(serialize_result(state.get_git_conflict(request.workspace_id, request.path)).await)
+        }
+        git_rpc::METHOD_RESOLVE_GIT_CONFLICT => {
+            let request = parse_request_or_err!(params, git_rpc::ResolveGitConflictRequest);
+            Some(
+                serialize_ok(state.resolve_git_conflict(
+                    request.workspace_id,
+                    request.path,
+                    request.resolution,
+                    request.content,
+                ))
+                .await,
+            )
+        }
         git_rpc::METHOD_GET_GITHUB_ISSUES => {
             let request = parse_request_or_err!(params, git_rpc::WorkspaceIdRequest);
             Some(serialize_result(state.get_github_issues(request.workspace_id)).await)
@@ -171,7 +326,12 @@ pub(super) async fn try_handle(
         }
         git_rpc::METHOD_LIST_GIT_BRANCHES => {
             let request = parse_request_or_err!(params, git_rpc::WorkspaceIdRequest);
-            Some(state.list_git_branches(request.workspace_id).await)
+            Some(
+                state
+                    .list_git_branches(request.workspace_id)
+                    .await
+                    .map_err(IntoGitError::into_git_error),
+            )
         }
         git_rpc::METHOD_CHECKOUT_GIT_BRANCH => {
             let request = parse_request_or_err!(params, git_rpc::WorkspaceNameRequest);
@@ -182,11 +342,10 @@ pub(super) async fn try_handle(
             Some(serialize_ok(state.create_git_branch(request.workspace_id, request.name)).await)
         }
         git_rpc::METHOD_GENERATE_COMMIT_MESSAGE => {
-            let request = parse_request_or_err!(params, git_rpc::WorkspaceIdRequest);
-            let commit_message_model_id = parse_optional_string(params, "commitMessageModelId");
+            let request = parse_request_or_err!(params, git_rpc::GenerateCommitMessageRequest);
             Some(
                 state
-                    .generate_commit_message(request.workspace_id, commit_message_model_id)
+                    .generate_commit_message(request.workspace_id, request.commit_message_model_id)
                     .await
                     .map(Value::String),
             )
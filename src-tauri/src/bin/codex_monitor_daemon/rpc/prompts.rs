@@ -1,148 +1,129 @@
 use super::*;
+use crate::shared::workspace_rpc::WorkspaceIdRequest;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct PromptCreateRequest {
+    workspace_id: String,
+    scope: String,
+    name: String,
+    description: Option<String>,
+    argument_hint: Option<String>,
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct PromptUpdateRequest {
+    workspace_id: String,
+    path: String,
+    name: String,
+    description: Option<String>,
+    argument_hint: Option<String>,
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct PromptPathRequest {
+    workspace_id: String,
+    path: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct PromptMoveRequest {
+    workspace_id: String,
+    path: String,
+    scope: String,
+}
 
 pub(super) async fn try_handle(
     state: &DaemonState,
     method: &str,
     params: &Value,
-) -> Option<Result<Value, String>> {
+) -> Option<Result<Value, RpcError>> {
     match method {
         "prompts_list" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
+            let request = parse_request_or_err!(params, WorkspaceIdRequest);
+            let prompts = match state.prompts_list(request.workspace_id).await {
                 Ok(value) => value,
-                Err(err) => return Some(Err(err)),
+                Err(err) => return Some(Err(err.into())),
             };
-            let prompts = match state.prompts_list(workspace_id).await {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(serde_json::to_value(prompts).map_err(|err| err.to_string()))
+            Some(serde_json::to_value(prompts).map_err(|err| err.to_string().into()))
         }
         "prompts_workspace_dir" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let dir = match state.prompts_workspace_dir(workspace_id).await {
+            let request = parse_request_or_err!(params, WorkspaceIdRequest);
+            let dir = match state.prompts_workspace_dir(request.workspace_id).await {
                 Ok(value) => value,
-                Err(err) => return Some(Err(err)),
+                Err(err) => return Some(Err(err.into())),
             };
             Some(Ok(Value::String(dir)))
         }
         "prompts_global_dir" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let dir = match state.prompts_global_dir(workspace_id).await {
+            let request = parse_request_or_err!(params, WorkspaceIdRequest);
+            let dir = match state.prompts_global_dir(request.workspace_id).await {
                 Ok(value) => value,
-                Err(err) => return Some(Err(err)),
+                Err(err) => return Some(Err(err.into())),
             };
             Some(Ok(Value::String(dir)))
         }
         "prompts_create" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let scope = match parse_string(params, "scope") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let name = match parse_string(params, "name") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let description = parse_optional_string(params, "description");
-            let argument_hint = parse_optional_string(params, "argumentHint");
-            let content = match parse_string(params, "content") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
+            let request = parse_request_or_err!(params, PromptCreateRequest);
             let prompt = match state
                 .prompts_create(
-                    workspace_id,
-                    scope,
-                    name,
-                    description,
-                    argument_hint,
-                    content,
+                    request.workspace_id,
+                    request.scope,
+                    request.name,
+                    request.description,
+                    request.argument_hint,
+                    request.content,
                 )
                 .await
             {
                 Ok(value) => value,
-                Err(err) => return Some(Err(err)),
+                Err(err) => return Some(Err(err.into())),
             };
-            Some(serde_json::to_value(prompt).map_err(|err| err.to_string()))
+            Some(serde_json::to_value(prompt).map_err(|err| err.to_string().into()))
         }
         "prompts_update" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let path = match parse_string(params, "path") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let name = match parse_string(params, "name") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let description = parse_optional_string(params, "description");
-            let argument_hint = parse_optional_string(params, "argumentHint");
-            let content = match parse_string(params, "content") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
+            let request = parse_request_or_err!(params, PromptUpdateRequest);
             let prompt = match state
                 .prompts_update(
-                    workspace_id,
-                    path,
-                    name,
-                    description,
-                    argument_hint,
-                    content,
+                    request.workspace_id,
+                    request.path,
+                    request.name,
+                    request.description,
+                    request.argument_hint,
+                    request.content,
                 )
                 .await
             {
                 Ok(value) => value,
-                Err(err) => return Some(Err(err)),
+                Err(err) => return Some(Err(err.into())),
             };
-            Some(serde_json::to_value(prompt).map_err(|err| err.to_string()))
+            Some(serde_json::to_value(prompt).map_err(|err| err.to_string().into()))
         }
         "prompts_delete" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let path = match parse_string(params, "path") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
+            let request = parse_request_or_err!(params, PromptPathRequest);
             Some(
                 state
-                    .prompts_delete(workspace_id, path)
+                    .prompts_delete(request.workspace_id, request.path)
                     .await
-                    .map(|_| json!({ "ok": true })),
+                    .map(|_| json!({ "ok": true }))
+                    .map_err(RpcError::from),
             )
         }
         "prompts_move" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let path = match parse_string(params, "path") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let scope = match parse_string(params, "scope") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let prompt = match state.prompts_move(workspace_id, path, scope).await {
+            let request = parse_request_or_err!(params, PromptMoveRequest);
+            let prompt = match state
+                .prompts_move(request.workspace_id, request.path, request.scope)
+                .await
+            {
                 Ok(value) => value,
-                Err(err) => return Some(Err(err)),
+                Err(err) => return Some(Err(err.into())),
             };
-            Some(serde_json::to_value(prompt).map_err(|err| err.to_string()))
+            Some(serde_json::to_value(prompt).map_err(|err| err.to_string().into()))
         }
         _ => None,
     }
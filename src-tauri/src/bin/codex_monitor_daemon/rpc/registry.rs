@@ -0,0 +1,904 @@
+use super::codex::{
+    AgentConfigRequest, AgentDescriptionRequest, AgentNameRequest, ApprovalRuleRequest,
+    AppsListRequest, CodexDoctorRequest, CodexRuleRequest, ExplainApprovalPolicyRequest,
+    ExportThreadRequest, FeatureFlagRequest, ImportThreadRequest, InputRequest, ListThreadsRequest,
+    PageRequest, RunMetadataRequest, SendUserMessageRequest, ServerRequestResponse,
+    SetThreadNameRequest, StartReviewRequest, ThreadRequest, TurnRequest, TurnSteerRequest,
+    UpdateCodexRuleRequest,
+};
+use super::daemon::{MenuAcceleratorsRequest, NotificationRequest};
+use super::prompts::{
+    PromptCreateRequest, PromptMoveRequest, PromptPathRequest, PromptUpdateRequest,
+};
+use super::terminal::{TerminalRequest, TerminalSizeRequest, TerminalWriteRequest};
+use super::workspace::{
+    FileReadRequest, FileWriteRequest, LocalUsageExportRequest, LocalUsageSnapshotRequest,
+    LocalUsageThreadTimelineRequest, LocalUsageWatchRequest, OverrideUsageBudgetRequest,
+    SearchThreadsRequest, UpdateAppSettingsRequest,
+};
+use super::*;
+use crate::audit::AuditQueryRequest;
+use crate::daemon_tokens::DaemonRole;
+use crate::schema::params_schema;
+use crate::shared::agents_config_core::{
+    CreateAgentInput, DeleteAgentInput, SetAgentsCoreInput, UpdateAgentInput,
+};
+use crate::shared::git_rpc;
+use crate::shared::workspace_rpc;
+
+/// Params of `auth`. The transport handles it before dispatch; this type only feeds the schema.
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AuthRequest {
+    token: Option<String>,
+    event_stream_id: Option<String>,
+    last_event_seq: Option<u64>,
+}
+
+/// The handler module that answers a method.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Handler {
+    /// Answered by the transport before dispatch (`auth`).
+    Transport,
+    Daemon,
+    Workspace,
+    Codex,
+    Git,
+    Prompts,
+    Terminal,
+}
+
+/// How a method's params name the workspaces it acts on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum WorkspaceParams {
+    /// `workspaceId`, `sourceWorkspaceId` or `parentId`, whichever are present.
+    Named,
+    /// The `id` param too: methods on a workspace entry itself.
+    Id,
+    /// Results span every workspace, so only tokens without a workspace scope may call it.
+    Every,
+    /// `workspaceId` filters recorded history and may name a removed workspace.
+    Filter,
+    /// Named like `Named`, but the call reaches the whole host (a shell), so only tokens
+    /// without a workspace scope may call it.
+    Host,
+}
+
+pub(crate) struct MethodSpec {
+    pub(crate) name: &'static str,
+    pub(crate) summary: &'static str,
+    params: fn() -> Result<Value, String>,
+    /// Lowest role allowed to call the method.
+    pub(crate) role: DaemonRole,
+    pub(crate) workspaces: WorkspaceParams,
+}
+
+impl MethodSpec {
+    pub(crate) fn params_schema(&self) -> Result<Value, String> {
+        (self.params)()
+    }
+}
+
+macro_rules! method {
+    ($name:expr, $ty:ty, $role:ident, $workspaces:expr, $summary:expr) => {
+        MethodSpec {
+            name: $name,
+            summary: $summary,
+            params: params_schema::<$ty>,
+            role: DaemonRole::$role,
+            workspaces: $workspaces,
+        }
+    };
+    ($name:expr, $ty:ty, $role:ident, $summary:expr) => {
+        method!($name, $ty, $role, WorkspaceParams::Named, $summary)
+    };
+}
+
+/// Every method the daemon answers, grouped by handler module, with the request type the
+/// handler parses. `rpc_describe`, authorization and dispatch all read this table.
+static REGISTRY: &[(Handler, &[MethodSpec])] = &[
+    (
+        Handler::Transport,
+        &[
+            method!(
+                "auth",
+                AuthRequest,
+                Admin,
+                "Authenticate the connection and (re)subscribe to events."
+            ),
+        ],
+    ),
+    (
+        Handler::Daemon,
+        &[
+            method!("ping", (), Observer, "Check that the daemon is responsive."),
+            method!("daemon_info", (), Observer, "Daemon name, version and listen mode."),
+            method!("daemon_shutdown", (), Admin, "Stop the daemon process."),
+            method!(
+                "daemon_audit_query",
+                AuditQueryRequest,
+                Admin,
+                WorkspaceParams::Filter,
+                "Read entries from the audit log of mutating calls."
+            ),
+            method!("rpc_describe", (), Observer, "This OpenRPC document describing every method."),
+            method!(
+                "menu_set_accelerators",
+                MenuAcceleratorsRequest,
+                Admin,
+                "Update menu keyboard accelerators."
+            ),
+            method!(
+                "is_macos_debug_build",
+                (),
+                Observer,
+                "Whether the daemon is a macOS debug build."
+            ),
+            method!(
+                "send_notification_fallback",
+                NotificationRequest,
+                Operator,
+                "Show a desktop notification on the daemon host."
+            ),
+        ],
+    ),
+    (
+        Handler::Workspace,
+        &[
+            method!("list_workspaces", (), Observer, "List registered workspaces."),
+            method!(
+                "is_workspace_path_dir",
+                workspace_rpc::IsWorkspacePathDirRequest,
+                Observer,
+                "Whether a path on the daemon host is a directory."
+            ),
+            method!(
+                "add_workspace",
+                workspace_rpc::AddWorkspaceRequest,
+                Admin,
+                "Register a local directory as a workspace."
+            ),
+            method!(
+                "add_workspace_from_git_url",
+                workspace_rpc::AddWorkspaceFromGitUrlRequest,
+                Admin,
+                "Clone a repository and register it as a workspace."
+            ),
+            method!(
+                "add_worktree",
+                workspace_rpc::AddWorktreeRequest,
+                Operator,
+                "Create a git worktree workspace under a parent workspace."
+            ),
+            method!(
+                "worktree_setup_status",
+                workspace_rpc::WorkspaceIdRequest,
+                Observer,
+                "Whether a worktree's setup script still needs to run."
+            ),
+            method!(
+                "worktree_setup_mark_ran",
+                workspace_rpc::WorkspaceIdRequest,
+                Operator,
+                "Record that a worktree's setup script has run."
+            ),
+            method!(
+                "connect_workspace",
+                workspace_rpc::IdRequest,
+                Operator,
+                WorkspaceParams::Id,
+                "Start the app-server session for a workspace."
+            ),
+            method!(
+                "set_workspace_runtime_codex_args",
+                workspace_rpc::SetWorkspaceRuntimeCodexArgsRequest,
+                Admin,
+                "Override the codex arguments for a running workspace."
+            ),
+            method!(
+                "remove_workspace",
+                workspace_rpc::IdRequest,
+                Admin,
+                WorkspaceParams::Id,
+                "Unregister a workspace."
+            ),
+            method!(
+                "remove_worktree",
+                workspace_rpc::IdRequest,
+                Admin,
+                WorkspaceParams::Id,
+                "Delete a worktree workspace and its checkout."
+            ),
+            method!(
+                "rename_worktree",
+                workspace_rpc::RenameWorktreeRequest,
+                Operator,
+                WorkspaceParams::Id,
+                "Rename a worktree's branch."
+            ),
+            method!(
+                "rename_worktree_upstream",
+                workspace_rpc::RenameWorktreeUpstreamRequest,
+                Operator,
+                WorkspaceParams::Id,
+                "Rename a worktree's upstream branch."
+            ),
+            method!(
+                "update_workspace_settings",
+                workspace_rpc::UpdateWorkspaceSettingsRequest,
+                Admin,
+                WorkspaceParams::Id,
+                "Replace a workspace's settings."
+            ),
+            method!(
+                "list_workspace_files",
+                workspace_rpc::WorkspaceIdRequest,
+                Observer,
+                "List files in a workspace, honoring ignore files."
+            ),
+            method!(
+                "read_workspace_file",
+                workspace_rpc::ReadWorkspaceFileRequest,
+                Observer,
+                "Read a file inside a workspace."
+            ),
+            method!(
+                "add_clone",
+                workspace_rpc::AddCloneRequest,
+                Admin,
+                "Copy a workspace into a sibling clone workspace."
+            ),
+            method!(
+                "file_read",
+                FileReadRequest,
+                Observer,
+                "Read a policy-managed file such as AGENTS.md or config.toml."
+            ),
+            method!(
+                "file_write",
+                FileWriteRequest,
+                Admin,
+                "Write a policy-managed file such as AGENTS.md or config.toml."
+            ),
+            method!(
+                "get_app_settings",
+                (),
+                Observer,
+                WorkspaceParams::Every,
+                "Read the app settings."
+            ),
+            method!(
+                "update_app_settings",
+                UpdateAppSettingsRequest,
+                Admin,
+                "Replace the app settings."
+            ),
+            method!(
+                "apply_worktree_changes",
+                workspace_rpc::WorkspaceIdRequest,
+                Operator,
+                "Apply a worktree's changes to its parent checkout."
+            ),
+            method!(
+                "preview_land_worktree",
+                workspace_rpc::WorkspaceIdRequest,
+                Observer,
+                "Commits and combined diff a worktree branch would land on its parent's branch."
+            ),
+            method!(
+                "land_worktree",
+                workspace_rpc::LandWorktreeRequest,
+                Operator,
+                "Fast-forward, squash or rebase a worktree branch onto its parent's branch; removing the worktree needs admin."
+            ),
+            method!(
+                "open_workspace_in",
+                workspace_rpc::OpenWorkspaceInRequest,
+                Admin,
+                "Open a path in an editor or app on the daemon host."
+            ),
+            method!(
+                "get_open_app_icon",
+                workspace_rpc::GetOpenAppIconRequest,
+                Observer,
+                "Icon of an app available to open_workspace_in."
+            ),
+            method!(
+                "local_usage_snapshot",
+                LocalUsageSnapshotRequest,
+                Observer,
+                WorkspaceParams::Every,
+                "Token usage aggregated from local session logs."
+            ),
+            method!(
+                "local_usage_thread_timeline",
+                LocalUsageThreadTimelineRequest,
+                Observer,
+                WorkspaceParams::Every,
+                "Token usage of one thread from its session log, per user turn."
+            ),
+            method!(
+                "local_usage_export",
+                LocalUsageExportRequest,
+                Observer,
+                WorkspaceParams::Every,
+                "Local usage as CSV (one row per day, model and workspace) or Prometheus text."
+            ),
+            method!(
+                "local_usage_watch",
+                LocalUsageWatchRequest,
+                Observer,
+                WorkspaceParams::Every,
+                "Push local-usage-updated events whenever session logs in the window change."
+            ),
+            method!(
+                "local_usage_unwatch",
+                LocalUsageWatchRequest,
+                Observer,
+                WorkspaceParams::Every,
+                "Stop a local_usage_watch for the same window."
+            ),
+            method!(
+                "usage_budget_status",
+                (),
+                Observer,
+                "Usage of each configured budget in its current day or week."
+            ),
+            method!(
+                "override_usage_budget",
+                OverrideUsageBudgetRequest,
+                Operator,
+                "Allow send_user_message past a blocking budget until its period ends."
+            ),
+            method!(
+                "search_threads",
+                SearchThreadsRequest,
+                Observer,
+                "Full-text search over local session logs, grouped by thread with snippets."
+            ),
+        ],
+    ),
+    (
+        Handler::Codex,
+        &[
+            method!("get_codex_config_path", (), Observer, "Path of the codex config.toml."),
+            method!(
+                "get_config_model",
+                workspace_rpc::WorkspaceIdRequest,
+                Observer,
+                "Model configured for a workspace."
+            ),
+            method!(
+                "start_thread",
+                workspace_rpc::WorkspaceIdRequest,
+                Operator,
+                "Start a new thread."
+            ),
+            method!("resume_thread", ThreadRequest, Observer, "Resume an existing thread."),
+            method!(
+                "export_thread",
+                ExportThreadRequest,
+                Observer,
+                "Render a thread's session log as Markdown, HTML or a JSON bundle."
+            ),
+            method!(
+                "import_thread",
+                ImportThreadRequest,
+                Operator,
+                "Write an exported thread bundle into the workspace's sessions as a new thread."
+            ),
+            method!(
+                "thread_live_subscribe",
+                ThreadRequest,
+                Observer,
+                "Subscribe to live updates for a thread."
+            ),
+            method!(
+                "thread_live_unsubscribe",
+                ThreadRequest,
+                Observer,
+                "Stop live updates for a thread."
+            ),
+            method!("fork_thread", ThreadRequest, Operator, "Fork a thread."),
+            method!(
+                "list_threads",
+                ListThreadsRequest,
+                Observer,
+                "List a workspace's threads, one page at a time."
+            ),
+            method!(
+                "list_mcp_server_status",
+                PageRequest,
+                Observer,
+                "Status of the configured MCP servers."
+            ),
+            method!("archive_thread", ThreadRequest, Operator, "Archive a thread."),
+            method!("compact_thread", ThreadRequest, Operator, "Compact a thread's context."),
+            method!("set_thread_name", SetThreadNameRequest, Operator, "Rename a thread."),
+            method!(
+                "send_user_message",
+                SendUserMessageRequest,
+                Operator,
+                "Send a user message, starting a turn."
+            ),
+            method!("turn_interrupt", TurnRequest, Operator, "Interrupt a running turn."),
+            method!("turn_steer", TurnSteerRequest, Operator, "Add input to a running turn."),
+            method!("start_review", StartReviewRequest, Operator, "Start a code review."),
+            method!(
+                "model_list",
+                workspace_rpc::WorkspaceIdRequest,
+                Observer,
+                "Models available to a workspace."
+            ),
+            method!(
+                "experimental_feature_list",
+                PageRequest,
+                Observer,
+                "Experimental codex features and their state."
+            ),
+            method!(
+                "collaboration_mode_list",
+                workspace_rpc::WorkspaceIdRequest,
+                Observer,
+                "Available collaboration modes."
+            ),
+            method!(
+                "set_codex_feature_flag",
+                FeatureFlagRequest,
+                Admin,
+                "Enable or disable a codex feature flag."
+            ),
+            method!("get_agents_settings", (), Observer, "Read the agents settings."),
+            method!(
+                "set_agents_core_settings",
+                InputRequest<SetAgentsCoreInput>,
+                Admin,
+                "Update the core agents settings."
+            ),
+            method!("create_agent", InputRequest<CreateAgentInput>, Admin, "Create an agent."),
+            method!("update_agent", InputRequest<UpdateAgentInput>, Admin, "Update an agent."),
+            method!("delete_agent", InputRequest<DeleteAgentInput>, Admin, "Delete an agent."),
+            method!(
+                "read_agent_config_toml",
+                AgentNameRequest,
+                Observer,
+                "Read an agent's config.toml."
+            ),
+            method!(
+                "write_agent_config_toml",
+                AgentConfigRequest,
+                Admin,
+                "Write an agent's config.toml."
+            ),
+            method!(
+                "account_rate_limits",
+                workspace_rpc::WorkspaceIdRequest,
+                Observer,
+                "Current account rate limits."
+            ),
+            method!(
+                "account_read",
+                workspace_rpc::WorkspaceIdRequest,
+                Observer,
+                "Current account details."
+            ),
+            method!(
+                "codex_login",
+                workspace_rpc::WorkspaceIdRequest,
+                Admin,
+                "Start the codex login flow."
+            ),
+            method!(
+                "codex_login_cancel",
+                workspace_rpc::WorkspaceIdRequest,
+                Admin,
+                "Cancel a pending codex login."
+            ),
+            method!(
+                "skills_list",
+                workspace_rpc::WorkspaceIdRequest,
+                Observer,
+                "Skills available to a workspace."
+            ),
+            method!(
+                "apps_list",
+                AppsListRequest,
+                Observer,
+                "Apps available to a workspace or thread."
+            ),
+            method!(
+                "respond_to_server_request",
+                ServerRequestResponse,
+                Operator,
+                "Answer an app-server request such as an approval."
+            ),
+            method!(
+                "remember_approval_rule",
+                ApprovalRuleRequest,
+                Admin,
+                "Persist an approval rule for a command prefix."
+            ),
+            method!(
+                "list_codex_rules",
+                workspace_rpc::WorkspaceIdRequest,
+                Observer,
+                "Parse the workspace's Codex rules files and report duplicates and conflicts."
+            ),
+            method!(
+                "update_codex_rule",
+                UpdateCodexRuleRequest,
+                Admin,
+                "Rewrite one Codex rule in place, keeping the rest of its file."
+            ),
+            method!(
+                "delete_codex_rule",
+                CodexRuleRequest,
+                Admin,
+                "Remove one Codex rule from its rules file."
+            ),
+            method!(
+                "explain_approval_policy",
+                ExplainApprovalPolicyRequest,
+                Observer,
+                "Dry-run the workspace approval rules against a request, without answering it."
+            ),
+            method!(
+                "codex_doctor",
+                CodexDoctorRequest,
+                Operator,
+                "Check that the codex binary runs."
+            ),
+            method!(
+                "generate_run_metadata",
+                RunMetadataRequest,
+                Operator,
+                "Generate a title and branch name for a prompt."
+            ),
+            method!(
+                "generate_agent_description",
+                AgentDescriptionRequest,
+                Operator,
+                "Generate an agent definition from a description."
+            ),
+        ],
+    ),
+    (
+        Handler::Git,
+        &[
+            method!(
+                git_rpc::METHOD_GET_GIT_STATUS,
+                git_rpc::WorkspaceIdRequest,
+                Observer,
+                "Working tree status."
+            ),
+            method!(
+                git_rpc::METHOD_INIT_GIT_REPO,
+                git_rpc::InitGitRepoRequest,
+                Operator,
+                "Initialize a git repository."
+            ),
+            method!(
+                git_rpc::METHOD_CREATE_GITHUB_REPO,
+                git_rpc::CreateGitHubRepoRequest,
+                Operator,
+                "Create a GitHub repository for a workspace."
+            ),
+            method!(
+                git_rpc::METHOD_LIST_GIT_ROOTS,
+                git_rpc::ListGitRootsRequest,
+                Observer,
+                "Git repositories found under a workspace."
+            ),
+            method!(
+                git_rpc::METHOD_GET_GIT_DIFFS,
+                git_rpc::WorkspaceIdRequest,
+                Observer,
+                "Diffs of changed files."
+            ),
+            method!(
+                git_rpc::METHOD_GET_GIT_LOG,
+                git_rpc::GetGitLogRequest,
+                Observer,
+                "Commits with author, date, message, path, branch and range filters, paged by cursor."
+            ),
+            method!(
+                git_rpc::METHOD_GET_GIT_COMMIT_DIFF,
+                git_rpc::WorkspaceShaRequest,
+                Observer,
+                "Diff of a single commit."
+            ),
+            method!(
+                git_rpc::METHOD_GET_GIT_REMOTE,
+                git_rpc::WorkspaceIdRequest,
+                Observer,
+                "The repository's remote."
+            ),
+            method!(
+                git_rpc::METHOD_STAGE_GIT_FILE,
+                git_rpc::WorkspacePathRequest,
+                Operator,
+                "Stage a file."
+            ),
+            method!(
+                git_rpc::METHOD_STAGE_GIT_ALL,
+                git_rpc::WorkspaceIdRequest,
+                Operator,
+                "Stage all changes."
+            ),
+            method!(
+                git_rpc::METHOD_UNSTAGE_GIT_FILE,
+                git_rpc::WorkspacePathRequest,
+                Operator,
+                "Unstage a file."
+            ),
+            method!(
+                git_rpc::METHOD_REVERT_GIT_FILE,
+                git_rpc::WorkspacePathRequest,
+                Operator,
+                "Discard changes to a file."
+            ),
+            method!(
+                git_rpc::METHOD_STAGE_GIT_SELECTION,
+                git_rpc::WorkspaceSelectionRequest,
+                Operator,
+                "Stage chosen hunks or lines of a file."
+            ),
+            method!(
+                git_rpc::METHOD_UNSTAGE_GIT_SELECTION,
+                git_rpc::WorkspaceSelectionRequest,
+                Operator,
+                "Unstage chosen hunks or lines of a file."
+            ),
+            method!(
+                git_rpc::METHOD_REVERT_GIT_SELECTION,
+                git_rpc::WorkspaceSelectionRequest,
+                Operator,
+                "Discard chosen hunks or lines of a file."
+            ),
+            method!(
+                git_rpc::METHOD_REVERT_GIT_ALL,
+                git_rpc::WorkspaceIdRequest,
+                Operator,
+                "Discard all changes."
+            ),
+            method!(
+                git_rpc::METHOD_COMMIT_GIT,
+                git_rpc::WorkspaceMessageRequest,
+                Operator,
+                "Commit staged changes."
+            ),
+            method!(
+                git_rpc::METHOD_PUSH_GIT,
+                git_rpc::WorkspaceIdRequest,
+                Operator,
+                "Push the current branch."
+            ),
+            method!(
+                git_rpc::METHOD_PULL_GIT,
+                git_rpc::WorkspaceIdRequest,
+                Operator,
+                "Pull the current branch."
+            ),
+            method!(
+                git_rpc::METHOD_FETCH_GIT,
+                git_rpc::WorkspaceIdRequest,
+                Operator,
+                "Fetch from the remote."
+            ),
+            method!(
+                git_rpc::METHOD_SYNC_GIT,
+                git_rpc::WorkspaceIdRequest,
+                Operator,
+                "Pull then push the current branch."
+            ),
+            method!(
+                git_rpc::METHOD_LIST_GIT_STASHES,
+                git_rpc::WorkspaceIdRequest,
+                Observer,
+                "Stashes with message, branch and timestamp."
+            ),
+            method!(
+                git_rpc::METHOD_GET_GIT_STASH_DIFF,
+                git_rpc::WorkspaceStashActionRequest,
+                Observer,
+                "Per-file diffs of a stash."
+            ),
+            method!(
+                git_rpc::METHOD_CREATE_GIT_STASH,
+                git_rpc::CreateGitStashRequest,
+                Operator,
+                "Stash local changes."
+            ),
+            method!(
+                git_rpc::METHOD_APPLY_GIT_STASH,
+                git_rpc::WorkspaceStashActionRequest,
+                Operator,
+                "Apply a stash and keep it."
+            ),
+            method!(
+                git_rpc::METHOD_POP_GIT_STASH,
+                git_rpc::WorkspaceStashActionRequest,
+                Operator,
+                "Apply a stash and drop it."
+            ),
+            method!(
+                git_rpc::METHOD_DROP_GIT_STASH,
+                git_rpc::WorkspaceStashActionRequest,
+                Operator,
+                "Delete a stash."
+            ),
+            method!(
+                git_rpc::METHOD_MERGE_GIT_BRANCH,
+                git_rpc::WorkspaceNameRequest,
+                Operator,
+                "Merge a branch into the current one; conflicts are reported, not errors."
+            ),
+            method!(
+                git_rpc::METHOD_REBASE_GIT_BRANCH,
+                git_rpc::WorkspaceNameRequest,
+                Operator,
+                "Rebase the current branch onto another."
+            ),
+            method!(
+                git_rpc::METHOD_CONTINUE_GIT_OPERATION,
+                git_rpc::WorkspaceIdRequest,
+                Operator,
+                "Continue the merge or rebase once conflicts are resolved."
+            ),
+            method!(
+                git_rpc::METHOD_SKIP_GIT_OPERATION,
+                git_rpc::WorkspaceIdRequest,
+                Operator,
+                "Skip the commit a rebase stopped on."
+            ),
+            method!(
+                git_rpc::METHOD_ABORT_GIT_OPERATION,
+                git_rpc::WorkspaceIdRequest,
+                Operator,
+                "Abort the merge or rebase in progress."
+            ),
+            method!(
+                git_rpc::METHOD_GET_GIT_CONFLICT,
+                git_rpc::WorkspacePathRequest,
+                Observer,
+                "Base, ours, theirs and working tree contents of a conflicted file."
+            ),
+            method!(
+                git_rpc::METHOD_RESOLVE_GIT_CONFLICT,
+                git_rpc::ResolveGitConflictRequest,
+                Operator,
+                "Resolve a conflicted file with ours, theirs or merged content."
+            ),
+            method!(
+                git_rpc::METHOD_GET_GITHUB_ISSUES,
+                git_rpc::WorkspaceIdRequest,
+                Observer,
+                "Open GitHub issues."
+            ),
+            method!(
+                git_rpc::METHOD_GET_GITHUB_PULL_REQUESTS,
+                git_rpc::WorkspaceIdRequest,
+                Observer,
+                "Open GitHub pull requests."
+            ),
+            method!(
+                git_rpc::METHOD_GET_GITHUB_PULL_REQUEST_DIFF,
+                git_rpc::GitHubPullRequestRequest,
+                Observer,
+                "Diff of a GitHub pull request."
+            ),
+            method!(
+                git_rpc::METHOD_GET_GITHUB_PULL_REQUEST_COMMENTS,
+                git_rpc::GitHubPullRequestRequest,
+                Observer,
+                "Comments on a GitHub pull request."
+            ),
+            method!(
+                git_rpc::METHOD_CHECKOUT_GITHUB_PULL_REQUEST,
+                git_rpc::GitHubPullRequestRequest,
+                Operator,
+                "Check out a GitHub pull request."
+            ),
+            method!(
+                git_rpc::METHOD_LIST_GIT_BRANCHES,
+                git_rpc::WorkspaceIdRequest,
+                Observer,
+                "Local and remote branches."
+            ),
+            method!(
+                git_rpc::METHOD_CHECKOUT_GIT_BRANCH,
+                git_rpc::WorkspaceNameRequest,
+                Operator,
+                "Check out a branch."
+            ),
+            method!(
+                git_rpc::METHOD_CREATE_GIT_BRANCH,
+                git_rpc::WorkspaceNameRequest,
+                Operator,
+                "Create and check out a branch."
+            ),
+            method!(
+                git_rpc::METHOD_GENERATE_COMMIT_MESSAGE,
+                git_rpc::GenerateCommitMessageRequest,
+                Operator,
+                "Generate a commit message for the staged changes."
+            ),
+        ],
+    ),
+    (
+        Handler::Prompts,
+        &[
+            method!(
+                "prompts_list",
+                workspace_rpc::WorkspaceIdRequest,
+                Observer,
+                "List custom prompts."
+            ),
+            method!(
+                "prompts_workspace_dir",
+                workspace_rpc::WorkspaceIdRequest,
+                Observer,
+                "Directory holding a workspace's prompts."
+            ),
+            method!(
+                "prompts_global_dir",
+                workspace_rpc::WorkspaceIdRequest,
+                Observer,
+                "Directory holding global prompts."
+            ),
+            method!("prompts_create", PromptCreateRequest, Operator, "Create a custom prompt."),
+            method!("prompts_update", PromptUpdateRequest, Operator, "Update a custom prompt."),
+            method!("prompts_delete", PromptPathRequest, Operator, "Delete a custom prompt."),
+            method!(
+                "prompts_move",
+                PromptMoveRequest,
+                Operator,
+                "Move a prompt between workspace and global scope."
+            ),
+        ],
+    ),
+    (
+        Handler::Terminal,
+        &[
+            method!(
+                "terminal_open",
+                TerminalSizeRequest,
+                Admin,
+                WorkspaceParams::Host,
+                "Open a terminal session in a workspace."
+            ),
+            method!(
+                "terminal_write",
+                TerminalWriteRequest,
+                Admin,
+                WorkspaceParams::Host,
+                "Write input to a terminal session."
+            ),
+            method!(
+                "terminal_resize",
+                TerminalSizeRequest,
+                Admin,
+                WorkspaceParams::Host,
+                "Resize a terminal session."
+            ),
+            method!(
+                "terminal_close",
+                TerminalRequest,
+                Admin,
+                WorkspaceParams::Host,
+                "Close a terminal session."
+            ),
+        ],
+    ),
+];
+
+pub(crate) fn methods() -> impl Iterator<Item = (Handler, &'static MethodSpec)> {
+    REGISTRY
+        .iter()
+        .flat_map(|(handler, specs)| specs.iter().map(move |spec| (*handler, spec)))
+}
+
+pub(crate) fn find_method(name: &str) -> Option<(Handler, &'static MethodSpec)> {
+    methods().find(|(_, spec)| spec.name == name)
+}
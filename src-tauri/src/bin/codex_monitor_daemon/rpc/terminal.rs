@@ -0,0 +1,85 @@
+use super::*;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct TerminalSizeRequest {
+    workspace_id: String,
+    terminal_id: String,
+    cols: u16,
+    rows: u16,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct TerminalWriteRequest {
+    workspace_id: String,
+    terminal_id: String,
+    data: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct TerminalRequest {
+    workspace_id: String,
+    terminal_id: String,
+}
+
+pub(super) async fn try_handle(
+    state: &DaemonState,
+    method: &str,
+    params: &Value,
+) -> Option<Result<Value, RpcError>> {
+    match method {
+        "terminal_open" => {
+            let request = parse_request_or_err!(params, TerminalSizeRequest);
+            Some(
+                state
+                    .terminal_open(
+                        request.workspace_id,
+                        request.terminal_id,
+                        request.cols,
+                        request.rows,
+                    )
+                    .await
+                    .and_then(|value| serde_json::to_value(value).map_err(|err| err.to_string()))
+                    .map_err(RpcError::from),
+            )
+        }
+        "terminal_write" => {
+            let request = parse_request_or_err!(params, TerminalWriteRequest);
+            Some(
+                state
+                    .terminal_write(request.workspace_id, request.terminal_id, request.data)
+                    .await
+                    .map(|_| json!({ "ok": true }))
+                    .map_err(RpcError::from),
+            )
+        }
+        "terminal_resize" => {
+            let request = parse_request_or_err!(params, TerminalSizeRequest);
+            Some(
+                state
+                    .terminal_resize(
+                        request.workspace_id,
+                        request.terminal_id,
+                        request.cols,
+                        request.rows,
+                    )
+                    .await
+                    .map(|_| json!({ "ok": true }))
+                    .map_err(RpcError::from),
+            )
+        }
+        "terminal_close" => {
+            let request = parse_request_or_err!(params, TerminalRequest);
+            Some(
+                state
+                    .terminal_close(request.workspace_id, request.terminal_id)
+                    .await
+                    .map(|_| json!({ "ok": true }))
+                    .map_err(RpcError::from),
+            )
+        }
+        _ => None,
+    }
+}
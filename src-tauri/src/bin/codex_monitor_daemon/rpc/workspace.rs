@@ -1,12 +1,12 @@
 use super::*;
+use crate::access::RpcCaller;
 use crate::shared::workspace_rpc;
-use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::future::Future;
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct FileReadRequest {
+pub(super) struct FileReadRequest {
     scope: file_policy::FileScope,
     kind: file_policy::FileKind,
     workspace_id: Option<String>,
@@ -14,59 +14,95 @@ struct FileReadRequest {
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct FileWriteRequest {
+pub(super) struct FileWriteRequest {
     scope: file_policy::FileScope,
     kind: file_policy::FileKind,
     workspace_id: Option<String>,
     content: String,
 }
 
-fn parse_file_read_request(params: &Value) -> Result<FileReadRequest, String> {
-    serde_json::from_value(params.clone()).map_err(|err| err.to_string())
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct LocalUsageSnapshotRequest {
+    days: Option<u32>,
+    workspace_path: Option<String>,
 }
 
-fn parse_file_write_request(params: &Value) -> Result<FileWriteRequest, String> {
-    serde_json::from_value(params.clone()).map_err(|err| err.to_string())
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct LocalUsageExportRequest {
+    format: LocalUsageExportFormat,
+    days: Option<u32>,
+    workspace_path: Option<String>,
 }
 
-fn parse_workspace_request<T: DeserializeOwned>(params: &Value) -> Result<T, String> {
-    workspace_rpc::from_params(params)
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct LocalUsageWatchRequest {
+    days: Option<u32>,
+    workspace_path: Option<String>,
 }
 
-fn serialize_value<T: Serialize>(value: T) -> Result<Value, String> {
-    serde_json::to_value(value).map_err(|err| err.to_string())
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct LocalUsageThreadTimelineRequest {
+    thread_id: String,
 }
 
-async fn serialize_result<T, Fut>(future: Fut) -> Result<Value, String>
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct OverrideUsageBudgetRequest {
+    budget_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct SearchThreadsRequest {
+    query: String,
+    /// Only threads whose cwd lies inside this workspace.
+    workspace_id: Option<String>,
+    limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct UpdateAppSettingsRequest {
+    settings: AppSettings,
+}
+
+fn serialize_value<T: Serialize>(value: T) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|err| RpcError::from(err.to_string()))
+}
+
+async fn serialize_result<T, E, Fut>(future: Fut) -> Result<Value, RpcError>
 where
     T: Serialize,
-    Fut: Future<Output = Result<T, String>>,
+    RpcError: From<E>,
+    Fut: Future<Output = Result<T, E>>,
 {
-    future.await.and_then(serialize_value)
+    future
+        .await
+        .map_err(RpcError::from)
+        .and_then(serialize_value)
 }
 
-async fn serialize_ok<Fut>(future: Fut) -> Result<Value, String>
+async fn serialize_ok<E, Fut>(future: Fut) -> Result<Value, RpcError>
 where
-    Fut: Future<Output = Result<(), String>>,
+    RpcError: From<E>,
+    Fut: Future<Output = Result<(), E>>,
 {
-    future.await.map(|_| json!({ "ok": true }))
-}
-
-macro_rules! parse_request_or_err {
-    ($params:expr, $ty:ty) => {
-        match parse_workspace_request::<$ty>($params) {
-            Ok(value) => value,
-            Err(err) => return Some(Err(err)),
-        }
-    };
+    future
+        .await
+        .map(|_| json!({ "ok": true }))
+        .map_err(RpcError::from)
 }
 
 pub(super) async fn try_handle(
     state: &DaemonState,
     method: &str,
     params: &Value,
-    client_version: &str,
-) -> Option<Result<Value, String>> {
+    caller: &RpcCaller,
+) -> Option<Result<Value, RpcError>> {
+    let client_version = caller.client_version.as_str();
     match method {
         "list_workspaces" => Some(serialize_value(state.list_workspaces().await)),
         "is_workspace_path_dir" => {
@@ -201,10 +237,7 @@ pub(super) async fn try_handle(
             )
         }
         "file_read" => {
-            let request = match parse_file_read_request(params) {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
+            let request = parse_request_or_err!(params, FileReadRequest);
             Some(
                 serialize_result(state.file_read(
                     request.scope,
@@ -215,10 +248,7 @@ pub(super) async fn try_handle(
             )
         }
         "file_write" => {
-            let request = match parse_file_write_request(params) {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
+            let request = parse_request_or_err!(params, FileWriteRequest);
             Some(
                 serialize_ok(state.file_write(
                     request.scope,
@@ -231,20 +261,29 @@ pub(super) async fn try_handle(
         }
         "get_app_settings" => Some(serialize_value(state.get_app_settings().await)),
         "update_app_settings" => {
-            let settings_value = match params {
-                Value::Object(map) => map.get("settings").cloned().unwrap_or(Value::Null),
-                _ => Value::Null,
-            };
-            let settings: AppSettings = match serde_json::from_value(settings_value) {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err.to_string())),
-            };
-            Some(serialize_result(state.update_app_settings(settings)).await)
+            let request = parse_request_or_err!(params, UpdateAppSettingsRequest);
+            Some(serialize_result(state.update_app_settings(request.settings)).await)
         }
         "apply_worktree_changes" => {
             let request = parse_request_or_err!(params, workspace_rpc::WorkspaceIdRequest);
             Some(serialize_ok(state.apply_worktree_changes(request.workspace_id)).await)
         }
+        "preview_land_worktree" => {
+            let request = parse_request_or_err!(params, workspace_rpc::WorkspaceIdRequest);
+            Some(serialize_result(state.preview_land_worktree(request.workspace_id)).await)
+        }
+        "land_worktree" => {
+            let request = parse_request_or_err!(params, workspace_rpc::LandWorktreeRequest);
+            Some(
+                serialize_result(state.land_worktree(
+                    request.workspace_id,
+                    request.strategy,
+                    request.message,
+                    request.remove_worktree.unwrap_or(false),
+                ))
+                .await,
+            )
+        }
         "open_workspace_in" => {
             let request = parse_request_or_err!(params, workspace_rpc::OpenWorkspaceInRequest);
             Some(
@@ -264,9 +303,68 @@ pub(super) async fn try_handle(
             Some(serialize_result(state.get_open_app_icon(request.app_name)).await)
         }
         "local_usage_snapshot" => {
-            let days = parse_optional_u32(params, "days");
-            let workspace_path = parse_optional_string(params, "workspacePath");
-            Some(serialize_result(state.local_usage_snapshot(days, workspace_path)).await)
+            let request = parse_request_or_err!(params, LocalUsageSnapshotRequest);
+            Some(
+                serialize_result(state.local_usage_snapshot(request.days, request.workspace_path))
+                    .await,
+            )
+        }
+        "local_usage_export" => {
+            let request = parse_request_or_err!(params, LocalUsageExportRequest);
+            Some(
+                serialize_result(state.local_usage_export(
+                    request.format,
+                    request.days,
+                    request.workspace_path,
+                ))
+                .await,
+            )
+        }
+        "local_usage_watch" => {
+            let request = parse_request_or_err!(params, LocalUsageWatchRequest);
+            Some(
+                state
+                    .local_usage_watch(caller.connection_id, request.days, request.workspace_path)
+                    .await
+                    .map(|_| json!({ "ok": true }))
+                    .map_err(RpcError::from),
+            )
+        }
+        "local_usage_unwatch" => {
+            let request = parse_request_or_err!(params, LocalUsageWatchRequest);
+            Some(
+                state
+                    .local_usage_unwatch(caller.connection_id, request.days, request.workspace_path)
+                    .map(|_| json!({ "ok": true }))
+                    .map_err(RpcError::from),
+            )
+        }
+        "local_usage_thread_timeline" => {
+            let request = parse_request_or_err!(params, LocalUsageThreadTimelineRequest);
+            Some(serialize_result(state.local_usage_thread_timeline(request.thread_id)).await)
+        }
+        "usage_budget_status" => Some(serialize_result(state.usage_budget_status()).await),
+        "override_usage_budget" => {
+            let request = parse_request_or_err!(params, OverrideUsageBudgetRequest);
+            Some(
+                state
+                    .override_usage_budget(request.budget_id)
+                    .await
+                    .map(|_| json!({ "ok": true }))
+                    .map_err(RpcError::from),
+            )
+        }
+        "search_threads" => {
+            let request = parse_request_or_err!(params, SearchThreadsRequest);
+            Some(
+                serialize_result(state.search_threads(
+                    request.query,
+                    request.workspace_id,
+                    request.limit,
+                    caller.access.workspace_scope().cloned(),
+                ))
+                .await,
+            )
         }
         _ => None,
     }
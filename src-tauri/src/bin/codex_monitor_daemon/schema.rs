@@ -0,0 +1,545 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fmt;
+
+use serde::de::value::StrDeserializer;
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess,
+    Visitor,
+};
+use serde_json::{json, Map, Value};
+
+/// Field names from the request root down to a struct, with `[]` for sequence items and
+/// `{}` for map values.
+type FieldPath = Vec<String>;
+
+#[derive(Default)]
+struct TraceState {
+    /// Every struct field presented during the full pass.
+    fields: Vec<(FieldPath, String)>,
+    record: bool,
+    /// Field left out while probing whether it is required.
+    omit: Option<(FieldPath, String)>,
+    /// Alias keys; sending them next to their primary field is a duplicate.
+    aliases: HashSet<(FieldPath, String)>,
+    last_key: Option<(FieldPath, String)>,
+}
+
+impl TraceState {
+    fn presents(&self, path: &FieldPath, field: &str) -> bool {
+        let key = (path.clone(), field.to_string());
+        !self.aliases.contains(&key) && self.omit.as_ref() != Some(&key)
+    }
+}
+
+#[derive(Debug)]
+struct TraceError(String);
+
+impl fmt::Display for TraceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for TraceError {}
+
+impl de::Error for TraceError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        TraceError(msg.to_string())
+    }
+}
+
+/// Derives a JSON Schema for the parameters accepted by `T`'s `Deserialize` impl.
+///
+/// `T` is deserialized from a tracing deserializer that records the shape every field asks
+/// for. A field is required when deserializing fails without it, so `Option` fields and
+/// `#[serde(default)]` fields come out optional. Enums are described by their variant names,
+/// which matches the unit enums used in requests.
+pub(crate) fn params_schema<T: DeserializeOwned>() -> Result<Value, String> {
+    let state = RefCell::new(TraceState::default());
+    let mut schema;
+    loop {
+        {
+            let mut state = state.borrow_mut();
+            state.fields.clear();
+            state.record = true;
+        }
+        schema = Value::Null;
+        let result = T::deserialize(Tracer::new(&state, Vec::new(), &mut schema));
+        match result {
+            Ok(_) => break,
+            Err(TraceError(message)) if message.starts_with("duplicate field") => {
+                let mut state = state.borrow_mut();
+                let alias = state.last_key.take().ok_or_else(|| message.clone())?;
+                if !state.aliases.insert(alias) {
+                    return Err(message);
+                }
+            }
+            Err(TraceError(message)) => return Err(message),
+        }
+    }
+
+    let fields = {
+        let mut state = state.borrow_mut();
+        state.record = false;
+        std::mem::take(&mut state.fields)
+    };
+    for (path, field) in fields {
+        state.borrow_mut().omit = Some((path.clone(), field.clone()));
+        let mut scratch = Value::Null;
+        if T::deserialize(Tracer::new(&state, Vec::new(), &mut scratch)).is_err() {
+            mark_required(&mut schema, &path, &field);
+        }
+    }
+    Ok(schema)
+}
+
+fn mark_required(schema: &mut Value, path: &[String], field: &str) {
+    let mut node = Some(schema);
+    for segment in path {
+        node = node.and_then(|node| match segment.as_str() {
+            "[]" => node.get_mut("items"),
+            "{}" => node.get_mut("additionalProperties"),
+            name => node
+                .get_mut("properties")
+                .and_then(|properties| properties.get_mut(name)),
+        });
+    }
+    let Some(Value::Object(node)) = node else {
+        return;
+    };
+    if !node.contains_key("properties") {
+        return;
+    }
+    if let Value::Array(required) = node.entry("required").or_insert_with(|| json!([])) {
+        required.push(json!(field));
+    }
+}
+
+fn child_path(path: &FieldPath, segment: &str) -> FieldPath {
+    let mut child = path.clone();
+    child.push(segment.to_string());
+    child
+}
+
+struct Tracer<'a> {
+    state: &'a RefCell<TraceState>,
+    path: FieldPath,
+    out: &'a mut Value,
+}
+
+impl<'a> Tracer<'a> {
+    fn new(state: &'a RefCell<TraceState>, path: FieldPath, out: &'a mut Value) -> Self {
+        Self { state, path, out }
+    }
+
+    fn traced_seq<'de, V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, TraceError> {
+        let mut items = Value::Null;
+        let value = visitor.visit_seq(SeqTracer {
+            state: self.state,
+            path: child_path(&self.path, "[]"),
+            remaining: len,
+            items: &mut items,
+        })?;
+        *self.out = json!({ "type": "array", "items": items });
+        Ok(value)
+    }
+}
+
+macro_rules! trace_primitive {
+    ($de:lifetime; $($method:ident => $schema:expr, $visit:ident($value:expr);)*) => {
+        $(
+            fn $method<V: Visitor<$de>>(self, visitor: V) -> Result<V::Value, TraceError> {
+                *self.out = $schema;
+                visitor.$visit($value)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Tracer<'_> {
+    type Error = TraceError;
+
+    trace_primitive! {
+        'de;
+        deserialize_bool => json!({ "type": "boolean" }), visit_bool(false);
+        deserialize_i8 => json!({ "type": "integer" }), visit_i64(0);
+        deserialize_i16 => json!({ "type": "integer" }), visit_i64(0);
+        deserialize_i32 => json!({ "type": "integer" }), visit_i64(0);
+        deserialize_i64 => json!({ "type": "integer" }), visit_i64(0);
+        deserialize_u8 => json!({ "type": "integer", "minimum": 0 }), visit_u64(0);
+        deserialize_u16 => json!({ "type": "integer", "minimum": 0 }), visit_u64(0);
+        deserialize_u32 => json!({ "type": "integer", "minimum": 0 }), visit_u64(0);
+        deserialize_u64 => json!({ "type": "integer", "minimum": 0 }), visit_u64(0);
+        deserialize_f32 => json!({ "type": "number" }), visit_f64(0.0);
+        deserialize_f64 => json!({ "type": "number" }), visit_f64(0.0);
+        deserialize_char => json!({ "type": "string" }), visit_char('a');
+        deserialize_str => json!({ "type": "string" }), visit_str("");
+        deserialize_string => json!({ "type": "string" }), visit_str("");
+        deserialize_identifier => json!({ "type": "string" }), visit_str("");
+        deserialize_bytes => json!({ "type": "string" }), visit_bytes(&[]);
+        deserialize_byte_buf => json!({ "type": "string" }), visit_bytes(&[]);
+    }
+
+    /// Untyped fields (`serde_json::Value`) accept anything.
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TraceError> {
+        *self.out = json!({});
+        visitor.visit_unit()
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TraceError> {
+        *self.out = json!({});
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TraceError> {
+        *self.out = json!({ "type": "null" });
+        visitor.visit_unit()
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TraceError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, TraceError> {
+        *self.out = json!({ "type": "null" });
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, TraceError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TraceError> {
+        self.traced_seq(1, visitor)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, TraceError> {
+        self.traced_seq(len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, TraceError> {
+        self.traced_seq(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, TraceError> {
+        let mut values = Value::Null;
+        let value = visitor.visit_map(MapTracer {
+            state: self.state,
+            path: child_path(&self.path, "{}"),
+            done: false,
+            values: &mut values,
+        })?;
+        *self.out = json!({ "type": "object", "additionalProperties": values });
+        Ok(value)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, TraceError> {
+        let keys: Vec<&'static str> = {
+            let state = self.state.borrow();
+            fields
+                .iter()
+                .copied()
+                .filter(|field| state.presents(&self.path, field))
+                .collect()
+        };
+        let mut properties = Map::new();
+        let value = visitor.visit_map(StructTracer {
+            state: self.state,
+            path: &self.path,
+            keys: keys.into_iter(),
+            current: None,
+            properties: &mut properties,
+        })?;
+        *self.out = json!({ "type": "object", "properties": properties });
+        Ok(value)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, TraceError> {
+        let variant = variants
+            .first()
+            .copied()
+            .ok_or_else(|| TraceError(format!("enum {name} has no variants")))?;
+        *self.out = json!({ "type": "string", "enum": variants });
+        visitor.visit_enum(EnumTracer {
+            state: self.state,
+            path: self.path,
+            variant,
+        })
+    }
+}
+
+struct StructTracer<'a, 'p> {
+    state: &'a RefCell<TraceState>,
+    path: &'p FieldPath,
+    keys: std::vec::IntoIter<&'static str>,
+    current: Option<&'static str>,
+    properties: &'p mut Map<String, Value>,
+}
+
+impl<'de> MapAccess<'de> for StructTracer<'_, '_> {
+    type Error = TraceError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, TraceError> {
+        let Some(key) = self.keys.next() else {
+            return Ok(None);
+        };
+        self.current = Some(key);
+        {
+            let mut state = self.state.borrow_mut();
+            let entry = (self.path.clone(), key.to_string());
+            if state.record {
+                state.fields.push(entry.clone());
+            }
+            state.last_key = Some(entry);
+        }
+        seed.deserialize(StrDeserializer::<TraceError>::new(key))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, TraceError> {
+        let key = self
+            .current
+            .take()
+            .ok_or_else(|| TraceError("value requested before key".to_string()))?;
+        let mut schema = Value::Null;
+        let value = seed.deserialize(Tracer::new(
+            self.state,
+            child_path(self.path, key),
+            &mut schema,
+        ))?;
+        self.properties.insert(key.to_string(), schema);
+        Ok(value)
+    }
+}
+
+struct SeqTracer<'a> {
+    state: &'a RefCell<TraceState>,
+    path: FieldPath,
+    remaining: usize,
+    items: &'a mut Value,
+}
+
+impl<'de> SeqAccess<'de> for SeqTracer<'_> {
+    type Error = TraceError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, TraceError> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        let mut schema = Value::Null;
+        let value = seed.deserialize(Tracer::new(self.state, self.path.clone(), &mut schema))?;
+        if self.items.is_null() {
+            *self.items = schema;
+        }
+        Ok(Some(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+struct MapTracer<'a> {
+    state: &'a RefCell<TraceState>,
+    path: FieldPath,
+    done: bool,
+    values: &'a mut Value,
+}
+
+impl<'de> MapAccess<'de> for MapTracer<'_> {
+    type Error = TraceError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, TraceError> {
+        if self.done {
+            return Ok(None);
+        }
+        self.done = true;
+        let mut scratch = Value::Null;
+        seed.deserialize(Tracer::new(self.state, self.path.clone(), &mut scratch))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, TraceError> {
+        seed.deserialize(Tracer::new(self.state, self.path.clone(), self.values))
+    }
+}
+
+struct EnumTracer<'a> {
+    state: &'a RefCell<TraceState>,
+    path: FieldPath,
+    variant: &'static str,
+}
+
+impl<'de, 'a> EnumAccess<'de> for EnumTracer<'a> {
+    type Error = TraceError;
+    type Variant = EnumTracer<'a>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), TraceError> {
+        let value = seed.deserialize(StrDeserializer::<TraceError>::new(self.variant))?;
+        Ok((value, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for EnumTracer<'_> {
+    type Error = TraceError;
+
+    fn unit_variant(self) -> Result<(), TraceError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, TraceError> {
+        let mut scratch = Value::Null;
+        seed.deserialize(Tracer::new(self.state, self.path, &mut scratch))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, TraceError> {
+        let mut scratch = Value::Null;
+        de::Deserializer::deserialize_tuple(
+            Tracer::new(self.state, self.path, &mut scratch),
+            len,
+            visitor,
+        )
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, TraceError> {
+        let mut scratch = Value::Null;
+        de::Deserializer::deserialize_struct(
+            Tracer::new(self.state, self.path, &mut scratch),
+            self.variant,
+            fields,
+            visitor,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::params_schema;
+    use serde::Deserialize;
+    use serde_json::{json, Value};
+
+    #[allow(dead_code)]
+    #[derive(Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum Scope {
+        Workspace,
+        Global,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Nested {
+        path: String,
+        line: Option<u32>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Request {
+        workspace_id: String,
+        #[serde(alias = "max")]
+        limit: Option<u32>,
+        #[serde(default)]
+        force: bool,
+        scope: Scope,
+        files: Vec<Nested>,
+        extra: Value,
+    }
+
+    #[test]
+    fn schema_reports_types_and_required_fields() {
+        let schema = params_schema::<Request>().expect("schema");
+        assert_eq!(schema["type"], json!("object"));
+        assert_eq!(
+            schema["required"],
+            json!(["workspaceId", "scope", "files", "extra"])
+        );
+        let properties = &schema["properties"];
+        assert_eq!(properties["workspaceId"], json!({ "type": "string" }));
+        assert_eq!(
+            properties["limit"],
+            json!({ "type": "integer", "minimum": 0 })
+        );
+        assert!(properties.get("max").is_none());
+        assert_eq!(properties["force"], json!({ "type": "boolean" }));
+        assert_eq!(
+            properties["scope"],
+            json!({ "type": "string", "enum": ["workspace", "global"] })
+        );
+        assert_eq!(properties["files"]["type"], json!("array"));
+        assert_eq!(properties["files"]["items"]["required"], json!(["path"]));
+        assert_eq!(properties["extra"], json!({}));
+    }
+
+    #[test]
+    fn unit_params_have_no_properties() {
+        assert_eq!(
+            params_schema::<()>().expect("schema"),
+            json!({ "type": "null" })
+        );
+    }
+}
@@ -0,0 +1,55 @@
+use super::*;
+
+use shared::tls_core;
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::{self, RootCertStore, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+
+pub(super) struct DaemonTlsConfig {
+    pub(super) cert_path: PathBuf,
+    pub(super) key_path: PathBuf,
+    pub(super) client_ca_path: Option<PathBuf>,
+}
+
+pub(super) struct DaemonTls {
+    pub(super) acceptor: TlsAcceptor,
+    pub(super) fingerprint: String,
+    pub(super) client_auth: bool,
+}
+
+pub(super) fn build_daemon_tls(config: &DaemonTlsConfig) -> Result<DaemonTls, String> {
+    let certs = tls_core::load_certificates(&config.cert_path)?;
+    let key = tls_core::load_private_key(&config.key_path)?;
+    let fingerprint = tls_core::certificate_fingerprint(certs[0].as_ref());
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = ServerConfig::builder_with_provider(Arc::clone(&provider))
+        .with_safe_default_protocol_versions()
+        .map_err(|err| format!("Failed to configure TLS: {err}"))?;
+
+    let builder = match config.client_ca_path.as_ref() {
+        Some(path) => {
+            let mut roots = RootCertStore::empty();
+            for cert in tls_core::load_certificates(path)? {
+                roots
+                    .add(cert)
+                    .map_err(|err| format!("Invalid client CA in {}: {err}", path.display()))?;
+            }
+            let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+                .build()
+                .map_err(|err| format!("Failed to configure client verification: {err}"))?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    let server_config = builder
+        .with_single_cert(certs, key)
+        .map_err(|err| format!("Invalid TLS certificate or key: {err}"))?;
+
+    Ok(DaemonTls {
+        acceptor: TlsAcceptor::from(Arc::new(server_config)),
+        fingerprint,
+        client_auth: config.client_ca_path.is_some(),
+    })
+}
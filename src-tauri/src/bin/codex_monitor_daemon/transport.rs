@@ -1,50 +1,196 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use futures_util::{SinkExt, StreamExt};
+use tokio_rustls::TlsAcceptor;
+use tokio_tungstenite::tungstenite::Message;
+
+use super::access::RpcCaller;
 use super::rpc::{
-    build_error_response, build_result_response, forward_events, parse_auth_token,
-    spawn_rpc_response_task,
+    build_error_response, build_event_gap_notification, build_result_response, forward_events,
+    parse_auth_token, spawn_rpc_response_task, ForwardedEvents,
 };
 use super::*;
 
-pub(super) async fn handle_client(
-    socket: TcpStream,
+/// Ids of client connections, distinct from the desktop app's own usage-watch subscriber.
+static NEXT_CONNECTION_ID: AtomicU64 = AtomicU64::new(local_usage_core::APP_WATCH_SUBSCRIBER + 1);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum ListenerKind {
+    Tcp,
+    WebSocket,
+    /// Plain HTTP for `/metrics` and `/usage.csv`.
+    Http,
+}
+
+/// Per-connection protocol state shared by the TCP and WebSocket listeners.
+struct ClientSession {
     config: Arc<DaemonConfig>,
     state: Arc<DaemonState>,
-    events: broadcast::Sender<DaemonEvent>,
-) {
-    let (reader, mut writer) = socket.into_split();
-    let mut lines = BufReader::new(reader).lines();
+    events: Arc<EventHub>,
+    out_tx: mpsc::UnboundedSender<String>,
+    peer: Option<SocketAddr>,
+    connection_id: u64,
+    /// Set once the client has authenticated (immediately with `--insecure-no-auth`).
+    caller: Option<Arc<RpcCaller>>,
+    events_task: Option<tokio::task::JoinHandle<()>>,
+    forwarded: Arc<ForwardedEvents>,
+    request_limiter: Arc<Semaphore>,
+}
 
-    let (out_tx, mut out_rx) = mpsc::unbounded_channel::<String>();
-    let write_task = tokio::spawn(async move {
-        while let Some(message) = out_rx.recv().await {
-            if writer.write_all(message.as_bytes()).await.is_err() {
-                break;
+impl ClientSession {
+    fn new(
+        config: Arc<DaemonConfig>,
+        state: Arc<DaemonState>,
+        out_tx: mpsc::UnboundedSender<String>,
+        peer: Option<SocketAddr>,
+    ) -> Self {
+        let mut session = Self {
+            caller: None,
+            events: Arc::clone(&state.event_sink.events),
+            config,
+            state,
+            out_tx,
+            peer,
+            connection_id: NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed),
+            events_task: None,
+            forwarded: Arc::new(ForwardedEvents::default()),
+            request_limiter: Arc::new(Semaphore::new(MAX_IN_FLIGHT_RPC_PER_CONNECTION)),
+        };
+        if session.config.insecure_no_auth {
+            session.caller = Some(session.new_caller(ClientAccess::unrestricted()));
+        }
+        if session.caller.is_some() {
+            let (after_seq, replay, rx, _) = session.prepare_subscription(None);
+            session.start_forwarding(after_seq, replay, rx);
+        }
+        session
+    }
+
+    fn new_caller(&self, access: ClientAccess) -> Arc<RpcCaller> {
+        Arc::new(RpcCaller {
+            access,
+            peer: self.peer.map(|addr| addr.to_string()),
+            client_version: format!("daemon-{}", env!("CARGO_PKG_VERSION")),
+            connection_id: self.connection_id,
+        })
+    }
+
+    /// Stops any current forwarding and snapshots what a new subscription should send.
+    /// With `resume_after`, buffered events newer than that sequence are included, minus
+    /// any this connection already delivered. Also returns the sequence the stream starts
+    /// after, and whether events after `resume_after` were already evicted.
+    fn prepare_subscription(
+        &mut self,
+        resume_after: Option<u64>,
+    ) -> (
+        u64,
+        Vec<SequencedEvent>,
+        broadcast::Receiver<SequencedEvent>,
+        bool,
+    ) {
+        if let Some(task) = self.events_task.take() {
+            task.abort();
+        }
+        match resume_after {
+            Some(after_seq) => {
+                let access = self.caller.as_deref().map(|caller| &caller.access);
+                let (missed, rx, evicted) =
+                    self.events.subscribe_after(after_seq, |workspace_id| {
+                        access.is_none_or(|access| access.allows_workspace(workspace_id))
+                    });
+                let missed = missed
+                    .into_iter()
+                    .filter(|event| !self.forwarded.contains(event.seq))
+                    .collect();
+                (after_seq, missed, rx, evicted)
             }
-            if writer.write_all(b"\n").await.is_err() {
-                break;
+            None => {
+                let (after_seq, rx) = self.events.subscribe();
+                (after_seq, Vec::new(), rx, false)
             }
         }
-    });
+    }
+
+    fn start_forwarding(
+        &mut self,
+        after_seq: u64,
+        replay: Vec<SequencedEvent>,
+        rx: broadcast::Receiver<SequencedEvent>,
+    ) {
+        let Some(caller) = self.caller.clone() else {
+            return;
+        };
+        let out_tx_events = self.out_tx.clone();
+        self.events_task = Some(tokio::spawn(forward_events(
+            Arc::clone(&self.events),
+            after_seq,
+            replay,
+            rx,
+            out_tx_events,
+            Arc::clone(&self.forwarded),
+            caller,
+        )));
+    }
 
-    let mut authenticated = config.token.is_none();
-    let mut events_task: Option<tokio::task::JoinHandle<()>> = None;
-    let request_limiter = Arc::new(Semaphore::new(MAX_IN_FLIGHT_RPC_PER_CONNECTION));
-    let client_version = format!("daemon-{}", env!("CARGO_PKG_VERSION"));
+    /// Sequence to resume after, if the client's cursor belongs to this daemon's stream.
+    fn parse_resume(&self, params: &Value) -> Option<u64> {
+        let stream_id = params.get("eventStreamId").and_then(Value::as_str)?;
+        if stream_id != self.events.stream_id() {
+            return None;
+        }
+        params.get("lastEventSeq").and_then(Value::as_u64)
+    }
 
-    if authenticated {
-        let rx = events.subscribe();
-        let out_tx_events = out_tx.clone();
-        events_task = Some(tokio::spawn(forward_events(rx, out_tx_events)));
+    fn resume_events(&mut self, id: Option<u64>, params: &Value) {
+        let resume_after = self.parse_resume(params);
+        if resume_after.is_none() && self.events_task.is_some() {
+            self.send_auth_result(id, 0);
+            return;
+        }
+        // A cursor from another stream means the daemon restarted since the client's last
+        // event, so whatever it missed is gone.
+        let restarted = resume_after.is_none() && params.get("eventStreamId").is_some();
+        let (after_seq, replay, rx, evicted) = self.prepare_subscription(resume_after);
+        // Reply first so the response precedes any replayed events on the wire, and report
+        // a gap before the replay so the client knows the events that follow are partial.
+        self.send_auth_result(id, replay.len());
+        let gap = match (evicted, restarted) {
+            (true, _) => Some("evicted"),
+            (_, true) => Some("restarted"),
+            _ => None,
+        };
+        if let Some(payload) =
+            gap.and_then(|reason| build_event_gap_notification(after_seq, reason))
+        {
+            let _ = self.out_tx.send(payload);
+        }
+        self.start_forwarding(after_seq, replay, rx);
     }
 
-    while let Ok(Some(line)) = lines.next_line().await {
+    fn send_auth_result(&self, id: Option<u64>, replayed: usize) {
+        let access = self.caller.as_deref().map(|caller| &caller.access);
+        let result = json!({
+            "ok": true,
+            "role": access.map(|access| access.role().as_str()),
+            "tokenName": access.and_then(ClientAccess::token_name),
+            "eventStreamId": self.events.stream_id(),
+            "lastEventSeq": self.events.last_seq(),
+            "replayedEvents": replayed,
+        });
+        if let Some(response) = build_result_response(id, result) {
+            let _ = self.out_tx.send(response);
+        }
+    }
+
+    fn handle_line(&mut self, line: &str) {
         let line = line.trim();
         if line.is_empty() {
-            continue;
+            return;
         }
 
         let message: Value = match serde_json::from_str(line) {
             Ok(value) => value,
-            Err(_) => continue,
+            Err(_) => return,
         };
 
         let id = message.get("id").and_then(|value| value.as_u64());
@@ -55,49 +201,202 @@ pub(super) async fn handle_client(
             .to_string();
         let params = message.get("params").cloned().unwrap_or(Value::Null);
 
-        if !authenticated {
+        if method.is_empty() {
+            if let Some(response) = build_error_response(
+                id,
+                &RpcError::new(RpcErrorCode::InvalidRequest, "missing `method`"),
+            ) {
+                let _ = self.out_tx.send(response);
+            }
+            return;
+        }
+
+        let Some(caller) = self.caller.clone() else {
             if method != "auth" {
-                if let Some(response) = build_error_response(id, "unauthorized") {
-                    let _ = out_tx.send(response);
+                if let Some(response) = build_error_response(
+                    id,
+                    &RpcError::new(RpcErrorCode::Unauthorized, "unauthorized"),
+                ) {
+                    let _ = self.out_tx.send(response);
                 }
-                continue;
+                return;
             }
 
-            let expected = config.token.clone().unwrap_or_default();
             let provided = parse_auth_token(&params).unwrap_or_default();
-            if expected != provided {
-                if let Some(response) = build_error_response(id, "invalid token") {
-                    let _ = out_tx.send(response);
+            let Some(access) = ClientAccess::authenticate(&self.config, &provided) else {
+                if let Some(response) = build_error_response(
+                    id,
+                    &RpcError::new(RpcErrorCode::Unauthorized, "invalid token"),
+                ) {
+                    let _ = self.out_tx.send(response);
                 }
-                continue;
-            }
-
-            authenticated = true;
-            if let Some(response) = build_result_response(id, json!({ "ok": true })) {
-                let _ = out_tx.send(response);
-            }
+                return;
+            };
 
-            let rx = events.subscribe();
-            let out_tx_events = out_tx.clone();
-            events_task = Some(tokio::spawn(forward_events(rx, out_tx_events)));
+            self.caller = Some(self.new_caller(access));
+            self.resume_events(id, &params);
+            return;
+        };
 
-            continue;
+        if method == "auth" {
+            // Already authenticated (or auth disabled): treat as a resume request.
+            self.resume_events(id, &params);
+            return;
         }
 
         spawn_rpc_response_task(
-            Arc::clone(&state),
-            out_tx.clone(),
+            Arc::clone(&self.state),
+            self.out_tx.clone(),
+            caller,
             id,
             method,
             params,
-            client_version.clone(),
-            Arc::clone(&request_limiter),
+            Arc::clone(&self.request_limiter),
         );
     }
+}
 
-    drop(out_tx);
-    if let Some(task) = events_task {
-        task.abort();
+impl Drop for ClientSession {
+    fn drop(&mut self) {
+        if let Some(task) = self.events_task.take() {
+            task.abort();
+        }
+        self.state
+            .local_usage_watchers
+            .unsubscribe_all(self.connection_id);
     }
+}
+
+pub(super) async fn handle_client<S>(
+    socket: S,
+    peer: Option<SocketAddr>,
+    config: Arc<DaemonConfig>,
+    state: Arc<DaemonState>,
+) where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let (reader, mut writer) = tokio::io::split(socket);
+    let mut lines = BufReader::new(reader).lines();
+
+    let (out_tx, mut out_rx) = mpsc::unbounded_channel::<String>();
+    let write_task = tokio::spawn(async move {
+        while let Some(message) = out_rx.recv().await {
+            if writer.write_all(message.as_bytes()).await.is_err() {
+                break;
+            }
+            if writer.write_all(b"\n").await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut session = ClientSession::new(config, state, out_tx, peer);
+    while let Ok(Some(line)) = lines.next_line().await {
+        session.handle_line(&line);
+    }
+
+    drop(session);
     write_task.abort();
 }
+
+/// Same protocol as [`handle_client`], with one JSON message per WebSocket text frame.
+pub(super) async fn handle_websocket_client<S>(
+    socket: S,
+    peer: Option<SocketAddr>,
+    config: Arc<DaemonConfig>,
+    state: Arc<DaemonState>,
+) where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let stream = match tokio_tungstenite::accept_async(socket).await {
+        Ok(stream) => stream,
+        Err(err) => {
+            eprintln!("daemon: WebSocket handshake failed: {err}");
+            return;
+        }
+    };
+    let (mut sink, mut source) = stream.split();
+
+    let (out_tx, mut out_rx) = mpsc::unbounded_channel::<String>();
+    let write_task = tokio::spawn(async move {
+        while let Some(message) = out_rx.recv().await {
+            if sink.send(Message::Text(message)).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut session = ClientSession::new(config, state, out_tx, peer);
+    while let Some(Ok(message)) = source.next().await {
+        match message {
+            Message::Text(text) => {
+                for line in text.lines() {
+                    session.handle_line(line);
+                }
+            }
+            Message::Binary(bytes) => {
+                if let Ok(text) = String::from_utf8(bytes) {
+                    for line in text.lines() {
+                        session.handle_line(line);
+                    }
+                }
+            }
+            Message::Close(_) => break,
+            _ => {}
+        }
+    }
+
+    drop(session);
+    write_task.abort();
+}
+
+pub(super) async fn accept_loop(
+    listener: TcpListener,
+    kind: ListenerKind,
+    acceptor: Option<TlsAcceptor>,
+    config: Arc<DaemonConfig>,
+    state: Arc<DaemonState>,
+) {
+    loop {
+        let (socket, addr) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(_) => continue,
+        };
+        let config = Arc::clone(&config);
+        let state = Arc::clone(&state);
+        let acceptor = acceptor.clone();
+        tokio::spawn(async move {
+            let Some(acceptor) = acceptor else {
+                serve_stream(kind, socket, addr, config, state).await;
+                return;
+            };
+            match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(socket)).await {
+                Ok(Ok(stream)) => serve_stream(kind, stream, addr, config, state).await,
+                Ok(Err(err)) => {
+                    eprintln!("daemon: TLS handshake with {addr} failed: {err}");
+                }
+                Err(_) => {
+                    eprintln!("daemon: TLS handshake with {addr} timed out");
+                }
+            }
+        });
+    }
+}
+
+async fn serve_stream<S>(
+    kind: ListenerKind,
+    stream: S,
+    peer: SocketAddr,
+    config: Arc<DaemonConfig>,
+    state: Arc<DaemonState>,
+) where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    match kind {
+        ListenerKind::Tcp => handle_client(stream, Some(peer), config, state).await,
+        ListenerKind::WebSocket => handle_websocket_client(stream, Some(peer), config, state).await,
+        ListenerKind::Http => {
+            super::metrics::handle_http_client(stream, Some(peer), config, state).await
+        }
+    }
+}
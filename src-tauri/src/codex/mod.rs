@@ -2,7 +2,7 @@ use serde_json::{json, Map, Value};
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use tauri::{AppHandle, Emitter, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 
 pub(crate) mod args;
 pub(crate) mod config;
@@ -13,10 +13,16 @@ pub(crate) use crate::backend::app_server::WorkspaceSession;
 use crate::backend::events::AppServerEvent;
 use crate::event_sink::TauriEventSink;
 use crate::remote_backend;
+use crate::rules::{PrefixRule, RuleEntry, RulesListing};
 use crate::shared::agents_config_core;
+use crate::shared::approval_policy_core::{self, ApprovalPolicyEngine, ApprovalPolicyExplanation};
 use crate::shared::codex_core;
+use crate::shared::thread_export_core::{
+    self, ThreadBundle, ThreadExport, ThreadExportFormat, ThreadImport,
+};
+use crate::shared::usage_budget_core;
 use crate::state::AppState;
-use crate::types::WorkspaceEntry;
+use crate::types::{ApprovalPolicyRule, WorkspaceEntry};
 
 fn emit_thread_live_event(app: &AppHandle, workspace_id: &str, method: &str, params: Value) {
     let _ = app.emit(
@@ -39,6 +45,13 @@ pub(crate) async fn spawn_workspace_session(
     codex_home: Option<PathBuf>,
 ) -> Result<Arc<WorkspaceSession>, String> {
     let client_version = app_handle.package_info().version.to_string();
+    let approval_policy = {
+        let state = app_handle.state::<AppState>();
+        state
+            .storage_path
+            .parent()
+            .map(|data_dir| ApprovalPolicyEngine::new(data_dir, Arc::clone(&state.workspaces)))
+    };
     let event_sink = TauriEventSink::new(app_handle);
     spawn_workspace_session_inner(
         entry,
@@ -47,6 +60,7 @@ pub(crate) async fn spawn_workspace_session(
         codex_home,
         client_version,
         event_sink,
+        approval_policy,
     )
     .await
 }
@@ -87,7 +101,9 @@ pub(crate) async fn start_thread(
         .await;
     }
 
-    codex_core::start_thread_core(&state.sessions, &state.workspaces, workspace_id).await
+    codex_core::start_thread_core(&state.sessions, &state.workspaces, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -107,7 +123,9 @@ pub(crate) async fn resume_thread(
         .await;
     }
 
-    codex_core::resume_thread_core(&state.sessions, workspace_id, thread_id).await
+    codex_core::resume_thread_core(&state.sessions, workspace_id, thread_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -203,7 +221,9 @@ pub(crate) async fn fork_thread(
         .await;
     }
 
-    codex_core::fork_thread_core(&state.sessions, workspace_id, thread_id).await
+    codex_core::fork_thread_core(&state.sessions, workspace_id, thread_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -230,7 +250,9 @@ pub(crate) async fn list_threads(
         .await;
     }
 
-    codex_core::list_threads_core(&state.sessions, workspace_id, cursor, limit, sort_key).await
+    codex_core::list_threads_core(&state.sessions, workspace_id, cursor, limit, sort_key)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -251,7 +273,9 @@ pub(crate) async fn list_mcp_server_status(
         .await;
     }
 
-    codex_core::list_mcp_server_status_core(&state.sessions, workspace_id, cursor, limit).await
+    codex_core::list_mcp_server_status_core(&state.sessions, workspace_id, cursor, limit)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -271,7 +295,9 @@ pub(crate) async fn archive_thread(
         .await;
     }
 
-    codex_core::archive_thread_core(&state.sessions, workspace_id, thread_id).await
+    codex_core::archive_thread_core(&state.sessions, workspace_id, thread_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -291,7 +317,9 @@ pub(crate) async fn compact_thread(
         .await;
     }
 
-    codex_core::compact_thread_core(&state.sessions, workspace_id, thread_id).await
+    codex_core::compact_thread_core(&state.sessions, workspace_id, thread_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -312,7 +340,9 @@ pub(crate) async fn set_thread_name(
         .await;
     }
 
-    codex_core::set_thread_name_core(&state.sessions, workspace_id, thread_id, name).await
+    codex_core::set_thread_name_core(&state.sessions, workspace_id, thread_id, name)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -359,6 +389,12 @@ pub(crate) async fn send_user_message(
         .await;
     }
 
+    usage_budget_core::ensure_send_allowed(
+        &state.usage_budgets,
+        &workspace_id,
+        TauriEventSink::new(app),
+    )
+    .await?;
     codex_core::send_user_message_core(
         &state.sessions,
         &state.workspaces,
@@ -373,6 +409,7 @@ pub(crate) async fn send_user_message(
         collaboration_mode,
     )
     .await
+    .map_err(String::from)
 }
 
 #[tauri::command]
@@ -419,6 +456,7 @@ pub(crate) async fn turn_steer(
         app_mentions,
     )
     .await
+    .map_err(String::from)
 }
 
 #[tauri::command]
@@ -437,7 +475,9 @@ pub(crate) async fn collaboration_mode_list(
         .await;
     }
 
-    codex_core::collaboration_mode_list_core(&state.sessions, workspace_id).await
+    codex_core::collaboration_mode_list_core(&state.sessions, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -458,7 +498,9 @@ pub(crate) async fn turn_interrupt(
         .await;
     }
 
-    codex_core::turn_interrupt_core(&state.sessions, workspace_id, thread_id, turn_id).await
+    codex_core::turn_interrupt_core(&state.sessions, workspace_id, thread_id, turn_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -485,7 +527,9 @@ pub(crate) async fn start_review(
         .await;
     }
 
-    codex_core::start_review_core(&state.sessions, workspace_id, thread_id, target, delivery).await
+    codex_core::start_review_core(&state.sessions, workspace_id, thread_id, target, delivery)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -504,7 +548,9 @@ pub(crate) async fn model_list(
         .await;
     }
 
-    codex_core::model_list_core(&state.sessions, workspace_id).await
+    codex_core::model_list_core(&state.sessions, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -529,7 +575,9 @@ pub(crate) async fn experimental_feature_list(
         .await;
     }
 
-    codex_core::experimental_feature_list_core(&state.sessions, workspace_id, cursor, limit).await
+    codex_core::experimental_feature_list_core(&state.sessions, workspace_id, cursor, limit)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -698,7 +746,9 @@ pub(crate) async fn account_rate_limits(
         .await;
     }
 
-    codex_core::account_rate_limits_core(&state.sessions, workspace_id).await
+    codex_core::account_rate_limits_core(&state.sessions, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -736,7 +786,9 @@ pub(crate) async fn codex_login(
         .await;
     }
 
-    codex_core::codex_login_core(&state.sessions, &state.codex_login_cancels, workspace_id).await
+    codex_core::codex_login_core(&state.sessions, &state.codex_login_cancels, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -757,6 +809,7 @@ pub(crate) async fn codex_login_cancel(
 
     codex_core::codex_login_cancel_core(&state.sessions, &state.codex_login_cancels, workspace_id)
         .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -775,7 +828,9 @@ pub(crate) async fn skills_list(
         .await;
     }
 
-    codex_core::skills_list_core(&state.sessions, &state.workspaces, workspace_id).await
+    codex_core::skills_list_core(&state.sessions, &state.workspaces, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -802,7 +857,9 @@ pub(crate) async fn apps_list(
         .await;
     }
 
-    codex_core::apps_list_core(&state.sessions, workspace_id, cursor, limit, thread_id).await
+    codex_core::apps_list_core(&state.sessions, workspace_id, cursor, limit, thread_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -826,6 +883,7 @@ pub(crate) async fn respond_to_server_request(
 
     codex_core::respond_to_server_request_core(&state.sessions, workspace_id, request_id, result)
         .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -837,6 +895,164 @@ pub(crate) async fn remember_approval_rule(
     codex_core::remember_approval_rule_core(&state.workspaces, workspace_id, command).await
 }
 
+#[tauri::command]
+pub(crate) async fn explain_approval_policy(
+    workspace_id: String,
+    method: String,
+    params: Value,
+    access_mode: Option<String>,
+    rules: Option<Vec<ApprovalPolicyRule>>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<ApprovalPolicyExplanation, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "explain_approval_policy",
+            json!({
+                "workspaceId": workspace_id,
+                "method": method,
+                "params": params,
+                "accessMode": access_mode,
+                "rules": rules,
+            }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    approval_policy_core::explain_approval_policy_core(
+        &state.workspaces,
+        workspace_id,
+        method,
+        params,
+        access_mode,
+        rules,
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn export_thread(
+    workspace_id: String,
+    thread_id: String,
+    format: ThreadExportFormat,
+    redact: Option<bool>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<ThreadExport, String> {
+    let redact = redact.unwrap_or(false);
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "export_thread",
+            json!({
+                "workspaceId": workspace_id,
+                "threadId": thread_id,
+                "format": format,
+                "redact": redact,
+            }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    thread_export_core::export_thread_core(
+        &state.workspaces,
+        workspace_id,
+        thread_id,
+        format,
+        redact,
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn import_thread(
+    workspace_id: String,
+    bundle: ThreadBundle,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<ThreadImport, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "import_thread",
+            json!({ "workspaceId": workspace_id, "bundle": bundle }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    thread_export_core::import_thread_core(&state.workspaces, workspace_id, bundle).await
+}
+
+#[tauri::command]
+pub(crate) async fn list_codex_rules(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<RulesListing, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "list_codex_rules",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    codex_core::list_codex_rules_core(&state.workspaces, workspace_id).await
+}
+
+#[tauri::command]
+pub(crate) async fn update_codex_rule(
+    workspace_id: String,
+    rule_id: String,
+    rule: PrefixRule,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<RuleEntry, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "update_codex_rule",
+            json!({ "workspaceId": workspace_id, "ruleId": rule_id, "rule": rule }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    codex_core::update_codex_rule_core(&state.workspaces, workspace_id, rule_id, rule).await
+}
+
+#[tauri::command]
+pub(crate) async fn delete_codex_rule(
+    workspace_id: String,
+    rule_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "delete_codex_rule",
+            json!({ "workspaceId": workspace_id, "ruleId": rule_id }),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    codex_core::delete_codex_rule_core(&state.workspaces, workspace_id, rule_id).await
+}
+
 #[tauri::command]
 pub(crate) async fn get_config_model(
     workspace_id: String,
@@ -908,6 +1124,7 @@ pub(crate) async fn generate_commit_message(
         },
     )
     .await
+    .map_err(String::from)
 }
 
 #[tauri::command]
@@ -949,6 +1166,7 @@ pub(crate) async fn generate_run_metadata(
         },
     )
     .await
+    .map_err(String::from)
 }
 
 #[tauri::command]
@@ -991,4 +1209,5 @@ pub(crate) async fn generate_agent_description(
         },
     )
     .await
+    .map_err(String::from)
 }
@@ -0,0 +1,220 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub(crate) const DAEMON_TOKENS_FILE: &str = "daemon-tokens.json";
+const TOKEN_PREFIX: &str = "cmd_";
+
+/// Access level granted to a daemon client. Higher roles include everything the lower ones can do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum DaemonRole {
+    Observer,
+    Operator,
+    Admin,
+}
+
+impl DaemonRole {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            DaemonRole::Observer => "observer",
+            DaemonRole::Operator => "operator",
+            DaemonRole::Admin => "admin",
+        }
+    }
+
+    pub(crate) fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "observer" => Ok(DaemonRole::Observer),
+            "operator" => Ok(DaemonRole::Operator),
+            "admin" => Ok(DaemonRole::Admin),
+            other => Err(format!(
+                "Unknown role `{other}` (expected observer, operator or admin)"
+            )),
+        }
+    }
+}
+
+/// A named token as stored on disk. Only the SHA-256 of the secret is kept.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DaemonTokenRecord {
+    pub(crate) name: String,
+    pub(crate) role: DaemonRole,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) workspace_ids: Option<Vec<String>>,
+    pub(crate) token_sha256: String,
+    pub(crate) created_at_ms: i64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DaemonTokenFile {
+    #[serde(default)]
+    tokens: Vec<DaemonTokenRecord>,
+}
+
+pub(crate) fn tokens_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(DAEMON_TOKENS_FILE)
+}
+
+pub(crate) fn read_tokens(path: &Path) -> Result<Vec<DaemonTokenRecord>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let file: DaemonTokenFile =
+        serde_json::from_str(&data).map_err(|err| format!("Invalid {}: {err}", path.display()))?;
+    Ok(file.tokens)
+}
+
+fn write_tokens(path: &Path, tokens: &[DaemonTokenRecord]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    let file = DaemonTokenFile {
+        tokens: tokens.to_vec(),
+    };
+    let data = serde_json::to_string_pretty(&file).map_err(|err| err.to_string())?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, data).map_err(|err| err.to_string())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&tmp_path, fs::Permissions::from_mode(0o600))
+            .map_err(|err| err.to_string())?;
+    }
+    fs::rename(&tmp_path, path).map_err(|err| err.to_string())
+}
+
+pub(crate) fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn generate_secret() -> String {
+    format!(
+        "{TOKEN_PREFIX}{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+fn now_unix_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Creates a new named token and returns its record together with the secret,
+/// which is never stored and cannot be recovered later.
+pub(crate) fn mint_token(
+    path: &Path,
+    name: &str,
+    role: DaemonRole,
+    workspace_ids: Option<Vec<String>>,
+) -> Result<(DaemonTokenRecord, String), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Token name is required.".to_string());
+    }
+    let mut tokens = read_tokens(path)?;
+    if tokens.iter().any(|record| record.name == name) {
+        return Err(format!("A token named `{name}` already exists."));
+    }
+    let workspace_ids = workspace_ids
+        .map(|ids| {
+            ids.into_iter()
+                .map(|id| id.trim().to_string())
+                .filter(|id| !id.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .filter(|ids| !ids.is_empty());
+    let secret = generate_secret();
+    let record = DaemonTokenRecord {
+        name: name.to_string(),
+        role,
+        workspace_ids,
+        token_sha256: hash_token(&secret),
+        created_at_ms: now_unix_ms(),
+    };
+    tokens.push(record.clone());
+    write_tokens(path, &tokens)?;
+    Ok((record, secret))
+}
+
+/// Removes the named token. Returns `false` when no such token exists.
+pub(crate) fn revoke_token(path: &Path, name: &str) -> Result<bool, String> {
+    let mut tokens = read_tokens(path)?;
+    let before = tokens.len();
+    tokens.retain(|record| record.name != name.trim());
+    if tokens.len() == before {
+        return Ok(false);
+    }
+    write_tokens(path, &tokens)?;
+    Ok(true)
+}
+
+pub(crate) fn find_token<'a>(
+    tokens: &'a [DaemonTokenRecord],
+    provided: &str,
+) -> Option<&'a DaemonTokenRecord> {
+    if provided.is_empty() {
+        return None;
+    }
+    let provided_hash = hash_token(provided);
+    tokens
+        .iter()
+        .find(|record| record.token_sha256 == provided_hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_token, mint_token, read_tokens, revoke_token, tokens_path, DaemonRole};
+    use uuid::Uuid;
+
+    #[test]
+    fn minted_tokens_are_found_by_secret_and_revocable() {
+        let dir = std::env::temp_dir().join(format!("daemon-tokens-{}", Uuid::new_v4()));
+        let path = tokens_path(&dir);
+
+        let (record, secret) = mint_token(
+            &path,
+            "phone",
+            DaemonRole::Observer,
+            Some(vec!["ws-1".to_string(), "  ".to_string()]),
+        )
+        .expect("mint token");
+        assert_eq!(record.workspace_ids, Some(vec!["ws-1".to_string()]));
+        assert!(!record.token_sha256.contains(&secret));
+        assert!(mint_token(&path, "phone", DaemonRole::Admin, None).is_err());
+
+        let tokens = read_tokens(&path).expect("read tokens");
+        let found = find_token(&tokens, &secret).expect("token by secret");
+        assert_eq!(found.name, "phone");
+        assert_eq!(found.role, DaemonRole::Observer);
+        assert!(find_token(&tokens, "cmd_wrong").is_none());
+        assert!(find_token(&tokens, "").is_none());
+
+        assert!(revoke_token(&path, "phone").expect("revoke"));
+        assert!(!revoke_token(&path, "phone").expect("revoke again"));
+        assert!(read_tokens(&path).expect("read tokens").is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn roles_are_ordered_by_privilege() {
+        assert!(DaemonRole::Observer < DaemonRole::Operator);
+        assert!(DaemonRole::Operator < DaemonRole::Admin);
+        assert_eq!(
+            DaemonRole::parse(" Admin ").expect("role"),
+            DaemonRole::Admin
+        );
+        assert!(DaemonRole::parse("root").is_err());
+    }
+}
@@ -1,6 +1,9 @@
 use tauri::{AppHandle, Emitter};
 
-use crate::backend::events::{AppServerEvent, EventSink, TerminalExit, TerminalOutput};
+use crate::backend::events::{
+    AppServerEvent, EventSink, LocalUsageUpdated, TerminalExit, TerminalOutput,
+};
+use crate::types::UsageBudgetAlert;
 
 #[derive(Clone)]
 pub(crate) struct TauriEventSink {
@@ -25,4 +28,12 @@ impl EventSink for TauriEventSink {
     fn emit_terminal_exit(&self, event: TerminalExit) {
         let _ = self.app.emit("terminal-exit", event);
     }
+
+    fn emit_local_usage_updated(&self, event: LocalUsageUpdated) {
+        let _ = self.app.emit("local-usage-updated", event);
+    }
+
+    fn emit_usage_budget_alert(&self, event: UsageBudgetAlert) {
+        let _ = self.app.emit("usage-budget-alert", event);
+    }
 }
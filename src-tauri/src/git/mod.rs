@@ -7,8 +7,9 @@ use crate::remote_backend;
 use crate::shared::{git_rpc, git_ui_core};
 use crate::state::AppState;
 use crate::types::{
-    GitCommitDiff, GitFileDiff, GitHubIssuesResponse, GitHubPullRequestComment,
-    GitHubPullRequestDiff, GitHubPullRequestsResponse, GitLogResponse,
+    GitCommitDiff, GitConflictDetail, GitConflictResolution, GitDiffSelection, GitFileDiff,
+    GitHubIssuesResponse, GitHubPullRequestComment, GitHubPullRequestDiff,
+    GitHubPullRequestsResponse, GitLogFilter, GitLogResponse, GitOperationStatus, GitStashEntry,
 };
 
 fn git_remote_params<T: Serialize>(request: &T) -> Result<Value, String> {
@@ -227,6 +228,93 @@ pub(crate) async fn revert_git_file(
     git_ui_core::revert_git_file_core(&state.workspaces, workspace_id, path).await
 }
 
+#[tauri::command]
+pub(crate) async fn stage_git_selection(
+    workspace_id: String,
+    path: String,
+    selection: GitDiffSelection,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let request = git_rpc::WorkspaceSelectionRequest {
+        workspace_id: workspace_id.clone(),
+        path: path.clone(),
+        selection: selection.clone(),
+    };
+    try_remote_unit!(
+        state,
+        app,
+        git_rpc::METHOD_STAGE_GIT_SELECTION,
+        git_remote_params(&request)?
+    );
+    git_ui_core::stage_git_selection_core(
+        &state.workspaces,
+        &state.app_settings,
+        workspace_id,
+        path,
+        selection,
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn unstage_git_selection(
+    workspace_id: String,
+    path: String,
+    selection: GitDiffSelection,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let request = git_rpc::WorkspaceSelectionRequest {
+        workspace_id: workspace_id.clone(),
+        path: path.clone(),
+        selection: selection.clone(),
+    };
+    try_remote_unit!(
+        state,
+        app,
+        git_rpc::METHOD_UNSTAGE_GIT_SELECTION,
+        git_remote_params(&request)?
+    );
+    git_ui_core::unstage_git_selection_core(
+        &state.workspaces,
+        &state.app_settings,
+        workspace_id,
+        path,
+        selection,
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn revert_git_selection(
+    workspace_id: String,
+    path: String,
+    selection: GitDiffSelection,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let request = git_rpc::WorkspaceSelectionRequest {
+        workspace_id: workspace_id.clone(),
+        path: path.clone(),
+        selection: selection.clone(),
+    };
+    try_remote_unit!(
+        state,
+        app,
+        git_rpc::METHOD_REVERT_GIT_SELECTION,
+        git_remote_params(&request)?
+    );
+    git_ui_core::revert_git_selection_core(
+        &state.workspaces,
+        &state.app_settings,
+        workspace_id,
+        path,
+        selection,
+    )
+    .await
+}
+
 #[tauri::command]
 pub(crate) async fn revert_git_all(
     workspace_id: String,
@@ -337,6 +425,308 @@ pub(crate) async fn sync_git(
     git_ui_core::sync_git_core(&state.workspaces, workspace_id).await
 }
 
+#[tauri::command]
+pub(crate) async fn list_git_stashes(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Vec<GitStashEntry>, String> {
+    let request = git_rpc::WorkspaceIdRequest {
+        workspace_id: workspace_id.clone(),
+    };
+    try_remote_typed!(
+        state,
+        app,
+        git_rpc::METHOD_LIST_GIT_STASHES,
+        git_remote_params(&request)?,
+        Vec<GitStashEntry>
+    );
+    git_ui_core::list_git_stashes_core(&state.workspaces, workspace_id).await
+}
+
+#[tauri::command]
+pub(crate) async fn get_git_stash_diff(
+    workspace_id: String,
+    index: usize,
+    sha: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Vec<GitFileDiff>, String> {
+    let request = git_rpc::WorkspaceStashActionRequest {
+        workspace_id: workspace_id.clone(),
+        index,
+        sha: sha.clone(),
+    };
+    try_remote_typed!(
+        state,
+        app,
+        git_rpc::METHOD_GET_GIT_STASH_DIFF,
+        git_remote_params(&request)?,
+        Vec<GitFileDiff>
+    );
+    git_ui_core::get_git_stash_diff_core(
+        &state.workspaces,
+        &state.app_settings,
+        workspace_id,
+        index,
+        sha,
+    )
+    .await
+    .map_err(String::from)
+}
+
+#[tauri::command]
+pub(crate) async fn create_git_stash(
+    workspace_id: String,
+    message: Option<String>,
+    include_untracked: Option<bool>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<GitStashEntry, String> {
+    let request = git_rpc::CreateGitStashRequest {
+        workspace_id: workspace_id.clone(),
+        message: message.clone(),
+        include_untracked,
+    };
+    try_remote_typed!(
+        state,
+        app,
+        git_rpc::METHOD_CREATE_GIT_STASH,
+        git_remote_params(&request)?,
+        GitStashEntry
+    );
+    git_ui_core::create_git_stash_core(
+        &state.workspaces,
+        workspace_id,
+        message,
+        include_untracked.unwrap_or(false),
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn apply_git_stash(
+    workspace_id: String,
+    index: usize,
+    sha: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let request = git_rpc::WorkspaceStashActionRequest {
+        workspace_id: workspace_id.clone(),
+        index,
+        sha: sha.clone(),
+    };
+    try_remote_unit!(
+        state,
+        app,
+        git_rpc::METHOD_APPLY_GIT_STASH,
+        git_remote_params(&request)?
+    );
+    git_ui_core::apply_git_stash_core(&state.workspaces, workspace_id, index, sha)
+        .await
+        .map_err(String::from)
+}
+
+#[tauri::command]
+pub(crate) async fn pop_git_stash(
+    workspace_id: String,
+    index: usize,
+    sha: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let request = git_rpc::WorkspaceStashActionRequest {
+        workspace_id: workspace_id.clone(),
+        index,
+        sha: sha.clone(),
+    };
+    try_remote_unit!(
+        state,
+        app,
+        git_rpc::METHOD_POP_GIT_STASH,
+        git_remote_params(&request)?
+    );
+    git_ui_core::pop_git_stash_core(&state.workspaces, workspace_id, index, sha)
+        .await
+        .map_err(String::from)
+}
+
+#[tauri::command]
+pub(crate) async fn drop_git_stash(
+    workspace_id: String,
+    index: usize,
+    sha: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let request = git_rpc::WorkspaceStashActionRequest {
+        workspace_id: workspace_id.clone(),
+        index,
+        sha: sha.clone(),
+    };
+    try_remote_unit!(
+        state,
+        app,
+        git_rpc::METHOD_DROP_GIT_STASH,
+        git_remote_params(&request)?
+    );
+    git_ui_core::drop_git_stash_core(&state.workspaces, workspace_id, index, sha)
+        .await
+        .map_err(String::from)
+}
+
+#[tauri::command]
+pub(crate) async fn merge_git_branch(
+    workspace_id: String,
+    name: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<GitOperationStatus, String> {
+    let request = git_rpc::WorkspaceNameRequest {
+        workspace_id: workspace_id.clone(),
+        name: name.clone(),
+    };
+    try_remote_typed!(
+        state,
+        app,
+        git_rpc::METHOD_MERGE_GIT_BRANCH,
+        git_remote_params(&request)?,
+        GitOperationStatus
+    );
+    git_ui_core::merge_git_branch_core(&state.workspaces, workspace_id, name).await
+}
+
+#[tauri::command]
+pub(crate) async fn rebase_git_branch(
+    workspace_id: String,
+    name: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<GitOperationStatus, String> {
+    let request = git_rpc::WorkspaceNameRequest {
+        workspace_id: workspace_id.clone(),
+        name: name.clone(),
+    };
+    try_remote_typed!(
+        state,
+        app,
+        git_rpc::METHOD_REBASE_GIT_BRANCH,
+        git_remote_params(&request)?,
+        GitOperationStatus
+    );
+    git_ui_core::rebase_git_branch_core(&state.workspaces, workspace_id, name).await
+}
+
+#[tauri::command]
+pub(crate) async fn continue_git_operation(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<GitOperationStatus, String> {
+    let request = git_rpc::WorkspaceIdRequest {
+        workspace_id: workspace_id.clone(),
+    };
+    try_remote_typed!(
+        state,
+        app,
+        git_rpc::METHOD_CONTINUE_GIT_OPERATION,
+        git_remote_params(&request)?,
+        GitOperationStatus
+    );
+    git_ui_core::continue_git_operation_core(&state.workspaces, workspace_id).await
+}
+
+#[tauri::command]
+pub(crate) async fn skip_git_operation(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<GitOperationStatus, String> {
+    let request = git_rpc::WorkspaceIdRequest {
+        workspace_id: workspace_id.clone(),
+    };
+    try_remote_typed!(
+        state,
+        app,
+        git_rpc::METHOD_SKIP_GIT_OPERATION,
+        git_remote_params(&request)?,
+        GitOperationStatus
+    );
+    git_ui_core::skip_git_operation_core(&state.workspaces, workspace_id).await
+}
+
+#[tauri::command]
+pub(crate) async fn abort_git_operation(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<GitOperationStatus, String> {
+    let request = git_rpc::WorkspaceIdRequest {
+        workspace_id: workspace_id.clone(),
+    };
+    try_remote_typed!(
+        state,
+        app,
+        git_rpc::METHOD_ABORT_GIT_OPERATION,
+        git_remote_params(&request)?,
+        GitOperationStatus
+    );
+    git_ui_core::abort_git_operation_core(&state.workspaces, workspace_id).await
+}
+
+#[tauri::command]
+pub(crate) async fn get_git_conflict(
+    workspace_id: String,
+    path: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<GitConflictDetail, String> {
+    let request = git_rpc::WorkspacePathRequest {
+        workspace_id: workspace_id.clone(),
+        path: path.clone(),
+    };
+    try_remote_typed!(
+        state,
+        app,
+        git_rpc::METHOD_GET_GIT_CONFLICT,
+        git_remote_params(&request)?,
+        GitConflictDetail
+    );
+    git_ui_core::get_git_conflict_core(&state.workspaces, workspace_id, path).await
+}
+
+#[tauri::command]
+pub(crate) async fn resolve_git_conflict(
+    workspace_id: String,
+    path: String,
+    resolution: GitConflictResolution,
+    content: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let request = git_rpc::ResolveGitConflictRequest {
+        workspace_id: workspace_id.clone(),
+        path: path.clone(),
+        resolution,
+        content: content.clone(),
+    };
+    try_remote_unit!(
+        state,
+        app,
+        git_rpc::METHOD_RESOLVE_GIT_CONFLICT,
+        git_remote_params(&request)?
+    );
+    git_ui_core::resolve_git_conflict_core(
+        &state.workspaces,
+        workspace_id,
+        path,
+        resolution,
+        content,
+    )
+    .await
+}
+
 #[tauri::command]
 pub(crate) async fn list_git_roots(
     workspace_id: String,
@@ -394,12 +784,16 @@ pub(crate) async fn get_git_diffs(
 pub(crate) async fn get_git_log(
     workspace_id: String,
     limit: Option<usize>,
+    cursor: Option<String>,
+    filter: Option<GitLogFilter>,
     state: State<'_, AppState>,
     app: AppHandle,
 ) -> Result<GitLogResponse, String> {
     let request = git_rpc::GetGitLogRequest {
         workspace_id: workspace_id.clone(),
         limit: optional_usize_to_u32(limit),
+        cursor: cursor.clone(),
+        filter: filter.clone(),
     };
     try_remote_typed!(
         state,
@@ -408,7 +802,7 @@ pub(crate) async fn get_git_log(
         git_remote_params(&request)?,
         GitLogResponse
     );
-    git_ui_core::get_git_log_core(&state.workspaces, workspace_id, limit).await
+    git_ui_core::get_git_log_core(&state.workspaces, workspace_id, limit, cursor, filter).await
 }
 
 #[tauri::command]
@@ -34,6 +34,7 @@ mod terminal;
 #[cfg(not(desktop))]
 #[path = "terminal_mobile.rs"]
 mod terminal;
+mod thread_search;
 mod tray;
 mod types;
 mod utils;
@@ -206,6 +207,8 @@ pub fn run() {
             workspaces::rename_worktree,
             workspaces::rename_worktree_upstream,
             workspaces::apply_worktree_changes,
+            workspaces::preview_land_worktree,
+            workspaces::land_worktree,
             workspaces::update_workspace_settings,
             workspaces::set_workspace_runtime_codex_args,
             codex::start_thread,
@@ -215,6 +218,12 @@ pub fn run() {
             codex::start_review,
             codex::respond_to_server_request,
             codex::remember_approval_rule,
+            codex::explain_approval_policy,
+            codex::export_thread,
+            codex::import_thread,
+            codex::list_codex_rules,
+            codex::update_codex_rule,
+            codex::delete_codex_rule,
             codex::generate_commit_message,
             codex::generate_run_metadata,
             codex::generate_agent_description,
@@ -241,12 +250,28 @@ pub fn run() {
             git::stage_git_all,
             git::unstage_git_file,
             git::revert_git_file,
+            git::stage_git_selection,
+            git::unstage_git_selection,
+            git::revert_git_selection,
             git::revert_git_all,
             git::commit_git,
             git::push_git,
             git::pull_git,
             git::fetch_git,
             git::sync_git,
+            git::list_git_stashes,
+            git::get_git_stash_diff,
+            git::create_git_stash,
+            git::apply_git_stash,
+            git::pop_git_stash,
+            git::drop_git_stash,
+            git::merge_git_branch,
+            git::rebase_git_branch,
+            git::continue_git_operation,
+            git::skip_git_operation,
+            git::abort_git_operation,
+            git::get_git_conflict,
+            git::resolve_git_conflict,
             git::get_github_issues,
             git::get_github_pull_requests,
             git::get_github_pull_request_diff,
@@ -295,6 +320,13 @@ pub fn run() {
             dictation::dictation_stop,
             dictation::dictation_cancel,
             local_usage::local_usage_snapshot,
+            local_usage::local_usage_thread_timeline,
+            local_usage::local_usage_export,
+            local_usage::local_usage_watch,
+            local_usage::local_usage_unwatch,
+            local_usage::usage_budget_status,
+            local_usage::override_usage_budget,
+            thread_search::search_threads,
             notifications::is_macos_debug_build,
             notifications::app_build_type,
             notifications::send_notification_fallback,
@@ -1,10 +1,12 @@
 use serde_json::json;
 use tauri::{AppHandle, State};
 
+use crate::event_sink::TauriEventSink;
 use crate::remote_backend;
-use crate::shared::local_usage_core;
+use crate::shared::usage_export_core::{self, LocalUsageExport, LocalUsageExportFormat};
+use crate::shared::{local_usage_core, usage_budget_core};
 use crate::state::AppState;
-use crate::types::LocalUsageSnapshot;
+use crate::types::{LocalUsageSnapshot, LocalUsageThreadTimeline, UsageBudgetStatus};
 
 #[tauri::command]
 pub(crate) async fn local_usage_snapshot(
@@ -24,5 +26,172 @@ pub(crate) async fn local_usage_snapshot(
         return serde_json::from_value(response).map_err(|err| err.to_string());
     }
 
-    local_usage_core::local_usage_snapshot_core(&state.workspaces, days, workspace_path).await
+    let data_dir = state
+        .storage_path
+        .parent()
+        .ok_or_else(|| "unable to resolve app data dir".to_string())?;
+    let model_prices = state.app_settings.lock().await.model_prices.clone();
+    let snapshot = local_usage_core::local_usage_snapshot_core(
+        &state.workspaces,
+        data_dir,
+        days,
+        workspace_path,
+        model_prices,
+    )
+    .await?;
+    usage_budget_core::spawn_usage_budget_check(&state.usage_budgets, TauriEventSink::new(app));
+    Ok(snapshot)
+}
+
+#[tauri::command]
+pub(crate) async fn local_usage_export(
+    format: LocalUsageExportFormat,
+    days: Option<u32>,
+    workspace_path: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<LocalUsageExport, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "local_usage_export",
+            json!({ "format": format, "days": days, "workspacePath": workspace_path }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    let data_dir = state
+        .storage_path
+        .parent()
+        .ok_or_else(|| "unable to resolve app data dir".to_string())?;
+    let model_prices = state.app_settings.lock().await.model_prices.clone();
+    usage_export_core::local_usage_export_core(
+        &state.workspaces,
+        data_dir,
+        format,
+        days,
+        workspace_path,
+        model_prices,
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn local_usage_watch(
+    days: Option<u32>,
+    workspace_path: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "local_usage_watch",
+            json!({ "days": days, "workspacePath": workspace_path }),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let data_dir = state
+        .storage_path
+        .parent()
+        .ok_or_else(|| "unable to resolve app data dir".to_string())?;
+    local_usage_core::local_usage_watch_core(
+        &state.local_usage_watchers,
+        local_usage_core::APP_WATCH_SUBSCRIBER,
+        &state.workspaces,
+        data_dir,
+        days,
+        workspace_path,
+        state.usage_budgets.clone(),
+        TauriEventSink::new(app),
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn local_usage_unwatch(
+    days: Option<u32>,
+    workspace_path: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "local_usage_unwatch",
+            json!({ "days": days, "workspacePath": workspace_path }),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    local_usage_core::local_usage_unwatch_core(
+        &state.local_usage_watchers,
+        local_usage_core::APP_WATCH_SUBSCRIBER,
+        days,
+        workspace_path,
+    )
+}
+
+#[tauri::command]
+pub(crate) async fn local_usage_thread_timeline(
+    thread_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<LocalUsageThreadTimeline, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "local_usage_thread_timeline",
+            json!({ "threadId": thread_id }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    let model_prices = state.app_settings.lock().await.model_prices.clone();
+    local_usage_core::local_usage_thread_timeline_core(&state.workspaces, thread_id, model_prices)
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn usage_budget_status(
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Vec<UsageBudgetStatus>, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response =
+            remote_backend::call_remote(&*state, app, "usage_budget_status", json!({})).await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    usage_budget_core::usage_budget_status_core(&state.usage_budgets, TauriEventSink::new(app))
+        .await
+}
+
+#[tauri::command]
+pub(crate) async fn override_usage_budget(
+    budget_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(
+            &*state,
+            app,
+            "override_usage_budget",
+            json!({ "budgetId": budget_id }),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    usage_budget_core::override_usage_budget_core(&state.usage_budgets, budget_id).await
 }
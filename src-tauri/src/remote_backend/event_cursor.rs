@@ -0,0 +1,109 @@
+use std::sync::Mutex;
+
+use serde_json::Value;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct EventCursorPosition {
+    pub(crate) stream_id: String,
+    pub(crate) last_seq: u64,
+}
+
+#[derive(Debug, Default)]
+struct CursorState {
+    stream_id: Option<String>,
+    last_seq: u64,
+}
+
+/// Last daemon event seen by this app, kept across reconnects so the next `auth` can ask
+/// the daemon to replay whatever was emitted while we were disconnected.
+#[derive(Debug, Default)]
+pub(crate) struct RemoteEventCursor {
+    state: Mutex<CursorState>,
+}
+
+impl RemoteEventCursor {
+    pub(crate) fn position(&self) -> Option<EventCursorPosition> {
+        let state = self.lock_state();
+        state
+            .stream_id
+            .as_ref()
+            .map(|stream_id| EventCursorPosition {
+                stream_id: stream_id.clone(),
+                last_seq: state.last_seq,
+            })
+    }
+
+    /// Applies an `auth` result. A different stream id means the daemon restarted, so the
+    /// old sequence numbers no longer apply.
+    pub(crate) fn observe_auth_result(&self, result: &Value) {
+        let Some(stream_id) = result.get("eventStreamId").and_then(Value::as_str) else {
+            return;
+        };
+        let mut state = self.lock_state();
+        if state.stream_id.as_deref() == Some(stream_id) {
+            return;
+        }
+        state.stream_id = Some(stream_id.to_string());
+        state.last_seq = result
+            .get("lastEventSeq")
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+    }
+
+    pub(crate) fn observe_event(&self, seq: u64) {
+        let mut state = self.lock_state();
+        state.last_seq = state.last_seq.max(seq);
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, CursorState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EventCursorPosition, RemoteEventCursor};
+    use serde_json::json;
+
+    #[test]
+    fn cursor_tracks_events_within_a_stream() {
+        let cursor = RemoteEventCursor::default();
+        assert_eq!(cursor.position(), None);
+
+        cursor.observe_auth_result(&json!({ "eventStreamId": "stream-a", "lastEventSeq": 10 }));
+        cursor.observe_event(11);
+        cursor.observe_event(12);
+        cursor.observe_auth_result(&json!({ "eventStreamId": "stream-a", "lastEventSeq": 40 }));
+        assert_eq!(
+            cursor.position(),
+            Some(EventCursorPosition {
+                stream_id: "stream-a".to_string(),
+                last_seq: 12,
+            })
+        );
+    }
+
+    #[test]
+    fn cursor_resets_when_daemon_stream_changes() {
+        let cursor = RemoteEventCursor::default();
+        cursor.observe_auth_result(&json!({ "eventStreamId": "stream-a", "lastEventSeq": 0 }));
+        cursor.observe_event(500);
+
+        cursor.observe_auth_result(&json!({ "eventStreamId": "stream-b", "lastEventSeq": 3 }));
+        assert_eq!(
+            cursor.position(),
+            Some(EventCursorPosition {
+                stream_id: "stream-b".to_string(),
+                last_seq: 3,
+            })
+        );
+
+        cursor.observe_auth_result(&json!({ "ok": true }));
+        assert_eq!(
+            cursor.position().map(|position| position.stream_id),
+            Some("stream-b".to_string())
+        );
+    }
+}
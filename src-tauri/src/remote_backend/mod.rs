@@ -1,6 +1,9 @@
+mod event_cursor;
 mod protocol;
 mod tcp_transport;
+mod tls;
 mod transport;
+mod ws_transport;
 
 use serde_json::{json, Value};
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -11,12 +14,16 @@ use tauri::AppHandle;
 use tokio::sync::Mutex;
 use tokio::time::timeout;
 
+use crate::shared::rpc_error::{RpcError, RpcErrorCode};
 use crate::state::AppState;
-use crate::types::BackendMode;
+use crate::types::{BackendMode, RemoteBackendProvider};
 
-use self::protocol::{build_request_line, DEFAULT_REMOTE_HOST, DISCONNECTED_MESSAGE};
+pub(crate) use self::event_cursor::RemoteEventCursor;
+use self::protocol::{build_request_line, disconnected_error, DEFAULT_REMOTE_HOST};
 use self::tcp_transport::TcpTransport;
+use self::tls::RemoteTlsConfig;
 use self::transport::{PendingMap, RemoteTransport, RemoteTransportConfig, RemoteTransportKind};
+use self::ws_transport::WebSocketTransport;
 
 const REMOTE_REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
 const REMOTE_SEND_TIMEOUT: Duration = Duration::from_secs(15);
@@ -68,39 +75,46 @@ struct RemoteBackendInner {
 }
 
 impl RemoteBackend {
-    pub(crate) async fn call(&self, method: &str, params: Value) -> Result<Value, String> {
+    pub(crate) async fn call(&self, method: &str, params: Value) -> Result<Value, RpcError> {
         if !self.inner.connected.load(Ordering::SeqCst) {
-            return Err(DISCONNECTED_MESSAGE.to_string());
+            return Err(disconnected_error());
         }
 
         let id = self.inner.next_id.fetch_add(1, Ordering::SeqCst);
         let (tx, rx) = tokio::sync::oneshot::channel();
         self.inner.pending.lock().await.insert(id, tx);
 
-        let message = build_request_line(id, method, params)?;
+        let message = build_request_line(id, method, params)
+            .map_err(|err| RpcError::new(RpcErrorCode::InvalidRequest, err))?;
         match timeout(REMOTE_SEND_TIMEOUT, self.inner.out_tx.send(message)).await {
             Ok(Ok(())) => {}
             Ok(Err(_)) => {
                 self.inner.pending.lock().await.remove(&id);
-                return Err(DISCONNECTED_MESSAGE.to_string());
+                return Err(disconnected_error());
             }
             Err(_) => {
                 self.inner.pending.lock().await.remove(&id);
-                return Err(format!(
-                    "remote backend request dispatch timed out after {} seconds",
-                    REMOTE_SEND_TIMEOUT.as_secs()
+                return Err(RpcError::new(
+                    RpcErrorCode::Timeout,
+                    format!(
+                        "remote backend request dispatch timed out after {} seconds",
+                        REMOTE_SEND_TIMEOUT.as_secs()
+                    ),
                 ));
             }
         }
 
         match timeout(REMOTE_REQUEST_TIMEOUT, rx).await {
             Ok(Ok(result)) => result,
-            Ok(Err(_)) => Err(DISCONNECTED_MESSAGE.to_string()),
+            Ok(Err(_)) => Err(disconnected_error()),
             Err(_) => {
                 self.inner.pending.lock().await.remove(&id);
-                Err(format!(
-                    "remote backend request timed out after {} seconds",
-                    REMOTE_REQUEST_TIMEOUT.as_secs()
+                Err(RpcError::new(
+                    RpcErrorCode::Timeout,
+                    format!(
+                        "remote backend request timed out after {} seconds",
+                        REMOTE_REQUEST_TIMEOUT.as_secs()
+                    ),
                 ))
             }
         }
@@ -121,23 +135,23 @@ pub(crate) async fn call_remote(
     let client = ensure_remote_backend(state, app.clone()).await?;
     match client.call(method, params.clone()).await {
         Ok(value) => Ok(value),
-        Err(err) if err == DISCONNECTED_MESSAGE => {
+        Err(err) if err.is(RpcErrorCode::Disconnected) => {
             *state.remote_backend.lock().await = None;
             if !can_retry_after_disconnect(method) {
-                return Err(err);
+                return Err(err.into());
             }
             let retry_client = ensure_remote_backend(state, app).await?;
             match retry_client.call(method, params).await {
                 Ok(value) => Ok(value),
                 Err(retry_err) => {
                     *state.remote_backend.lock().await = None;
-                    Err(retry_err)
+                    Err(retry_err.into())
                 }
             }
         }
         Err(err) => {
             *state.remote_backend.lock().await = None;
-            Err(err)
+            Err(err.into())
         }
     }
 }
@@ -151,14 +165,17 @@ fn can_retry_after_disconnect(method: &str) -> bool {
             | "collaboration_mode_list"
             | "connect_workspace"
             | "experimental_feature_list"
+            | "export_thread"
             | "set_workspace_runtime_codex_args"
             | "file_read"
             | "get_agents_settings"
             | "get_config_model"
             | "get_git_commit_diff"
+            | "get_git_conflict"
             | "get_git_diffs"
             | "get_git_log"
             | "get_git_remote"
+            | "get_git_stash_diff"
             | "get_git_status"
             | "get_github_issues"
             | "get_github_pull_request_comments"
@@ -167,15 +184,21 @@ fn can_retry_after_disconnect(method: &str) -> bool {
             | "is_workspace_path_dir"
             | "list_git_branches"
             | "list_git_roots"
+            | "list_git_stashes"
             | "list_mcp_server_status"
             | "list_threads"
             | "local_usage_snapshot"
+            | "local_usage_thread_timeline"
+            | "local_usage_export"
+            | "usage_budget_status"
             | "list_workspace_files"
             | "list_workspaces"
             | "model_list"
+            | "preview_land_worktree"
             | "read_agent_config_toml"
             | "read_workspace_file"
             | "resume_thread"
+            | "search_threads"
             | "thread_live_subscribe"
             | "thread_live_unsubscribe"
             | "skills_list"
@@ -195,11 +218,11 @@ async fn ensure_remote_backend(state: &AppState, app: AppHandle) -> Result<Remot
         let settings = state.app_settings.lock().await;
         resolve_transport_config(&settings)?
     };
-    let transport_kind = transport_config.kind();
     let auth_token = transport_config.auth_token().map(|value| value.to_string());
 
     let transport: Box<dyn RemoteTransport> = match transport_config.kind() {
         RemoteTransportKind::Tcp => Box::new(TcpTransport),
+        RemoteTransportKind::WebSocket => Box::new(WebSocketTransport),
     };
     let connection = transport.connect(app, transport_config).await?;
 
@@ -212,13 +235,20 @@ async fn ensure_remote_backend(state: &AppState, app: AppHandle) -> Result<Remot
         }),
     };
 
-    if matches!(transport_kind, RemoteTransportKind::Tcp) {
-        if let Some(token) = auth_token {
-            client
-                .call("auth", json!({ "token": token }))
-                .await
-                .map(|_| ())?;
-        }
+    // `auth` also establishes the event cursor, so it is sent even without a token.
+    let mut auth_params = json!({});
+    if let Some(token) = auth_token.as_ref() {
+        auth_params["token"] = json!(token);
+    }
+    if let Some(position) = state.remote_event_cursor.position() {
+        auth_params["eventStreamId"] = json!(position.stream_id);
+        auth_params["lastEventSeq"] = json!(position.last_seq);
+    }
+    match client.call("auth", auth_params).await {
+        Ok(_) => {}
+        // Daemons without auth or event replay reject the call; only a token makes it required.
+        Err(err) if auth_token.is_none() && !err.is(RpcErrorCode::Disconnected) => {}
+        Err(err) => return Err(err.into()),
     }
 
     {
@@ -237,17 +267,73 @@ fn resolve_transport_config(
     } else {
         settings.remote_backend_host.clone()
     };
-    Ok(RemoteTransportConfig::Tcp {
-        host,
-        auth_token: settings.remote_backend_token.clone(),
+    let active_target = settings.active_remote_backend_id.as_deref().and_then(|id| {
+        settings
+            .remote_backends
+            .iter()
+            .find(|target| target.id == id)
+    });
+    let tls = active_target
+        .filter(|target| target.tls_enabled)
+        .map(|target| RemoteTlsConfig {
+            fingerprint: non_empty(target.tls_fingerprint.as_deref()),
+            client_cert_path: non_empty(target.tls_client_cert_path.as_deref()),
+            client_key_path: non_empty(target.tls_client_key_path.as_deref()),
+        });
+    let auth_token = settings.remote_backend_token.clone();
+    Ok(match settings.remote_backend_provider {
+        RemoteBackendProvider::Tcp => RemoteTransportConfig::Tcp {
+            host,
+            auth_token,
+            tls,
+        },
+        RemoteBackendProvider::WebSocket => RemoteTransportConfig::WebSocket {
+            url: websocket_url(&host, tls.is_some()),
+            auth_token,
+            tls,
+        },
     })
 }
 
+/// Accepts either a full `ws://`/`wss://` URL (e.g. behind a reverse proxy) or a bare `host:port`.
+fn websocket_url(host: &str, tls_enabled: bool) -> String {
+    let trimmed = host.trim();
+    let lower = trimmed.to_ascii_lowercase();
+    if lower.starts_with("ws://") || lower.starts_with("wss://") {
+        return trimmed.to_string();
+    }
+    let scheme = if tls_enabled { "wss" } else { "ws" };
+    format!("{scheme}://{trimmed}")
+}
+
+fn non_empty(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{can_retry_after_disconnect, resolve_transport_config};
+    use super::{can_retry_after_disconnect, resolve_transport_config, websocket_url};
+    use crate::remote_backend::tls::RemoteTlsConfig;
     use crate::remote_backend::transport::RemoteTransportConfig;
-    use crate::types::AppSettings;
+    use crate::types::{AppSettings, RemoteBackendProvider, RemoteBackendTarget};
+
+    fn remote_target(id: &str, tls_enabled: bool) -> RemoteBackendTarget {
+        RemoteBackendTarget {
+            id: id.to_string(),
+            name: id.to_string(),
+            provider: RemoteBackendProvider::Tcp,
+            host: "tcp.example:4732".to_string(),
+            token: None,
+            last_connected_at_ms: None,
+            tls_enabled,
+            tls_fingerprint: Some(" AA:BB ".to_string()),
+            tls_client_cert_path: Some(String::new()),
+            tls_client_key_path: None,
+        }
+    }
 
     #[test]
     fn resolve_tcp_transport_uses_remote_host() {
@@ -261,6 +347,60 @@ mod tests {
         assert_eq!(host, "tcp.example:4732");
     }
 
+    #[test]
+    fn resolve_websocket_transport_builds_url_from_host() {
+        let mut settings = AppSettings::default();
+        settings.remote_backend_provider = RemoteBackendProvider::WebSocket;
+        settings.remote_backend_host = "ws.example:4733".to_string();
+        settings.remote_backend_token = Some("token-1".to_string());
+
+        let config = resolve_transport_config(&settings).expect("transport config");
+        assert_eq!(config.auth_token(), Some("token-1"));
+        let RemoteTransportConfig::WebSocket { url, tls, .. } = config else {
+            panic!("expected websocket transport config");
+        };
+        assert_eq!(url, "ws://ws.example:4733");
+        assert_eq!(tls, None);
+    }
+
+    #[test]
+    fn websocket_url_keeps_explicit_scheme() {
+        assert_eq!(
+            websocket_url("wss://proxy.example/codex", false),
+            "wss://proxy.example/codex"
+        );
+        assert_eq!(websocket_url(" WS://host:1 ", true), "WS://host:1");
+        assert_eq!(websocket_url("host:4733", true), "wss://host:4733");
+    }
+
+    #[test]
+    fn resolve_tcp_transport_uses_active_target_tls_settings() {
+        let mut settings = AppSettings::default();
+        settings.remote_backends =
+            vec![remote_target("plain", false), remote_target("secure", true)];
+
+        settings.active_remote_backend_id = Some("plain".to_string());
+        let config = resolve_transport_config(&settings).expect("transport config");
+        let RemoteTransportConfig::Tcp { tls, .. } = config else {
+            panic!("expected tcp transport config");
+        };
+        assert_eq!(tls, None);
+
+        settings.active_remote_backend_id = Some("secure".to_string());
+        let config = resolve_transport_config(&settings).expect("transport config");
+        let RemoteTransportConfig::Tcp { tls, .. } = config else {
+            panic!("expected tcp transport config");
+        };
+        assert_eq!(
+            tls,
+            Some(RemoteTlsConfig {
+                fingerprint: Some("AA:BB".to_string()),
+                client_cert_path: None,
+                client_key_path: None,
+            })
+        );
+    }
+
     #[test]
     fn retries_only_retry_safe_methods_after_disconnect() {
         assert!(can_retry_after_disconnect("resume_thread"));
@@ -1,21 +1,29 @@
 use serde_json::{json, Value};
 
+use crate::shared::rpc_error::{RpcError, RpcErrorCode};
+
 pub(crate) const DEFAULT_REMOTE_HOST: &str = "127.0.0.1:4732";
 pub(crate) const DISCONNECTED_MESSAGE: &str = "remote backend disconnected";
 
 pub(crate) enum IncomingMessage {
     Response {
         id: u64,
-        payload: Result<Value, String>,
+        payload: Result<Value, RpcError>,
     },
     Notification {
         method: String,
         params: Value,
+        seq: Option<u64>,
     },
 }
 
+pub(crate) fn disconnected_error() -> RpcError {
+    RpcError::new(RpcErrorCode::Disconnected, DISCONNECTED_MESSAGE)
+}
+
 pub(crate) fn build_request_line(id: u64, method: &str, params: Value) -> Result<String, String> {
     let request = json!({
+        "jsonrpc": "2.0",
         "id": id,
         "method": method,
         "params": params,
@@ -28,14 +36,9 @@ pub(crate) fn parse_incoming_line(line: &str) -> Option<IncomingMessage> {
 
     if let Some(id) = message.get("id").and_then(|value| value.as_u64()) {
         if let Some(error) = message.get("error") {
-            let error_message = error
-                .get("message")
-                .and_then(|value| value.as_str())
-                .unwrap_or("remote error")
-                .to_string();
             return Some(IncomingMessage::Response {
                 id,
-                payload: Err(error_message),
+                payload: Err(RpcError::from_value(error)),
             });
         }
 
@@ -54,8 +57,10 @@ pub(crate) fn parse_incoming_line(line: &str) -> Option<IncomingMessage> {
         return None;
     }
     let params = message.get("params").cloned().unwrap_or(Value::Null);
+    let seq = message.get("seq").and_then(|value| value.as_u64());
     Some(IncomingMessage::Notification {
         method: method.to_string(),
         params,
+        seq,
     })
 }
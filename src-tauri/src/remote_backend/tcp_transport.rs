@@ -1,21 +1,38 @@
 use tauri::AppHandle;
 use tokio::net::TcpStream;
 
+use super::tls::build_connector;
 use super::transport::{
     spawn_transport_io, RemoteTransport, RemoteTransportConfig, TransportFuture,
 };
+use crate::shared::tls_core::server_name_for_host;
 
 pub(crate) struct TcpTransport;
 
 impl RemoteTransport for TcpTransport {
     fn connect(&self, app: AppHandle, config: RemoteTransportConfig) -> TransportFuture {
         Box::pin(async move {
-            let RemoteTransportConfig::Tcp { host, .. } = config;
+            let RemoteTransportConfig::Tcp { host, tls, .. } = config else {
+                return Err("TCP transport requires a TCP transport config".to_string());
+            };
 
             let stream = TcpStream::connect(host.clone())
                 .await
                 .map_err(|err| format!("Failed to connect to remote backend at {host}: {err}"))?;
-            let (reader, writer) = stream.into_split();
+            let Some(tls) = tls else {
+                let (reader, writer) = stream.into_split();
+                return Ok(spawn_transport_io(app, reader, writer));
+            };
+
+            let connector = build_connector(&tls)?;
+            let server_name = server_name_for_host(&host)?;
+            let stream = connector
+                .connect(server_name, stream)
+                .await
+                .map_err(|err| {
+                    format!("TLS handshake with remote backend at {host} failed: {err}")
+                })?;
+            let (reader, writer) = tokio::io::split(stream);
             Ok(spawn_transport_io(app, reader, writer))
         })
     }
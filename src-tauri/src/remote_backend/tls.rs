@@ -0,0 +1,130 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use tokio_rustls::rustls::client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+};
+use tokio_rustls::rustls::crypto::{self, CryptoProvider};
+use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use tokio_rustls::rustls::{
+    ClientConfig, DigitallySignedStruct, Error as TlsError, RootCertStore, SignatureScheme,
+};
+use tokio_rustls::TlsConnector;
+
+use crate::shared::tls_core;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct RemoteTlsConfig {
+    pub(crate) fingerprint: Option<String>,
+    pub(crate) client_cert_path: Option<String>,
+    pub(crate) client_key_path: Option<String>,
+}
+
+/// Trusts exactly one certificate, identified by its SHA-256 fingerprint.
+/// Used for self-signed daemons where no CA is available.
+#[derive(Debug)]
+struct PinnedCertVerifier {
+    fingerprint: String,
+    provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for PinnedCertVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, TlsError> {
+        let actual = tls_core::certificate_fingerprint(end_entity.as_ref());
+        if actual == self.fingerprint {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(TlsError::General(format!(
+                "Remote backend certificate fingerprint mismatch (expected {}, got {actual})",
+                self.fingerprint
+            )))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, TlsError> {
+        crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, TlsError> {
+        crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+pub(crate) fn build_connector(config: &RemoteTlsConfig) -> Result<TlsConnector, String> {
+    build_client_config(config).map(|client_config| TlsConnector::from(Arc::new(client_config)))
+}
+
+pub(crate) fn build_client_config(config: &RemoteTlsConfig) -> Result<ClientConfig, String> {
+    let provider = Arc::new(crypto::ring::default_provider());
+    let builder = ClientConfig::builder_with_provider(Arc::clone(&provider))
+        .with_safe_default_protocol_versions()
+        .map_err(|err| format!("Failed to configure TLS: {err}"))?;
+
+    let builder = match config.fingerprint.as_deref() {
+        Some(raw) => {
+            let fingerprint = tls_core::normalize_fingerprint(raw)
+                .ok_or_else(|| format!("Invalid TLS fingerprint: {raw}"))?;
+            builder
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(PinnedCertVerifier {
+                    fingerprint,
+                    provider,
+                }))
+        }
+        None => {
+            let mut roots = RootCertStore::empty();
+            roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+            builder.with_root_certificates(roots)
+        }
+    };
+
+    let client_config = match (
+        config.client_cert_path.as_deref(),
+        config.client_key_path.as_deref(),
+    ) {
+        (Some(cert_path), Some(key_path)) => {
+            let certs = tls_core::load_certificates(Path::new(cert_path))?;
+            let key = tls_core::load_private_key(Path::new(key_path))?;
+            builder
+                .with_client_auth_cert(certs, key)
+                .map_err(|err| format!("Invalid TLS client certificate or key: {err}"))?
+        }
+        (None, None) => builder.with_no_client_auth(),
+        _ => return Err("TLS client certificate and key must be configured together".to_string()),
+    };
+
+    Ok(client_config)
+}
@@ -5,38 +5,50 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use serde_json::Value;
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
 use tokio::sync::{mpsc, oneshot, Mutex};
 
-use super::protocol::{parse_incoming_line, IncomingMessage, DISCONNECTED_MESSAGE};
+use super::protocol::{disconnected_error, parse_incoming_line, IncomingMessage};
+use super::tls::RemoteTlsConfig;
+use crate::shared::rpc_error::RpcError;
+use crate::state::AppState;
 
-pub(crate) type PendingMap = HashMap<u64, oneshot::Sender<Result<Value, String>>>;
-const OUTBOUND_QUEUE_CAPACITY: usize = 512;
+pub(crate) type PendingMap = HashMap<u64, oneshot::Sender<Result<Value, RpcError>>>;
+pub(crate) const OUTBOUND_QUEUE_CAPACITY: usize = 512;
 
 #[derive(Clone, Debug)]
 pub(crate) enum RemoteTransportConfig {
     Tcp {
         host: String,
         auth_token: Option<String>,
+        tls: Option<RemoteTlsConfig>,
+    },
+    WebSocket {
+        url: String,
+        auth_token: Option<String>,
+        tls: Option<RemoteTlsConfig>,
     },
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub(crate) enum RemoteTransportKind {
     Tcp,
+    WebSocket,
 }
 
 impl RemoteTransportConfig {
     pub(crate) fn kind(&self) -> RemoteTransportKind {
         match self {
             RemoteTransportConfig::Tcp { .. } => RemoteTransportKind::Tcp,
+            RemoteTransportConfig::WebSocket { .. } => RemoteTransportKind::WebSocket,
         }
     }
 
     pub(crate) fn auth_token(&self) -> Option<&str> {
         match self {
-            RemoteTransportConfig::Tcp { auth_token, .. } => auth_token.as_deref(),
+            RemoteTransportConfig::Tcp { auth_token, .. }
+            | RemoteTransportConfig::WebSocket { auth_token, .. } => auth_token.as_deref(),
         }
     }
 }
@@ -126,23 +138,51 @@ pub(crate) async fn dispatch_incoming_line(
 
     match message {
         IncomingMessage::Response { id, payload } => {
+            // Applied here rather than by the caller so it is ordered before any replayed
+            // events that follow the auth response on the wire.
+            if let Ok(result) = payload.as_ref() {
+                app.state::<AppState>()
+                    .remote_event_cursor
+                    .observe_auth_result(result);
+            }
             let sender = pending.lock().await.remove(&id);
             if let Some(sender) = sender {
                 let _ = sender.send(payload);
             }
         }
-        IncomingMessage::Notification { method, params } => match method.as_str() {
-            "app-server-event" => {
-                let _ = app.emit("app-server-event", params);
+        IncomingMessage::Notification {
+            method,
+            params,
+            seq,
+        } => {
+            if let Some(seq) = seq {
+                app.state::<AppState>()
+                    .remote_event_cursor
+                    .observe_event(seq);
             }
-            "terminal-output" => {
-                let _ = app.emit("terminal-output", params);
+            match method.as_str() {
+                "app-server-event" => {
+                    let _ = app.emit("app-server-event", params);
+                }
+                "terminal-output" => {
+                    let _ = app.emit("terminal-output", params);
+                }
+                "terminal-exit" => {
+                    let _ = app.emit("terminal-exit", params);
+                }
+                "local-usage-updated" => {
+                    let _ = app.emit("local-usage-updated", params);
+                }
+                "usage-budget-alert" => {
+                    let _ = app.emit("usage-budget-alert", params);
+                }
+                // Events were lost on the daemon side; the frontend reloads thread state.
+                "event-gap" => {
+                    let _ = app.emit("event-gap", params);
+                }
+                _ => {}
             }
-            "terminal-exit" => {
-                let _ = app.emit("terminal-exit", params);
-            }
-            _ => {}
-        },
+        }
     }
 }
 
@@ -153,6 +193,6 @@ pub(crate) async fn mark_disconnected(
     connected.store(false, Ordering::SeqCst);
     let mut pending = pending.lock().await;
     for (_, sender) in pending.drain() {
-        let _ = sender.send(Err(DISCONNECTED_MESSAGE.to_string()));
+        let _ = sender.send(Err(disconnected_error()));
     }
 }
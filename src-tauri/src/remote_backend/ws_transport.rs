@@ -0,0 +1,89 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use futures_util::{SinkExt, StreamExt};
+use tauri::AppHandle;
+use tokio::sync::{mpsc, Mutex};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::Connector;
+
+use super::tls::build_client_config;
+use super::transport::{
+    dispatch_incoming_line, mark_disconnected, PendingMap, RemoteTransport, RemoteTransportConfig,
+    TransportConnection, TransportFuture, OUTBOUND_QUEUE_CAPACITY,
+};
+
+pub(crate) struct WebSocketTransport;
+
+impl RemoteTransport for WebSocketTransport {
+    fn connect(&self, app: AppHandle, config: RemoteTransportConfig) -> TransportFuture {
+        Box::pin(async move {
+            let RemoteTransportConfig::WebSocket { url, tls, .. } = config else {
+                return Err("WebSocket transport requires a WebSocket transport config".to_string());
+            };
+
+            let connector = tls
+                .as_ref()
+                .map(build_client_config)
+                .transpose()?
+                .map(|client_config| Connector::Rustls(Arc::new(client_config)));
+            let (stream, _response) = tokio_tungstenite::connect_async_tls_with_config(
+                url.as_str(),
+                None,
+                false,
+                connector,
+            )
+            .await
+            .map_err(|err| format!("Failed to connect to remote backend at {url}: {err}"))?;
+            let (mut sink, mut source) = stream.split();
+
+            let (out_tx, mut out_rx) = mpsc::channel::<String>(OUTBOUND_QUEUE_CAPACITY);
+            let pending = Arc::new(Mutex::new(PendingMap::new()));
+            let pending_for_writer = Arc::clone(&pending);
+            let pending_for_reader = Arc::clone(&pending);
+
+            let connected = Arc::new(AtomicBool::new(true));
+            let connected_for_writer = Arc::clone(&connected);
+            let connected_for_reader = Arc::clone(&connected);
+
+            // Each frame carries exactly one protocol line, so the TCP framing is reused as-is.
+            tokio::spawn(async move {
+                while let Some(message) = out_rx.recv().await {
+                    if sink.send(Message::Text(message)).await.is_err() {
+                        mark_disconnected(&pending_for_writer, &connected_for_writer).await;
+                        break;
+                    }
+                }
+                let _ = sink.close().await;
+            });
+
+            tokio::spawn(async move {
+                while let Some(Ok(message)) = source.next().await {
+                    let text = match message {
+                        Message::Text(text) => text,
+                        Message::Binary(bytes) => match String::from_utf8(bytes) {
+                            Ok(text) => text,
+                            Err(_) => continue,
+                        },
+                        Message::Close(_) => break,
+                        _ => continue,
+                    };
+                    for line in text.lines() {
+                        let trimmed = line.trim();
+                        if trimmed.is_empty() {
+                            continue;
+                        }
+                        dispatch_incoming_line(&app, &pending_for_reader, trimmed).await;
+                    }
+                }
+                mark_disconnected(&pending_for_reader, &connected_for_reader).await;
+            });
+
+            Ok(TransportConnection {
+                out_tx,
+                pending,
+                connected,
+            })
+        })
+    }
+}
@@ -4,13 +4,239 @@ use std::path::{Path, PathBuf};
 use std::thread;
 use std::time::{Duration, Instant, SystemTime};
 
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
 const RULES_DIR: &str = "rules";
 const DEFAULT_RULES_FILE: &str = "default.rules";
+const RULES_EXTENSION: &str = "rules";
 
 pub(crate) fn default_rules_path(codex_home: &Path) -> PathBuf {
     codex_home.join(RULES_DIR).join(DEFAULT_RULES_FILE)
 }
 
+pub(crate) fn rules_dir(codex_home: &Path) -> PathBuf {
+    codex_home.join(RULES_DIR)
+}
+
+/// What Codex does with a command that matches a rule. Ordered from least to most strict;
+/// when several rules match, the strictest decision wins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum RuleDecision {
+    Allow,
+    Prompt,
+    Forbidden,
+}
+
+impl RuleDecision {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Prompt => "prompt",
+            Self::Forbidden => "forbidden",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "allow" => Some(Self::Allow),
+            "prompt" => Some(Self::Prompt),
+            "forbidden" => Some(Self::Forbidden),
+            _ => None,
+        }
+    }
+}
+
+fn default_decision() -> RuleDecision {
+    RuleDecision::Allow
+}
+
+/// One position of a rule pattern: a literal word, or a list of accepted alternatives.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum RulePatternToken {
+    Literal(String),
+    Alternatives(Vec<String>),
+}
+
+impl RulePatternToken {
+    fn words(&self) -> Vec<&str> {
+        match self {
+            Self::Literal(word) => vec![word.as_str()],
+            Self::Alternatives(words) => words.iter().map(String::as_str).collect(),
+        }
+    }
+
+    fn intersects(&self, other: &Self) -> bool {
+        let other_words = other.words();
+        self.words().iter().any(|word| other_words.contains(word))
+    }
+
+    fn equivalent(&self, other: &Self) -> bool {
+        let mut left = self.words();
+        let mut right = other.words();
+        left.sort_unstable();
+        left.dedup();
+        right.sort_unstable();
+        right.dedup();
+        left == right
+    }
+}
+
+/// Example command attached to a rule: either a shell string or pre-split words.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum RuleExample {
+    Command(String),
+    Words(Vec<String>),
+}
+
+/// A `prefix_rule(...)` entry from a Codex `.rules` file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PrefixRule {
+    pub(crate) pattern: Vec<RulePatternToken>,
+    #[serde(default = "default_decision")]
+    pub(crate) decision: RuleDecision,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) justification: Option<String>,
+    #[serde(default, rename = "match", skip_serializing_if = "Vec::is_empty")]
+    pub(crate) match_examples: Vec<RuleExample>,
+    #[serde(default, rename = "notMatch", skip_serializing_if = "Vec::is_empty")]
+    pub(crate) not_match_examples: Vec<RuleExample>,
+}
+
+impl PrefixRule {
+    fn allow(pattern: &[String]) -> Self {
+        Self {
+            pattern: pattern
+                .iter()
+                .cloned()
+                .map(RulePatternToken::Literal)
+                .collect(),
+            decision: RuleDecision::Allow,
+            justification: None,
+            match_examples: Vec::new(),
+            not_match_examples: Vec::new(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.pattern.is_empty() {
+            return Err("rule pattern must not be empty".to_string());
+        }
+        for token in &self.pattern {
+            let words = token.words();
+            if words.is_empty() {
+                return Err("rule pattern alternatives must not be empty".to_string());
+            }
+            if words.iter().any(|word| word.is_empty()) {
+                return Err("rule pattern words must not be empty".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    fn same_pattern(&self, other: &Self) -> bool {
+        self.pattern.len() == other.pattern.len()
+            && self
+                .pattern
+                .iter()
+                .zip(&other.pattern)
+                .all(|(left, right)| left.equivalent(right))
+    }
+
+    /// True when every command word of `words` is accepted at its position.
+    fn covers(&self, words: &[String]) -> bool {
+        self.pattern.len() == words.len()
+            && self
+                .pattern
+                .iter()
+                .zip(words)
+                .all(|(token, word)| token.words().contains(&word.as_str()))
+    }
+
+    /// True when some command can match both rules, i.e. one pattern is a prefix of the other
+    /// and every shared position has a common alternative.
+    fn overlaps(&self, other: &Self) -> bool {
+        self.pattern
+            .iter()
+            .zip(&other.pattern)
+            .all(|(left, right)| left.intersects(right))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RulesParseError {
+    pub(crate) line: usize,
+    pub(crate) message: String,
+}
+
+/// A rule together with where it sits in its file, so edits can splice the source in place.
+#[derive(Debug, Clone)]
+struct ParsedRule {
+    rule: PrefixRule,
+    start: usize,
+    end: usize,
+    line: usize,
+}
+
+#[derive(Debug, Default)]
+struct ParsedRulesFile {
+    rules: Vec<ParsedRule>,
+    errors: Vec<RulesParseError>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RuleEntry {
+    /// `<file>#<index>:<fingerprint>`; stale ids are rejected after the file changes.
+    pub(crate) id: String,
+    pub(crate) file: String,
+    pub(crate) index: usize,
+    pub(crate) line: usize,
+    pub(crate) rule: PrefixRule,
+    /// Comment lines directly above the rule.
+    pub(crate) comments: Vec<String>,
+    pub(crate) source: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RulesFileListing {
+    pub(crate) file: String,
+    pub(crate) path: String,
+    pub(crate) rules: Vec<RuleEntry>,
+    pub(crate) errors: Vec<RulesParseError>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RuleDuplicate {
+    pub(crate) rule_id: String,
+    pub(crate) duplicate_of: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RuleConflict {
+    pub(crate) rule_ids: Vec<String>,
+    /// Decision Codex applies to commands matched by both rules.
+    pub(crate) effective_decision: RuleDecision,
+    pub(crate) message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RulesListing {
+    pub(crate) rules_dir: String,
+    pub(crate) files: Vec<RulesFileListing>,
+    pub(crate) duplicates: Vec<RuleDuplicate>,
+    pub(crate) conflicts: Vec<RuleConflict>,
+}
+
 pub(crate) fn append_prefix_rule(path: &Path, pattern: &[String]) -> Result<(), String> {
     if pattern.is_empty() {
         return Err("empty command pattern".to_string());
@@ -34,7 +260,7 @@ pub(crate) fn append_prefix_rule(path: &Path, pattern: &[String]) -> Result<(),
         updated.push('\n');
     }
 
-    let rule = format_prefix_rule(pattern);
+    let rule = format_prefix_rule(&PrefixRule::allow(pattern));
     updated.push_str(&rule);
 
     if !updated.ends_with('\n') {
@@ -44,6 +270,252 @@ pub(crate) fn append_prefix_rule(path: &Path, pattern: &[String]) -> Result<(),
     fs::write(path, updated).map_err(|err| err.to_string())
 }
 
+/// Parses every `.rules` file under `<codex_home>/rules` and cross-checks the rules.
+pub(crate) fn list_rules(codex_home: &Path) -> Result<RulesListing, String> {
+    let dir = rules_dir(codex_home);
+    let mut files = Vec::new();
+    for path in collect_rules_files(&dir)? {
+        let contents = fs::read_to_string(&path).map_err(|err| err.to_string())?;
+        let file = relative_rules_name(&dir, &path);
+        let parsed = parse_rules(&contents);
+        let rules = parsed
+            .rules
+            .iter()
+            .enumerate()
+            .map(|(index, parsed_rule)| rule_entry(&file, index, parsed_rule, &contents))
+            .collect();
+        files.push(RulesFileListing {
+            file,
+            path: path.to_string_lossy().to_string(),
+            rules,
+            errors: parsed.errors,
+        });
+    }
+    let all_rules = files
+        .iter()
+        .flat_map(|file| file.rules.iter())
+        .collect::<Vec<_>>();
+    let (duplicates, conflicts) = find_rule_issues(&all_rules);
+    Ok(RulesListing {
+        rules_dir: dir.to_string_lossy().to_string(),
+        files,
+        duplicates,
+        conflicts,
+    })
+}
+
+/// Rewrites one rule in place. Comments and every other byte of the file are kept.
+pub(crate) fn update_rule(
+    codex_home: &Path,
+    rule_id: &str,
+    rule: PrefixRule,
+) -> Result<RuleEntry, String> {
+    rule.validate()?;
+    let (path, file, index) = resolve_rule_path(codex_home, rule_id)?;
+    let _lock = acquire_rules_lock(&path)?;
+    let contents = fs::read_to_string(&path).map_err(|err| err.to_string())?;
+    let target = find_rule(&file, &contents, index, rule_id)?;
+
+    let formatted = format_prefix_rule(&rule);
+    let formatted = formatted.trim_end_matches('\n');
+    let mut updated = String::with_capacity(contents.len() + formatted.len());
+    updated.push_str(&contents[..target.start]);
+    updated.push_str(formatted);
+    updated.push_str(&contents[target.end..]);
+    fs::write(&path, &updated).map_err(|err| err.to_string())?;
+
+    let reparsed = parse_rules(&updated);
+    let parsed_rule = reparsed
+        .rules
+        .get(index)
+        .ok_or_else(|| "updated rule could not be read back".to_string())?;
+    Ok(rule_entry(&file, index, parsed_rule, &updated))
+}
+
+/// Removes one rule. Surrounding comments are kept; the blank line separating the rule
+/// from its neighbours is dropped so deletes do not leave gaps behind.
+pub(crate) fn delete_rule(codex_home: &Path, rule_id: &str) -> Result<(), String> {
+    let (path, file, index) = resolve_rule_path(codex_home, rule_id)?;
+    let _lock = acquire_rules_lock(&path)?;
+    let contents = fs::read_to_string(&path).map_err(|err| err.to_string())?;
+    let target = find_rule(&file, &contents, index, rule_id)?;
+    let updated = remove_span(&contents, target.start, target.end);
+    fs::write(&path, updated).map_err(|err| err.to_string())
+}
+
+fn collect_rules_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut found = Vec::new();
+    if !dir.is_dir() {
+        return Ok(found);
+    }
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let entries = fs::read_dir(&current).map_err(|err| err.to_string())?;
+        for entry in entries {
+            let path = entry.map_err(|err| err.to_string())?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().and_then(|ext| ext.to_str()) == Some(RULES_EXTENSION) {
+                found.push(path);
+            }
+        }
+    }
+    found.sort();
+    Ok(found)
+}
+
+fn relative_rules_name(dir: &Path, path: &Path) -> String {
+    path.strip_prefix(dir)
+        .unwrap_or(path)
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn rule_entry(file: &str, index: usize, parsed: &ParsedRule, contents: &str) -> RuleEntry {
+    let source = contents[parsed.start..parsed.end].to_string();
+    RuleEntry {
+        id: format!("{file}#{index}:{}", fingerprint(&source)),
+        file: file.to_string(),
+        index,
+        line: parsed.line,
+        rule: parsed.rule.clone(),
+        comments: leading_comments(contents, parsed.start),
+        source,
+    }
+}
+
+fn fingerprint(source: &str) -> String {
+    let digest = Sha256::digest(source.as_bytes());
+    digest[..4]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn parse_rule_id(rule_id: &str) -> Result<(&str, usize, &str), String> {
+    let invalid = || format!("invalid rule id `{rule_id}`");
+    let (location, hash) = rule_id.rsplit_once(':').ok_or_else(invalid)?;
+    let (file, index) = location.rsplit_once('#').ok_or_else(invalid)?;
+    let index = index.parse::<usize>().map_err(|_| invalid())?;
+    if file.is_empty() || hash.is_empty() {
+        return Err(invalid());
+    }
+    Ok((file, index, hash))
+}
+
+fn resolve_rule_path(codex_home: &Path, rule_id: &str) -> Result<(PathBuf, String, usize), String> {
+    let (file, index, _) = parse_rule_id(rule_id)?;
+    let relative = Path::new(file);
+    let escapes = relative
+        .components()
+        .any(|component| !matches!(component, std::path::Component::Normal(_)));
+    if escapes || relative.extension().and_then(|ext| ext.to_str()) != Some(RULES_EXTENSION) {
+        return Err(format!("invalid rules file `{file}`"));
+    }
+    let path = rules_dir(codex_home).join(relative);
+    if !path.is_file() {
+        return Err(format!("rules file `{file}` not found"));
+    }
+    Ok((path, file.to_string(), index))
+}
+
+fn find_rule(
+    file: &str,
+    contents: &str,
+    index: usize,
+    rule_id: &str,
+) -> Result<ParsedRule, String> {
+    let parsed = parse_rules(contents);
+    let stale = || format!("rule `{rule_id}` changed on disk; list the rules again");
+    let target = parsed.rules.get(index).ok_or_else(stale)?;
+    if rule_entry(file, index, target, contents).id != rule_id {
+        return Err(stale());
+    }
+    Ok(target.clone())
+}
+
+fn leading_comments(contents: &str, start: usize) -> Vec<String> {
+    let line_start = contents[..start].rfind('\n').map_or(0, |pos| pos + 1);
+    let mut comments = contents[..line_start]
+        .lines()
+        .rev()
+        .map(str::trim)
+        .take_while(|line| line.starts_with('#'))
+        .map(|line| line.trim_start_matches('#').trim().to_string())
+        .collect::<Vec<_>>();
+    comments.reverse();
+    comments
+}
+
+fn remove_span(contents: &str, start: usize, end: usize) -> String {
+    let line_start = contents[..start].rfind('\n').map_or(0, |pos| pos + 1);
+    let rest_end = contents[end..]
+        .find('\n')
+        .map_or(contents.len(), |pos| end + pos + 1);
+    let whole_lines =
+        contents[line_start..start].trim().is_empty() && contents[end..rest_end].trim().is_empty();
+    let (mut cut_start, mut cut_end) = if whole_lines {
+        (line_start, rest_end)
+    } else {
+        (start, end)
+    };
+    if whole_lines {
+        let blank_before = cut_start == 0 || contents[..cut_start].ends_with("\n\n");
+        let blank_after = cut_end == contents.len() || contents[cut_end..].starts_with('\n');
+        if blank_before && blank_after {
+            if cut_end < contents.len() {
+                cut_end += 1;
+            } else {
+                cut_start = cut_start.saturating_sub(1);
+            }
+        }
+    }
+    format!("{}{}", &contents[..cut_start], &contents[cut_end..])
+}
+
+fn find_rule_issues(rules: &[&RuleEntry]) -> (Vec<RuleDuplicate>, Vec<RuleConflict>) {
+    let mut duplicates = Vec::new();
+    let mut conflicts = Vec::new();
+    for (later_index, later) in rules.iter().enumerate() {
+        for earlier in &rules[..later_index] {
+            if earlier.rule.same_pattern(&later.rule)
+                && earlier.rule.decision == later.rule.decision
+            {
+                duplicates.push(RuleDuplicate {
+                    rule_id: later.id.clone(),
+                    duplicate_of: earlier.id.clone(),
+                });
+                break;
+            }
+        }
+        for earlier in &rules[..later_index] {
+            if earlier.rule.decision == later.rule.decision || !earlier.rule.overlaps(&later.rule) {
+                continue;
+            }
+            let effective = earlier.rule.decision.max(later.rule.decision);
+            let (strict, lenient) = if earlier.rule.decision == effective {
+                (earlier, later)
+            } else {
+                (later, earlier)
+            };
+            conflicts.push(RuleConflict {
+                rule_ids: vec![earlier.id.clone(), later.id.clone()],
+                effective_decision: effective,
+                message: format!(
+                    "`{}` ({}) overrides `{}` ({}) for commands matching both",
+                    format_pattern_list(&strict.rule.pattern),
+                    strict.rule.decision.as_str(),
+                    format_pattern_list(&lenient.rule.pattern),
+                    lenient.rule.decision.as_str(),
+                ),
+            });
+        }
+    }
+    (duplicates, conflicts)
+}
+
 struct RulesFileLock {
     path: PathBuf,
 }
@@ -94,69 +566,553 @@ fn is_lock_stale(path: &Path, stale_after: Duration) -> bool {
     age > stale_after
 }
 
-fn format_prefix_rule(pattern: &[String]) -> String {
-    let items = format_pattern_list(pattern);
-    format!("prefix_rule(\n    pattern = [{items}],\n    decision = \"allow\",\n)\n")
+fn format_prefix_rule(rule: &PrefixRule) -> String {
+    let mut out = String::from("prefix_rule(\n");
+    out.push_str(&format!(
+        "    pattern = [{}],\n",
+        format_pattern_list(&rule.pattern)
+    ));
+    out.push_str(&format!("    decision = \"{}\",\n", rule.decision.as_str()));
+    if let Some(justification) = rule.justification.as_deref() {
+        out.push_str(&format!(
+            "    justification = \"{}\",\n",
+            escape_string(justification)
+        ));
+    }
+    if !rule.match_examples.is_empty() {
+        out.push_str(&format!(
+            "    match = [{}],\n",
+            format_examples(&rule.match_examples)
+        ));
+    }
+    if !rule.not_match_examples.is_empty() {
+        out.push_str(&format!(
+            "    not_match = [{}],\n",
+            format_examples(&rule.not_match_examples)
+        ));
+    }
+    out.push_str(")\n");
+    out
 }
 
-fn format_pattern_list(pattern: &[String]) -> String {
-    pattern
+fn format_string_list(items: &[String]) -> String {
+    items
         .iter()
         .map(|item| format!("\"{}\"", escape_string(item)))
         .collect::<Vec<_>>()
         .join(", ")
 }
 
+fn format_pattern_list(pattern: &[RulePatternToken]) -> String {
+    pattern
+        .iter()
+        .map(|token| match token {
+            RulePatternToken::Literal(word) => format!("\"{}\"", escape_string(word)),
+            RulePatternToken::Alternatives(words) => format!("[{}]", format_string_list(words)),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn format_examples(examples: &[RuleExample]) -> String {
+    examples
+        .iter()
+        .map(|example| match example {
+            RuleExample::Command(command) => format!("\"{}\"", escape_string(command)),
+            RuleExample::Words(words) => format!("[{}]", format_string_list(words)),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 fn rule_already_present(contents: &str, pattern: &[String]) -> bool {
-    let target_pattern = normalize_rule_value(&format!("[{}]", format_pattern_list(pattern)));
-    let mut in_rule = false;
-    let mut pattern_matches = false;
-    let mut decision_allows = false;
-
-    for line in contents.lines() {
-        let trimmed = line.trim();
-        if trimmed.starts_with("prefix_rule(") {
-            in_rule = true;
-            pattern_matches = false;
-            decision_allows = false;
-            continue;
-        }
-        if !in_rule {
-            continue;
-        }
-        if trimmed.starts_with("pattern") {
-            if let Some((_, value)) = trimmed.split_once('=') {
-                let candidate = value.trim().trim_end_matches(',');
-                if normalize_rule_value(candidate) == target_pattern {
-                    pattern_matches = true;
+    parse_rules(contents)
+        .rules
+        .iter()
+        .any(|parsed| parsed.rule.decision == RuleDecision::Allow && parsed.rule.covers(pattern))
+}
+
+fn escape_string(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t")
+}
+
+/// Literal values accepted inside a `prefix_rule(...)` call.
+#[derive(Debug, Clone, PartialEq)]
+enum RuleValue {
+    Str(String),
+    List(Vec<RuleValue>),
+    Other(String),
+}
+
+/// Parses the subset of Starlark used by Codex `.rules` files: comments and top-level
+/// `prefix_rule(key = value, ...)` calls with string and list literals. Anything else is
+/// reported as an error and skipped, so one bad entry does not hide the rest of the file.
+fn parse_rules(contents: &str) -> ParsedRulesFile {
+    let mut parser = RulesParser {
+        src: contents,
+        pos: 0,
+    };
+    let mut parsed = ParsedRulesFile::default();
+    loop {
+        parser.skip_trivia();
+        if parser.at_end() {
+            break;
+        }
+        let start = parser.pos;
+        let line = parser.line_at(start);
+        let result = match parser.parse_identifier() {
+            Some("prefix_rule") => parser.parse_prefix_rule(),
+            Some(other) => Err(format!("unsupported statement `{other}`")),
+            None => Err("expected `prefix_rule(...)`".to_string()),
+        };
+        match result {
+            Ok(rule) => {
+                let end = parser.pos;
+                parser.skip_statement_terminator();
+                parsed.rules.push(ParsedRule {
+                    rule,
+                    start,
+                    end,
+                    line,
+                });
+            }
+            Err(message) => {
+                parsed.errors.push(RulesParseError { line, message });
+                parser.pos = start;
+                parser.skip_statement();
+            }
+        }
+    }
+    parsed
+}
+
+struct RulesParser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> RulesParser<'a> {
+    fn at_end(&self) -> bool {
+        self.pos >= self.src.len()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.pos += ch.len_utf8();
+        Some(ch)
+    }
+
+    fn line_at(&self, pos: usize) -> usize {
+        self.src[..pos].matches('\n').count() + 1
+    }
+
+    fn skip_trivia(&mut self) {
+        while let Some(ch) = self.peek() {
+            if ch.is_whitespace() {
+                self.bump();
+            } else if ch == '#' {
+                self.skip_line();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn skip_line(&mut self) {
+        match self.src[self.pos..].find('\n') {
+            Some(offset) => self.pos += offset + 1,
+            None => self.pos = self.src.len(),
+        }
+    }
+
+    fn skip_statement_terminator(&mut self) {
+        let rest = &self.src[self.pos..];
+        let trimmed = rest.trim_start_matches([' ', '\t']);
+        if trimmed.starts_with(';') {
+            self.pos += rest.len() - trimmed.len() + 1;
+        }
+    }
+
+    /// Error recovery: skips to the end of the current statement, honouring brackets and
+    /// strings so a malformed call spanning several lines is skipped as a whole.
+    fn skip_statement(&mut self) {
+        let mut depth = 0usize;
+        while let Some(ch) = self.peek() {
+            match ch {
+                '"' | '\'' => {
+                    if self.parse_string().is_err() {
+                        self.skip_line();
+                        return;
+                    }
+                    continue;
+                }
+                '#' => {
+                    self.skip_line();
+                    if depth == 0 {
+                        return;
+                    }
+                    continue;
                 }
+                '(' | '[' => depth += 1,
+                ')' | ']' => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        self.bump();
+                        return;
+                    }
+                }
+                '\n' if depth == 0 => {
+                    self.bump();
+                    return;
+                }
+                _ => {}
+            }
+            self.bump();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_trivia();
+        match self.peek() {
+            Some(ch) if ch == expected => {
+                self.bump();
+                Ok(())
+            }
+            Some(ch) => Err(format!(
+                "expected `{expected}` but found `{ch}` on line {}",
+                self.line_at(self.pos)
+            )),
+            None => Err(format!("expected `{expected}` but reached end of file")),
+        }
+    }
+
+    fn parse_identifier(&mut self) -> Option<&'a str> {
+        let rest = &self.src[self.pos..];
+        let len = rest
+            .char_indices()
+            .find(|(_, ch)| !(ch.is_ascii_alphanumeric() || *ch == '_'))
+            .map_or(rest.len(), |(index, _)| index);
+        if len == 0 || rest.starts_with(|ch: char| ch.is_ascii_digit()) {
+            return None;
+        }
+        self.pos += len;
+        Some(&rest[..len])
+    }
+
+    fn parse_prefix_rule(&mut self) -> Result<PrefixRule, String> {
+        self.expect('(')?;
+        let mut pattern = None;
+        let mut decision = None;
+        let mut justification = None;
+        let mut match_examples = Vec::new();
+        let mut not_match_examples = Vec::new();
+        loop {
+            self.skip_trivia();
+            if self.peek() == Some(')') {
+                self.bump();
+                break;
+            }
+            let key = self.parse_identifier().ok_or_else(|| {
+                format!(
+                    "expected an argument name on line {}",
+                    self.line_at(self.pos)
+                )
+            })?;
+            self.expect('=')?;
+            let value = self.parse_value()?;
+            match key {
+                "pattern" => pattern = Some(pattern_from_value(value)?),
+                "decision" => {
+                    let RuleValue::Str(value) = value else {
+                        return Err("`decision` must be a string".to_string());
+                    };
+                    decision = Some(
+                        RuleDecision::parse(&value)
+                            .ok_or_else(|| format!("unknown decision `{value}`"))?,
+                    );
+                }
+                "justification" => {
+                    let RuleValue::Str(value) = value else {
+                        return Err("`justification` must be a string".to_string());
+                    };
+                    justification = Some(value);
+                }
+                "match" => match_examples = examples_from_value(value, "match")?,
+                "not_match" => not_match_examples = examples_from_value(value, "not_match")?,
+                other => return Err(format!("unknown `prefix_rule` argument `{other}`")),
             }
-        } else if trimmed.starts_with("decision") {
-            if let Some((_, value)) = trimmed.split_once('=') {
-                let candidate = value.trim().trim_end_matches(',');
-                if candidate.contains("\"allow\"") || candidate.contains("'allow'") {
-                    decision_allows = true;
+            self.skip_trivia();
+            match self.peek() {
+                Some(',') => {
+                    self.bump();
+                }
+                Some(')') => {}
+                _ => {
+                    return Err(format!(
+                        "expected `,` or `)` on line {}",
+                        self.line_at(self.pos)
+                    ))
+                }
+            }
+        }
+        let rule = PrefixRule {
+            pattern: pattern.ok_or_else(|| "`prefix_rule` is missing `pattern`".to_string())?,
+            decision: decision.unwrap_or_else(default_decision),
+            justification,
+            match_examples,
+            not_match_examples,
+        };
+        rule.validate()?;
+        Ok(rule)
+    }
+
+    fn parse_value(&mut self) -> Result<RuleValue, String> {
+        self.skip_trivia();
+        match self.peek() {
+            Some('"') | Some('\'') => self.parse_string().map(RuleValue::Str),
+            Some('[') => {
+                self.bump();
+                let mut items = Vec::new();
+                loop {
+                    self.skip_trivia();
+                    if self.peek() == Some(']') {
+                        self.bump();
+                        return Ok(RuleValue::List(items));
+                    }
+                    items.push(self.parse_value()?);
+                    self.skip_trivia();
+                    match self.peek() {
+                        Some(',') => {
+                            self.bump();
+                        }
+                        Some(']') => {}
+                        _ => {
+                            return Err(format!(
+                                "expected `,` or `]` on line {}",
+                                self.line_at(self.pos)
+                            ))
+                        }
+                    }
                 }
             }
-        } else if trimmed.starts_with(')') {
-            if pattern_matches && decision_allows {
-                return true;
+            Some(_) => match self.parse_identifier() {
+                Some(word) => Ok(RuleValue::Other(word.to_string())),
+                None => Err(format!(
+                    "unexpected value on line {}",
+                    self.line_at(self.pos)
+                )),
+            },
+            None => Err("unexpected end of file".to_string()),
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, String> {
+        let line = self.line_at(self.pos);
+        let quote = self.bump().ok_or_else(|| "expected a string".to_string())?;
+        let mut value = String::new();
+        loop {
+            match self.bump() {
+                Some(ch) if ch == quote => return Ok(value),
+                Some('\\') => match self.bump() {
+                    Some('n') => value.push('\n'),
+                    Some('r') => value.push('\r'),
+                    Some('t') => value.push('\t'),
+                    Some(other) => value.push(other),
+                    None => break,
+                },
+                Some('\n') | None => break,
+                Some(ch) => value.push(ch),
             }
-            in_rule = false;
         }
+        Err(format!("unterminated string starting on line {line}"))
     }
-    false
 }
 
-fn normalize_rule_value(value: &str) -> String {
-    value.chars().filter(|ch| !ch.is_whitespace()).collect()
+fn string_list(items: Vec<RuleValue>, what: &str) -> Result<Vec<String>, String> {
+    items
+        .into_iter()
+        .map(|item| match item {
+            RuleValue::Str(value) => Ok(value),
+            _ => Err(format!("{what} must contain only strings")),
+        })
+        .collect()
 }
 
-fn escape_string(value: &str) -> String {
-    value
-        .replace('\\', "\\\\")
-        .replace('\"', "\\\"")
-        .replace('\n', "\\n")
-        .replace('\r', "\\r")
-        .replace('\t', "\\t")
+fn pattern_from_value(value: RuleValue) -> Result<Vec<RulePatternToken>, String> {
+    let RuleValue::List(items) = value else {
+        return Err("`pattern` must be a list".to_string());
+    };
+    items
+        .into_iter()
+        .map(|item| match item {
+            RuleValue::Str(word) => Ok(RulePatternToken::Literal(word)),
+            RuleValue::List(words) => Ok(RulePatternToken::Alternatives(string_list(
+                words,
+                "pattern alternatives",
+            )?)),
+            RuleValue::Other(word) => Err(format!("unexpected `{word}` in `pattern`")),
+        })
+        .collect()
+}
+
+fn examples_from_value(value: RuleValue, key: &str) -> Result<Vec<RuleExample>, String> {
+    let RuleValue::List(items) = value else {
+        return Err(format!("`{key}` must be a list"));
+    };
+    items
+        .into_iter()
+        .map(|item| match item {
+            RuleValue::Str(command) => Ok(RuleExample::Command(command)),
+            RuleValue::List(words) => Ok(RuleExample::Words(string_list(
+                words,
+                &format!("`{key}` examples"),
+            )?)),
+            RuleValue::Other(word) => Err(format!("unexpected `{word}` in `{key}`")),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    const SAMPLE: &str = "# Allow read-only git commands.\nprefix_rule(\n    pattern = [\"git\", [\"status\", \"log\"]],  # common\n    decision = \"allow\",\n    match = [\"git status\", [\"git\", \"log\"]],\n)\n\n# Never push.\nprefix_rule(pattern = ['git', 'push'], decision = 'forbidden', justification = \"use the UI\")\n";
+
+    fn temp_codex_home(contents: &[(&str, &str)]) -> PathBuf {
+        let home = std::env::temp_dir().join(format!("codex-monitor-rules-{}", Uuid::new_v4()));
+        for (name, body) in contents {
+            let path = rules_dir(&home).join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, body).unwrap();
+        }
+        home
+    }
+
+    #[test]
+    fn parses_prefix_rules_with_alternatives_and_comments() {
+        let parsed = parse_rules(SAMPLE);
+        assert!(parsed.errors.is_empty(), "{:?}", parsed.errors);
+        assert_eq!(parsed.rules.len(), 2);
+        let first = &parsed.rules[0];
+        assert_eq!(first.line, 2);
+        assert_eq!(
+            first.rule.pattern,
+            vec![
+                RulePatternToken::Literal("git".to_string()),
+                RulePatternToken::Alternatives(vec!["status".to_string(), "log".to_string()]),
+            ]
+        );
+        assert_eq!(
+            first.rule.match_examples,
+            vec![
+                RuleExample::Command("git status".to_string()),
+                RuleExample::Words(vec!["git".to_string(), "log".to_string()]),
+            ]
+        );
+        let second = &parsed.rules[1].rule;
+        assert_eq!(second.decision, RuleDecision::Forbidden);
+        assert_eq!(second.justification.as_deref(), Some("use the UI"));
+        assert_eq!(
+            leading_comments(SAMPLE, parsed.rules[1].start),
+            vec!["Never push.".to_string()]
+        );
+    }
+
+    #[test]
+    fn reports_bad_entries_and_keeps_parsing() {
+        let contents = "load(\"x.star\", \"y\")\nprefix_rule(pattern = [\"rm\"], decision = \"maybe\")\nprefix_rule(pattern = [\"ls\"])\n";
+        let parsed = parse_rules(contents);
+        assert_eq!(parsed.rules.len(), 1);
+        assert_eq!(parsed.rules[0].line, 3);
+        assert_eq!(parsed.rules[0].rule.decision, RuleDecision::Allow);
+        let lines = parsed
+            .errors
+            .iter()
+            .map(|error| error.line)
+            .collect::<Vec<_>>();
+        assert_eq!(lines, vec![1, 2]);
+    }
+
+    #[test]
+    fn update_and_delete_keep_comments_and_other_rules() {
+        let home = temp_codex_home(&[("default.rules", SAMPLE)]);
+        let listing = list_rules(&home).unwrap();
+        let ids = listing.files[0]
+            .rules
+            .iter()
+            .map(|rule| rule.id.clone())
+            .collect::<Vec<_>>();
+
+        let mut rule = listing.files[0].rules[1].rule.clone();
+        rule.decision = RuleDecision::Prompt;
+        let updated = update_rule(&home, &ids[1], rule).unwrap();
+        assert_eq!(updated.rule.decision, RuleDecision::Prompt);
+        assert_eq!(updated.comments, vec!["Never push.".to_string()]);
+        assert!(update_rule(&home, &ids[1], updated.rule.clone()).is_err());
+
+        delete_rule(&home, &ids[0]).unwrap();
+        let contents = fs::read_to_string(default_rules_path(&home)).unwrap();
+        assert!(contents
+            .starts_with("# Allow read-only git commands.\n\n# Never push.\nprefix_rule(\n"));
+        assert!(contents.contains("decision = \"prompt\""));
+        assert_eq!(parse_rules(&contents).rules.len(), 1);
+        let _ = fs::remove_dir_all(home);
+    }
+
+    #[test]
+    fn detects_duplicates_and_conflicts_across_files() {
+        let home = temp_codex_home(&[
+            ("default.rules", SAMPLE),
+            (
+                "team/extra.rules",
+                "prefix_rule(pattern = [\"git\", [\"log\", \"status\"]])\nprefix_rule(pattern = [\"git\"], decision = \"prompt\")\n",
+            ),
+        ]);
+        let listing = list_rules(&home).unwrap();
+        assert_eq!(listing.files[1].file, "team/extra.rules");
+        let default_ids = &listing.files[0].rules;
+        let extra_ids = &listing.files[1].rules;
+
+        assert_eq!(listing.duplicates.len(), 1);
+        assert_eq!(listing.duplicates[0].rule_id, extra_ids[0].id);
+        assert_eq!(listing.duplicates[0].duplicate_of, default_ids[0].id);
+
+        let conflicting = listing
+            .conflicts
+            .iter()
+            .map(|conflict| (conflict.rule_ids.clone(), conflict.effective_decision))
+            .collect::<Vec<_>>();
+        assert!(conflicting.contains(&(
+            vec![default_ids[0].id.clone(), extra_ids[1].id.clone()],
+            RuleDecision::Prompt
+        )));
+        assert!(conflicting.contains(&(
+            vec![default_ids[1].id.clone(), extra_ids[1].id.clone()],
+            RuleDecision::Forbidden
+        )));
+        let _ = fs::remove_dir_all(home);
+    }
+
+    #[test]
+    fn append_skips_rules_that_already_allow_the_command() {
+        let home = temp_codex_home(&[("default.rules", SAMPLE)]);
+        let path = default_rules_path(&home);
+        append_prefix_rule(&path, &["git".to_string(), "log".to_string()]).unwrap();
+        append_prefix_rule(&path, &["git".to_string(), "push".to_string()]).unwrap();
+        append_prefix_rule(&path, &["git".to_string(), "push".to_string()]).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(contents.starts_with(SAMPLE));
+        assert_eq!(parse_rules(&contents).rules.len(), 3);
+        let _ = fs::remove_dir_all(home);
+    }
 }
@@ -54,6 +54,23 @@ fn should_reset_remote_backend(previous: &AppSettings, updated: &AppSettings) ->
         || previous.remote_backend_provider != updated.remote_backend_provider
         || previous.remote_backend_host != updated.remote_backend_host
         || previous.remote_backend_token != updated.remote_backend_token
+        || active_remote_tls_settings(previous) != active_remote_tls_settings(updated)
+}
+
+fn active_remote_tls_settings(
+    settings: &AppSettings,
+) -> Option<(bool, Option<&str>, Option<&str>, Option<&str>)> {
+    let active_id = settings.active_remote_backend_id.as_deref()?;
+    let target = settings
+        .remote_backends
+        .iter()
+        .find(|target| target.id == active_id)?;
+    Some((
+        target.tls_enabled,
+        target.tls_fingerprint.as_deref(),
+        target.tls_client_cert_path.as_deref(),
+        target.tls_client_key_path.as_deref(),
+    ))
 }
 
 async fn ensure_remote_runtime_for_settings(settings: &AppSettings, state: State<'_, AppState>) {
@@ -70,7 +87,7 @@ async fn ensure_remote_runtime_for_settings(settings: &AppSettings, state: State
 #[cfg(test)]
 mod tests {
     use super::should_reset_remote_backend;
-    use crate::types::{AppSettings, BackendMode};
+    use crate::types::{AppSettings, BackendMode, RemoteBackendProvider, RemoteBackendTarget};
 
     #[test]
     fn should_reset_remote_backend_when_provider_changes() {
@@ -89,6 +106,31 @@ mod tests {
         assert!(should_reset_remote_backend(&previous, &updated));
     }
 
+    #[test]
+    fn should_reset_remote_backend_when_active_target_tls_changes() {
+        let mut previous = AppSettings::default();
+        previous.remote_backends = vec![RemoteBackendTarget {
+            id: "remote-a".to_string(),
+            name: "Remote A".to_string(),
+            provider: RemoteBackendProvider::Tcp,
+            host: "remote.example:4732".to_string(),
+            token: None,
+            last_connected_at_ms: None,
+            tls_enabled: false,
+            tls_fingerprint: None,
+            tls_client_cert_path: None,
+            tls_client_key_path: None,
+        }];
+        previous.active_remote_backend_id = Some("remote-a".to_string());
+
+        let mut updated = previous.clone();
+        updated.remote_backends[0].last_connected_at_ms = Some(1);
+        assert!(!should_reset_remote_backend(&previous, &updated));
+
+        updated.remote_backends[0].tls_enabled = true;
+        assert!(should_reset_remote_backend(&previous, &updated));
+    }
+
     #[test]
     fn should_not_reset_remote_backend_for_non_transport_setting_changes() {
         let previous = AppSettings::default();
@@ -10,7 +10,9 @@ use tokio::time::timeout;
 use crate::backend::app_server::{
     build_codex_command_with_bin, build_codex_path_env, check_codex_installation, WorkspaceSession,
 };
+use crate::shared::codex_core::get_session_clone;
 use crate::shared::process_core::tokio_command;
+use crate::shared::rpc_error::{RpcError, RpcErrorCode};
 use crate::types::{AppSettings, WorkspaceEntry};
 
 const DEFAULT_COMMIT_MESSAGE_PROMPT: &str =
@@ -401,22 +403,18 @@ pub(crate) async fn run_background_prompt_core<F>(
     on_hide_thread: F,
     timeout_error: &str,
     turn_error_fallback: &str,
-) -> Result<String, String>
+) -> Result<String, RpcError>
 where
     F: Fn(&str, &str),
 {
     let workspace_path = {
         let workspaces = workspaces.lock().await;
-        let entry = workspaces.get(&workspace_id).ok_or("workspace not found")?;
-        entry.path.clone()
-    };
-    let session = {
-        let sessions = sessions.lock().await;
-        sessions
+        let entry = workspaces
             .get(&workspace_id)
-            .ok_or("workspace not connected")?
-            .clone()
+            .ok_or_else(|| RpcError::new(RpcErrorCode::WorkspaceNotFound, "workspace not found"))?;
+        entry.path.clone()
     };
+    let session = get_session_clone(sessions, &workspace_id).await?;
 
     let thread_params = json!({
         "cwd": workspace_path.clone(),
@@ -431,7 +429,7 @@ where
             .get("message")
             .and_then(|m| m.as_str())
             .unwrap_or("Unknown error starting thread");
-        return Err(error_msg.to_string());
+        return Err(error_msg.to_string().into());
     }
 
     let thread_id = thread_result
@@ -486,7 +484,7 @@ where
             let _ = session
                 .send_request_for_workspace(&workspace_id, "thread/archive", archive_params)
                 .await;
-            return Err(error);
+            return Err(error.into());
         }
     };
 
@@ -503,7 +501,7 @@ where
         let _ = session
             .send_request_for_workspace(&workspace_id, "thread/archive", archive_params)
             .await;
-        return Err(error_msg.to_string());
+        return Err(error_msg.to_string().into());
     }
 
     let mut response_text = String::new();
@@ -549,13 +547,13 @@ where
 
     match collect_result {
         Ok(Ok(())) => {}
-        Ok(Err(error)) => return Err(error),
-        Err(_) => return Err(timeout_error.to_string()),
+        Ok(Err(error)) => return Err(error.into()),
+        Err(_) => return Err(RpcError::new(RpcErrorCode::Timeout, timeout_error)),
     }
 
     let trimmed = response_text.trim().to_string();
     if trimmed.is_empty() {
-        return Err("No response was generated".to_string());
+        return Err("No response was generated".to_string().into());
     }
 
     Ok(trimmed)
@@ -569,7 +567,7 @@ pub(crate) async fn generate_commit_message_core<F>(
     template: &str,
     model: Option<&str>,
     on_hide_thread: F,
-) -> Result<String, String>
+) -> Result<String, RpcError>
 where
     F: Fn(&str, &str),
 {
@@ -593,13 +591,16 @@ pub(crate) async fn generate_run_metadata_core<F>(
     workspace_id: String,
     prompt: &str,
     on_hide_thread: F,
-) -> Result<Value, String>
+) -> Result<Value, RpcError>
 where
     F: Fn(&str, &str),
 {
     let cleaned_prompt = prompt.trim();
     if cleaned_prompt.is_empty() {
-        return Err("Prompt is required.".to_string());
+        return Err(RpcError::new(
+            RpcErrorCode::InvalidParams,
+            "Prompt is required.",
+        ));
     }
 
     let metadata_prompt = build_run_metadata_prompt(cleaned_prompt);
@@ -615,7 +616,7 @@ where
     )
     .await?;
 
-    parse_run_metadata_value(&response)
+    Ok(parse_run_metadata_value(&response)?)
 }
 
 pub(crate) async fn generate_agent_description_core<F>(
@@ -624,13 +625,16 @@ pub(crate) async fn generate_agent_description_core<F>(
     workspace_id: String,
     description: &str,
     on_hide_thread: F,
-) -> Result<GeneratedAgentConfiguration, String>
+) -> Result<GeneratedAgentConfiguration, RpcError>
 where
     F: Fn(&str, &str),
 {
     let cleaned_description = description.trim();
     if cleaned_description.is_empty() {
-        return Err("Description is required.".to_string());
+        return Err(RpcError::new(
+            RpcErrorCode::InvalidParams,
+            "Description is required.",
+        ));
     }
 
     let prompt = build_agent_description_prompt(cleaned_description);
@@ -646,7 +650,7 @@ where
     )
     .await?;
 
-    parse_agent_description_value(&response)
+    Ok(parse_agent_description_value(&response)?)
 }
 
 #[cfg(test)]
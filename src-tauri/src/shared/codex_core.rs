@@ -16,6 +16,7 @@ use crate::codex::config as codex_config;
 use crate::codex::home::{resolve_default_codex_home, resolve_workspace_codex_home};
 use crate::rules;
 use crate::shared::account::{build_account_response, read_auth_account};
+use crate::shared::rpc_error::{RpcError, RpcErrorCode};
 use crate::types::WorkspaceEntry;
 
 const LOGIN_START_TIMEOUT: Duration = Duration::from_secs(30);
@@ -116,15 +117,18 @@ pub(crate) enum CodexLoginCancelState {
     LoginId(String),
 }
 
-async fn get_session_clone(
+pub(crate) async fn get_session_clone(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspace_id: &str,
-) -> Result<Arc<WorkspaceSession>, String> {
+) -> Result<Arc<WorkspaceSession>, RpcError> {
     let sessions = sessions.lock().await;
-    sessions
-        .get(workspace_id)
-        .cloned()
-        .ok_or_else(|| "workspace not connected".to_string())
+    sessions.get(workspace_id).cloned().ok_or_else(|| {
+        RpcError::new(
+            RpcErrorCode::WorkspaceNotConnected,
+            format!("workspace `{workspace_id}` not connected"),
+        )
+        .with_data(json!({ "workspaceId": workspace_id }))
+    })
 }
 
 async fn resolve_workspace_and_parent(
@@ -144,7 +148,7 @@ async fn resolve_workspace_and_parent(
     Ok((entry, parent_entry))
 }
 
-async fn resolve_codex_home_for_workspace_core(
+pub(crate) async fn resolve_codex_home_for_workspace_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: &str,
 ) -> Result<PathBuf, String> {
@@ -154,7 +158,7 @@ async fn resolve_codex_home_for_workspace_core(
         .ok_or_else(|| "Unable to resolve CODEX_HOME".to_string())
 }
 
-async fn resolve_workspace_path_core(
+pub(crate) async fn resolve_workspace_path_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: &str,
 ) -> Result<String, String> {
@@ -169,7 +173,7 @@ pub(crate) async fn start_thread_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, RpcError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let workspace_path = resolve_workspace_path_core(workspaces, &workspace_id).await?;
     let params = json!({
@@ -179,27 +183,32 @@ pub(crate) async fn start_thread_core(
     session
         .send_request_for_workspace(&workspace_id, "thread/start", params)
         .await
+        .map_err(RpcError::from)
 }
 
 pub(crate) async fn resume_thread_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspace_id: String,
     thread_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, RpcError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let params = json!({ "threadId": thread_id });
     session
         .send_request_for_workspace(&workspace_id, "thread/resume", params)
         .await
+        .map_err(RpcError::from)
 }
 
 pub(crate) async fn thread_live_subscribe_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspace_id: String,
     thread_id: String,
-) -> Result<(), String> {
+) -> Result<(), RpcError> {
     if thread_id.trim().is_empty() {
-        return Err("threadId is required".to_string());
+        return Err(RpcError::new(
+            RpcErrorCode::InvalidParams,
+            "threadId is required",
+        ));
     }
     let _ = get_session_clone(sessions, &workspace_id).await?;
     Ok(())
@@ -209,9 +218,12 @@ pub(crate) async fn thread_live_unsubscribe_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspace_id: String,
     thread_id: String,
-) -> Result<(), String> {
+) -> Result<(), RpcError> {
     if thread_id.trim().is_empty() {
-        return Err("threadId is required".to_string());
+        return Err(RpcError::new(
+            RpcErrorCode::InvalidParams,
+            "threadId is required",
+        ));
     }
     let _ = get_session_clone(sessions, &workspace_id).await?;
     Ok(())
@@ -221,12 +233,13 @@ pub(crate) async fn fork_thread_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspace_id: String,
     thread_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, RpcError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let params = json!({ "threadId": thread_id });
     session
         .send_request_for_workspace(&workspace_id, "thread/fork", params)
         .await
+        .map_err(RpcError::from)
 }
 
 pub(crate) async fn list_threads_core(
@@ -235,7 +248,7 @@ pub(crate) async fn list_threads_core(
     cursor: Option<String>,
     limit: Option<u32>,
     sort_key: Option<String>,
-) -> Result<Value, String> {
+) -> Result<Value, RpcError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let params = json!({
         "cursor": cursor,
@@ -260,6 +273,7 @@ pub(crate) async fn list_threads_core(
     session
         .send_request_for_workspace(&workspace_id, "thread/list", params)
         .await
+        .map_err(RpcError::from)
 }
 
 pub(crate) async fn list_mcp_server_status_core(
@@ -267,36 +281,39 @@ pub(crate) async fn list_mcp_server_status_core(
     workspace_id: String,
     cursor: Option<String>,
     limit: Option<u32>,
-) -> Result<Value, String> {
+) -> Result<Value, RpcError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let params = json!({ "cursor": cursor, "limit": limit });
     session
         .send_request_for_workspace(&workspace_id, "mcpServerStatus/list", params)
         .await
+        .map_err(RpcError::from)
 }
 
 pub(crate) async fn archive_thread_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspace_id: String,
     thread_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, RpcError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let params = json!({ "threadId": thread_id });
     session
         .send_request_for_workspace(&workspace_id, "thread/archive", params)
         .await
+        .map_err(RpcError::from)
 }
 
 pub(crate) async fn compact_thread_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspace_id: String,
     thread_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, RpcError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let params = json!({ "threadId": thread_id });
     session
         .send_request_for_workspace(&workspace_id, "thread/compact/start", params)
         .await
+        .map_err(RpcError::from)
 }
 
 pub(crate) async fn set_thread_name_core(
@@ -304,12 +321,13 @@ pub(crate) async fn set_thread_name_core(
     workspace_id: String,
     thread_id: String,
     name: String,
-) -> Result<Value, String> {
+) -> Result<Value, RpcError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let params = json!({ "threadId": thread_id, "name": name });
     session
         .send_request_for_workspace(&workspace_id, "thread/name/set", params)
         .await
+        .map_err(RpcError::from)
 }
 
 fn build_turn_input_items(
@@ -383,7 +401,7 @@ pub(crate) async fn send_user_message_core(
     images: Option<Vec<String>>,
     app_mentions: Option<Vec<Value>>,
     collaboration_mode: Option<Value>,
-) -> Result<Value, String> {
+) -> Result<Value, RpcError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let workspace_path = resolve_workspace_path_core(workspaces, &workspace_id).await?;
     let access_mode = access_mode.unwrap_or_else(|| "current".to_string());
@@ -404,6 +422,11 @@ pub(crate) async fn send_user_message_core(
     };
 
     let input = build_turn_input_items(text, images, app_mentions)?;
+    session
+        .thread_access_modes
+        .lock()
+        .await
+        .insert(thread_id.clone(), access_mode.clone());
 
     let mut params = Map::new();
     params.insert("threadId".to_string(), json!(thread_id));
@@ -421,6 +444,7 @@ pub(crate) async fn send_user_message_core(
     session
         .send_request_for_workspace(&workspace_id, "turn/start", Value::Object(params))
         .await
+        .map_err(RpcError::from)
 }
 
 pub(crate) async fn turn_steer_core(
@@ -431,9 +455,12 @@ pub(crate) async fn turn_steer_core(
     text: String,
     images: Option<Vec<String>>,
     app_mentions: Option<Vec<Value>>,
-) -> Result<Value, String> {
+) -> Result<Value, RpcError> {
     if turn_id.trim().is_empty() {
-        return Err("missing active turn id".to_string());
+        return Err(RpcError::new(
+            RpcErrorCode::InvalidParams,
+            "missing active turn id",
+        ));
     }
     let session = get_session_clone(sessions, &workspace_id).await?;
     let input = build_turn_input_items(text, images, app_mentions)?;
@@ -445,16 +472,18 @@ pub(crate) async fn turn_steer_core(
     session
         .send_request_for_workspace(&workspace_id, "turn/steer", params)
         .await
+        .map_err(RpcError::from)
 }
 
 pub(crate) async fn collaboration_mode_list_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspace_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, RpcError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     session
         .send_request_for_workspace(&workspace_id, "collaborationMode/list", json!({}))
         .await
+        .map_err(RpcError::from)
 }
 
 pub(crate) async fn turn_interrupt_core(
@@ -462,12 +491,13 @@ pub(crate) async fn turn_interrupt_core(
     workspace_id: String,
     thread_id: String,
     turn_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, RpcError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let params = json!({ "threadId": thread_id, "turnId": turn_id });
     session
         .send_request_for_workspace(&workspace_id, "turn/interrupt", params)
         .await
+        .map_err(RpcError::from)
 }
 
 pub(crate) async fn start_review_core(
@@ -476,7 +506,7 @@ pub(crate) async fn start_review_core(
     thread_id: String,
     target: Value,
     delivery: Option<String>,
-) -> Result<Value, String> {
+) -> Result<Value, RpcError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let mut params = Map::new();
     params.insert("threadId".to_string(), json!(thread_id));
@@ -487,16 +517,18 @@ pub(crate) async fn start_review_core(
     session
         .send_request_for_workspace(&workspace_id, "review/start", Value::Object(params))
         .await
+        .map_err(RpcError::from)
 }
 
 pub(crate) async fn model_list_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspace_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, RpcError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     session
         .send_request_for_workspace(&workspace_id, "model/list", json!({}))
         .await
+        .map_err(RpcError::from)
 }
 
 pub(crate) async fn experimental_feature_list_core(
@@ -504,22 +536,24 @@ pub(crate) async fn experimental_feature_list_core(
     workspace_id: String,
     cursor: Option<String>,
     limit: Option<u32>,
-) -> Result<Value, String> {
+) -> Result<Value, RpcError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let params = json!({ "cursor": cursor, "limit": limit });
     session
         .send_request_for_workspace(&workspace_id, "experimentalFeature/list", params)
         .await
+        .map_err(RpcError::from)
 }
 
 pub(crate) async fn account_rate_limits_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspace_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, RpcError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     session
         .send_request_for_workspace(&workspace_id, "account/rateLimits/read", Value::Null)
         .await
+        .map_err(RpcError::from)
 }
 
 pub(crate) async fn account_read_core(
@@ -552,7 +586,7 @@ pub(crate) async fn codex_login_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     codex_login_cancels: &Mutex<HashMap<String, CodexLoginCancelState>>,
     workspace_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, RpcError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let (cancel_tx, cancel_rx) = oneshot::channel::<()>();
     {
@@ -585,12 +619,18 @@ pub(crate) async fn codex_login_core(
             Ok(_) => {
                 let mut cancels = codex_login_cancels.lock().await;
                 cancels.remove(&workspace_id);
-                return Err("Codex login canceled.".to_string());
+                return Err(RpcError::new(
+                    RpcErrorCode::Internal,
+                    "Codex login canceled.",
+                ));
             }
             Err(TryRecvError::Closed) => {
                 let mut cancels = codex_login_cancels.lock().await;
                 cancels.remove(&workspace_id);
-                return Err("Codex login canceled.".to_string());
+                return Err(RpcError::new(
+                    RpcErrorCode::Internal,
+                    "Codex login canceled.",
+                ));
             }
             Err(TryRecvError::Empty) => {}
         }
@@ -599,7 +639,10 @@ pub(crate) async fn codex_login_core(
         if elapsed >= LOGIN_START_TIMEOUT {
             let mut cancels = codex_login_cancels.lock().await;
             cancels.remove(&workspace_id);
-            return Err("Codex login start timed out.".to_string());
+            return Err(RpcError::new(
+                RpcErrorCode::Timeout,
+                "Codex login start timed out.",
+            ));
         }
 
         let tick = Duration::from_millis(150);
@@ -645,7 +688,7 @@ pub(crate) async fn codex_login_cancel_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     codex_login_cancels: &Mutex<HashMap<String, CodexLoginCancelState>>,
     workspace_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, RpcError> {
     let cancel_state = {
         let mut cancels = codex_login_cancels.lock().await;
         cancels.remove(&workspace_id)
@@ -695,7 +738,7 @@ pub(crate) async fn skills_list_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, RpcError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let workspace_path = resolve_workspace_path_core(workspaces, &workspace_id).await?;
 
@@ -735,12 +778,13 @@ pub(crate) async fn apps_list_core(
     cursor: Option<String>,
     limit: Option<u32>,
     thread_id: Option<String>,
-) -> Result<Value, String> {
+) -> Result<Value, RpcError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let params = json!({ "cursor": cursor, "limit": limit, "threadId": thread_id });
     session
         .send_request_for_workspace(&workspace_id, "app/list", params)
         .await
+        .map_err(RpcError::from)
 }
 
 pub(crate) async fn respond_to_server_request_core(
@@ -748,9 +792,12 @@ pub(crate) async fn respond_to_server_request_core(
     workspace_id: String,
     request_id: Value,
     result: Value,
-) -> Result<(), String> {
+) -> Result<(), RpcError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
-    session.send_response(request_id, result).await
+    session
+        .send_response(request_id, result)
+        .await
+        .map_err(RpcError::from)
 }
 
 pub(crate) async fn remember_approval_rule_core(
@@ -777,6 +824,33 @@ pub(crate) async fn remember_approval_rule_core(
     }))
 }
 
+pub(crate) async fn list_codex_rules_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+) -> Result<rules::RulesListing, String> {
+    let codex_home = resolve_codex_home_for_workspace_core(workspaces, &workspace_id).await?;
+    rules::list_rules(&codex_home)
+}
+
+pub(crate) async fn update_codex_rule_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    rule_id: String,
+    rule: rules::PrefixRule,
+) -> Result<rules::RuleEntry, String> {
+    let codex_home = resolve_codex_home_for_workspace_core(workspaces, &workspace_id).await?;
+    rules::update_rule(&codex_home, &rule_id, rule)
+}
+
+pub(crate) async fn delete_codex_rule_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    rule_id: String,
+) -> Result<(), String> {
+    let codex_home = resolve_codex_home_for_workspace_core(workspaces, &workspace_id).await?;
+    rules::delete_rule(&codex_home, &rule_id)
+}
+
 pub(crate) async fn get_config_model_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
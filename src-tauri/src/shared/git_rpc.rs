@@ -2,6 +2,8 @@ use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::types::{GitConflictResolution, GitDiffSelection, GitLogFilter};
+
 pub(crate) const METHOD_GET_GIT_STATUS: &str = "get_git_status";
 pub(crate) const METHOD_INIT_GIT_REPO: &str = "init_git_repo";
 pub(crate) const METHOD_CREATE_GITHUB_REPO: &str = "create_github_repo";
@@ -9,6 +11,9 @@ pub(crate) const METHOD_STAGE_GIT_FILE: &str = "stage_git_file";
 pub(crate) const METHOD_STAGE_GIT_ALL: &str = "stage_git_all";
 pub(crate) const METHOD_UNSTAGE_GIT_FILE: &str = "unstage_git_file";
 pub(crate) const METHOD_REVERT_GIT_FILE: &str = "revert_git_file";
+pub(crate) const METHOD_STAGE_GIT_SELECTION: &str = "stage_git_selection";
+pub(crate) const METHOD_UNSTAGE_GIT_SELECTION: &str = "unstage_git_selection";
+pub(crate) const METHOD_REVERT_GIT_SELECTION: &str = "revert_git_selection";
 pub(crate) const METHOD_REVERT_GIT_ALL: &str = "revert_git_all";
 pub(crate) const METHOD_COMMIT_GIT: &str = "commit_git";
 pub(crate) const METHOD_PUSH_GIT: &str = "push_git";
@@ -20,6 +25,19 @@ pub(crate) const METHOD_GET_GIT_DIFFS: &str = "get_git_diffs";
 pub(crate) const METHOD_GET_GIT_LOG: &str = "get_git_log";
 pub(crate) const METHOD_GET_GIT_COMMIT_DIFF: &str = "get_git_commit_diff";
 pub(crate) const METHOD_GET_GIT_REMOTE: &str = "get_git_remote";
+pub(crate) const METHOD_LIST_GIT_STASHES: &str = "list_git_stashes";
+pub(crate) const METHOD_GET_GIT_STASH_DIFF: &str = "get_git_stash_diff";
+pub(crate) const METHOD_CREATE_GIT_STASH: &str = "create_git_stash";
+pub(crate) const METHOD_APPLY_GIT_STASH: &str = "apply_git_stash";
+pub(crate) const METHOD_POP_GIT_STASH: &str = "pop_git_stash";
+pub(crate) const METHOD_DROP_GIT_STASH: &str = "drop_git_stash";
+pub(crate) const METHOD_MERGE_GIT_BRANCH: &str = "merge_git_branch";
+pub(crate) const METHOD_REBASE_GIT_BRANCH: &str = "rebase_git_branch";
+pub(crate) const METHOD_CONTINUE_GIT_OPERATION: &str = "continue_git_operation";
+pub(crate) const METHOD_SKIP_GIT_OPERATION: &str = "skip_git_operation";
+pub(crate) const METHOD_ABORT_GIT_OPERATION: &str = "abort_git_operation";
+pub(crate) const METHOD_GET_GIT_CONFLICT: &str = "get_git_conflict";
+pub(crate) const METHOD_RESOLVE_GIT_CONFLICT: &str = "resolve_git_conflict";
 pub(crate) const METHOD_GET_GITHUB_ISSUES: &str = "get_github_issues";
 pub(crate) const METHOD_GET_GITHUB_PULL_REQUESTS: &str = "get_github_pull_requests";
 pub(crate) const METHOD_GET_GITHUB_PULL_REQUEST_DIFF: &str = "get_github_pull_request_diff";
@@ -55,13 +73,6 @@ pub(crate) struct InitGitRepoRequest {
     pub(crate) force: Option<bool>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub(crate) struct InitGitRepoRequiredRequest {
-    pub(crate) workspace_id: String,
-    pub(crate) branch: String,
-}
-
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct CreateGitHubRepoRequest {
@@ -73,17 +84,17 @@ pub(crate) struct CreateGitHubRepoRequest {
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub(crate) struct CreateGitHubRepoRequiredRequest {
+pub(crate) struct WorkspacePathRequest {
     pub(crate) workspace_id: String,
-    pub(crate) repo: String,
-    pub(crate) visibility: String,
+    pub(crate) path: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub(crate) struct WorkspacePathRequest {
+pub(crate) struct WorkspaceSelectionRequest {
     pub(crate) workspace_id: String,
     pub(crate) path: String,
+    pub(crate) selection: GitDiffSelection,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -98,6 +109,8 @@ pub(crate) struct ListGitRootsRequest {
 pub(crate) struct GetGitLogRequest {
     pub(crate) workspace_id: String,
     pub(crate) limit: Option<u32>,
+    pub(crate) cursor: Option<String>,
+    pub(crate) filter: Option<GitLogFilter>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -107,6 +120,32 @@ pub(crate) struct WorkspaceShaRequest {
     pub(crate) sha: String,
 }
 
+/// Stash requests carry the `sha` from the listing so a stash that moved is rejected.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WorkspaceStashActionRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) index: usize,
+    pub(crate) sha: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CreateGitStashRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) message: Option<String>,
+    pub(crate) include_untracked: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ResolveGitConflictRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) path: String,
+    pub(crate) resolution: GitConflictResolution,
+    pub(crate) content: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct WorkspaceMessageRequest {
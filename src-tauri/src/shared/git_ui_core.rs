@@ -4,9 +4,12 @@ use std::path::{Path, PathBuf};
 use serde_json::Value;
 use tokio::sync::Mutex;
 
+use crate::shared::rpc_error::RpcError;
 use crate::types::{
-    AppSettings, GitCommitDiff, GitFileDiff, GitHubIssuesResponse, GitHubPullRequestComment,
-    GitHubPullRequestDiff, GitHubPullRequestsResponse, GitLogResponse, WorkspaceEntry,
+    AppSettings, GitCommitDiff, GitConflictDetail, GitConflictResolution, GitDiffSelection,
+    GitFileDiff, GitHubIssuesResponse, GitHubPullRequestComment, GitHubPullRequestDiff,
+    GitHubPullRequestsResponse, GitLogFilter, GitLogResponse, GitOperationStatus, GitStashEntry,
+    WorkspaceEntry,
 };
 
 #[path = "git_ui_core/commands.rs"]
@@ -19,6 +22,12 @@ mod diff;
 mod github;
 #[path = "git_ui_core/log.rs"]
 mod log;
+#[path = "git_ui_core/merge.rs"]
+mod merge;
+#[path = "git_ui_core/selection.rs"]
+mod selection;
+#[path = "git_ui_core/stash.rs"]
+mod stash;
 
 #[cfg(test)]
 #[path = "git_ui_core/tests.rs"]
@@ -35,6 +44,15 @@ pub(crate) fn collect_workspace_diff_core(repo_root: &Path) -> Result<String, St
     diff::collect_workspace_diff(repo_root)
 }
 
+pub(crate) fn diff_commits_core(
+    repo: &git2::Repository,
+    from: git2::Oid,
+    to: git2::Oid,
+    ignore_whitespace_changes: bool,
+) -> Result<Vec<GitCommitDiff>, String> {
+    diff::diff_commits(repo, from, to, ignore_whitespace_changes)
+}
+
 pub(crate) async fn get_git_status_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
@@ -81,8 +99,10 @@ pub(crate) async fn get_git_log_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     limit: Option<usize>,
+    cursor: Option<String>,
+    filter: Option<GitLogFilter>,
 ) -> Result<GitLogResponse, String> {
-    log::get_git_log_inner(workspaces, workspace_id, limit).await
+    log::get_git_log_inner(workspaces, workspace_id, limit, cursor, filter).await
 }
 
 pub(crate) async fn get_git_commit_diff_core(
@@ -132,6 +152,60 @@ pub(crate) async fn revert_git_file_core(
     commands::revert_git_file_inner(workspaces, workspace_id, path).await
 }
 
+pub(crate) async fn stage_git_selection_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: String,
+    path: String,
+    selection: GitDiffSelection,
+) -> Result<(), String> {
+    selection::apply_git_selection_inner(
+        workspaces,
+        app_settings,
+        workspace_id,
+        path,
+        selection,
+        selection::SelectionAction::Stage,
+    )
+    .await
+}
+
+pub(crate) async fn unstage_git_selection_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: String,
+    path: String,
+    selection: GitDiffSelection,
+) -> Result<(), String> {
+    selection::apply_git_selection_inner(
+        workspaces,
+        app_settings,
+        workspace_id,
+        path,
+        selection,
+        selection::SelectionAction::Unstage,
+    )
+    .await
+}
+
+pub(crate) async fn revert_git_selection_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: String,
+    path: String,
+    selection: GitDiffSelection,
+) -> Result<(), String> {
+    selection::apply_git_selection_inner(
+        workspaces,
+        app_settings,
+        workspace_id,
+        path,
+        selection,
+        selection::SelectionAction::Revert,
+    )
+    .await
+}
+
 pub(crate) async fn revert_git_all_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
@@ -175,6 +249,114 @@ pub(crate) async fn sync_git_core(
     commands::sync_git_inner(workspaces, workspace_id).await
 }
 
+pub(crate) async fn list_git_stashes_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+) -> Result<Vec<GitStashEntry>, String> {
+    stash::list_git_stashes_inner(workspaces, workspace_id).await
+}
+
+pub(crate) async fn get_git_stash_diff_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: String,
+    index: usize,
+    sha: String,
+) -> Result<Vec<GitFileDiff>, RpcError> {
+    stash::get_git_stash_diff_inner(workspaces, app_settings, workspace_id, index, sha).await
+}
+
+pub(crate) async fn create_git_stash_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    message: Option<String>,
+    include_untracked: bool,
+) -> Result<GitStashEntry, String> {
+    stash::create_git_stash_inner(workspaces, workspace_id, message, include_untracked).await
+}
+
+pub(crate) async fn apply_git_stash_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    index: usize,
+    sha: String,
+) -> Result<(), RpcError> {
+    stash::apply_git_stash_inner(workspaces, workspace_id, index, sha).await
+}
+
+pub(crate) async fn pop_git_stash_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    index: usize,
+    sha: String,
+) -> Result<(), RpcError> {
+    stash::pop_git_stash_inner(workspaces, workspace_id, index, sha).await
+}
+
+pub(crate) async fn drop_git_stash_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    index: usize,
+    sha: String,
+) -> Result<(), RpcError> {
+    stash::drop_git_stash_inner(workspaces, workspace_id, index, sha).await
+}
+
+pub(crate) async fn merge_git_branch_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    name: String,
+) -> Result<GitOperationStatus, String> {
+    merge::merge_git_branch_inner(workspaces, workspace_id, name).await
+}
+
+pub(crate) async fn rebase_git_branch_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    name: String,
+) -> Result<GitOperationStatus, String> {
+    merge::rebase_git_branch_inner(workspaces, workspace_id, name).await
+}
+
+pub(crate) async fn continue_git_operation_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+) -> Result<GitOperationStatus, String> {
+    merge::continue_git_operation_inner(workspaces, workspace_id).await
+}
+
+pub(crate) async fn skip_git_operation_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+) -> Result<GitOperationStatus, String> {
+    merge::skip_git_operation_inner(workspaces, workspace_id).await
+}
+
+pub(crate) async fn abort_git_operation_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+) -> Result<GitOperationStatus, String> {
+    merge::abort_git_operation_inner(workspaces, workspace_id).await
+}
+
+pub(crate) async fn get_git_conflict_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    path: String,
+) -> Result<GitConflictDetail, String> {
+    merge::get_git_conflict_inner(workspaces, workspace_id, path).await
+}
+
+pub(crate) async fn resolve_git_conflict_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    path: String,
+    resolution: GitConflictResolution,
+    content: Option<String>,
+) -> Result<(), String> {
+    merge::resolve_git_conflict_inner(workspaces, workspace_id, path, resolution, content).await
+}
+
 pub(crate) async fn get_github_issues_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
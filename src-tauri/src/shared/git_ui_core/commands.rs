@@ -15,7 +15,7 @@ use crate::utils::{git_env_path, normalize_git_path, resolve_git_binary};
 
 use super::context::workspace_entry_for_id;
 
-async fn run_git_command(repo_root: &Path, args: &[&str]) -> Result<(), String> {
+pub(super) async fn run_git_command(repo_root: &Path, args: &[&str]) -> Result<(), String> {
     let git_bin = resolve_git_binary().map_err(|e| format!("Failed to run git: {e}"))?;
     let output = tokio_command(git_bin)
         .args(args)
@@ -267,7 +267,12 @@ pub(super) fn action_paths_for_file(repo_root: &Path, path: &str) -> Vec<String>
         if !(status.contains(Status::WT_RENAMED) || status.contains(Status::INDEX_RENAMED)) {
             continue;
         }
-        let delta = entry.index_to_workdir().or_else(|| entry.head_to_index());
+        // A staged rename may also have unstaged edits; the index delta carries both paths.
+        let delta = if status.contains(Status::INDEX_RENAMED) {
+            entry.head_to_index()
+        } else {
+            entry.index_to_workdir()
+        };
         let Some(delta) = delta else {
             continue;
         };
@@ -5,7 +5,7 @@ use std::path::{Path, PathBuf};
 use std::process::Stdio;
 
 use base64::{engine::general_purpose::STANDARD, Engine as _};
-use git2::{DiffOptions, Repository, Status, StatusOptions};
+use git2::{DiffFindOptions, DiffOptions, Repository, Status, StatusOptions};
 use serde_json::{json, Value};
 use tokio::sync::Mutex;
 
@@ -17,6 +17,7 @@ use crate::types::{AppSettings, GitCommitDiff, GitFileDiff, GitFileStatus, Works
 use crate::utils::{git_env_path, normalize_git_path, resolve_git_binary};
 
 use super::context::workspace_entry_for_id;
+use super::merge;
 
 const INDEX_SKIP_WORKTREE_FLAG: u16 = 0x4000;
 const MAX_IMAGE_BYTES: usize = 10 * 1024 * 1024;
@@ -365,6 +366,7 @@ pub(super) async fn get_git_status_inner(
 
     let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
     let index = repo.index().ok();
+    let conflicted_files = merge::conflicted_files(&repo)?;
 
     let mut files = Vec::new();
     let mut staged_files = Vec::new();
@@ -388,6 +390,16 @@ pub(super) async fn get_git_status_inner(
         }
         let status = entry.status();
         let normalized_path = normalize_git_path(path);
+        if status.contains(Status::CONFLICTED) {
+            // Conflicts are resolved through the conflict commands, not staged.
+            files.push(GitFileStatus {
+                path: normalized_path,
+                status: "U".to_string(),
+                additions: 0,
+                deletions: 0,
+            });
+            continue;
+        }
         let include_index = status.intersects(
             Status::INDEX_NEW
                 | Status::INDEX_MODIFIED
@@ -457,6 +469,8 @@ pub(super) async fn get_git_status_inner(
         "files": files,
         "stagedFiles": staged_files,
         "unstagedFiles": unstaged_files,
+        "conflictedFiles": conflicted_files,
+        "operation": merge::current_operation(&repo),
         "totalAdditions": total_additions,
         "totalDeletions": total_deletions,
     }))
@@ -485,7 +499,7 @@ pub(super) async fn get_git_diffs_inner(
             .show_untracked_content(true);
         options.ignore_whitespace_change(ignore_whitespace_changes);
 
-        let diff = match head_tree.as_ref() {
+        let mut diff = match head_tree.as_ref() {
             Some(tree) => repo
                 .diff_tree_to_workdir_with_index(Some(tree), Some(&mut options))
                 .map_err(|e| e.to_string())?,
@@ -493,6 +507,9 @@ pub(super) async fn get_git_diffs_inner(
                 .diff_tree_to_workdir_with_index(None, Some(&mut options))
                 .map_err(|e| e.to_string())?,
         };
+        // Pair staged renames so the file gets one entry whose hunks can be staged by line.
+        diff.find_similar(Some(DiffFindOptions::new().renames(true)))
+            .map_err(|e| e.to_string())?;
         let diff_paths: Vec<PathBuf> = diff
             .deltas()
             .filter_map(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
@@ -634,11 +651,45 @@ pub(super) async fn get_git_commit_diff_inner(
     let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
     let commit_tree = commit.tree().map_err(|e| e.to_string())?;
     let parent_tree = commit.parent(0).ok().and_then(|parent| parent.tree().ok());
+    diff_trees(
+        &repo,
+        parent_tree.as_ref(),
+        &commit_tree,
+        ignore_whitespace_changes,
+    )
+}
+
+pub(super) fn diff_commits(
+    repo: &Repository,
+    from: git2::Oid,
+    to: git2::Oid,
+    ignore_whitespace_changes: bool,
+) -> Result<Vec<GitCommitDiff>, String> {
+    let tree_for = |oid| {
+        repo.find_commit(oid)
+            .and_then(|commit| commit.tree())
+            .map_err(|e| e.to_string())
+    };
+    diff_trees(
+        repo,
+        Some(&tree_for(from)?),
+        &tree_for(to)?,
+        ignore_whitespace_changes,
+    )
+}
 
+/// Per-file diffs between two trees, with the same line and image payloads as the
+/// workspace diff.
+pub(super) fn diff_trees(
+    repo: &Repository,
+    parent_tree: Option<&git2::Tree>,
+    commit_tree: &git2::Tree,
+    ignore_whitespace_changes: bool,
+) -> Result<Vec<GitCommitDiff>, String> {
     let mut options = DiffOptions::new();
     options.ignore_whitespace_change(ignore_whitespace_changes);
     let diff = repo
-        .diff_tree_to_tree(parent_tree.as_ref(), Some(&commit_tree), Some(&mut options))
+        .diff_tree_to_tree(parent_tree, Some(commit_tree), Some(&mut options))
         .map_err(|e| e.to_string())?;
 
     let mut results = Vec::new();
@@ -661,7 +712,6 @@ pub(super) async fn get_git_commit_diff_inner(
 
         let old_lines = if !is_added {
             parent_tree
-                .as_ref()
                 .and_then(|tree| old_path.and_then(|path| tree.get_path(path).ok()))
                 .and_then(|entry| repo.find_blob(entry.id()).ok())
                 .and_then(blob_to_lines)
@@ -681,7 +731,6 @@ pub(super) async fn get_git_commit_diff_inner(
         if is_image {
             let old_image_data = if !is_added && old_image_mime.is_some() {
                 parent_tree
-                    .as_ref()
                     .and_then(|tree| old_path.and_then(|path| tree.get_path(path).ok()))
                     .and_then(|entry| repo.find_blob(entry.id()).ok())
                     .and_then(blob_to_base64)
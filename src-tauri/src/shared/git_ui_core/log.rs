@@ -1,92 +1,373 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
-use git2::{BranchType, Repository, Sort};
+use git2::{BranchType, Commit, Delta, DiffFindOptions, Oid, Repository, Revwalk, Sort};
 use tokio::sync::Mutex;
 
 use crate::git_utils::{commit_to_entry, resolve_git_root};
-use crate::types::{GitLogResponse, WorkspaceEntry};
+use crate::types::{GitLogEntry, GitLogFilter, GitLogRange, GitLogResponse, WorkspaceEntry};
+use crate::utils::normalize_git_path;
 
 use super::context::workspace_entry_for_id;
 
-pub(super) async fn get_git_log_inner(
-    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
-    workspace_id: String,
-    limit: Option<usize>,
-) -> Result<GitLogResponse, String> {
-    let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
-    let repo_root = resolve_git_root(&entry)?;
-    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
-    let max_items = limit.unwrap_or(40);
-    let mut revwalk = repo.revwalk().map_err(|e| e.to_string())?;
-    revwalk.push_head().map_err(|e| e.to_string())?;
-    revwalk.set_sorting(Sort::TIME).map_err(|e| e.to_string())?;
+const DEFAULT_PAGE_SIZE: usize = 40;
+
+/// Where the previous page stopped: the tip the walk started from (to notice rewritten
+/// history), the total from the first page (if it counted one), the commits the walk had
+/// queued after the last returned one, and the followed path's name at that point.
+#[derive(Debug, PartialEq)]
+struct LogCursor {
+    tip: String,
+    total: Option<usize>,
+    frontier: Vec<String>,
+    path: Option<String>,
+}
+
+impl LogCursor {
+    fn encode(&self) -> String {
+        let frontier = self.frontier.join(",");
+        let total = self
+            .total
+            .map(|total| total.to_string())
+            .unwrap_or_default();
+        match self.path.as_deref() {
+            Some(path) => format!("{total}:{}:{frontier}:{path}", self.tip),
+            None => format!("{total}:{}:{frontier}", self.tip),
+        }
+    }
+
+    fn decode(raw: &str) -> Result<Self, String> {
+        let invalid = || "Invalid log cursor.".to_string();
+        let mut parts = raw.splitn(4, ':');
+        let total = match parts.next().ok_or_else(invalid)? {
+            "" => None,
+            value => Some(value.parse::<usize>().map_err(|_| invalid())?),
+        };
+        let tip = parts
+            .next()
+            .filter(|value| !value.is_empty())
+            .ok_or_else(invalid)?
+            .to_string();
+        let frontier = parts
+            .next()
+            .filter(|value| !value.is_empty())
+            .ok_or_else(invalid)?
+            .split(',')
+            .map(str::to_string)
+            .collect();
+        let path = parts.next().map(str::to_string);
+        Ok(Self {
+            tip,
+            total,
+            frontier,
+            path,
+        })
+    }
+}
+
+enum PathChange {
+    Untouched,
+    Changed,
+    RenamedFrom(String),
+}
+
+/// Like `git log -- <path>`, a commit that leaves the path as in any parent did not
+/// change it. An added path is checked for a rename from the first parent.
+fn path_change(repo: &Repository, commit: &Commit, path: &str) -> Result<PathChange, String> {
+    let tree = commit.tree().map_err(|e| e.to_string())?;
+    let current = tree.get_path(Path::new(path)).ok().map(|entry| entry.id());
+    let mut first_parent_tree = None;
+    for parent in commit.parents() {
+        let parent_tree = parent.tree().map_err(|e| e.to_string())?;
+        let before = parent_tree
+            .get_path(Path::new(path))
+            .ok()
+            .map(|entry| entry.id());
+        if before == current {
+            return Ok(PathChange::Untouched);
+        }
+        if first_parent_tree.is_none() {
+            first_parent_tree = Some((parent_tree, before));
+        }
+    }
+    let Some((parent_tree, before)) = first_parent_tree else {
+        return Ok(if current.is_some() {
+            PathChange::Changed
+        } else {
+            PathChange::Untouched
+        });
+    };
+    if before.is_some() || current.is_none() {
+        return Ok(PathChange::Changed);
+    }
 
-    let mut total = 0usize;
-    for oid_result in revwalk {
-        oid_result.map_err(|e| e.to_string())?;
-        total += 1;
+    let mut diff = repo
+        .diff_tree_to_tree(Some(&parent_tree), Some(&tree), None)
+        .map_err(|e| e.to_string())?;
+    diff.find_similar(Some(DiffFindOptions::new().renames(true)))
+        .map_err(|e| e.to_string())?;
+    let renamed_from = diff.deltas().find_map(|delta| {
+        let is_target =
+            delta.status() == Delta::Renamed && delta.new_file().path() == Some(Path::new(path));
+        is_target
+            .then(|| delta.old_file().path())
+            .flatten()
+            .map(|old| normalize_git_path(&old.to_string_lossy()))
+    });
+    Ok(renamed_from.map_or(PathChange::Changed, PathChange::RenamedFrom))
+}
+
+fn matches_filter(commit: &Commit, filter: &GitLogFilter) -> bool {
+    let time = commit.time().seconds();
+    if filter.since.is_some_and(|since| time < since)
+        || filter.until.is_some_and(|until| time > until)
+    {
+        return false;
+    }
+    if let Some(author) = filter.author.as_deref() {
+        let needle = author.to_lowercase();
+        let signature = commit.author();
+        let name = signature.name().unwrap_or("").to_lowercase();
+        let email = signature.email().unwrap_or("").to_lowercase();
+        if !name.contains(&needle) && !email.contains(&needle) {
+            return false;
+        }
+    }
+    if let Some(message) = filter.message.as_deref() {
+        let haystack = commit.message().unwrap_or("").to_lowercase();
+        if !haystack.contains(&message.to_lowercase()) {
+            return false;
+        }
     }
+    true
+}
+
+/// Whether `commit` belongs in the log. Follows renames of the tracked path, so commits
+/// must be passed in walk order.
+fn includes_commit(
+    repo: &Repository,
+    commit: &Commit,
+    filter: &GitLogFilter,
+    tracked_path: &mut Option<String>,
+) -> Result<bool, String> {
+    let mut include = matches_filter(commit, filter);
+    if let Some(path) = tracked_path.as_deref() {
+        match path_change(repo, commit, path)? {
+            PathChange::Untouched => include = false,
+            PathChange::Changed => {}
+            PathChange::RenamedFrom(old_path) => *tracked_path = Some(old_path),
+        }
+    }
+    Ok(include)
+}
+
+/// The commit to walk from, and the local branch it names, used for upstream lookups.
+fn resolve_start(repo: &Repository, branch: Option<&str>) -> Result<(Oid, Option<String>), String> {
+    let Some(branch) = branch else {
+        let head = repo.head().map_err(|e| e.to_string())?;
+        let oid = head.peel_to_commit().map_err(|e| e.to_string())?.id();
+        let name = head
+            .shorthand()
+            .filter(|_| head.is_branch())
+            .map(str::to_string);
+        return Ok((oid, name));
+    };
+    let oid = repo
+        .revparse_single(branch)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|_| format!("Unknown branch or revision '{branch}'."))?
+        .id();
+    let name = repo
+        .find_branch(branch, BranchType::Local)
+        .ok()
+        .map(|_| branch.to_string());
+    Ok((oid, name))
+}
+
+fn upstream_of(repo: &Repository, branch_name: &str) -> Option<(String, Oid)> {
+    let branch = repo.find_branch(branch_name, BranchType::Local).ok()?;
+    let upstream_branch = branch.upstream().ok()?;
+    let upstream_ref = upstream_branch.get();
+    let name = upstream_ref
+        .shorthand()
+        .map(|name| name.to_string())
+        .or_else(|| upstream_ref.name().map(|name| name.to_string()))?;
+    Some((name, upstream_ref.target()?))
+}
 
+fn walk<'repo>(
+    repo: &'repo Repository,
+    push: &[Oid],
+    hide: &[Oid],
+) -> Result<Revwalk<'repo>, String> {
     let mut revwalk = repo.revwalk().map_err(|e| e.to_string())?;
-    revwalk.push_head().map_err(|e| e.to_string())?;
+    for oid in push {
+        revwalk.push(*oid).map_err(|e| e.to_string())?;
+    }
+    for oid in hide {
+        revwalk.hide(*oid).map_err(|e| e.to_string())?;
+    }
     revwalk.set_sorting(Sort::TIME).map_err(|e| e.to_string())?;
+    Ok(revwalk)
+}
 
+fn walk_entries(
+    repo: &Repository,
+    push: Oid,
+    hide: Oid,
+    max_items: usize,
+) -> Result<Vec<GitLogEntry>, String> {
     let mut entries = Vec::new();
-    for oid_result in revwalk.take(max_items) {
+    for oid_result in walk(repo, &[push], &[hide])?.take(max_items) {
         let oid = oid_result.map_err(|e| e.to_string())?;
         let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
         entries.push(commit_to_entry(commit));
     }
+    Ok(entries)
+}
+
+pub(super) fn git_log(
+    repo: &Repository,
+    limit: Option<usize>,
+    cursor: Option<&str>,
+    filter: &GitLogFilter,
+) -> Result<GitLogResponse, String> {
+    let page_size = limit.unwrap_or(DEFAULT_PAGE_SIZE).max(1);
+    let cursor = cursor.map(LogCursor::decode).transpose()?;
+    let (start, branch_name) = resolve_start(repo, filter.branch.as_deref())?;
+    let upstream = branch_name
+        .as_deref()
+        .and_then(|name| upstream_of(repo, name));
+
+    let (mut push, mut hide) = (start, Vec::new());
+    if let Some(range) = filter.range {
+        let (_, upstream_oid) = upstream.as_ref().ok_or_else(|| {
+            format!(
+                "{} has no upstream branch.",
+                branch_name.as_deref().unwrap_or("HEAD")
+            )
+        })?;
+        match range {
+            GitLogRange::Ahead => hide.push(*upstream_oid),
+            GitLogRange::Behind => {
+                push = *upstream_oid;
+                hide.push(start);
+            }
+        }
+    }
+    if let Some(base) = filter.base.as_deref() {
+        let base_oid = repo
+            .revparse_single(base)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|_| format!("Unknown branch or revision '{base}'."))?
+            .id();
+        hide.push(base_oid);
+    }
+
+    let history_changed = || "The history changed since this page was loaded.".to_string();
+    let (starts, known_total, mut tracked_path) = match cursor {
+        Some(cursor) => {
+            if cursor.tip != push.to_string() {
+                return Err(history_changed());
+            }
+            let starts = cursor
+                .frontier
+                .iter()
+                .map(|sha| Oid::from_str(sha).map_err(|_| "Invalid log cursor.".to_string()))
+                .collect::<Result<Vec<_>, _>>()?;
+            (starts, Some(cursor.total), cursor.path)
+        }
+        None => (
+            vec![push],
+            None,
+            filter
+                .path
+                .as_deref()
+                .map(|path| {
+                    normalize_git_path(path.trim())
+                        .trim_matches('/')
+                        .to_string()
+                })
+                .filter(|path| !path.is_empty()),
+        ),
+    };
+    if starts.iter().any(|oid| repo.find_commit(*oid).is_err()) {
+        return Err(history_changed());
+    }
+    // Counting commits that need a filter check (a tree diff per commit for a path)
+    // would cost a walk of the whole history on every first page, so those go uncounted.
+    let filters_commits = tracked_path.is_some()
+        || filter.author.is_some()
+        || filter.message.is_some()
+        || filter.since.is_some()
+        || filter.until.is_some();
+
+    // Commits the walk has queued but not visited yet; a page resumes from them.
+    let mut frontier: HashSet<Oid> = starts.iter().copied().collect();
+    let mut visited = HashSet::new();
+    let mut entries = Vec::new();
+    let mut resume = None;
+    let mut revwalk = walk(repo, &starts, &hide)?;
+    for oid_result in revwalk.by_ref() {
+        let oid = oid_result.map_err(|e| e.to_string())?;
+        let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+        let path_before = tracked_path.clone();
+        if includes_commit(repo, &commit, filter, &mut tracked_path)? {
+            if entries.len() == page_size {
+                let mut frontier = frontier
+                    .iter()
+                    .map(|oid| oid.to_string())
+                    .collect::<Vec<_>>();
+                frontier.sort();
+                resume = Some((frontier, path_before));
+                break;
+            }
+            entries.push(commit_to_entry(commit.clone()));
+        }
+        frontier.remove(&oid);
+        visited.insert(oid);
+        frontier.extend(
+            commit
+                .parent_ids()
+                .filter(|parent| !visited.contains(parent)),
+        );
+    }
+
+    // Only the first page counts; later pages carry its total in the cursor.
+    let total = match known_total {
+        Some(total) => total,
+        None if filters_commits => None,
+        None => {
+            let mut total = entries.len() + usize::from(resume.is_some());
+            for oid_result in revwalk {
+                oid_result.map_err(|e| e.to_string())?;
+                total += 1;
+            }
+            Some(total)
+        }
+    };
+    let next_cursor = resume.map(|(frontier, path)| {
+        LogCursor {
+            tip: push.to_string(),
+            total,
+            frontier,
+            path,
+        }
+        .encode()
+    });
 
     let mut ahead = 0usize;
     let mut behind = 0usize;
     let mut ahead_entries = Vec::new();
     let mut behind_entries = Vec::new();
-    let mut upstream = None;
-
-    if let Ok(head) = repo.head() {
-        if head.is_branch() {
-            if let Some(branch_name) = head.shorthand() {
-                if let Ok(branch) = repo.find_branch(branch_name, BranchType::Local) {
-                    if let Ok(upstream_branch) = branch.upstream() {
-                        let upstream_ref = upstream_branch.get();
-                        upstream = upstream_ref
-                            .shorthand()
-                            .map(|name| name.to_string())
-                            .or_else(|| upstream_ref.name().map(|name| name.to_string()));
-                        if let (Some(head_oid), Some(upstream_oid)) =
-                            (head.target(), upstream_ref.target())
-                        {
-                            let (ahead_count, behind_count) = repo
-                                .graph_ahead_behind(head_oid, upstream_oid)
-                                .map_err(|e| e.to_string())?;
-                            ahead = ahead_count;
-                            behind = behind_count;
-
-                            let mut revwalk = repo.revwalk().map_err(|e| e.to_string())?;
-                            revwalk.push(head_oid).map_err(|e| e.to_string())?;
-                            revwalk.hide(upstream_oid).map_err(|e| e.to_string())?;
-                            revwalk.set_sorting(Sort::TIME).map_err(|e| e.to_string())?;
-                            for oid_result in revwalk.take(max_items) {
-                                let oid = oid_result.map_err(|e| e.to_string())?;
-                                let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
-                                ahead_entries.push(commit_to_entry(commit));
-                            }
-
-                            let mut revwalk = repo.revwalk().map_err(|e| e.to_string())?;
-                            revwalk.push(upstream_oid).map_err(|e| e.to_string())?;
-                            revwalk.hide(head_oid).map_err(|e| e.to_string())?;
-                            revwalk.set_sorting(Sort::TIME).map_err(|e| e.to_string())?;
-                            for oid_result in revwalk.take(max_items) {
-                                let oid = oid_result.map_err(|e| e.to_string())?;
-                                let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
-                                behind_entries.push(commit_to_entry(commit));
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    if let Some((_, upstream_oid)) = upstream.as_ref() {
+        let (ahead_count, behind_count) = repo
+            .graph_ahead_behind(start, *upstream_oid)
+            .map_err(|e| e.to_string())?;
+        ahead = ahead_count;
+        behind = behind_count;
+        ahead_entries = walk_entries(repo, start, *upstream_oid, page_size)?;
+        behind_entries = walk_entries(repo, *upstream_oid, start, page_size)?;
     }
 
     Ok(GitLogResponse {
@@ -96,8 +377,27 @@ pub(super) async fn get_git_log_inner(
         behind,
         ahead_entries,
         behind_entries,
-        upstream,
+        upstream: upstream.map(|(name, _)| name),
+        next_cursor,
+    })
+}
+
+pub(super) async fn get_git_log_inner(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    limit: Option<usize>,
+    cursor: Option<String>,
+    filter: Option<GitLogFilter>,
+) -> Result<GitLogResponse, String> {
+    let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
+    let repo_root = resolve_git_root(&entry)?;
+    let filter = filter.unwrap_or_default();
+    tokio::task::spawn_blocking(move || {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        git_log(&repo, limit, cursor.as_deref(), &filter)
     })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 pub(super) async fn get_git_remote_inner(
@@ -0,0 +1,300 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use git2::{IndexConflict, Repository, RepositoryState};
+use tokio::sync::Mutex;
+
+use crate::git_utils::resolve_git_root;
+use crate::types::{
+    GitConflictDetail, GitConflictFile, GitConflictResolution, GitConflictState, GitOperationKind,
+    GitOperationStatus, WorkspaceEntry,
+};
+
+use super::commands::run_git_command;
+use super::context::workspace_entry_for_id;
+
+pub(super) fn current_operation(repo: &Repository) -> Option<GitOperationKind> {
+    match repo.state() {
+        RepositoryState::Merge => Some(GitOperationKind::Merge),
+        RepositoryState::Rebase
+        | RepositoryState::RebaseInteractive
+        | RepositoryState::RebaseMerge
+        | RepositoryState::ApplyMailboxOrRebase => Some(GitOperationKind::Rebase),
+        RepositoryState::CherryPick | RepositoryState::CherryPickSequence => {
+            Some(GitOperationKind::CherryPick)
+        }
+        RepositoryState::Revert | RepositoryState::RevertSequence => Some(GitOperationKind::Revert),
+        _ => None,
+    }
+}
+
+fn operation_command(kind: GitOperationKind) -> &'static str {
+    match kind {
+        GitOperationKind::Merge => "merge",
+        GitOperationKind::Rebase => "rebase",
+        GitOperationKind::CherryPick => "cherry-pick",
+        GitOperationKind::Revert => "revert",
+    }
+}
+
+fn conflict_state(conflict: &IndexConflict) -> GitConflictState {
+    match (
+        conflict.ancestor.is_some(),
+        conflict.our.is_some(),
+        conflict.their.is_some(),
+    ) {
+        (true, true, true) => GitConflictState::BothModified,
+        (false, true, true) => GitConflictState::BothAdded,
+        (true, false, false) => GitConflictState::BothDeleted,
+        (false, true, false) => GitConflictState::AddedByUs,
+        (false, false, true) => GitConflictState::AddedByThem,
+        (true, false, true) => GitConflictState::DeletedByUs,
+        (true, true, false) => GitConflictState::DeletedByThem,
+        // libgit2 never reports a conflict without any stage.
+        (false, false, false) => GitConflictState::BothModified,
+    }
+}
+
+fn conflict_path(conflict: &IndexConflict) -> Option<String> {
+    [&conflict.our, &conflict.their, &conflict.ancestor]
+        .into_iter()
+        .flatten()
+        .next()
+        .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+}
+
+fn index_conflicts(repo: &Repository) -> Result<Vec<(String, IndexConflict)>, String> {
+    let index = repo.index().map_err(|e| e.to_string())?;
+    if !index.has_conflicts() {
+        return Ok(Vec::new());
+    }
+    let mut conflicts = Vec::new();
+    for conflict in index.conflicts().map_err(|e| e.to_string())? {
+        let conflict = conflict.map_err(|e| e.to_string())?;
+        if let Some(path) = conflict_path(&conflict) {
+            conflicts.push((path, conflict));
+        }
+    }
+    conflicts.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(conflicts)
+}
+
+pub(super) fn conflicted_files(repo: &Repository) -> Result<Vec<GitConflictFile>, String> {
+    Ok(index_conflicts(repo)?
+        .into_iter()
+        .map(|(path, conflict)| GitConflictFile {
+            state: conflict_state(&conflict),
+            path,
+        })
+        .collect())
+}
+
+fn operation_status(repo_root: &Path) -> Result<GitOperationStatus, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    Ok(GitOperationStatus {
+        operation: current_operation(&repo),
+        conflicts: conflicted_files(&repo)?,
+    })
+}
+
+/// Git exits with an error when a merge or rebase stops on conflicts. That is an expected
+/// outcome here, so it is reported through the returned status instead.
+async fn run_operation_step(repo_root: &Path, args: &[&str]) -> Result<GitOperationStatus, String> {
+    match run_git_command(repo_root, args).await {
+        Ok(()) => operation_status(repo_root),
+        Err(error) => {
+            let status = operation_status(repo_root)?;
+            if status.operation.is_some() && !status.conflicts.is_empty() {
+                Ok(status)
+            } else {
+                Err(error)
+            }
+        }
+    }
+}
+
+fn require_operation(repo_root: &Path) -> Result<(GitOperationKind, usize), String> {
+    let status = operation_status(repo_root)?;
+    let operation = status
+        .operation
+        .ok_or_else(|| "No merge, rebase, cherry-pick or revert is in progress.".to_string())?;
+    Ok((operation, status.conflicts.len()))
+}
+
+fn validate_revision(repo_root: &Path, name: &str) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() || name.starts_with('-') {
+        return Err("Branch name is required.".to_string());
+    }
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    repo.revparse_single(name)
+        .map(|_| ())
+        .map_err(|_| format!("Unknown branch or revision '{name}'."))
+}
+
+pub(super) async fn merge_git_branch_inner(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    name: String,
+) -> Result<GitOperationStatus, String> {
+    let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
+    let repo_root = resolve_git_root(&entry)?;
+    validate_revision(&repo_root, &name)?;
+    run_operation_step(&repo_root, &["merge", "--no-edit", name.trim()]).await
+}
+
+pub(super) async fn rebase_git_branch_inner(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    name: String,
+) -> Result<GitOperationStatus, String> {
+    let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
+    let repo_root = resolve_git_root(&entry)?;
+    validate_revision(&repo_root, &name)?;
+    run_operation_step(&repo_root, &["rebase", name.trim()]).await
+}
+
+pub(super) async fn continue_git_operation_inner(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+) -> Result<GitOperationStatus, String> {
+    let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
+    let repo_root = resolve_git_root(&entry)?;
+    let (operation, remaining) = require_operation(&repo_root)?;
+    if remaining > 0 {
+        return Err(format!(
+            "Resolve all conflicts before continuing ({remaining} remaining)."
+        ));
+    }
+    // Keep the prepared commit message instead of waiting on an editor.
+    run_operation_step(
+        &repo_root,
+        &[
+            "-c",
+            "core.editor=true",
+            operation_command(operation),
+            "--continue",
+        ],
+    )
+    .await
+}
+
+pub(super) async fn skip_git_operation_inner(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+) -> Result<GitOperationStatus, String> {
+    let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
+    let repo_root = resolve_git_root(&entry)?;
+    let (operation, _) = require_operation(&repo_root)?;
+    if operation == GitOperationKind::Merge {
+        return Err("A merge cannot skip commits; abort it instead.".to_string());
+    }
+    run_operation_step(&repo_root, &[operation_command(operation), "--skip"]).await
+}
+
+pub(super) async fn abort_git_operation_inner(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+) -> Result<GitOperationStatus, String> {
+    let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
+    let repo_root = resolve_git_root(&entry)?;
+    let (operation, _) = require_operation(&repo_root)?;
+    run_git_command(&repo_root, &[operation_command(operation), "--abort"]).await?;
+    operation_status(&repo_root)
+}
+
+fn find_conflict(repo: &Repository, path: &str) -> Result<IndexConflict, String> {
+    index_conflicts(repo)?
+        .into_iter()
+        .find(|(conflict_path, _)| conflict_path == path)
+        .map(|(_, conflict)| conflict)
+        .ok_or_else(|| format!("'{path}' has no unresolved conflict."))
+}
+
+/// UTF-8 text, or `None` for binary content.
+fn text_content(bytes: &[u8]) -> Option<String> {
+    if bytes.contains(&0) {
+        return None;
+    }
+    String::from_utf8(bytes.to_vec()).ok()
+}
+
+pub(super) fn conflict_detail(repo_root: &Path, path: &str) -> Result<GitConflictDetail, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let conflict = find_conflict(&repo, path)?;
+    let mut is_binary = false;
+    let mut stage_content = |entry: &Option<git2::IndexEntry>| -> Result<Option<String>, String> {
+        let Some(entry) = entry else {
+            return Ok(None);
+        };
+        let blob = repo.find_blob(entry.id).map_err(|e| e.to_string())?;
+        let content = text_content(blob.content());
+        is_binary |= content.is_none();
+        Ok(content)
+    };
+    let base = stage_content(&conflict.ancestor)?;
+    let ours = stage_content(&conflict.our)?;
+    let theirs = stage_content(&conflict.their)?;
+    let worktree = match fs::read(repo_root.join(path)) {
+        Ok(bytes) => {
+            let content = text_content(&bytes);
+            is_binary |= content.is_none();
+            content
+        }
+        Err(_) => None,
+    };
+    let state = conflict_state(&conflict);
+    Ok(GitConflictDetail {
+        path: path.to_string(),
+        state,
+        base: base.filter(|_| !is_binary),
+        ours: ours.filter(|_| !is_binary),
+        theirs: theirs.filter(|_| !is_binary),
+        worktree: worktree.filter(|_| !is_binary),
+        is_binary,
+    })
+}
+
+pub(super) async fn get_git_conflict_inner(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    path: String,
+) -> Result<GitConflictDetail, String> {
+    let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
+    let repo_root = resolve_git_root(&entry)?;
+    conflict_detail(&repo_root, &path)
+}
+
+/// Ours and theirs follow `git checkout --ours/--theirs`; taking a side that deleted the
+/// file removes it.
+pub(super) async fn resolve_git_conflict_inner(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    path: String,
+    resolution: GitConflictResolution,
+    content: Option<String>,
+) -> Result<(), String> {
+    let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
+    let repo_root = resolve_git_root(&entry)?;
+    let conflict = {
+        let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+        find_conflict(&repo, &path)?
+    };
+
+    let (flag, side_exists) = match resolution {
+        GitConflictResolution::Ours => ("--ours", conflict.our.is_some()),
+        GitConflictResolution::Theirs => ("--theirs", conflict.their.is_some()),
+        GitConflictResolution::Merged => {
+            let content = content.ok_or_else(|| "Merged content is required.".to_string())?;
+            fs::write(repo_root.join(&path), content).map_err(|e| e.to_string())?;
+            return run_git_command(&repo_root, &["add", "--", &path]).await;
+        }
+    };
+    if side_exists {
+        run_git_command(&repo_root, &["checkout", flag, "--", &path]).await?;
+        run_git_command(&repo_root, &["add", "--", &path]).await
+    } else {
+        run_git_command(&repo_root, &["rm", "--quiet", "--force", "--", &path]).await
+    }
+}
@@ -0,0 +1,594 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use git2::{ApplyLocation, ApplyOptions, Delta, Diff, DiffFindOptions, DiffOptions, Repository};
+use tokio::sync::Mutex;
+
+use crate::git_utils::resolve_git_root;
+use crate::types::{AppSettings, GitDiffLineSide, GitDiffSelection, WorkspaceEntry};
+use crate::utils::normalize_git_path;
+
+use super::commands::action_paths_for_file;
+use super::context::workspace_entry_for_id;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum SelectionAction {
+    Stage,
+    Unstage,
+    Revert,
+}
+
+impl SelectionAction {
+    fn verb(self) -> &'static str {
+        match self {
+            Self::Stage => "stage",
+            Self::Unstage => "unstage",
+            Self::Revert => "revert",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineKind {
+    Context,
+    Addition,
+    Deletion,
+}
+
+#[derive(Debug, Clone)]
+struct PatchLine {
+    kind: LineKind,
+    old_lineno: Option<u32>,
+    new_lineno: Option<u32>,
+    content: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+struct PatchHunk {
+    old_start: u32,
+    old_lines: u32,
+    new_start: u32,
+    new_lines: u32,
+    lines: Vec<PatchLine>,
+}
+
+/// One file of a git2 diff, copied out so diffs against HEAD, the index and the working
+/// tree can be compared after their `Diff`s are dropped.
+#[derive(Debug, Clone)]
+struct FilePatch {
+    old_path: String,
+    new_path: String,
+    status: Delta,
+    old_mode: u32,
+    new_mode: u32,
+    hunks: Vec<PatchHunk>,
+}
+
+impl FilePatch {
+    fn is_added(&self) -> bool {
+        matches!(self.status, Delta::Added | Delta::Untracked)
+    }
+
+    fn is_deleted(&self) -> bool {
+        self.status == Delta::Deleted
+    }
+
+    /// Maps a line number on one side to the other side, or `None` when the line is
+    /// changed by this patch.
+    fn map_line(&self, line: u32, from: GitDiffLineSide) -> Option<u32> {
+        let mut offset = 0i64;
+        for hunk in &self.hunks {
+            let (start, count, other_count) = match from {
+                GitDiffLineSide::Old => (hunk.old_start, hunk.old_lines, hunk.new_lines),
+                GitDiffLineSide::New => (hunk.new_start, hunk.new_lines, hunk.old_lines),
+            };
+            if (count == 0 && line <= start) || (count > 0 && line < start) {
+                break;
+            }
+            if count > 0 && line < start + count {
+                return hunk.lines.iter().find_map(|patch_line| {
+                    let (this, other) = match from {
+                        GitDiffLineSide::Old => (patch_line.old_lineno, patch_line.new_lineno),
+                        GitDiffLineSide::New => (patch_line.new_lineno, patch_line.old_lineno),
+                    };
+                    (patch_line.kind == LineKind::Context && this == Some(line))
+                        .then_some(other)
+                        .flatten()
+                });
+            }
+            offset += i64::from(other_count) - i64::from(count);
+        }
+        u32::try_from(i64::from(line) + offset).ok()
+    }
+}
+
+/// Changed lines picked from the HEAD-to-worktree diff: removed lines by HEAD line number
+/// and added lines by working tree line number.
+#[derive(Debug, Default)]
+struct SelectedLines {
+    old: Vec<(u32, u32)>,
+    new: Vec<(u32, u32)>,
+}
+
+impl SelectedLines {
+    fn contains(&self, side: GitDiffLineSide, line: u32) -> bool {
+        let ranges = match side {
+            GitDiffLineSide::Old => &self.old,
+            GitDiffLineSide::New => &self.new,
+        };
+        ranges
+            .iter()
+            .any(|(start, end)| *start <= line && line <= *end)
+    }
+}
+
+fn diff_options(paths: &[String], ignore_whitespace_changes: bool) -> DiffOptions {
+    let mut options = DiffOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true)
+        .disable_pathspec_match(true)
+        .ignore_whitespace_change(ignore_whitespace_changes);
+    for path in paths {
+        options.pathspec(path);
+    }
+    options
+}
+
+fn find_renames(diff: &mut Diff) -> Result<(), String> {
+    diff.find_similar(Some(DiffFindOptions::new().renames(true)))
+        .map_err(|e| e.to_string())
+}
+
+fn file_patch(diff: &Diff, path: &str) -> Result<Option<FilePatch>, String> {
+    let matches_path = |file: git2::DiffFile| {
+        file.path()
+            .map(|value| normalize_git_path(&value.to_string_lossy()) == path)
+            .unwrap_or(false)
+    };
+    let index = diff
+        .deltas()
+        .position(|delta| matches_path(delta.new_file()))
+        .or_else(|| {
+            diff.deltas()
+                .position(|delta| matches_path(delta.old_file()))
+        });
+    let Some(index) = index else {
+        return Ok(None);
+    };
+    let delta = diff.get_delta(index).ok_or("Missing diff entry.")?;
+    if delta.flags().is_binary() {
+        return Err(format!(
+            "{path} is binary and can only be changed as a whole file."
+        ));
+    }
+    let file_path = |file: git2::DiffFile| {
+        file.path()
+            .map(|value| normalize_git_path(&value.to_string_lossy()))
+            .unwrap_or_default()
+    };
+    let old_path = file_path(delta.old_file());
+    let new_path = file_path(delta.new_file());
+    let old_mode = u32::from(delta.old_file().mode());
+    let new_mode = u32::from(delta.new_file().mode());
+    let status = delta.status();
+
+    let Some(patch) = git2::Patch::from_diff(diff, index).map_err(|e| e.to_string())? else {
+        return Err(format!(
+            "{path} is binary and can only be changed as a whole file."
+        ));
+    };
+    let mut hunks = Vec::with_capacity(patch.num_hunks());
+    for hunk_index in 0..patch.num_hunks() {
+        let (hunk, line_count) = patch.hunk(hunk_index).map_err(|e| e.to_string())?;
+        let mut lines = Vec::with_capacity(line_count);
+        for line_index in 0..line_count {
+            let line = patch
+                .line_in_hunk(hunk_index, line_index)
+                .map_err(|e| e.to_string())?;
+            // The "\ No newline at end of file" markers are implied by the content of the
+            // line before them.
+            let kind = match line.origin() {
+                ' ' => LineKind::Context,
+                '+' => LineKind::Addition,
+                '-' => LineKind::Deletion,
+                _ => continue,
+            };
+            lines.push(PatchLine {
+                kind,
+                old_lineno: line.old_lineno(),
+                new_lineno: line.new_lineno(),
+                content: line.content().to_vec(),
+            });
+        }
+        hunks.push(PatchHunk {
+            old_start: hunk.old_start(),
+            old_lines: hunk.old_lines(),
+            new_start: hunk.new_start(),
+            new_lines: hunk.new_lines(),
+            lines,
+        });
+    }
+
+    Ok(Some(FilePatch {
+        old_path,
+        new_path,
+        status,
+        old_mode,
+        new_mode,
+        hunks,
+    }))
+}
+
+fn head_to_workdir_patch(
+    repo: &Repository,
+    paths: &[String],
+    path: &str,
+    ignore_whitespace_changes: bool,
+) -> Result<Option<FilePatch>, String> {
+    let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+    let mut options = diff_options(paths, ignore_whitespace_changes);
+    let mut diff = repo
+        .diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut options))
+        .map_err(|e| e.to_string())?;
+    find_renames(&mut diff)?;
+    file_patch(&diff, path)
+}
+
+fn head_to_index_patch(
+    repo: &Repository,
+    paths: &[String],
+    path: &str,
+) -> Result<Option<FilePatch>, String> {
+    let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+    let index = repo.index().map_err(|e| e.to_string())?;
+    let mut options = diff_options(paths, false);
+    let mut diff = repo
+        .diff_tree_to_index(head_tree.as_ref(), Some(&index), Some(&mut options))
+        .map_err(|e| e.to_string())?;
+    find_renames(&mut diff)?;
+    file_patch(&diff, path)
+}
+
+fn index_to_workdir_patch(
+    repo: &Repository,
+    paths: &[String],
+    path: &str,
+) -> Result<Option<FilePatch>, String> {
+    let index = repo.index().map_err(|e| e.to_string())?;
+    let mut options = diff_options(paths, false);
+    let diff = repo
+        .diff_index_to_workdir(Some(&index), Some(&mut options))
+        .map_err(|e| e.to_string())?;
+    file_patch(&diff, path)
+}
+
+/// Resolves hunk indices against the diff `get_git_diffs` returned, which honours the
+/// whitespace setting, into line numbers that hold for the exact diffs.
+fn selected_lines(
+    display: &FilePatch,
+    selection: &GitDiffSelection,
+    path: &str,
+) -> Result<SelectedLines, String> {
+    let mut selected = SelectedLines::default();
+    for &hunk_index in &selection.hunks {
+        let hunk = display.hunks.get(hunk_index).ok_or_else(|| {
+            format!(
+                "Hunk {hunk_index} is out of range for {path}; it has {} hunks.",
+                display.hunks.len()
+            )
+        })?;
+        for line in &hunk.lines {
+            match (line.kind, line.old_lineno, line.new_lineno) {
+                (LineKind::Deletion, Some(old), _) => selected.old.push((old, old)),
+                (LineKind::Addition, _, Some(new)) => selected.new.push((new, new)),
+                _ => {}
+            }
+        }
+    }
+    for range in &selection.lines {
+        if range.start == 0 || range.end < range.start {
+            return Err(format!(
+                "Invalid line range {}-{}; line numbers start at 1.",
+                range.start, range.end
+            ));
+        }
+        match range.side {
+            GitDiffLineSide::Old => selected.old.push((range.start, range.end)),
+            GitDiffLineSide::New => selected.new.push((range.start, range.end)),
+        }
+    }
+    if selected.old.is_empty() && selected.new.is_empty() {
+        return Err("Select at least one hunk or line.".to_string());
+    }
+    Ok(selected)
+}
+
+fn push_line(buffer: &mut Vec<u8>, origin: u8, content: &[u8]) {
+    buffer.push(origin);
+    buffer.extend_from_slice(content);
+    if !content.ends_with(b"\n") {
+        buffer.extend_from_slice(b"\n\\ No newline at end of file\n");
+    }
+}
+
+/// Writes a patch holding only the chosen changes of `patch`. Applied forward, its preimage
+/// is the old side of `patch`; with `reverse` it undoes the chosen changes and its preimage
+/// is the new side. Changes that are not chosen stay as they are in the preimage. Returns
+/// `None` when nothing was chosen.
+fn build_partial_patch(
+    patch: &FilePatch,
+    reverse: bool,
+    is_selected: impl Fn(&PatchLine) -> bool,
+) -> Option<Vec<u8>> {
+    let mut body = Vec::new();
+    let mut offset = 0i64;
+    let mut post_total = 0u32;
+    for hunk in &patch.hunks {
+        let (pre_start, pre_lines) = if reverse {
+            (hunk.new_start, hunk.new_lines)
+        } else {
+            (hunk.old_start, hunk.old_lines)
+        };
+        let mut lines: Vec<(u8, Vec<u8>)> = Vec::new();
+        let mut has_change = false;
+        for line in &hunk.lines {
+            let kind = match (line.kind, reverse) {
+                (LineKind::Addition, true) => LineKind::Deletion,
+                (LineKind::Deletion, true) => LineKind::Addition,
+                (kind, _) => kind,
+            };
+            let selected = line.kind != LineKind::Context && is_selected(line);
+            match kind {
+                LineKind::Context => lines.push((b' ', line.content.clone())),
+                LineKind::Deletion if selected => {
+                    has_change = true;
+                    lines.push((b'-', line.content.clone()));
+                }
+                LineKind::Deletion => lines.push((b' ', line.content.clone())),
+                LineKind::Addition if selected => {
+                    has_change = true;
+                    lines.push((b'+', line.content.clone()));
+                }
+                LineKind::Addition => {}
+            }
+        }
+        if !has_change {
+            continue;
+        }
+
+        // A kept last line without a newline needs one once lines are added after it.
+        if let Some(position) = lines
+            .iter()
+            .position(|(origin, content)| *origin == b' ' && !content.ends_with(b"\n"))
+        {
+            if lines[position + 1..]
+                .iter()
+                .any(|(origin, _)| *origin == b'+')
+            {
+                let eol: &[u8] = if lines.iter().any(|(_, content)| content.ends_with(b"\r\n")) {
+                    b"\r\n"
+                } else {
+                    b"\n"
+                };
+                let content = lines[position].1.clone();
+                let mut with_eol = content.clone();
+                with_eol.extend_from_slice(eol);
+                lines.splice(position..=position, [(b'-', content), (b'+', with_eol)]);
+            }
+        }
+
+        let old_count = lines.iter().filter(|(origin, _)| *origin != b'+').count() as u32;
+        let new_count = lines.iter().filter(|(origin, _)| *origin != b'-').count() as u32;
+        debug_assert_eq!(old_count, pre_lines);
+        let new_start = if old_count == 0 {
+            i64::from(pre_start) + offset + 1
+        } else {
+            i64::from(pre_start) + offset
+        };
+        body.extend_from_slice(
+            format!("@@ -{pre_start},{old_count} +{new_start},{new_count} @@\n").as_bytes(),
+        );
+        for (origin, content) in &lines {
+            push_line(&mut body, *origin, content);
+        }
+        offset += i64::from(new_count) - i64::from(old_count);
+        post_total += new_count;
+    }
+    if body.is_empty() {
+        return None;
+    }
+
+    let (pre_exists, pre_path, pre_mode, post_exists, post_path, post_mode) = if reverse {
+        (
+            !patch.is_deleted(),
+            &patch.new_path,
+            patch.new_mode,
+            !(patch.is_added() && post_total == 0),
+            &patch.old_path,
+            patch.old_mode,
+        )
+    } else {
+        (
+            !patch.is_added(),
+            &patch.old_path,
+            patch.old_mode,
+            !(patch.is_deleted() && post_total == 0),
+            &patch.new_path,
+            patch.new_mode,
+        )
+    };
+    // The patch edits a single path in place: a staged rename keeps its new name.
+    let path = if pre_exists { pre_path } else { post_path };
+    let mut header = format!("diff --git a/{path} b/{path}\n");
+    if !pre_exists {
+        header.push_str(&format!("new file mode {post_mode:o}\n--- /dev/null\n"));
+    } else if !post_exists {
+        header.push_str(&format!("deleted file mode {pre_mode:o}\n--- a/{path}\n"));
+    } else {
+        header.push_str(&format!("--- a/{path}\n"));
+    }
+    if post_exists {
+        header.push_str(&format!("+++ b/{path}\n"));
+    } else {
+        header.push_str("+++ /dev/null\n");
+    }
+    let mut buffer = header.into_bytes();
+    buffer.extend_from_slice(&body);
+    Some(buffer)
+}
+
+fn apply_patch(
+    repo: &Repository,
+    patch: &[u8],
+    location: ApplyLocation,
+    check: bool,
+) -> Result<(), String> {
+    let diff = Diff::from_buffer(patch).map_err(|e| e.to_string())?;
+    let mut options = ApplyOptions::new();
+    options.check(check);
+    repo.apply(&diff, location, Some(&mut options))
+        .map_err(|e| {
+            format!(
+                "The selected changes no longer match the file: {}",
+                e.message()
+            )
+        })
+}
+
+/// Patch that removes the chosen changes from the index, or `None` when none are staged.
+fn unstage_patch(
+    repo: &Repository,
+    paths: &[String],
+    path: &str,
+    selected: &SelectedLines,
+) -> Result<Option<Vec<u8>>, String> {
+    let Some(staged) = head_to_index_patch(repo, paths, path)? else {
+        return Ok(None);
+    };
+    let unstaged = index_to_workdir_patch(repo, paths, &staged.new_path)?;
+    Ok(build_partial_patch(&staged, true, |line| match line.kind {
+        LineKind::Deletion => line
+            .old_lineno
+            .is_some_and(|old| selected.contains(GitDiffLineSide::Old, old)),
+        _ => line
+            .new_lineno
+            .and_then(|index_line| match &unstaged {
+                Some(unstaged) => unstaged.map_line(index_line, GitDiffLineSide::Old),
+                None => Some(index_line),
+            })
+            .is_some_and(|new| selected.contains(GitDiffLineSide::New, new)),
+    }))
+}
+
+/// Stages, unstages or reverts part of one file. `selection` refers to the file's entry in
+/// `get_git_diffs`, a diff from HEAD to the working tree: hunk indices and line numbers are
+/// resolved against it, then applied through git2 to the index (stage and unstage) or to
+/// both the index and the working tree (revert).
+pub(super) fn apply_git_selection(
+    repo_root: &Path,
+    path: &str,
+    selection: &GitDiffSelection,
+    action: SelectionAction,
+    ignore_whitespace_changes: bool,
+) -> Result<(), String> {
+    let path = normalize_git_path(path).trim().to_string();
+    if path.is_empty() {
+        return Err("File path is required.".to_string());
+    }
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let paths = action_paths_for_file(repo_root, &path);
+    let display = head_to_workdir_patch(&repo, &paths, &path, ignore_whitespace_changes)?
+        .ok_or_else(|| format!("No changes found for {path}."))?;
+    let selected = selected_lines(&display, selection, &path)?;
+    let nothing_selected = || {
+        format!(
+            "The selected lines have no changes to {} in {path}.",
+            action.verb()
+        )
+    };
+
+    match action {
+        SelectionAction::Stage => {
+            let index_path = if display.is_deleted() {
+                &display.old_path
+            } else {
+                &display.new_path
+            };
+            let unstaged =
+                index_to_workdir_patch(&repo, &paths, index_path)?.ok_or_else(nothing_selected)?;
+            let staged = head_to_index_patch(&repo, &paths, index_path)?;
+            let patch = build_partial_patch(&unstaged, false, |line| match line.kind {
+                LineKind::Addition => line
+                    .new_lineno
+                    .is_some_and(|new| selected.contains(GitDiffLineSide::New, new)),
+                _ => line
+                    .old_lineno
+                    .and_then(|index_line| match &staged {
+                        Some(staged) => staged.map_line(index_line, GitDiffLineSide::New),
+                        None => Some(index_line),
+                    })
+                    .is_some_and(|old| selected.contains(GitDiffLineSide::Old, old)),
+            })
+            .ok_or_else(nothing_selected)?;
+            apply_patch(&repo, &patch, ApplyLocation::Index, false)
+        }
+        SelectionAction::Unstage => {
+            let patch =
+                unstage_patch(&repo, &paths, &path, &selected)?.ok_or_else(nothing_selected)?;
+            apply_patch(&repo, &patch, ApplyLocation::Index, false)
+        }
+        SelectionAction::Revert => {
+            let exact = if ignore_whitespace_changes {
+                head_to_workdir_patch(&repo, &paths, &path, false)?.ok_or_else(nothing_selected)?
+            } else {
+                display
+            };
+            let workdir_patch = build_partial_patch(&exact, true, |line| match line.kind {
+                LineKind::Deletion => line
+                    .old_lineno
+                    .is_some_and(|old| selected.contains(GitDiffLineSide::Old, old)),
+                _ => line
+                    .new_lineno
+                    .is_some_and(|new| selected.contains(GitDiffLineSide::New, new)),
+            })
+            .ok_or_else(nothing_selected)?;
+            let index_patch = unstage_patch(&repo, &paths, &path, &selected)?;
+            // Check both patches before touching either, so a stale selection changes nothing.
+            apply_patch(&repo, &workdir_patch, ApplyLocation::WorkDir, true)?;
+            if let Some(index_patch) = &index_patch {
+                apply_patch(&repo, index_patch, ApplyLocation::Index, true)?;
+                apply_patch(&repo, index_patch, ApplyLocation::Index, false)?;
+            }
+            apply_patch(&repo, &workdir_patch, ApplyLocation::WorkDir, false)
+        }
+    }
+}
+
+pub(super) async fn apply_git_selection_inner(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: String,
+    path: String,
+    selection: GitDiffSelection,
+    action: SelectionAction,
+) -> Result<(), String> {
+    let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
+    let repo_root = resolve_git_root(&entry)?;
+    let ignore_whitespace_changes = {
+        let settings = app_settings.lock().await;
+        settings.git_diff_ignore_whitespace_changes
+    };
+    tokio::task::spawn_blocking(move || {
+        apply_git_selection(
+            &repo_root,
+            &path,
+            &selection,
+            action,
+            ignore_whitespace_changes,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
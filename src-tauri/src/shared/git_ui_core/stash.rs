@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use git2::Repository;
+use tokio::sync::Mutex;
+
+use crate::git_utils::resolve_git_root;
+use crate::shared::rpc_error::{RpcError, RpcErrorCode};
+use crate::types::{AppSettings, GitFileDiff, GitStashEntry, WorkspaceEntry};
+
+use super::commands::run_git_command;
+use super::context::workspace_entry_for_id;
+use super::diff::diff_trees;
+
+/// Branch from git's stash messages, `WIP on <branch>: ...` or `On <branch>: ...`.
+pub(super) fn stash_branch(message: &str) -> Option<String> {
+    let rest = message
+        .strip_prefix("WIP on ")
+        .or_else(|| message.strip_prefix("On "))?;
+    let (branch, _) = rest.split_once(": ")?;
+    (branch != "(no branch)").then(|| branch.to_string())
+}
+
+pub(super) fn list_stashes(repo_root: &Path) -> Result<Vec<GitStashEntry>, String> {
+    let mut repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let mut stashes = Vec::new();
+    repo.stash_foreach(|index, message, oid| {
+        stashes.push((index, message.to_string(), *oid));
+        true
+    })
+    .map_err(|e| e.to_string())?;
+
+    stashes
+        .into_iter()
+        .map(|(index, message, oid)| {
+            let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+            Ok(GitStashEntry {
+                index,
+                sha: oid.to_string(),
+                branch: stash_branch(&message),
+                message,
+                timestamp: commit.time().seconds(),
+            })
+        })
+        .collect()
+}
+
+/// The stash at `index`, which the caller listed as `sha`. Indexes shift whenever a stash is
+/// pushed or dropped, so a stash that moved is rejected as a conflict rather than acting on
+/// (or showing) another.
+fn listed_stash(repo_root: &Path, index: usize, sha: &str) -> Result<GitStashEntry, RpcError> {
+    let stashes = list_stashes(repo_root).map_err(git_failure)?;
+    let Some(stash) = stashes.iter().find(|stash| stash.index == index) else {
+        let message = if stashes.is_empty() {
+            "There are no stashes.".to_string()
+        } else {
+            format!(
+                "Stash {index} does not exist; there are {} stashes.",
+                stashes.len()
+            )
+        };
+        return Err(RpcError::new(RpcErrorCode::Conflict, message));
+    };
+    if stash.sha != sha.trim() {
+        return Err(RpcError::new(
+            RpcErrorCode::Conflict,
+            format!(
+                "Stash {index} changed since it was listed; refresh the stash list and try again."
+            ),
+        ));
+    }
+    Ok(stash.clone())
+}
+
+fn git_failure(message: String) -> RpcError {
+    RpcError::new(RpcErrorCode::GitFailure, message)
+}
+
+async fn stash_repo_root(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: &str,
+) -> Result<PathBuf, RpcError> {
+    let entry = workspace_entry_for_id(workspaces, workspace_id)
+        .await
+        .map_err(|message| RpcError::new(RpcErrorCode::WorkspaceNotFound, message))?;
+    resolve_git_root(&entry).map_err(git_failure)
+}
+
+/// Repo root and verified `stash@{index}` for the stash actions below.
+async fn stash_target(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: &str,
+    index: usize,
+    sha: &str,
+) -> Result<(PathBuf, String), RpcError> {
+    let repo_root = stash_repo_root(workspaces, workspace_id).await?;
+    listed_stash(&repo_root, index, sha)?;
+    Ok((repo_root, format!("stash@{{{index}}}")))
+}
+
+/// Tracked changes come from the stash commit's tree; files stashed with
+/// `--include-untracked` live in its third parent and show up as added.
+pub(super) fn stash_diff(
+    repo_root: &Path,
+    index: usize,
+    sha: &str,
+    ignore_whitespace_changes: bool,
+) -> Result<Vec<GitFileDiff>, RpcError> {
+    let stash = listed_stash(repo_root, index, sha)?;
+    stash_tree_diffs(repo_root, &stash.sha, ignore_whitespace_changes).map_err(git_failure)
+}
+
+fn stash_tree_diffs(
+    repo_root: &Path,
+    sha: &str,
+    ignore_whitespace_changes: bool,
+) -> Result<Vec<GitFileDiff>, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let oid = git2::Oid::from_str(sha).map_err(|e| e.to_string())?;
+    let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+    let stash_tree = commit.tree().map_err(|e| e.to_string())?;
+    let base_tree = commit.parent(0).ok().and_then(|parent| parent.tree().ok());
+
+    let mut diffs = diff_trees(
+        &repo,
+        base_tree.as_ref(),
+        &stash_tree,
+        ignore_whitespace_changes,
+    )?;
+    if let Some(untracked_tree) = commit.parent(2).ok().and_then(|parent| parent.tree().ok()) {
+        diffs.extend(diff_trees(
+            &repo,
+            None,
+            &untracked_tree,
+            ignore_whitespace_changes,
+        )?);
+    }
+
+    Ok(diffs
+        .into_iter()
+        .map(|diff| GitFileDiff {
+            path: diff.path,
+            diff: diff.diff,
+            old_lines: diff.old_lines,
+            new_lines: diff.new_lines,
+            is_binary: diff.is_binary,
+            is_image: diff.is_image,
+            old_image_data: diff.old_image_data,
+            new_image_data: diff.new_image_data,
+            old_image_mime: diff.old_image_mime,
+            new_image_mime: diff.new_image_mime,
+        })
+        .collect())
+}
+
+pub(super) async fn list_git_stashes_inner(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+) -> Result<Vec<GitStashEntry>, String> {
+    let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
+    let repo_root = resolve_git_root(&entry)?;
+    list_stashes(&repo_root)
+}
+
+pub(super) async fn get_git_stash_diff_inner(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: String,
+    index: usize,
+    sha: String,
+) -> Result<Vec<GitFileDiff>, RpcError> {
+    let repo_root = stash_repo_root(workspaces, &workspace_id).await?;
+    let ignore_whitespace_changes = {
+        let settings = app_settings.lock().await;
+        settings.git_diff_ignore_whitespace_changes
+    };
+    tokio::task::spawn_blocking(move || {
+        stash_diff(&repo_root, index, &sha, ignore_whitespace_changes)
+    })
+    .await
+    .map_err(|e| RpcError::new(RpcErrorCode::Internal, e.to_string()))?
+}
+
+pub(super) async fn create_git_stash_inner(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    message: Option<String>,
+    include_untracked: bool,
+) -> Result<GitStashEntry, String> {
+    let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
+    let repo_root = resolve_git_root(&entry)?;
+    let previous_top = list_stashes(&repo_root)?
+        .into_iter()
+        .next()
+        .map(|stash| stash.sha);
+
+    let mut args = vec!["stash", "push"];
+    if include_untracked {
+        args.push("--include-untracked");
+    }
+    let message = message
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    if let Some(message) = message.as_deref() {
+        args.extend(["-m", message]);
+    }
+    run_git_command(&repo_root, &args).await?;
+
+    // `git stash push` succeeds without stashing anything when the tree is clean.
+    list_stashes(&repo_root)?
+        .into_iter()
+        .next()
+        .filter(|stash| Some(&stash.sha) != previous_top.as_ref())
+        .ok_or_else(|| "No local changes to stash.".to_string())
+}
+
+pub(super) async fn apply_git_stash_inner(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    index: usize,
+    sha: String,
+) -> Result<(), RpcError> {
+    let (repo_root, stash) = stash_target(workspaces, &workspace_id, index, &sha).await?;
+    run_git_command(&repo_root, &["stash", "apply", &stash])
+        .await
+        .map_err(git_failure)
+}
+
+/// Applies and drops the stash. On conflicts git keeps the stash, so nothing is lost.
+pub(super) async fn pop_git_stash_inner(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    index: usize,
+    sha: String,
+) -> Result<(), RpcError> {
+    let (repo_root, stash) = stash_target(workspaces, &workspace_id, index, &sha).await?;
+    run_git_command(&repo_root, &["stash", "pop", &stash])
+        .await
+        .map_err(git_failure)
+}
+
+pub(super) async fn drop_git_stash_inner(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    index: usize,
+    sha: String,
+) -> Result<(), RpcError> {
+    let (repo_root, stash) = stash_target(workspaces, &workspace_id, index, &sha).await?;
+    run_git_command(&repo_root, &["stash", "drop", &stash])
+        .await
+        .map_err(git_failure)
+}
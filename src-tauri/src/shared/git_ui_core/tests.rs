@@ -7,10 +7,19 @@ use serde_json::Value;
 use tokio::runtime::Runtime;
 use tokio::sync::Mutex;
 
-use crate::types::{AppSettings, WorkspaceEntry, WorkspaceKind, WorkspaceSettings};
+use crate::shared::rpc_error::{RpcError, RpcErrorCode};
+use crate::types::{
+    AppSettings, GitConflictResolution, GitConflictState, GitDiffLineRange, GitDiffLineSide,
+    GitDiffSelection, GitLogFilter, GitLogRange, GitLogResponse, GitOperationKind, WorkspaceEntry,
+    WorkspaceKind, WorkspaceSettings,
+};
 
 use super::commands;
 use super::diff;
+use super::log;
+use super::merge;
+use super::selection::{self, SelectionAction};
+use super::stash;
 
 fn create_temp_repo() -> (PathBuf, Repository) {
     let root = std::env::temp_dir().join(format!("codex-monitor-test-{}", uuid::Uuid::new_v4()));
@@ -435,3 +444,825 @@ fn collect_ignored_paths_with_git_handles_large_ignored_output() {
 
     assert_eq!(ignored_paths.len(), total);
 }
+
+fn commit_files(root: &Path, repo: &Repository, files: &[(&str, &str)]) {
+    let mut index = repo.index().expect("repo index");
+    for (path, content) in files {
+        fs::write(root.join(path), content).expect("write file");
+        index.add_path(Path::new(path)).expect("add path");
+    }
+    index.write().expect("write index");
+    let tree_id = index.write_tree().expect("write tree");
+    let tree = repo.find_tree(tree_id).expect("find tree");
+    let sig = git2::Signature::now("Test", "test@example.com").expect("signature");
+    repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+        .expect("commit");
+}
+
+fn index_content(repo: &Repository, path: &str) -> String {
+    let mut index = repo.index().expect("repo index");
+    index.read(true).expect("reload index");
+    let entry = index.get_path(Path::new(path), 0).expect("index entry");
+    let blob = repo.find_blob(entry.id).expect("index blob");
+    String::from_utf8(blob.content().to_vec()).expect("utf8 blob")
+}
+
+fn numbered_lines(count: usize, edit: impl Fn(usize) -> Option<String>) -> String {
+    (1..=count)
+        .map(|line| edit(line).unwrap_or_else(|| format!("line {line}\n")))
+        .collect()
+}
+
+fn hunks(indices: &[usize]) -> GitDiffSelection {
+    GitDiffSelection {
+        hunks: indices.to_vec(),
+        lines: Vec::new(),
+    }
+}
+
+fn lines(side: GitDiffLineSide, start: u32, end: u32) -> GitDiffSelection {
+    GitDiffSelection {
+        hunks: Vec::new(),
+        lines: vec![GitDiffLineRange { side, start, end }],
+    }
+}
+
+fn apply(root: &Path, path: &str, selection: GitDiffSelection, action: SelectionAction) {
+    selection::apply_git_selection(root, path, &selection, action, false).expect("apply selection");
+}
+
+#[test]
+fn stage_and_unstage_selected_hunks_and_lines() {
+    let (root, repo) = create_temp_repo();
+    let original = numbered_lines(20, |_| None);
+    commit_files(&root, &repo, &[("notes.txt", &original)]);
+    let edited = numbered_lines(20, |line| match line {
+        2 => Some("line 2 changed\nline 2 extra\n".to_string()),
+        18 => Some("line 18 changed\n".to_string()),
+        _ => None,
+    });
+    fs::write(root.join("notes.txt"), &edited).expect("edit file");
+
+    // Staging the second hunk first shifts nothing; staging the first after it must still
+    // find its lines in the index.
+    apply(&root, "notes.txt", hunks(&[1]), SelectionAction::Stage);
+    assert_eq!(
+        index_content(&repo, "notes.txt"),
+        original.replace("line 18\n", "line 18 changed\n")
+    );
+    apply(
+        &root,
+        "notes.txt",
+        lines(GitDiffLineSide::New, 3, 3),
+        SelectionAction::Stage,
+    );
+    assert_eq!(
+        index_content(&repo, "notes.txt"),
+        original
+            .replace("line 2\n", "line 2\nline 2 extra\n")
+            .replace("line 18\n", "line 18 changed\n")
+    );
+
+    apply(&root, "notes.txt", hunks(&[1]), SelectionAction::Unstage);
+    assert_eq!(
+        index_content(&repo, "notes.txt"),
+        original.replace("line 2\n", "line 2\nline 2 extra\n")
+    );
+    assert_eq!(
+        fs::read_to_string(root.join("notes.txt")).expect("read file"),
+        edited
+    );
+
+    let err = selection::apply_git_selection(
+        &root,
+        "notes.txt",
+        &hunks(&[5]),
+        SelectionAction::Stage,
+        false,
+    )
+    .expect_err("out of range hunk");
+    assert!(err.contains("out of range"), "{err}");
+}
+
+#[test]
+fn revert_selected_lines_restores_index_and_worktree() {
+    let (root, repo) = create_temp_repo();
+    let original = numbered_lines(20, |_| None);
+    commit_files(&root, &repo, &[("notes.txt", &original)]);
+    let edited = numbered_lines(20, |line| match line {
+        2 => Some("line 2 changed\n".to_string()),
+        18 => Some("line 18 changed\n".to_string()),
+        _ => None,
+    });
+    fs::write(root.join("notes.txt"), &edited).expect("edit file");
+    apply(&root, "notes.txt", hunks(&[0, 1]), SelectionAction::Stage);
+
+    // Reverting only the removal of line 2 brings it back next to its replacement.
+    apply(
+        &root,
+        "notes.txt",
+        lines(GitDiffLineSide::Old, 2, 2),
+        SelectionAction::Revert,
+    );
+    let expected = original
+        .replace("line 2\n", "line 2\nline 2 changed\n")
+        .replace("line 18\n", "line 18 changed\n");
+    assert_eq!(index_content(&repo, "notes.txt"), expected);
+    assert_eq!(
+        fs::read_to_string(root.join("notes.txt")).expect("read file"),
+        expected
+    );
+
+    apply(
+        &root,
+        "notes.txt",
+        lines(GitDiffLineSide::New, 3, 3),
+        SelectionAction::Revert,
+    );
+    let expected = original.replace("line 18\n", "line 18 changed\n");
+    assert_eq!(index_content(&repo, "notes.txt"), expected);
+    assert_eq!(
+        fs::read_to_string(root.join("notes.txt")).expect("read file"),
+        expected
+    );
+}
+
+#[test]
+fn stage_selection_follows_staged_renames() {
+    let (root, repo) = create_temp_repo();
+    let original = numbered_lines(20, |_| None);
+    commit_files(&root, &repo, &[("old.txt", &original)]);
+    fs::rename(root.join("old.txt"), root.join("new.txt")).expect("rename file");
+    let mut index = repo.index().expect("repo index");
+    index
+        .remove_path(Path::new("old.txt"))
+        .expect("remove old path");
+    index.add_path(Path::new("new.txt")).expect("add new path");
+    index.write().expect("write index");
+    let edited = numbered_lines(20, |line| match line {
+        1 => Some(String::new()),
+        19 => Some("line 19 changed\n".to_string()),
+        _ => None,
+    });
+    fs::write(root.join("new.txt"), &edited).expect("edit file");
+
+    let workspace = WorkspaceEntry {
+        id: "w1".to_string(),
+        name: "w1".to_string(),
+        path: root.to_string_lossy().to_string(),
+        kind: WorkspaceKind::Main,
+        parent_id: None,
+        worktree: None,
+        settings: WorkspaceSettings::default(),
+    };
+    let workspaces = Mutex::new(HashMap::from([("w1".to_string(), workspace)]));
+    let app_settings = Mutex::new(AppSettings::default());
+    let runtime = Runtime::new().expect("create tokio runtime");
+    let diffs = runtime
+        .block_on(diff::get_git_diffs_inner(
+            &workspaces,
+            &app_settings,
+            "w1".to_string(),
+        ))
+        .expect("get git diffs");
+    assert_eq!(diffs.len(), 1);
+    assert_eq!(diffs[0].path, "new.txt");
+    assert!(diffs[0].diff.contains("-line 1\n"), "{}", diffs[0].diff);
+
+    apply(
+        &root,
+        "new.txt",
+        lines(GitDiffLineSide::Old, 1, 1),
+        SelectionAction::Stage,
+    );
+    assert_eq!(
+        index_content(&repo, "new.txt"),
+        original.replacen("line 1\n", "", 1)
+    );
+
+    apply(&root, "new.txt", hunks(&[0]), SelectionAction::Unstage);
+    assert_eq!(index_content(&repo, "new.txt"), original);
+    let index = repo.index().expect("repo index");
+    assert!(index.get_path(Path::new("old.txt"), 0).is_none());
+}
+
+#[test]
+fn stage_selection_keeps_crlf_line_endings() {
+    let (root, repo) = create_temp_repo();
+    let original = numbered_lines(20, |_| None).replace('\n', "\r\n");
+    commit_files(&root, &repo, &[("windows.txt", &original)]);
+    let edited = original
+        .replace("line 3\r\n", "line 3 changed\r\n")
+        .replace("line 17\r\n", "line 17 changed\r\n");
+    fs::write(root.join("windows.txt"), &edited).expect("edit file");
+
+    apply(&root, "windows.txt", hunks(&[0]), SelectionAction::Stage);
+
+    assert_eq!(
+        index_content(&repo, "windows.txt"),
+        original.replace("line 3\r\n", "line 3 changed\r\n")
+    );
+}
+
+#[test]
+fn selection_handles_missing_newline_at_end_of_file() {
+    let (root, repo) = create_temp_repo();
+    commit_files(&root, &repo, &[("tail.txt", "a\nb")]);
+    fs::write(root.join("tail.txt"), "a\nb\nc\n").expect("edit file");
+
+    apply(
+        &root,
+        "tail.txt",
+        lines(GitDiffLineSide::New, 3, 3),
+        SelectionAction::Stage,
+    );
+    assert_eq!(index_content(&repo, "tail.txt"), "a\nb\nc\n");
+
+    fs::write(root.join("tail.txt"), "a\nb\nc\nd").expect("edit file");
+    apply(
+        &root,
+        "tail.txt",
+        lines(GitDiffLineSide::New, 4, 4),
+        SelectionAction::Stage,
+    );
+    assert_eq!(index_content(&repo, "tail.txt"), "a\nb\nc\nd");
+
+    apply(
+        &root,
+        "tail.txt",
+        lines(GitDiffLineSide::New, 3, 4),
+        SelectionAction::Revert,
+    );
+    assert_eq!(index_content(&repo, "tail.txt"), "a\nb\n");
+    assert_eq!(
+        fs::read_to_string(root.join("tail.txt")).expect("read file"),
+        "a\nb\n"
+    );
+}
+
+#[test]
+fn stash_branch_parses_git_messages() {
+    assert_eq!(
+        stash::stash_branch("WIP on main: 1a2b3c4 init"),
+        Some("main".to_string())
+    );
+    assert_eq!(
+        stash::stash_branch("On feature/x: before rebase"),
+        Some("feature/x".to_string())
+    );
+    assert_eq!(
+        stash::stash_branch("WIP on (no branch): 1a2b3c4 init"),
+        None
+    );
+    assert_eq!(stash::stash_branch("custom message"), None);
+}
+
+#[test]
+fn create_list_diff_and_drop_stashes() {
+    let (root, repo) = create_temp_repo();
+    let mut config = repo.config().expect("repo config");
+    config.set_str("user.name", "Test").expect("set user.name");
+    config
+        .set_str("user.email", "test@example.com")
+        .expect("set user.email");
+    commit_files(&root, &repo, &[("tracked.txt", "one\n")]);
+
+    let workspace = WorkspaceEntry {
+        id: "w1".to_string(),
+        name: "w1".to_string(),
+        path: root.to_string_lossy().to_string(),
+        kind: WorkspaceKind::Main,
+        parent_id: None,
+        worktree: None,
+        settings: WorkspaceSettings::default(),
+    };
+    let mut entries = HashMap::new();
+    entries.insert("w1".to_string(), workspace);
+    let workspaces = Mutex::new(entries);
+    let app_settings = Mutex::new(AppSettings::default());
+    let runtime = Runtime::new().expect("create tokio runtime");
+
+    let empty = runtime.block_on(stash::create_git_stash_inner(
+        &workspaces,
+        "w1".to_string(),
+        None,
+        true,
+    ));
+    assert_eq!(empty.unwrap_err(), "No local changes to stash.");
+
+    fs::write(root.join("tracked.txt"), "one\ntwo\n").expect("edit tracked file");
+    fs::write(root.join("untracked.txt"), "new\n").expect("write untracked file");
+    let created = runtime
+        .block_on(stash::create_git_stash_inner(
+            &workspaces,
+            "w1".to_string(),
+            Some("  wip edits  ".to_string()),
+            true,
+        ))
+        .expect("create stash");
+    assert_eq!(created.index, 0);
+    let head = repo.head().expect("head");
+    let branch = head.shorthand().expect("branch name");
+    assert_eq!(created.message, format!("On {branch}: wip edits"));
+    assert_eq!(created.branch.as_deref(), Some(branch));
+    assert!(!root.join("untracked.txt").exists());
+    assert_eq!(
+        fs::read_to_string(root.join("tracked.txt")).expect("read tracked"),
+        "one\n"
+    );
+
+    let stashes = runtime
+        .block_on(stash::list_git_stashes_inner(&workspaces, "w1".to_string()))
+        .expect("list stashes");
+    assert_eq!(stashes.len(), 1);
+    assert_eq!(stashes[0].sha, created.sha);
+
+    let diffs = runtime
+        .block_on(stash::get_git_stash_diff_inner(
+            &workspaces,
+            &app_settings,
+            "w1".to_string(),
+            0,
+            created.sha.clone(),
+        ))
+        .expect("stash diff");
+    let mut paths: Vec<&str> = diffs.iter().map(|diff| diff.path.as_str()).collect();
+    paths.sort_unstable();
+    assert_eq!(paths, vec!["tracked.txt", "untracked.txt"]);
+    let tracked = diffs
+        .iter()
+        .find(|diff| diff.path == "tracked.txt")
+        .expect("tracked diff");
+    assert!(tracked.diff.contains("+two"));
+
+    runtime
+        .block_on(stash::apply_git_stash_inner(
+            &workspaces,
+            "w1".to_string(),
+            0,
+            created.sha.clone(),
+        ))
+        .expect("apply stash");
+    assert_eq!(
+        fs::read_to_string(root.join("untracked.txt")).expect("read untracked"),
+        "new\n"
+    );
+
+    // A newer stash shifts the first one to index 1.
+    let newer = runtime
+        .block_on(stash::create_git_stash_inner(
+            &workspaces,
+            "w1".to_string(),
+            Some("newer".to_string()),
+            true,
+        ))
+        .expect("create newer stash");
+    let missing = runtime.block_on(stash::drop_git_stash_inner(
+        &workspaces,
+        "w1".to_string(),
+        2,
+        created.sha.clone(),
+    ));
+    assert_eq!(
+        missing.unwrap_err(),
+        RpcError::new(
+            RpcErrorCode::Conflict,
+            "Stash 2 does not exist; there are 2 stashes."
+        )
+    );
+    let moved = runtime.block_on(stash::drop_git_stash_inner(
+        &workspaces,
+        "w1".to_string(),
+        0,
+        created.sha.clone(),
+    ));
+    assert_eq!(
+        moved.unwrap_err(),
+        RpcError::new(
+            RpcErrorCode::Conflict,
+            "Stash 0 changed since it was listed; refresh the stash list and try again."
+        )
+    );
+    let moved_preview = runtime.block_on(stash::get_git_stash_diff_inner(
+        &workspaces,
+        &app_settings,
+        "w1".to_string(),
+        0,
+        created.sha.clone(),
+    ));
+    assert!(moved_preview.unwrap_err().is(RpcErrorCode::Conflict));
+    runtime
+        .block_on(stash::drop_git_stash_inner(
+            &workspaces,
+            "w1".to_string(),
+            1,
+            created.sha.clone(),
+        ))
+        .expect("drop stash");
+    let stashes = runtime
+        .block_on(stash::list_git_stashes_inner(&workspaces, "w1".to_string()))
+        .expect("list stashes");
+    assert_eq!(stashes.len(), 1);
+    assert_eq!(stashes[0].sha, newer.sha);
+}
+
+fn git(root: &Path, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .args(args)
+        .current_dir(root)
+        .output()
+        .expect("run git");
+    assert!(
+        status.status.success(),
+        "git {args:?} failed: {}",
+        String::from_utf8_lossy(&status.stderr)
+    );
+}
+
+/// Repo whose current branch and `feature` both changed line 2 of `shared.txt`.
+fn create_diverged_repo() -> (PathBuf, Repository, Mutex<HashMap<String, WorkspaceEntry>>) {
+    let (root, repo) = create_temp_repo();
+    git(&root, &["config", "user.name", "Test"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    commit_files(&root, &repo, &[("shared.txt", "one\ntwo\nthree\n")]);
+    git(&root, &["checkout", "-q", "-b", "feature"]);
+    fs::write(root.join("shared.txt"), "one\nfeature\nthree\n").expect("write feature");
+    git(&root, &["commit", "-q", "-am", "feature change"]);
+    git(&root, &["checkout", "-q", "-"]);
+    fs::write(root.join("shared.txt"), "one\nmain\nthree\n").expect("write main");
+    git(&root, &["commit", "-q", "-am", "main change"]);
+
+    let workspace = WorkspaceEntry {
+        id: "w1".to_string(),
+        name: "w1".to_string(),
+        path: root.to_string_lossy().to_string(),
+        kind: WorkspaceKind::Main,
+        parent_id: None,
+        worktree: None,
+        settings: WorkspaceSettings::default(),
+    };
+    let mut entries = HashMap::new();
+    entries.insert("w1".to_string(), workspace);
+    (root, repo, Mutex::new(entries))
+}
+
+#[test]
+fn merge_conflict_reports_versions_and_resolves() {
+    let (root, repo, workspaces) = create_diverged_repo();
+    let runtime = Runtime::new().expect("create tokio runtime");
+
+    let status = runtime
+        .block_on(merge::merge_git_branch_inner(
+            &workspaces,
+            "w1".to_string(),
+            "feature".to_string(),
+        ))
+        .expect("merge reports conflicts");
+    assert_eq!(status.operation, Some(GitOperationKind::Merge));
+    assert_eq!(status.conflicts.len(), 1);
+    assert_eq!(status.conflicts[0].path, "shared.txt");
+    assert_eq!(status.conflicts[0].state, GitConflictState::BothModified);
+
+    let git_status = runtime
+        .block_on(diff::get_git_status_inner(&workspaces, "w1".to_string()))
+        .expect("get git status");
+    assert_eq!(git_status["operation"], "merge");
+    assert_eq!(git_status["conflictedFiles"][0]["state"], "both_modified");
+    assert_eq!(git_status["files"][0]["status"], "U");
+    assert_eq!(git_status["stagedFiles"].as_array().map(Vec::len), Some(0));
+
+    let detail = merge::conflict_detail(&root, "shared.txt").expect("conflict detail");
+    assert_eq!(detail.base.as_deref(), Some("one\ntwo\nthree\n"));
+    assert_eq!(detail.ours.as_deref(), Some("one\nmain\nthree\n"));
+    assert_eq!(detail.theirs.as_deref(), Some("one\nfeature\nthree\n"));
+    assert!(detail
+        .worktree
+        .as_deref()
+        .is_some_and(|content| content.contains("<<<<<<<")));
+    assert!(!detail.is_binary);
+
+    let early = runtime.block_on(merge::continue_git_operation_inner(
+        &workspaces,
+        "w1".to_string(),
+    ));
+    assert_eq!(
+        early.unwrap_err(),
+        "Resolve all conflicts before continuing (1 remaining)."
+    );
+
+    runtime
+        .block_on(merge::resolve_git_conflict_inner(
+            &workspaces,
+            "w1".to_string(),
+            "shared.txt".to_string(),
+            GitConflictResolution::Merged,
+            Some("one\nmain and feature\nthree\n".to_string()),
+        ))
+        .expect("resolve conflict");
+    let status = runtime
+        .block_on(merge::continue_git_operation_inner(
+            &workspaces,
+            "w1".to_string(),
+        ))
+        .expect("continue merge");
+    assert_eq!(status.operation, None);
+    assert!(status.conflicts.is_empty());
+
+    let head = repo.head().expect("head").peel_to_commit().expect("commit");
+    assert_eq!(head.parent_count(), 2);
+    assert_eq!(
+        fs::read_to_string(root.join("shared.txt")).expect("read merged"),
+        "one\nmain and feature\nthree\n"
+    );
+}
+
+#[test]
+fn rebase_conflict_resolves_with_theirs_skips_and_aborts() {
+    let (root, repo, workspaces) = create_diverged_repo();
+    let runtime = Runtime::new().expect("create tokio runtime");
+    let main_branch = repo
+        .head()
+        .expect("head")
+        .shorthand()
+        .expect("branch name")
+        .to_string();
+    git(&root, &["checkout", "-q", "feature"]);
+
+    let status = runtime
+        .block_on(merge::rebase_git_branch_inner(
+            &workspaces,
+            "w1".to_string(),
+            "-".to_string(),
+        ))
+        .unwrap_err();
+    assert_eq!(status, "Branch name is required.");
+
+    let status = runtime
+        .block_on(merge::rebase_git_branch_inner(
+            &workspaces,
+            "w1".to_string(),
+            main_branch.clone(),
+        ))
+        .expect("rebase reports conflicts");
+    assert_eq!(status.operation, Some(GitOperationKind::Rebase));
+    assert_eq!(status.conflicts.len(), 1);
+
+    let aborted = runtime
+        .block_on(merge::abort_git_operation_inner(
+            &workspaces,
+            "w1".to_string(),
+        ))
+        .expect("abort rebase");
+    assert_eq!(aborted.operation, None);
+    assert_eq!(
+        fs::read_to_string(root.join("shared.txt")).expect("read feature"),
+        "one\nfeature\nthree\n"
+    );
+
+    runtime
+        .block_on(merge::rebase_git_branch_inner(
+            &workspaces,
+            "w1".to_string(),
+            main_branch.clone(),
+        ))
+        .expect("rebase reports conflicts");
+    runtime
+        .block_on(merge::resolve_git_conflict_inner(
+            &workspaces,
+            "w1".to_string(),
+            "shared.txt".to_string(),
+            GitConflictResolution::Theirs,
+            None,
+        ))
+        .expect("resolve with theirs");
+    let status = runtime
+        .block_on(merge::continue_git_operation_inner(
+            &workspaces,
+            "w1".to_string(),
+        ))
+        .expect("continue rebase");
+    assert_eq!(status.operation, None);
+    assert_eq!(
+        fs::read_to_string(root.join("shared.txt")).expect("read rebased"),
+        "one\nfeature\nthree\n"
+    );
+
+    git(&root, &["reset", "-q", "--hard", "ORIG_HEAD"]);
+    runtime
+        .block_on(merge::rebase_git_branch_inner(
+            &workspaces,
+            "w1".to_string(),
+            main_branch,
+        ))
+        .expect("rebase reports conflicts");
+    let status = runtime
+        .block_on(merge::skip_git_operation_inner(
+            &workspaces,
+            "w1".to_string(),
+        ))
+        .expect("skip commit");
+    assert_eq!(status.operation, None);
+    assert_eq!(
+        fs::read_to_string(root.join("shared.txt")).expect("read skipped"),
+        "one\nmain\nthree\n"
+    );
+}
+
+fn commit_as(root: &Path, author: &str, time: i64, message: &str) {
+    let date = format!("@{time} +0000");
+    let status = std::process::Command::new("git")
+        .args(["commit", "-q", "--allow-empty", "-m", message])
+        .env("GIT_AUTHOR_NAME", author)
+        .env(
+            "GIT_AUTHOR_EMAIL",
+            format!("{}@example.com", author.to_lowercase()),
+        )
+        .env("GIT_AUTHOR_DATE", &date)
+        .env("GIT_COMMITTER_DATE", &date)
+        .current_dir(root)
+        .output()
+        .expect("run git commit");
+    assert!(
+        status.status.success(),
+        "git commit failed: {}",
+        String::from_utf8_lossy(&status.stderr)
+    );
+}
+
+fn summaries(response: &GitLogResponse) -> Vec<&str> {
+    response
+        .entries
+        .iter()
+        .map(|entry| entry.summary.as_str())
+        .collect()
+}
+
+#[test]
+fn git_log_filters_commits_and_follows_renames() {
+    let (root, repo) = create_temp_repo();
+    git(&root, &["config", "user.name", "Test"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    fs::write(root.join("notes.txt"), "one\ntwo\nthree\n").expect("write notes");
+    git(&root, &["add", "notes.txt"]);
+    commit_as(&root, "Alice", 1_000, "Add notes");
+    fs::write(root.join("other.txt"), "other\n").expect("write other");
+    git(&root, &["add", "other.txt"]);
+    commit_as(&root, "Bob", 2_000, "Unrelated change");
+    git(&root, &["mv", "notes.txt", "docs.txt"]);
+    commit_as(&root, "Alice", 3_000, "Rename notes");
+    fs::write(root.join("docs.txt"), "one\ntwo\nthree\nfour\n").expect("write docs");
+    git(&root, &["add", "docs.txt"]);
+    commit_as(&root, "Bob", 4_000, "Edit docs");
+
+    let all = log::git_log(&repo, None, None, &GitLogFilter::default()).expect("log");
+    assert_eq!(all.total, Some(4));
+    assert!(all.next_cursor.is_none());
+
+    let by_author = GitLogFilter {
+        author: Some("ALICE@example".to_string()),
+        ..Default::default()
+    };
+    let response = log::git_log(&repo, None, None, &by_author).expect("author log");
+    assert_eq!(summaries(&response), ["Rename notes", "Add notes"]);
+    assert_eq!(response.total, None);
+
+    let by_message_and_date = GitLogFilter {
+        message: Some("CHANGE".to_string()),
+        since: Some(2_000),
+        until: Some(3_000),
+        ..Default::default()
+    };
+    let response = log::git_log(&repo, None, None, &by_message_and_date).expect("message log");
+    assert_eq!(summaries(&response), ["Unrelated change"]);
+
+    let by_path = GitLogFilter {
+        path: Some("docs.txt".to_string()),
+        ..Default::default()
+    };
+    let response = log::git_log(&repo, None, None, &by_path).expect("path log");
+    assert_eq!(
+        summaries(&response),
+        ["Edit docs", "Rename notes", "Add notes"]
+    );
+
+    let mut pages = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = log::git_log(&repo, Some(1), cursor.as_deref(), &by_path).expect("page");
+        assert_eq!(page.total, None);
+        pages.extend(page.entries.iter().map(|entry| entry.summary.clone()));
+        cursor = page.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    assert_eq!(pages, ["Edit docs", "Rename notes", "Add notes"]);
+
+    let first = log::git_log(&repo, Some(1), None, &GitLogFilter::default()).expect("first");
+    let cursor = first.next_cursor.expect("next cursor");
+    git(&root, &["commit", "-q", "--amend", "-m", "Edit docs again"]);
+    let error = log::git_log(&repo, Some(1), Some(&cursor), &GitLogFilter::default())
+        .expect_err("stale cursor");
+    assert!(error.contains("history changed"), "{error}");
+    assert!(log::git_log(&repo, None, Some("nope"), &GitLogFilter::default()).is_err());
+
+    fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn git_log_pages_resume_across_merged_history() {
+    let (root, repo) = create_temp_repo();
+    git(&root, &["config", "user.name", "Test"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    commit_as(&root, "Alice", 1_000, "Base");
+    git(&root, &["checkout", "-q", "-b", "side"]);
+    commit_as(&root, "Bob", 2_000, "Side one");
+    commit_as(&root, "Bob", 4_000, "Side two");
+    git(&root, &["checkout", "-q", "-"]);
+    commit_as(&root, "Alice", 3_000, "Main one");
+    commit_as(&root, "Alice", 5_000, "Main two");
+    git(
+        &root,
+        &["merge", "-q", "--no-ff", "-m", "Merge side", "side"],
+    );
+
+    let all = log::git_log(&repo, None, None, &GitLogFilter::default()).expect("log");
+    assert_eq!(all.total, Some(6));
+    for page_size in 1..=4 {
+        let mut pages = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = log::git_log(
+                &repo,
+                Some(page_size),
+                cursor.as_deref(),
+                &GitLogFilter::default(),
+            )
+            .expect("page");
+            assert_eq!(page.total, Some(6));
+            pages.extend(page.entries.iter().map(|entry| entry.summary.clone()));
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(pages, summaries(&all), "page size {page_size}");
+    }
+
+    let by_author = GitLogFilter {
+        author: Some("bob".to_string()),
+        ..Default::default()
+    };
+    let first = log::git_log(&repo, Some(1), None, &by_author).expect("first bob page");
+    assert_eq!(summaries(&first), ["Side two"]);
+    assert_eq!(first.total, None);
+    let cursor = first.next_cursor.expect("next cursor");
+    let second = log::git_log(&repo, Some(1), Some(&cursor), &by_author).expect("second page");
+    assert_eq!(summaries(&second), ["Side one"]);
+    assert_eq!(second.total, None);
+    assert!(second.next_cursor.is_none());
+
+    fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn git_log_walks_upstream_ranges_and_bases() {
+    let (root, repo, _workspaces) = create_diverged_repo();
+    let main_branch = repo
+        .head()
+        .expect("head")
+        .shorthand()
+        .unwrap_or("")
+        .to_string();
+
+    let ahead = GitLogFilter {
+        range: Some(GitLogRange::Ahead),
+        ..Default::default()
+    };
+    let error = log::git_log(&repo, None, None, &ahead).expect_err("no upstream");
+    assert!(error.contains("no upstream"), "{error}");
+
+    git(&root, &["branch", "--set-upstream-to=feature"]);
+    let response = log::git_log(&repo, None, None, &ahead).expect("ahead log");
+    assert_eq!(summaries(&response), ["main change"]);
+    assert_eq!(response.total, Some(1));
+    assert_eq!(response.upstream.as_deref(), Some("feature"));
+    assert_eq!((response.ahead, response.behind), (1, 1));
+
+    let behind = GitLogFilter {
+        range: Some(GitLogRange::Behind),
+        ..Default::default()
+    };
+    let response = log::git_log(&repo, None, None, &behind).expect("behind log");
+    assert_eq!(summaries(&response), ["feature change"]);
+
+    let feature_only = GitLogFilter {
+        branch: Some("feature".to_string()),
+        base: Some(main_branch),
+        ..Default::default()
+    };
+    let response = log::git_log(&repo, None, None, &feature_only).expect("base log");
+    assert_eq!(summaries(&response), ["feature change"]);
+
+    fs::remove_dir_all(&root).ok();
+}
@@ -1,15 +1,30 @@
 use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
 use std::fs::File;
+use std::hash::{Hash, Hasher};
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::Mutex;
+use uuid::Uuid;
 
+use crate::backend::events::{EventSink, LocalUsageUpdated};
 use crate::codex::home::{resolve_default_codex_home, resolve_workspace_codex_home};
+use crate::shared::session_log_tail::{
+    log_resume, modified_ms, read_lines_from, LogCursor, LogResume, MAX_LINE_BYTES,
+};
+use crate::shared::thread_export_core::{find_rollout_path, validate_thread_id};
+use crate::shared::usage_budget_core::UsageBudgetTracker;
+use crate::storage::read_settings;
 use crate::types::{
-    LocalUsageDay, LocalUsageModel, LocalUsageSnapshot, LocalUsageTotals, WorkspaceEntry,
+    LocalUsageDay, LocalUsageModel, LocalUsageSnapshot, LocalUsageThread, LocalUsageThreadTimeline,
+    LocalUsageTotals, LocalUsageTurn, LocalUsageWorkspace, LocalUsageWorkspaceCost, ModelPrice,
+    WorkspaceEntry,
 };
 
 #[derive(Default, Clone, Copy)]
@@ -21,20 +36,131 @@ struct DailyTotals {
     agent_runs: i64,
 }
 
-#[derive(Default, Clone, Copy)]
+#[derive(Default, Clone, Copy, Serialize, Deserialize)]
 struct UsageTotals {
     input: i64,
     cached: i64,
     output: i64,
 }
 
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct UsageKey {
+    day: String,
+    model: String,
+    workspace_id: Option<String>,
+    thread_id: Option<String>,
+}
+
+#[derive(Default)]
+struct UsageThread {
+    cwd: Option<String>,
+    preview: Option<String>,
+    updated_at: Option<i64>,
+}
+
+/// Token totals split by day, model, workspace and thread so each slice can be priced.
+#[derive(Default)]
+struct UsageBreakdown {
+    totals: HashMap<UsageKey, UsageTotals>,
+    threads: HashMap<String, UsageThread>,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub(crate) struct UsageAmount {
+    pub(crate) tokens: i64,
+    pub(crate) cost_usd: f64,
+}
+
+/// Tokens of one day, model and workspace, summed over threads.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LocalUsageRow {
+    pub(crate) day: String,
+    pub(crate) model: String,
+    pub(crate) workspace_id: Option<String>,
+    pub(crate) workspace_name: Option<String>,
+    pub(crate) input_tokens: i64,
+    pub(crate) cached_input_tokens: i64,
+    pub(crate) output_tokens: i64,
+    pub(crate) estimated_cost_usd: f64,
+}
+
+/// Flat usage of a window, the input of the CSV and Prometheus exports.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LocalUsageReport {
+    pub(crate) days: u32,
+    pub(crate) rows: Vec<LocalUsageRow>,
+    pub(crate) agent_runs: i64,
+    pub(crate) agent_time_ms: i64,
+}
+
+#[derive(Clone)]
+struct UsageWorkspace {
+    id: String,
+    name: String,
+    path: PathBuf,
+}
+
 const MAX_ACTIVITY_GAP_MS: i64 = 2 * 60 * 1000;
+const TOP_WORKSPACES_LIMIT: usize = 5;
+const TOP_THREADS_LIMIT: usize = 10;
+const PREVIEW_MAX_CHARS: usize = 120;
+const WATCH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3);
+/// Windows one subscriber (the app, or one daemon connection) may watch at once.
+const MAX_WATCHES_PER_SUBSCRIBER: usize = 8;
+/// Windows the poller scans at once, across all subscribers.
+const MAX_WATCHED_WINDOWS: usize = 32;
+
+/// Subscriber id of the desktop app's own watches; daemon connections use ids from 1 up.
+pub(crate) const APP_WATCH_SUBSCRIBER: u64 = 0;
+
+const CACHE_FILE: &str = "local-usage-cache.sqlite3";
+/// Bump when `FileUsage` or the parsing rules change; the cache is then rebuilt.
+const CACHE_VERSION: i64 = 1;
+const CACHE_SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS files (
+    path TEXT PRIMARY KEY,
+    size INTEGER NOT NULL,
+    modified_ms INTEGER NOT NULL,
+    offset INTEGER NOT NULL,
+    usage TEXT NOT NULL
+);
+";
 
 pub(crate) async fn local_usage_snapshot_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    data_dir: &Path,
     days: Option<u32>,
     workspace_path: Option<String>,
+    model_prices: Vec<ModelPrice>,
 ) -> Result<LocalUsageSnapshot, String> {
+    let (days, workspace_path) = normalize_usage_request(days, workspace_path);
+    let (sessions_roots, usage_workspaces) = {
+        let workspaces = workspaces.lock().await;
+        (
+            resolve_sessions_roots(&workspaces, workspace_path.as_deref()),
+            usage_workspaces(&workspaces),
+        )
+    };
+    let cache_path = cache_path(data_dir);
+    let snapshot = tokio::task::spawn_blocking(move || {
+        scan_local_usage(
+            days,
+            workspace_path.as_deref(),
+            &sessions_roots,
+            &usage_workspaces,
+            &model_prices,
+            Some(&cache_path),
+        )
+    })
+    .await
+    .map_err(|err| err.to_string())??;
+    Ok(snapshot)
+}
+
+fn normalize_usage_request(
+    days: Option<u32>,
+    workspace_path: Option<String>,
+) -> (u32, Option<PathBuf>) {
     let days = days.unwrap_or(30).clamp(1, 90);
     let workspace_path = workspace_path.and_then(|value| {
         let trimmed = value.trim();
@@ -44,22 +170,322 @@ pub(crate) async fn local_usage_snapshot_core(
             Some(PathBuf::from(trimmed))
         }
     });
-    let sessions_roots = {
+    (days, workspace_path)
+}
+
+pub(crate) async fn local_usage_report_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    data_dir: &Path,
+    days: Option<u32>,
+    workspace_path: Option<String>,
+    model_prices: Vec<ModelPrice>,
+) -> Result<LocalUsageReport, String> {
+    let (days, workspace_path) = normalize_usage_request(days, workspace_path);
+    let (sessions_roots, usage_workspaces) = {
         let workspaces = workspaces.lock().await;
-        resolve_sessions_roots(&workspaces, workspace_path.as_deref())
+        (
+            resolve_sessions_roots(&workspaces, workspace_path.as_deref()),
+            usage_workspaces(&workspaces),
+        )
     };
-    let snapshot = tokio::task::spawn_blocking(move || {
-        scan_local_usage(days, workspace_path.as_deref(), &sessions_roots)
+    let cache_path = cache_path(data_dir);
+    tokio::task::spawn_blocking(move || {
+        scan_local_usage_report(
+            days,
+            workspace_path.as_deref(),
+            &sessions_roots,
+            &usage_workspaces,
+            &model_prices,
+            Some(&cache_path),
+        )
     })
     .await
-    .map_err(|err| err.to_string())??;
-    Ok(snapshot)
+    .map_err(|err| err.to_string())?
+}
+
+/// Subscribes `subscriber` to a window: it emits `local-usage-updated` right away, then
+/// whenever a session log in the window changes. Repeating a subscription is a no-op.
+pub(crate) async fn local_usage_watch_core(
+    watchers: &LocalUsageWatchers,
+    subscriber: u64,
+    workspaces: &Arc<Mutex<HashMap<String, WorkspaceEntry>>>,
+    data_dir: &Path,
+    days: Option<u32>,
+    workspace_path: Option<String>,
+    usage_budgets: Arc<UsageBudgetTracker>,
+    event_sink: impl EventSink,
+) -> Result<(), String> {
+    let (days, workspace_path) = normalize_usage_request(days, workspace_path);
+    let key = WatchKey {
+        days,
+        workspace_path,
+    };
+    if !watchers.subscribe(&key, subscriber)? {
+        return Ok(());
+    }
+    let poller = UsagePoller {
+        watchers: Arc::clone(&watchers.shared),
+        workspaces: Arc::clone(workspaces),
+        data_dir: data_dir.to_path_buf(),
+        usage_budgets,
+        event_sink,
+    };
+    tokio::task::spawn_blocking(move || {
+        poller.poll(std::slice::from_ref(&key));
+        poller.start_if_idle();
+    })
+    .await
+    .map_err(|err| err.to_string())
+}
+
+/// Drops one subscription; the window stops being scanned once nobody watches it.
+pub(crate) fn local_usage_unwatch_core(
+    watchers: &LocalUsageWatchers,
+    subscriber: u64,
+    days: Option<u32>,
+    workspace_path: Option<String>,
+) -> Result<(), String> {
+    let (days, workspace_path) = normalize_usage_request(days, workspace_path);
+    watchers.unsubscribe(
+        &WatchKey {
+            days,
+            workspace_path,
+        },
+        subscriber,
+    );
+    Ok(())
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct WatchKey {
+    days: u32,
+    workspace_path: Option<PathBuf>,
+}
+
+#[derive(Default)]
+struct WatchedWindow {
+    subscribers: HashSet<u64>,
+    /// State of the window when it was last emitted.
+    fingerprint: Option<u64>,
+}
+
+#[derive(Default)]
+struct WatchRegistry {
+    windows: HashMap<WatchKey, WatchedWindow>,
+    poller_running: bool,
+    stopped: bool,
+}
+
+/// Usage watches by window and subscriber, scanned by one shared poller thread.
+#[derive(Default)]
+pub(crate) struct LocalUsageWatchers {
+    shared: Arc<std::sync::Mutex<WatchRegistry>>,
+}
+
+fn lock_registry(
+    registry: &std::sync::Mutex<WatchRegistry>,
+) -> std::sync::MutexGuard<'_, WatchRegistry> {
+    registry
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl LocalUsageWatchers {
+    /// Returns `false` when `subscriber` already watches the window.
+    fn subscribe(&self, key: &WatchKey, subscriber: u64) -> Result<bool, String> {
+        let mut registry = lock_registry(&self.shared);
+        if let Some(window) = registry.windows.get(key) {
+            if window.subscribers.contains(&subscriber) {
+                return Ok(false);
+            }
+        } else if registry.windows.len() >= MAX_WATCHED_WINDOWS {
+            return Err(format!(
+                "at most {MAX_WATCHED_WINDOWS} usage windows can be watched at once"
+            ));
+        }
+        let watched = registry
+            .windows
+            .values()
+            .filter(|window| window.subscribers.contains(&subscriber))
+            .count();
+        if watched >= MAX_WATCHES_PER_SUBSCRIBER {
+            return Err(format!(
+                "a client can watch at most {MAX_WATCHES_PER_SUBSCRIBER} usage windows; unwatch one first"
+            ));
+        }
+        let window = registry.windows.entry(key.clone()).or_default();
+        window.subscribers.insert(subscriber);
+        // Emit again so the new subscriber gets the current snapshot too.
+        window.fingerprint = None;
+        Ok(true)
+    }
+
+    fn unsubscribe(&self, key: &WatchKey, subscriber: u64) {
+        let mut registry = lock_registry(&self.shared);
+        if let Some(window) = registry.windows.get_mut(key) {
+            window.subscribers.remove(&subscriber);
+            if window.subscribers.is_empty() {
+                registry.windows.remove(key);
+            }
+        }
+    }
+
+    /// Drops every watch of a subscriber, e.g. when its connection closes.
+    pub(crate) fn unsubscribe_all(&self, subscriber: u64) {
+        lock_registry(&self.shared).windows.retain(|_, window| {
+            window.subscribers.remove(&subscriber);
+            !window.subscribers.is_empty()
+        });
+    }
+}
+
+impl Drop for LocalUsageWatchers {
+    fn drop(&mut self) {
+        lock_registry(&self.shared).stopped = true;
+    }
+}
+
+/// Scans watched windows. Workspaces and model prices are read again on every pass, so
+/// edits to either are picked up without re-subscribing.
+struct UsagePoller<S> {
+    watchers: Arc<std::sync::Mutex<WatchRegistry>>,
+    workspaces: Arc<Mutex<HashMap<String, WorkspaceEntry>>>,
+    data_dir: PathBuf,
+    usage_budgets: Arc<UsageBudgetTracker>,
+    event_sink: S,
+}
+
+impl<S: EventSink> UsagePoller<S> {
+    fn start_if_idle(self) {
+        {
+            let mut registry = lock_registry(&self.watchers);
+            if registry.poller_running || registry.stopped {
+                return;
+            }
+            registry.poller_running = true;
+        }
+        std::thread::spawn(move || loop {
+            std::thread::sleep(WATCH_INTERVAL);
+            let keys: Vec<WatchKey> = {
+                let mut registry = lock_registry(&self.watchers);
+                if registry.stopped || registry.windows.is_empty() {
+                    registry.poller_running = false;
+                    return;
+                }
+                registry.windows.keys().cloned().collect()
+            };
+            self.poll(&keys);
+        });
+    }
+
+    /// Emits every window in `keys` whose fingerprint moved since its last emit, then
+    /// re-evaluates usage budgets if anything was emitted. Blocking.
+    fn poll(&self, keys: &[WatchKey]) {
+        let workspaces = self.workspaces.blocking_lock().clone();
+        let model_prices = read_settings(&self.data_dir.join("settings.json"))
+            .map(|settings| settings.model_prices)
+            .unwrap_or_default();
+        let usage_workspaces = usage_workspaces(&workspaces);
+        let mut emitted = false;
+        for key in keys {
+            let sessions_roots = resolve_sessions_roots(&workspaces, key.workspace_path.as_deref());
+            let fingerprint = watch_fingerprint(
+                &sessions_roots,
+                &make_day_keys(key.days),
+                &usage_workspaces,
+                &model_prices,
+            );
+            let last = lock_registry(&self.watchers)
+                .windows
+                .get(key)
+                .map(|window| window.fingerprint);
+            // `None`: unwatched meanwhile.
+            if last.is_none() || last == Some(Some(fingerprint)) {
+                continue;
+            }
+            let snapshot = match scan_local_usage(
+                key.days,
+                key.workspace_path.as_deref(),
+                &sessions_roots,
+                &usage_workspaces,
+                &model_prices,
+                Some(&cache_path(&self.data_dir)),
+            ) {
+                Ok(snapshot) => snapshot,
+                Err(err) => {
+                    eprintln!("local usage: watch scan failed: {err}");
+                    continue;
+                }
+            };
+            match lock_registry(&self.watchers).windows.get_mut(key) {
+                Some(window) => window.fingerprint = Some(fingerprint),
+                None => continue,
+            }
+            self.event_sink.emit_local_usage_updated(LocalUsageUpdated {
+                days: key.days,
+                workspace_path: key
+                    .workspace_path
+                    .as_ref()
+                    .map(|path| path.to_string_lossy().to_string()),
+                snapshot,
+            });
+            emitted = true;
+        }
+        if emitted {
+            if let Err(err) = self.usage_budgets.check(&self.event_sink) {
+                eprintln!("local usage: budget evaluation failed: {err}");
+            }
+        }
+    }
+}
+
+/// Changes whenever a session log in the window is added, removed, grown or touched, the
+/// window moves to a new day, or the workspaces or prices used to build the snapshot change.
+fn watch_fingerprint(
+    sessions_roots: &[PathBuf],
+    day_keys: &[String],
+    workspaces: &[UsageWorkspace],
+    model_prices: &[ModelPrice],
+) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    day_keys.hash(&mut hasher);
+    let mut workspace_keys: Vec<_> = workspaces
+        .iter()
+        .map(|workspace| (&workspace.id, &workspace.name, &workspace.path))
+        .collect();
+    workspace_keys.sort();
+    workspace_keys.hash(&mut hasher);
+    serde_json::to_string(model_prices)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    for path in session_files(sessions_roots, day_keys) {
+        path.hash(&mut hasher);
+        if let Ok(metadata) = std::fs::metadata(&path) {
+            metadata.len().hash(&mut hasher);
+            modified_ms(&metadata).hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+fn usage_workspaces(workspaces: &HashMap<String, WorkspaceEntry>) -> Vec<UsageWorkspace> {
+    workspaces
+        .values()
+        .map(|entry| UsageWorkspace {
+            id: entry.id.clone(),
+            name: entry.name.clone(),
+            path: PathBuf::from(&entry.path),
+        })
+        .collect()
 }
 
 fn scan_local_usage(
     days: u32,
     workspace_path: Option<&Path>,
     sessions_roots: &[PathBuf],
+    workspaces: &[UsageWorkspace],
+    model_prices: &[ModelPrice],
+    cache_path: Option<&Path>,
 ) -> Result<LocalUsageSnapshot, String> {
     let updated_at = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -71,44 +497,556 @@ fn scan_local_usage(
         .iter()
         .map(|key| (key.clone(), DailyTotals::default()))
         .collect();
-    let mut model_totals: HashMap<String, i64> = HashMap::new();
+    let mut usage = UsageBreakdown::default();
+    collect_usage(
+        sessions_roots,
+        &day_keys,
+        &mut daily,
+        &mut usage,
+        workspace_path,
+        workspaces,
+        cache_path,
+    )?;
+
+    Ok(build_snapshot(
+        updated_at,
+        day_keys,
+        daily,
+        usage,
+        workspaces,
+        model_prices,
+    ))
+}
+
+fn scan_local_usage_report(
+    days: u32,
+    workspace_path: Option<&Path>,
+    sessions_roots: &[PathBuf],
+    workspaces: &[UsageWorkspace],
+    model_prices: &[ModelPrice],
+    cache_path: Option<&Path>,
+) -> Result<LocalUsageReport, String> {
+    let day_keys = make_day_keys(days);
+    let mut daily: HashMap<String, DailyTotals> = day_keys
+        .iter()
+        .map(|key| (key.clone(), DailyTotals::default()))
+        .collect();
+    let mut usage = UsageBreakdown::default();
+    collect_usage(
+        sessions_roots,
+        &day_keys,
+        &mut daily,
+        &mut usage,
+        workspace_path,
+        workspaces,
+        cache_path,
+    )?;
+
+    let mut totals: HashMap<(String, String, Option<String>), UsageTotals> = HashMap::new();
+    for (key, entry) in usage.totals {
+        let total = totals
+            .entry((key.day, key.model, key.workspace_id))
+            .or_default();
+        total.input += entry.input;
+        total.cached += entry.cached;
+        total.output += entry.output;
+    }
+    let mut rows: Vec<LocalUsageRow> = totals
+        .into_iter()
+        .filter(|(_, totals)| totals.input + totals.output > 0)
+        .map(|((day, model, workspace_id), totals)| LocalUsageRow {
+            workspace_name: workspace_id.as_ref().and_then(|id| {
+                workspaces
+                    .iter()
+                    .find(|workspace| &workspace.id == id)
+                    .map(|workspace| workspace.name.clone())
+            }),
+            estimated_cost_usd: find_model_price(model_prices, &model).map_or(0.0, |price| {
+                round_usd(estimate_cost_usd(
+                    price,
+                    totals.input,
+                    totals.cached,
+                    totals.output,
+                ))
+            }),
+            day,
+            model,
+            workspace_id,
+            input_tokens: totals.input,
+            cached_input_tokens: totals.cached,
+            output_tokens: totals.output,
+        })
+        .collect();
+    rows.sort_by(|a, b| {
+        a.day
+            .cmp(&b.day)
+            .then_with(|| a.model.cmp(&b.model))
+            .then_with(|| a.workspace_name.cmp(&b.workspace_name))
+            .then_with(|| a.workspace_id.cmp(&b.workspace_id))
+    });
+
+    Ok(LocalUsageReport {
+        days,
+        rows,
+        agent_runs: daily.values().map(|day| day.agent_runs).sum(),
+        agent_time_ms: daily.values().map(|day| day.agent_ms).sum(),
+    })
+}
+
+/// Tokens and estimated cost per day and workspace over the last `days` days, in every
+/// sessions root. Sessions outside all workspaces are keyed by `None`.
+pub(crate) fn scan_daily_workspace_usage(
+    days: u32,
+    workspaces: &HashMap<String, WorkspaceEntry>,
+    model_prices: &[ModelPrice],
+    cache_path: Option<&Path>,
+) -> Result<HashMap<(String, Option<String>), UsageAmount>, String> {
+    let sessions_roots = resolve_sessions_roots(workspaces, None);
+    let usage_workspaces = usage_workspaces(workspaces);
+    let day_keys = make_day_keys(days);
+    let mut daily: HashMap<String, DailyTotals> = day_keys
+        .iter()
+        .map(|key| (key.clone(), DailyTotals::default()))
+        .collect();
+    let mut usage = UsageBreakdown::default();
+    collect_usage(
+        &sessions_roots,
+        &day_keys,
+        &mut daily,
+        &mut usage,
+        None,
+        &usage_workspaces,
+        cache_path,
+    )?;
+
+    let mut amounts: HashMap<(String, Option<String>), UsageAmount> = HashMap::new();
+    for (key, totals) in usage.totals {
+        let cost = find_model_price(model_prices, &key.model).map_or(0.0, |price| {
+            estimate_cost_usd(price, totals.input, totals.cached, totals.output)
+        });
+        let amount = amounts.entry((key.day, key.workspace_id)).or_default();
+        amount.tokens += totals.input + totals.output;
+        amount.cost_usd += cost;
+    }
+    Ok(amounts)
+}
 
-    if sessions_roots.is_empty() {
-        return Ok(build_snapshot(updated_at, day_keys, daily, HashMap::new()));
+/// Merges every session log of the window into `daily` and `usage`, through the scan cache
+/// when one is given and can be opened.
+fn collect_usage(
+    sessions_roots: &[PathBuf],
+    day_keys: &[String],
+    daily: &mut HashMap<String, DailyTotals>,
+    usage: &mut UsageBreakdown,
+    workspace_path: Option<&Path>,
+    workspaces: &[UsageWorkspace],
+    cache_path: Option<&Path>,
+) -> Result<(), String> {
+    let files = session_files(sessions_roots, day_keys);
+    let cache = match cache_path.map(open_cache).transpose() {
+        Ok(cache) => cache,
+        Err(err) => {
+            eprintln!("local usage: scanning without cache: {err}");
+            None
+        }
+    };
+    match cache {
+        Some(mut conn) => {
+            let tx = conn.transaction().map_err(|err| err.to_string())?;
+            prune_cache(&tx)?;
+            for path in &files {
+                cached_file_usage(&tx, path)?.merge_into(daily, usage, workspace_path, workspaces);
+            }
+            tx.commit().map_err(|err| err.to_string())?;
+        }
+        None => {
+            for path in &files {
+                scan_file(path, daily, usage, workspace_path, workspaces)?;
+            }
+        }
     }
+    Ok(())
+}
 
+/// Session logs under the day directories of the window, in every sessions root.
+fn session_files(sessions_roots: &[PathBuf], day_keys: &[String]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
     for root in sessions_roots {
-        for day_key in &day_keys {
-            let day_dir = day_dir_for_key(root, day_key);
-            if !day_dir.exists() {
-                continue;
-            }
-            let entries = match std::fs::read_dir(&day_dir) {
+        for day_key in day_keys {
+            let entries = match std::fs::read_dir(day_dir_for_key(root, day_key)) {
                 Ok(entries) => entries,
                 Err(_) => continue,
             };
             for entry in entries.flatten() {
                 let path = entry.path();
-                if path.extension().and_then(|ext| ext.to_str()) != Some("jsonl") {
-                    continue;
+                if path.extension().and_then(|ext| ext.to_str()) == Some("jsonl") {
+                    files.push(path);
+                }
+            }
+        }
+    }
+    files
+}
+
+pub(crate) fn cache_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(CACHE_FILE)
+}
+
+fn open_cache(path: &Path) -> Result<Connection, String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    let conn = Connection::open(path).map_err(|err| err.to_string())?;
+    conn.busy_timeout(std::time::Duration::from_secs(10))
+        .map_err(|err| err.to_string())?;
+    let version: i64 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|err| err.to_string())?;
+    if version != CACHE_VERSION {
+        conn.execute_batch("DROP TABLE IF EXISTS files;")
+            .map_err(|err| err.to_string())?;
+    }
+    conn.execute_batch(CACHE_SCHEMA)
+        .map_err(|err| err.to_string())?;
+    conn.execute_batch(&format!("PRAGMA user_version = {CACHE_VERSION}"))
+        .map_err(|err| err.to_string())?;
+    Ok(conn)
+}
+
+/// Drops cached files that no longer exist.
+fn prune_cache(conn: &Connection) -> Result<(), String> {
+    let known = {
+        let mut statement = conn
+            .prepare("SELECT path FROM files")
+            .map_err(|err| err.to_string())?;
+        let rows = statement
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|err| err.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|err| err.to_string())?
+    };
+    for path in known.iter().filter(|path| !Path::new(path).exists()) {
+        conn.execute("DELETE FROM files WHERE path = ?1", params![path])
+            .map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+/// Returns the usage of one session log, parsing only what was appended since it was cached.
+fn cached_file_usage(conn: &Connection, path: &Path) -> Result<FileUsage, String> {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return Ok(FileUsage::default());
+    };
+    let size = metadata.len();
+    let modified_ms = modified_ms(&metadata);
+    let path_key = path.to_string_lossy().to_string();
+    let previous = conn
+        .query_row(
+            "SELECT size, modified_ms, offset, usage FROM files WHERE path = ?1",
+            params![path_key],
+            |row| {
+                Ok((
+                    LogCursor {
+                        size: row.get::<_, i64>(0)? as u64,
+                        modified_ms: row.get(1)?,
+                        offset: row.get::<_, i64>(2)? as u64,
+                    },
+                    row.get::<_, String>(3)?,
+                ))
+            },
+        )
+        .optional()
+        .map_err(|err| err.to_string())?
+        .and_then(|(cursor, usage)| {
+            let usage = serde_json::from_str::<FileUsage>(&usage).ok()?;
+            Some((cursor, usage))
+        });
+    let resume = log_resume(
+        previous.as_ref().map(|(cursor, _)| cursor),
+        size,
+        modified_ms,
+    );
+    let (mut file_usage, offset) = match (resume, previous) {
+        (LogResume::Unchanged, Some((_, usage))) => return Ok(usage),
+        (LogResume::From(offset), Some((_, usage))) => (usage, offset),
+        _ => (FileUsage::default(), 0),
+    };
+
+    let offset = file_usage.read_from(path, offset)?;
+    let usage_json = serde_json::to_string(&file_usage).map_err(|err| err.to_string())?;
+    conn.execute(
+        "INSERT INTO files (path, size, modified_ms, offset, usage)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(path) DO UPDATE SET
+             size = excluded.size,
+             modified_ms = excluded.modified_ms,
+             offset = excluded.offset,
+             usage = excluded.usage",
+        params![
+            path_key,
+            size as i64,
+            modified_ms,
+            offset as i64,
+            usage_json
+        ],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(file_usage)
+}
+
+pub(crate) async fn local_usage_thread_timeline_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    thread_id: String,
+    model_prices: Vec<ModelPrice>,
+) -> Result<LocalUsageThreadTimeline, String> {
+    let thread_id = thread_id.trim().to_string();
+    validate_thread_id(&thread_id)?;
+    let (codex_homes, usage_workspaces) = {
+        let workspaces = workspaces.lock().await;
+        let codex_homes: Vec<PathBuf> = resolve_sessions_roots(&workspaces, None)
+            .into_iter()
+            .filter_map(|root| root.parent().map(Path::to_path_buf))
+            .collect();
+        (codex_homes, usage_workspaces(&workspaces))
+    };
+    tokio::task::spawn_blocking(move || {
+        let path = codex_homes
+            .iter()
+            .find_map(|codex_home| find_rollout_path(codex_home, &thread_id))
+            .ok_or_else(|| format!("no session log found for thread `{thread_id}`"))?;
+        scan_thread_timeline(&path, &thread_id, &usage_workspaces, &model_prices)
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+/// Splits a session log into turns at each user message and sums the token deltas of each.
+fn scan_thread_timeline(
+    path: &Path,
+    thread_id: &str,
+    workspaces: &[UsageWorkspace],
+    model_prices: &[ModelPrice],
+) -> Result<LocalUsageThreadTimeline, String> {
+    let file = File::open(path).map_err(|err| err.to_string())?;
+    let mut previous_totals: Option<UsageTotals> = None;
+    let mut current_model: Option<String> = None;
+    let mut cwd: Option<String> = None;
+    let mut turns: Vec<LocalUsageTurn> = Vec::new();
+
+    for line in BufReader::new(file).lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => continue,
+        };
+        if line.len() > MAX_LINE_BYTES {
+            continue;
+        }
+        let value = match serde_json::from_str::<Value>(&line) {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+        let entry_type = value
+            .get("type")
+            .and_then(|value| value.as_str())
+            .unwrap_or("");
+        let timestamp_ms = read_timestamp_ms(&value);
+
+        if entry_type == "session_meta" || entry_type == "turn_context" {
+            if let Some(next_cwd) = extract_cwd(&value) {
+                cwd = Some(next_cwd);
+            }
+            if entry_type == "turn_context" {
+                if let Some(model) = extract_model_from_turn_context(&value) {
+                    current_model = Some(model);
                 }
-                scan_file(&path, &mut daily, &mut model_totals, workspace_path)?;
             }
+            continue;
+        }
+
+        let payload_type = value
+            .get("payload")
+            .and_then(|payload| payload.get("type"))
+            .and_then(|value| value.as_str());
+        if payload_type == Some("user_message") {
+            turns.push(LocalUsageTurn {
+                index: turns.len() as i64,
+                started_at: timestamp_ms,
+                ended_at: timestamp_ms,
+                model: current_model.clone(),
+                prompt_preview: extract_user_message_preview(&value),
+                input_tokens: 0,
+                cached_input_tokens: 0,
+                output_tokens: 0,
+                total_tokens: 0,
+                estimated_cost_usd: 0.0,
+            });
+            continue;
+        }
+
+        if let Some(turn) = turns.last_mut() {
+            turn.ended_at = turn.ended_at.max(timestamp_ms);
+        }
+        if payload_type != Some("token_count") {
+            continue;
+        }
+        let Some(delta) = token_usage_delta(&value, &mut previous_totals) else {
+            continue;
+        };
+        if delta.input == 0 && delta.cached == 0 && delta.output == 0 {
+            continue;
+        }
+        if turns.is_empty() {
+            // Usage logged before the first user message still belongs to the thread.
+            turns.push(LocalUsageTurn {
+                index: 0,
+                started_at: timestamp_ms,
+                ended_at: timestamp_ms,
+                model: None,
+                prompt_preview: None,
+                input_tokens: 0,
+                cached_input_tokens: 0,
+                output_tokens: 0,
+                total_tokens: 0,
+                estimated_cost_usd: 0.0,
+            });
+        }
+        let Some(turn) = turns.last_mut() else {
+            continue;
+        };
+        if let Some(model) = current_model
+            .clone()
+            .or_else(|| extract_model_from_token_count(&value))
+        {
+            turn.model = Some(model);
         }
+        turn.input_tokens += delta.input;
+        turn.cached_input_tokens += delta.cached.min(delta.input);
+        turn.output_tokens += delta.output;
+        turn.total_tokens = turn.input_tokens + turn.output_tokens;
+    }
+
+    let mut total_tokens = 0;
+    let mut total_cost = 0.0;
+    for turn in &mut turns {
+        let cost = turn
+            .model
+            .as_deref()
+            .and_then(|model| find_model_price(model_prices, model))
+            .map_or(0.0, |price| {
+                estimate_cost_usd(
+                    price,
+                    turn.input_tokens,
+                    turn.cached_input_tokens,
+                    turn.output_tokens,
+                )
+            });
+        turn.estimated_cost_usd = round_usd(cost);
+        total_tokens += turn.total_tokens;
+        total_cost += cost;
     }
 
-    Ok(build_snapshot(updated_at, day_keys, daily, model_totals))
+    Ok(LocalUsageThreadTimeline {
+        thread_id: thread_id.to_string(),
+        workspace_id: cwd
+            .as_deref()
+            .and_then(|cwd| workspace_for_cwd(cwd, workspaces)),
+        cwd,
+        turns,
+        total_tokens,
+        estimated_cost_usd: round_usd(total_cost),
+    })
+}
+
+/// Picks the exact entry for `model`, else the longest matching `prefix*` entry.
+pub(crate) fn find_model_price<'a>(
+    model_prices: &'a [ModelPrice],
+    model: &str,
+) -> Option<&'a ModelPrice> {
+    model_prices
+        .iter()
+        .find(|price| price.model == model)
+        .or_else(|| {
+            model_prices
+                .iter()
+                .filter_map(|price| {
+                    let prefix = price.model.strip_suffix('*')?;
+                    model.starts_with(prefix).then_some((prefix.len(), price))
+                })
+                .max_by_key(|(len, _)| *len)
+                .map(|(_, price)| price)
+        })
+}
+
+/// Cached tokens are a subset of input tokens, so only the uncached rest pays the input price.
+pub(crate) fn estimate_cost_usd(price: &ModelPrice, input: i64, cached: i64, output: i64) -> f64 {
+    let cached = cached.clamp(0, input.max(0));
+    ((input - cached) as f64 * price.input_per_million
+        + cached as f64 * price.cached_input_per_million
+        + output as f64 * price.output_per_million)
+        / 1_000_000.0
+}
+
+fn round_usd(value: f64) -> f64 {
+    (value * 10_000.0).round() / 10_000.0
 }
 
 fn build_snapshot(
     updated_at: i64,
     day_keys: Vec<String>,
     daily: HashMap<String, DailyTotals>,
-    model_totals: HashMap<String, i64>,
+    usage: UsageBreakdown,
+    workspaces: &[UsageWorkspace],
+    model_prices: &[ModelPrice],
 ) -> LocalUsageSnapshot {
     let mut days: Vec<LocalUsageDay> = Vec::with_capacity(day_keys.len());
     let mut total_tokens = 0;
 
+    let mut day_costs: HashMap<String, f64> = HashMap::new();
+    let mut model_totals: HashMap<String, (i64, f64)> = HashMap::new();
+    let mut workspace_totals: HashMap<String, (i64, f64)> = HashMap::new();
+    let mut workspace_threads: HashMap<String, HashSet<String>> = HashMap::new();
+    let mut thread_totals: HashMap<String, (Option<String>, UsageTotals, f64)> = HashMap::new();
+    let mut unpriced_tokens = 0;
+    for (key, totals) in &usage.totals {
+        let tokens = totals.input + totals.output;
+        let cost = match find_model_price(model_prices, &key.model) {
+            Some(price) => estimate_cost_usd(price, totals.input, totals.cached, totals.output),
+            None => {
+                unpriced_tokens += tokens;
+                0.0
+            }
+        };
+        *day_costs.entry(key.day.clone()).or_insert(0.0) += cost;
+        let model_entry = model_totals.entry(key.model.clone()).or_insert((0, 0.0));
+        model_entry.0 += tokens;
+        model_entry.1 += cost;
+        if let Some(workspace_id) = key.workspace_id.as_ref() {
+            let workspace_entry = workspace_totals
+                .entry(workspace_id.clone())
+                .or_insert((0, 0.0));
+            workspace_entry.0 += tokens;
+            workspace_entry.1 += cost;
+            if let Some(thread_id) = key.thread_id.as_ref() {
+                workspace_threads
+                    .entry(workspace_id.clone())
+                    .or_default()
+                    .insert(thread_id.clone());
+            }
+        }
+        if let Some(thread_id) = key.thread_id.as_ref() {
+            let thread_entry = thread_totals
+                .entry(thread_id.clone())
+                .or_insert_with(|| (key.workspace_id.clone(), UsageTotals::default(), 0.0));
+            if thread_entry.0.is_none() {
+                thread_entry.0.clone_from(&key.workspace_id);
+            }
+            thread_entry.1.input += totals.input;
+            thread_entry.1.cached += totals.cached;
+            thread_entry.1.output += totals.output;
+            thread_entry.2 += cost;
+        }
+    }
+
     for day_key in &day_keys {
         let totals = daily.get(day_key).copied().unwrap_or_default();
         let total = totals.input + totals.output;
@@ -121,6 +1059,7 @@ fn build_snapshot(
             total_tokens: total,
             agent_time_ms: totals.agent_ms,
             agent_runs: totals.agent_runs,
+            estimated_cost_usd: round_usd(day_costs.get(day_key).copied().unwrap_or(0.0)),
         });
     }
 
@@ -128,6 +1067,13 @@ fn build_snapshot(
     let last7_tokens: i64 = last7.iter().map(|day| day.total_tokens).sum();
     let last7_input: i64 = last7.iter().map(|day| day.input_tokens).sum();
     let last7_cached: i64 = last7.iter().map(|day| day.cached_input_tokens).sum();
+    let last7_cost: f64 = day_keys
+        .iter()
+        .rev()
+        .take(7)
+        .filter_map(|day_key| day_costs.get(day_key))
+        .sum();
+    let total_cost: f64 = day_costs.values().sum();
 
     let average_daily_tokens = if last7.is_empty() {
         0
@@ -150,20 +1096,85 @@ fn build_snapshot(
 
     let mut top_models: Vec<LocalUsageModel> = model_totals
         .into_iter()
-        .filter(|(model, tokens)| model != "unknown" && *tokens > 0)
-        .map(|(model, tokens)| LocalUsageModel {
+        .filter(|(model, (tokens, _))| model != "unknown" && *tokens > 0)
+        .map(|(model, (tokens, cost))| LocalUsageModel {
             model,
             tokens,
-            share_percent: if total_tokens > 0 {
-                ((tokens as f64) / (total_tokens as f64) * 1000.0).round() / 10.0
-            } else {
-                0.0
-            },
+            share_percent: share_percent(tokens, total_tokens),
+            estimated_cost_usd: round_usd(cost),
         })
         .collect();
     top_models.sort_by(|a, b| b.tokens.cmp(&a.tokens));
     top_models.truncate(4);
 
+    let mut workspace_costs: Vec<LocalUsageWorkspaceCost> = workspaces
+        .iter()
+        .filter_map(|workspace| {
+            let (tokens, cost) = workspace_totals.get(&workspace.id).copied()?;
+            Some(LocalUsageWorkspaceCost {
+                workspace_id: workspace.id.clone(),
+                workspace_name: workspace.name.clone(),
+                total_tokens: tokens,
+                estimated_cost_usd: round_usd(cost),
+            })
+        })
+        .filter(|entry| entry.total_tokens > 0)
+        .collect();
+    workspace_costs.sort_by(|a, b| {
+        b.estimated_cost_usd
+            .total_cmp(&a.estimated_cost_usd)
+            .then_with(|| b.total_tokens.cmp(&a.total_tokens))
+            .then_with(|| a.workspace_name.cmp(&b.workspace_name))
+    });
+
+    let mut top_workspaces: Vec<LocalUsageWorkspace> = workspace_costs
+        .iter()
+        .map(|entry| LocalUsageWorkspace {
+            workspace_id: entry.workspace_id.clone(),
+            workspace_name: entry.workspace_name.clone(),
+            total_tokens: entry.total_tokens,
+            share_percent: share_percent(entry.total_tokens, total_tokens),
+            estimated_cost_usd: entry.estimated_cost_usd,
+            thread_count: workspace_threads
+                .get(&entry.workspace_id)
+                .map_or(0, |threads| threads.len() as i64),
+        })
+        .collect();
+    top_workspaces.sort_by(|a, b| {
+        b.total_tokens
+            .cmp(&a.total_tokens)
+            .then_with(|| a.workspace_name.cmp(&b.workspace_name))
+    });
+    top_workspaces.truncate(TOP_WORKSPACES_LIMIT);
+
+    let mut top_threads: Vec<LocalUsageThread> = thread_totals
+        .into_iter()
+        .filter(|(_, (_, totals, _))| totals.input + totals.output > 0)
+        .map(|(thread_id, (workspace_id, totals, cost))| {
+            let thread = usage.threads.get(&thread_id);
+            let tokens = totals.input + totals.output;
+            LocalUsageThread {
+                workspace_id,
+                cwd: thread.and_then(|thread| thread.cwd.clone()),
+                preview: thread.and_then(|thread| thread.preview.clone()),
+                updated_at: thread.and_then(|thread| thread.updated_at),
+                thread_id,
+                input_tokens: totals.input,
+                cached_input_tokens: totals.cached,
+                output_tokens: totals.output,
+                total_tokens: tokens,
+                share_percent: share_percent(tokens, total_tokens),
+                estimated_cost_usd: round_usd(cost),
+            }
+        })
+        .collect();
+    top_threads.sort_by(|a, b| {
+        b.total_tokens
+            .cmp(&a.total_tokens)
+            .then_with(|| a.thread_id.cmp(&b.thread_id))
+    });
+    top_threads.truncate(TOP_THREADS_LIMIT);
+
     LocalUsageSnapshot {
         updated_at,
         days,
@@ -174,81 +1185,107 @@ fn build_snapshot(
             cache_hit_rate_percent,
             peak_day,
             peak_day_tokens,
+            last7_days_cost_usd: round_usd(last7_cost),
+            last30_days_cost_usd: round_usd(total_cost),
+            unpriced_tokens,
         },
         top_models,
+        workspace_costs,
+        top_workspaces,
+        top_threads,
     }
 }
 
-fn scan_file(
-    path: &Path,
-    daily: &mut HashMap<String, DailyTotals>,
-    model_totals: &mut HashMap<String, i64>,
-    workspace_path: Option<&Path>,
-) -> Result<(), String> {
-    let file = match File::open(path) {
-        Ok(file) => file,
-        Err(_) => {
-            return Ok(());
-        }
-    };
-    let reader = BufReader::new(file);
-    let mut previous_totals: Option<UsageTotals> = None;
-    let mut current_model: Option<String> = None;
-    let mut last_activity_ms: Option<i64> = None;
-    let mut seen_runs: HashSet<i64> = HashSet::new();
-    let mut match_known = workspace_path.is_none();
-    let mut matches_workspace = workspace_path.is_none();
+fn share_percent(tokens: i64, total_tokens: i64) -> f64 {
+    if total_tokens > 0 {
+        ((tokens as f64) / (total_tokens as f64) * 1000.0).round() / 10.0
+    } else {
+        0.0
+    }
+}
 
-    for line in reader.lines() {
-        let line = match line {
-            Ok(line) => line,
-            Err(_) => continue,
-        };
-        if line.len() > 512_000 {
-            continue;
+/// Parser position inside one session log, persisted so appended lines can be read later.
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScanState {
+    previous_totals: Option<UsageTotals>,
+    model: Option<String>,
+    cwd: Option<String>,
+    thread_id: Option<String>,
+    last_activity_ms: Option<i64>,
+    seen_runs: HashSet<i64>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileTokens {
+    day: String,
+    model: String,
+    cwd: Option<String>,
+    #[serde(flatten)]
+    totals: UsageTotals,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileActivity {
+    day: String,
+    cwd: Option<String>,
+    agent_ms: i64,
+    agent_runs: i64,
+}
+
+/// Usage parsed from one session log. Entries keep the cwd they were logged under, so the
+/// workspace filter and workspace attribution are applied when snapshots are built.
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileUsage {
+    state: ScanState,
+    tokens: Vec<FileTokens>,
+    activity: Vec<FileActivity>,
+    preview: Option<String>,
+    updated_at: Option<i64>,
+}
+
+impl FileUsage {
+    /// Parses what was appended from `offset` on; see [`read_lines_from`].
+    fn read_from(&mut self, path: &Path, offset: u64) -> Result<u64, String> {
+        if self.state.thread_id.is_none() {
+            self.state.thread_id = thread_id_from_path(path);
         }
+        read_lines_from(path, offset, |value| {
+            self.apply_line(value);
+            Ok(())
+        })
+    }
 
-        let value = match serde_json::from_str::<Value>(&line) {
-            Ok(value) => value,
-            Err(_) => continue,
-        };
+    fn apply_line(&mut self, value: &Value) {
         let entry_type = value
             .get("type")
             .and_then(|value| value.as_str())
             .unwrap_or("");
 
-        if entry_type == "session_meta" || entry_type == "turn_context" {
-            if let Some(cwd) = extract_cwd(&value) {
-                if let Some(filter) = workspace_path {
-                    matches_workspace = path_matches_workspace(&cwd, filter);
-                    match_known = true;
-                    if !matches_workspace {
-                        break;
-                    }
-                }
+        if entry_type == "session_meta" {
+            if let Some(id) = extract_session_id(value) {
+                self.state.thread_id = Some(id);
             }
         }
 
-        if entry_type == "turn_context" {
-            if let Some(model) = extract_model_from_turn_context(&value) {
-                current_model = Some(model);
+        if entry_type == "session_meta" || entry_type == "turn_context" {
+            if let Some(cwd) = extract_cwd(value) {
+                self.state.cwd = Some(cwd);
             }
-            continue;
-        }
-
-        if entry_type == "session_meta" {
-            continue;
         }
 
-        if !matches_workspace {
-            if match_known {
-                break;
+        if entry_type == "turn_context" {
+            if let Some(model) = extract_model_from_turn_context(value) {
+                self.state.model = Some(model);
             }
-            continue;
+            return;
         }
 
-        if !match_known {
-            continue;
+        if entry_type == "session_meta" {
+            return;
         }
 
         if entry_type == "event_msg" || entry_type.is_empty() {
@@ -258,126 +1295,54 @@ fn scan_file(
                 .and_then(|value| value.as_str());
 
             if payload_type == Some("agent_message") {
-                if let Some(timestamp_ms) = read_timestamp_ms(&value) {
-                    if seen_runs.insert(timestamp_ms) {
-                        if let Some(day_key) = day_key_for_timestamp_ms(timestamp_ms) {
-                            if let Some(entry) = daily.get_mut(&day_key) {
-                                entry.agent_runs += 1;
-                            }
-                        }
-                    }
-                    track_activity(daily, &mut last_activity_ms, timestamp_ms);
+                if let Some(timestamp_ms) = read_timestamp_ms(value) {
+                    self.count_run(timestamp_ms);
+                    self.track_activity(timestamp_ms);
                 }
-                continue;
+                return;
             }
 
             if payload_type == Some("agent_reasoning") {
-                if let Some(timestamp_ms) = read_timestamp_ms(&value) {
-                    track_activity(daily, &mut last_activity_ms, timestamp_ms);
+                if let Some(timestamp_ms) = read_timestamp_ms(value) {
+                    self.track_activity(timestamp_ms);
                 }
-                continue;
-            }
-
-            if payload_type != Some("token_count") {
-                continue;
+                return;
             }
 
-            let info = payload
-                .and_then(|payload| payload.get("info"))
-                .and_then(|v| v.as_object());
-            let (input, cached, output, used_total) = if let Some(info) = info {
-                if let Some(total) = find_usage_map(info, &["total_token_usage", "totalTokenUsage"])
-                {
-                    (
-                        read_i64(total, &["input_tokens", "inputTokens"]),
-                        read_i64(
-                            total,
-                            &[
-                                "cached_input_tokens",
-                                "cache_read_input_tokens",
-                                "cachedInputTokens",
-                                "cacheReadInputTokens",
-                            ],
-                        ),
-                        read_i64(total, &["output_tokens", "outputTokens"]),
-                        true,
-                    )
-                } else if let Some(last) =
-                    find_usage_map(info, &["last_token_usage", "lastTokenUsage"])
-                {
-                    (
-                        read_i64(last, &["input_tokens", "inputTokens"]),
-                        read_i64(
-                            last,
-                            &[
-                                "cached_input_tokens",
-                                "cache_read_input_tokens",
-                                "cachedInputTokens",
-                                "cacheReadInputTokens",
-                            ],
-                        ),
-                        read_i64(last, &["output_tokens", "outputTokens"]),
-                        false,
-                    )
-                } else {
-                    continue;
+            if payload_type == Some("user_message") {
+                if self.preview.is_none() {
+                    self.preview = extract_user_message_preview(value);
                 }
-            } else {
-                continue;
-            };
-
-            let mut delta = UsageTotals {
-                input,
-                cached,
-                output,
-            };
+                return;
+            }
 
-            if used_total {
-                let prev = previous_totals.unwrap_or_default();
-                delta = UsageTotals {
-                    input: (input - prev.input).max(0),
-                    cached: (cached - prev.cached).max(0),
-                    output: (output - prev.output).max(0),
-                };
-                previous_totals = Some(UsageTotals {
-                    input,
-                    cached,
-                    output,
-                });
-            } else {
-                // Some streams emit `last_token_usage` deltas between `total_token_usage` snapshots.
-                // Treat those as already-counted to avoid double-counting when the next total arrives.
-                let mut next = previous_totals.unwrap_or_default();
-                next.input += delta.input;
-                next.cached += delta.cached;
-                next.output += delta.output;
-                previous_totals = Some(next);
+            if payload_type != Some("token_count") {
+                return;
             }
 
+            let Some(delta) = token_usage_delta(value, &mut self.state.previous_totals) else {
+                return;
+            };
             if delta.input == 0 && delta.cached == 0 && delta.output == 0 {
-                continue;
+                return;
             }
 
-            let timestamp_ms = read_timestamp_ms(&value);
-            if let Some(day_key) = timestamp_ms.and_then(|ms| day_key_for_timestamp_ms(ms)) {
-                if let Some(entry) = daily.get_mut(&day_key) {
-                    let cached = delta.cached.min(delta.input);
-                    entry.input += delta.input;
-                    entry.cached += cached;
-                    entry.output += delta.output;
-
-                    let model = current_model
-                        .clone()
-                        .or_else(|| extract_model_from_token_count(&value))
-                        .unwrap_or_else(|| "unknown".to_string());
-                    *model_totals.entry(model).or_insert(0) += delta.input + delta.output;
-                }
+            let timestamp_ms = read_timestamp_ms(value);
+            if let Some(day) = timestamp_ms.and_then(day_key_for_timestamp_ms) {
+                let model = self
+                    .state
+                    .model
+                    .clone()
+                    .or_else(|| extract_model_from_token_count(value))
+                    .unwrap_or_else(|| "unknown".to_string());
+                self.add_tokens(day, model, delta);
+                self.updated_at = self.updated_at.max(timestamp_ms);
             }
 
             if let Some(timestamp_ms) = timestamp_ms {
-                track_activity(daily, &mut last_activity_ms, timestamp_ms);
+                self.track_activity(timestamp_ms);
             }
-            continue;
+            return;
         }
 
         if entry_type == "response_item" {
@@ -391,24 +1356,152 @@ fn scan_file(
                 .unwrap_or("");
 
             if role == "assistant" {
-                if let Some(timestamp_ms) = read_timestamp_ms(&value) {
-                    if seen_runs.insert(timestamp_ms) {
-                        if let Some(day_key) = day_key_for_timestamp_ms(timestamp_ms) {
-                            if let Some(entry) = daily.get_mut(&day_key) {
-                                entry.agent_runs += 1;
-                            }
-                        }
-                    }
-                    track_activity(daily, &mut last_activity_ms, timestamp_ms);
+                if let Some(timestamp_ms) = read_timestamp_ms(value) {
+                    self.count_run(timestamp_ms);
+                    self.track_activity(timestamp_ms);
                 }
             } else if payload_type != Some("message") {
-                if let Some(timestamp_ms) = read_timestamp_ms(&value) {
-                    track_activity(daily, &mut last_activity_ms, timestamp_ms);
+                if let Some(timestamp_ms) = read_timestamp_ms(value) {
+                    self.track_activity(timestamp_ms);
+                }
+            }
+        }
+    }
+
+    fn add_tokens(&mut self, day: String, model: String, delta: UsageTotals) {
+        let cwd = &self.state.cwd;
+        let index = match self
+            .tokens
+            .iter()
+            .position(|entry| entry.day == day && entry.model == model && &entry.cwd == cwd)
+        {
+            Some(index) => index,
+            None => {
+                self.tokens.push(FileTokens {
+                    day,
+                    model,
+                    cwd: cwd.clone(),
+                    totals: UsageTotals::default(),
+                });
+                self.tokens.len() - 1
+            }
+        };
+        let totals = &mut self.tokens[index].totals;
+        totals.input += delta.input;
+        totals.cached += delta.cached.min(delta.input);
+        totals.output += delta.output;
+    }
+
+    fn activity_entry(&mut self, day: String) -> &mut FileActivity {
+        let cwd = &self.state.cwd;
+        let index = match self
+            .activity
+            .iter()
+            .position(|entry| entry.day == day && &entry.cwd == cwd)
+        {
+            Some(index) => index,
+            None => {
+                self.activity.push(FileActivity {
+                    day,
+                    cwd: cwd.clone(),
+                    agent_ms: 0,
+                    agent_runs: 0,
+                });
+                self.activity.len() - 1
+            }
+        };
+        &mut self.activity[index]
+    }
+
+    fn count_run(&mut self, timestamp_ms: i64) {
+        if !self.state.seen_runs.insert(timestamp_ms) {
+            return;
+        }
+        if let Some(day) = day_key_for_timestamp_ms(timestamp_ms) {
+            self.activity_entry(day).agent_runs += 1;
+        }
+    }
+
+    fn track_activity(&mut self, timestamp_ms: i64) {
+        if let Some(prev_ms) = self.state.last_activity_ms {
+            let delta = timestamp_ms - prev_ms;
+            if delta > 0 && delta <= MAX_ACTIVITY_GAP_MS {
+                if let Some(day) = day_key_for_timestamp_ms(timestamp_ms) {
+                    self.activity_entry(day).agent_ms += delta;
                 }
             }
         }
+        self.state.last_activity_ms = Some(timestamp_ms);
+    }
+
+    /// Adds the entries that fall on `daily` days and, with a filter, under `workspace_path`.
+    fn merge_into(
+        &self,
+        daily: &mut HashMap<String, DailyTotals>,
+        usage: &mut UsageBreakdown,
+        workspace_path: Option<&Path>,
+        workspaces: &[UsageWorkspace],
+    ) {
+        let included = |cwd: &Option<String>| match workspace_path {
+            Some(filter) => cwd
+                .as_deref()
+                .is_some_and(|cwd| path_matches_workspace(cwd, filter)),
+            None => true,
+        };
+        let thread_id = self.state.thread_id.clone();
+        let mut counted = false;
+
+        for entry in self.tokens.iter().filter(|entry| included(&entry.cwd)) {
+            let Some(day) = daily.get_mut(&entry.day) else {
+                continue;
+            };
+            day.input += entry.totals.input;
+            day.cached += entry.totals.cached;
+            day.output += entry.totals.output;
+            let totals = usage
+                .totals
+                .entry(UsageKey {
+                    day: entry.day.clone(),
+                    model: entry.model.clone(),
+                    workspace_id: entry
+                        .cwd
+                        .as_deref()
+                        .and_then(|cwd| workspace_for_cwd(cwd, workspaces)),
+                    thread_id: thread_id.clone(),
+                })
+                .or_default();
+            totals.input += entry.totals.input;
+            totals.cached += entry.totals.cached;
+            totals.output += entry.totals.output;
+            counted = true;
+        }
+
+        for entry in self.activity.iter().filter(|entry| included(&entry.cwd)) {
+            if let Some(day) = daily.get_mut(&entry.day) {
+                day.agent_ms += entry.agent_ms;
+                day.agent_runs += entry.agent_runs;
+            }
+        }
+
+        if let (true, Some(thread_id)) = (counted, thread_id) {
+            let thread = usage.threads.entry(thread_id).or_default();
+            thread.cwd.clone_from(&self.state.cwd);
+            thread.preview = thread.preview.take().or_else(|| self.preview.clone());
+            thread.updated_at = thread.updated_at.max(self.updated_at);
+        }
     }
+}
 
+fn scan_file(
+    path: &Path,
+    daily: &mut HashMap<String, DailyTotals>,
+    usage: &mut UsageBreakdown,
+    workspace_path: Option<&Path>,
+    workspaces: &[UsageWorkspace],
+) -> Result<(), String> {
+    let mut file_usage = FileUsage::default();
+    file_usage.read_from(path, 0)?;
+    file_usage.merge_into(daily, usage, workspace_path, workspaces);
     Ok(())
 }
 
@@ -437,6 +1530,88 @@ fn extract_model_from_token_count(value: &Value) -> Option<String> {
     model.map(|value| value.to_string())
 }
 
+/// Turns a `token_count` event into the tokens it adds on top of `previous_totals`.
+fn token_usage_delta(
+    value: &Value,
+    previous_totals: &mut Option<UsageTotals>,
+) -> Option<UsageTotals> {
+    let info = value
+        .get("payload")
+        .and_then(|payload| payload.get("info"))
+        .and_then(|v| v.as_object())?;
+    let read_usage = |usage: &serde_json::Map<String, Value>| UsageTotals {
+        input: read_i64(usage, &["input_tokens", "inputTokens"]),
+        cached: read_i64(
+            usage,
+            &[
+                "cached_input_tokens",
+                "cache_read_input_tokens",
+                "cachedInputTokens",
+                "cacheReadInputTokens",
+            ],
+        ),
+        output: read_i64(usage, &["output_tokens", "outputTokens"]),
+    };
+
+    if let Some(total) = find_usage_map(info, &["total_token_usage", "totalTokenUsage"]) {
+        let total = read_usage(total);
+        let prev = previous_totals.unwrap_or_default();
+        *previous_totals = Some(total);
+        return Some(UsageTotals {
+            input: (total.input - prev.input).max(0),
+            cached: (total.cached - prev.cached).max(0),
+            output: (total.output - prev.output).max(0),
+        });
+    }
+
+    let delta = read_usage(find_usage_map(
+        info,
+        &["last_token_usage", "lastTokenUsage"],
+    )?);
+    // Some streams emit `last_token_usage` deltas between `total_token_usage` snapshots.
+    // Treat those as already-counted to avoid double-counting when the next total arrives.
+    let mut next = previous_totals.unwrap_or_default();
+    next.input += delta.input;
+    next.cached += delta.cached;
+    next.output += delta.output;
+    *previous_totals = Some(next);
+    Some(delta)
+}
+
+fn extract_session_id(value: &Value) -> Option<String> {
+    value
+        .get("payload")
+        .and_then(|payload| payload.get("id"))
+        .and_then(|id| id.as_str())
+        .map(|id| id.to_string())
+}
+
+/// Rollout files end in the thread id: `rollout-<timestamp>-<uuid>.jsonl`.
+fn thread_id_from_path(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_str()?;
+    let start = stem.len().checked_sub(36)?;
+    let candidate = stem.get(start..)?;
+    Uuid::parse_str(candidate).ok()?;
+    Some(candidate.to_string())
+}
+
+fn extract_user_message_preview(value: &Value) -> Option<String> {
+    let message = value
+        .get("payload")
+        .and_then(|payload| payload.get("message"))
+        .and_then(|message| message.as_str())?;
+    let collapsed = message.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.is_empty() {
+        return None;
+    }
+    if collapsed.chars().count() <= PREVIEW_MAX_CHARS {
+        return Some(collapsed);
+    }
+    let mut preview: String = collapsed.chars().take(PREVIEW_MAX_CHARS - 1).collect();
+    preview.push('…');
+    Some(preview)
+}
+
 fn find_usage_map<'a>(
     info: &'a serde_json::Map<String, Value>,
     keys: &[&str],
@@ -456,7 +1631,7 @@ fn read_i64(map: &serde_json::Map<String, Value>, keys: &[&str]) -> i64 {
         .unwrap_or(0)
 }
 
-fn read_timestamp_ms(value: &Value) -> Option<i64> {
+pub(crate) fn read_timestamp_ms(value: &Value) -> Option<i64> {
     let raw = value.get("timestamp")?;
     if let Some(text) = raw.as_str() {
         return DateTime::parse_from_rfc3339(text)
@@ -472,30 +1647,12 @@ fn read_timestamp_ms(value: &Value) -> Option<i64> {
     Some(numeric)
 }
 
-fn track_activity(
-    daily: &mut HashMap<String, DailyTotals>,
-    last_activity_ms: &mut Option<i64>,
-    timestamp_ms: i64,
-) {
-    if let Some(prev_ms) = *last_activity_ms {
-        let delta = timestamp_ms - prev_ms;
-        if delta > 0 && delta <= MAX_ACTIVITY_GAP_MS {
-            if let Some(day_key) = day_key_for_timestamp_ms(timestamp_ms) {
-                if let Some(entry) = daily.get_mut(&day_key) {
-                    entry.agent_ms += delta;
-                }
-            }
-        }
-    }
-    *last_activity_ms = Some(timestamp_ms);
-}
-
 fn day_key_for_timestamp_ms(timestamp_ms: i64) -> Option<String> {
     let utc = Utc.timestamp_millis_opt(timestamp_ms).single()?;
     Some(utc.with_timezone(&Local).format("%Y-%m-%d").to_string())
 }
 
-fn extract_cwd(value: &Value) -> Option<String> {
+pub(crate) fn extract_cwd(value: &Value) -> Option<String> {
     value
         .get("payload")
         .and_then(|payload| payload.get("cwd"))
@@ -503,6 +1660,15 @@ fn extract_cwd(value: &Value) -> Option<String> {
         .map(|cwd| cwd.to_string())
 }
 
+/// The innermost workspace containing `cwd`, so worktrees win over their parent repo.
+fn workspace_for_cwd(cwd: &str, workspaces: &[UsageWorkspace]) -> Option<String> {
+    workspaces
+        .iter()
+        .filter(|workspace| path_matches_workspace(cwd, &workspace.path))
+        .max_by_key(|workspace| workspace.path.as_os_str().len())
+        .map(|workspace| workspace.id.clone())
+}
+
 fn path_matches_workspace(cwd: &str, workspace_path: &Path) -> bool {
     let cwd_path = Path::new(cwd);
     cwd_path == workspace_path || cwd_path.starts_with(workspace_path)
@@ -525,7 +1691,7 @@ fn resolve_codex_sessions_root(codex_home_override: Option<PathBuf>) -> Option<P
         .map(|home| home.join("sessions"))
 }
 
-fn resolve_sessions_roots(
+pub(crate) fn resolve_sessions_roots(
     workspaces: &HashMap<String, WorkspaceEntry>,
     workspace_path: Option<&Path>,
 ) -> Vec<PathBuf> {
@@ -644,8 +1810,8 @@ mod tests {
 
         let mut daily: HashMap<String, DailyTotals> = HashMap::new();
         daily.insert(day_key.to_string(), DailyTotals::default());
-        let mut model_totals: HashMap<String, i64> = HashMap::new();
-        scan_file(&path, &mut daily, &mut model_totals, None).expect("scan file");
+        let mut usage = UsageBreakdown::default();
+        scan_file(&path, &mut daily, &mut usage, None, &[]).expect("scan file");
 
         let totals = daily.get(day_key).copied().unwrap_or_default();
         assert_eq!(totals.input, 10);
@@ -662,8 +1828,8 @@ mod tests {
 
         let mut daily: HashMap<String, DailyTotals> = HashMap::new();
         daily.insert(day_key.to_string(), DailyTotals::default());
-        let mut model_totals: HashMap<String, i64> = HashMap::new();
-        scan_file(&path, &mut daily, &mut model_totals, None).expect("scan file");
+        let mut usage = UsageBreakdown::default();
+        scan_file(&path, &mut daily, &mut usage, None, &[]).expect("scan file");
 
         let totals = daily.get(day_key).copied().unwrap_or_default();
         assert_eq!(totals.input, 20);
@@ -681,8 +1847,8 @@ mod tests {
 
         let mut daily: HashMap<String, DailyTotals> = HashMap::new();
         daily.insert(day_key.to_string(), DailyTotals::default());
-        let mut model_totals: HashMap<String, i64> = HashMap::new();
-        scan_file(&path, &mut daily, &mut model_totals, None).expect("scan file");
+        let mut usage = UsageBreakdown::default();
+        scan_file(&path, &mut daily, &mut usage, None, &[]).expect("scan file");
 
         let totals = daily.get(day_key).copied().unwrap_or_default();
         assert_eq!(totals.input, 12);
@@ -699,8 +1865,8 @@ mod tests {
 
         let mut daily: HashMap<String, DailyTotals> = HashMap::new();
         daily.insert(day_key.to_string(), DailyTotals::default());
-        let mut model_totals: HashMap<String, i64> = HashMap::new();
-        scan_file(&path, &mut daily, &mut model_totals, None).expect("scan file");
+        let mut usage = UsageBreakdown::default();
+        scan_file(&path, &mut daily, &mut usage, None, &[]).expect("scan file");
 
         let totals = daily.get(day_key).copied().unwrap_or_default();
         assert_eq!(totals.agent_ms, 5_000);
@@ -716,8 +1882,8 @@ mod tests {
 
         let mut daily: HashMap<String, DailyTotals> = HashMap::new();
         daily.insert(day_key.to_string(), DailyTotals::default());
-        let mut model_totals: HashMap<String, i64> = HashMap::new();
-        scan_file(&path, &mut daily, &mut model_totals, None).expect("scan file");
+        let mut usage = UsageBreakdown::default();
+        scan_file(&path, &mut daily, &mut usage, None, &[]).expect("scan file");
 
         let totals = daily.get(day_key).copied().unwrap_or_default();
         assert_eq!(totals.agent_runs, 2);
@@ -734,8 +1900,8 @@ mod tests {
 
         let mut daily: HashMap<String, DailyTotals> = HashMap::new();
         daily.insert(day_key.to_string(), DailyTotals::default());
-        let mut model_totals: HashMap<String, i64> = HashMap::new();
-        scan_file(&path, &mut daily, &mut model_totals, None).expect("scan file");
+        let mut usage = UsageBreakdown::default();
+        scan_file(&path, &mut daily, &mut usage, None, &[]).expect("scan file");
 
         let totals = daily.get(day_key).copied().unwrap_or_default();
         assert_eq!(totals.agent_ms, 10_000);
@@ -752,12 +1918,13 @@ mod tests {
 
         let mut daily: HashMap<String, DailyTotals> = HashMap::new();
         daily.insert(day_key.to_string(), DailyTotals::default());
-        let mut model_totals: HashMap<String, i64> = HashMap::new();
+        let mut usage = UsageBreakdown::default();
         scan_file(
             &path,
             &mut daily,
-            &mut model_totals,
+            &mut usage,
             Some(Path::new("/tmp/other-project")),
+            &[],
         )
         .expect("scan file");
 
@@ -795,7 +1962,8 @@ mod tests {
         write_session_file(&root_a, &day_key, &[line_a]);
         write_session_file(&root_b, &day_key, &[line_b]);
 
-        let snapshot = scan_local_usage(2, None, &[root_a, root_b]).expect("scan usage");
+        let snapshot =
+            scan_local_usage(2, None, &[root_a, root_b], &[], &[], None).expect("scan usage");
         let day = snapshot
             .days
             .iter()
@@ -807,6 +1975,355 @@ mod tests {
         assert_eq!(snapshot.totals.last30_days_tokens, 11);
     }
 
+    #[test]
+    fn scan_local_usage_prices_models_per_day_and_workspace() {
+        let day_keys = make_day_keys(1);
+        let day_key = day_keys[0].clone();
+        let naive =
+            NaiveDateTime::parse_from_str(&format!("{day_key} 12:00:00"), "%Y-%m-%d %H:%M:%S")
+                .expect("timestamp");
+        let timestamp_ms = Local
+            .from_local_datetime(&naive)
+            .single()
+            .expect("timestamp")
+            .timestamp_millis();
+        let root = make_temp_sessions_root();
+        write_session_file(
+            &root,
+            &day_key,
+            &[
+                r#"{"type":"session_meta","payload":{"cwd":"/tmp/project-a/sub"}}"#.to_string(),
+                r#"{"type":"turn_context","payload":{"cwd":"/tmp/project-a/sub","model":"gpt-5-codex"}}"#.to_string(),
+                format!(
+                    r#"{{"timestamp":{timestamp_ms},"payload":{{"type":"token_count","info":{{"total_token_usage":{{"input_tokens":1000000,"cached_input_tokens":600000,"output_tokens":100000}}}}}}}}"#
+                ),
+            ],
+        );
+        write_session_file(
+            &root,
+            &day_key,
+            &[
+                r#"{"type":"turn_context","payload":{"cwd":"/tmp/elsewhere","model":"mystery-model"}}"#.to_string(),
+                format!(
+                    r#"{{"timestamp":{timestamp_ms},"payload":{{"type":"token_count","info":{{"total_token_usage":{{"input_tokens":50,"cached_input_tokens":0,"output_tokens":25}}}}}}}}"#
+                ),
+            ],
+        );
+        let workspaces = vec![UsageWorkspace {
+            id: "a".to_string(),
+            name: "A".to_string(),
+            path: PathBuf::from("/tmp/project-a"),
+        }];
+        let prices = vec![
+            ModelPrice {
+                model: "gpt-5*".to_string(),
+                input_per_million: 1.0,
+                cached_input_per_million: 0.1,
+                output_per_million: 10.0,
+            },
+            ModelPrice {
+                model: "gpt-5-codex*".to_string(),
+                input_per_million: 2.0,
+                cached_input_per_million: 0.5,
+                output_per_million: 20.0,
+            },
+        ];
+
+        let snapshot =
+            scan_local_usage(1, None, &[root], &workspaces, &prices, None).expect("scan usage");
+
+        // 400k uncached * $2 + 600k cached * $0.5 + 100k output * $20, per million.
+        assert_eq!(snapshot.days[0].estimated_cost_usd, 3.1);
+        assert_eq!(snapshot.totals.last7_days_cost_usd, 3.1);
+        assert_eq!(snapshot.totals.last30_days_cost_usd, 3.1);
+        assert_eq!(snapshot.totals.unpriced_tokens, 75);
+        assert_eq!(snapshot.top_models[0].model, "gpt-5-codex");
+        assert_eq!(snapshot.top_models[0].estimated_cost_usd, 3.1);
+        assert_eq!(snapshot.workspace_costs.len(), 1);
+        assert_eq!(snapshot.workspace_costs[0].workspace_id, "a");
+        assert_eq!(snapshot.workspace_costs[0].total_tokens, 1_100_000);
+        assert_eq!(snapshot.workspace_costs[0].estimated_cost_usd, 3.1);
+    }
+
+    #[test]
+    fn scan_local_usage_report_sums_threads_per_day_model_and_workspace() {
+        let day_key = make_day_keys(1)[0].clone();
+        let naive =
+            NaiveDateTime::parse_from_str(&format!("{day_key} 12:00:00"), "%Y-%m-%d %H:%M:%S")
+                .expect("timestamp");
+        let timestamp_ms = Local
+            .from_local_datetime(&naive)
+            .single()
+            .expect("timestamp")
+            .timestamp_millis();
+        let root = make_temp_sessions_root();
+        for (cwd, input) in [
+            ("/tmp/project-a", 100),
+            ("/tmp/project-a/sub", 300),
+            ("/tmp/elsewhere", 7),
+        ] {
+            write_session_file(
+                &root,
+                &day_key,
+                &[
+                    format!(
+                        r#"{{"type":"turn_context","payload":{{"cwd":"{cwd}","model":"gpt-5"}}}}"#
+                    ),
+                    format!(
+                        r#"{{"timestamp":{timestamp_ms},"payload":{{"type":"token_count","info":{{"total_token_usage":{{"input_tokens":{input},"cached_input_tokens":0,"output_tokens":10}}}}}}}}"#
+                    ),
+                ],
+            );
+        }
+        let workspaces = vec![UsageWorkspace {
+            id: "a".to_string(),
+            name: "A".to_string(),
+            path: PathBuf::from("/tmp/project-a"),
+        }];
+        let prices = vec![ModelPrice {
+            model: "gpt-5".to_string(),
+            input_per_million: 1_000.0,
+            cached_input_per_million: 0.0,
+            output_per_million: 1_000.0,
+        }];
+
+        let report = scan_local_usage_report(1, None, &[root], &workspaces, &prices, None)
+            .expect("scan report");
+
+        assert_eq!(report.days, 1);
+        assert_eq!(report.rows.len(), 2);
+        assert_eq!(report.rows[0].workspace_id, None);
+        assert_eq!(report.rows[0].input_tokens, 7);
+        let workspace_row = &report.rows[1];
+        assert_eq!(workspace_row.day, day_key);
+        assert_eq!(workspace_row.workspace_id.as_deref(), Some("a"));
+        assert_eq!(workspace_row.workspace_name.as_deref(), Some("A"));
+        assert_eq!(workspace_row.input_tokens, 400);
+        assert_eq!(workspace_row.output_tokens, 20);
+        assert_eq!(workspace_row.estimated_cost_usd, 0.42);
+    }
+
+    #[test]
+    fn scan_local_usage_ranks_workspaces_and_threads() {
+        let day_key = make_day_keys(1)[0].clone();
+        let naive =
+            NaiveDateTime::parse_from_str(&format!("{day_key} 12:00:00"), "%Y-%m-%d %H:%M:%S")
+                .expect("timestamp");
+        let timestamp_ms = Local
+            .from_local_datetime(&naive)
+            .single()
+            .expect("timestamp")
+            .timestamp_millis();
+        let token_line = |input: i64, output: i64| {
+            format!(
+                r#"{{"timestamp":{timestamp_ms},"payload":{{"type":"token_count","info":{{"total_token_usage":{{"input_tokens":{input},"cached_input_tokens":0,"output_tokens":{output}}}}}}}}}"#
+            )
+        };
+        let root = make_temp_sessions_root();
+        write_session_file(
+            &root,
+            &day_key,
+            &[
+                r#"{"type":"session_meta","payload":{"id":"thread-big","cwd":"/tmp/project-a"}}"#
+                    .to_string(),
+                r#"{"type":"event_msg","payload":{"type":"user_message","message":"Fix   the\nbuild"}}"#
+                    .to_string(),
+                token_line(600, 100),
+            ],
+        );
+        write_session_file(
+            &root,
+            &day_key,
+            &[
+                r#"{"type":"session_meta","payload":{"id":"thread-small","cwd":"/tmp/project-a/sub"}}"#
+                    .to_string(),
+                token_line(150, 50),
+            ],
+        );
+        write_session_file(
+            &root,
+            &day_key,
+            &[
+                r#"{"type":"session_meta","payload":{"id":"thread-other","cwd":"/tmp/project-b"}}"#
+                    .to_string(),
+                token_line(80, 20),
+            ],
+        );
+        let workspaces = vec![
+            UsageWorkspace {
+                id: "a".to_string(),
+                name: "A".to_string(),
+                path: PathBuf::from("/tmp/project-a"),
+            },
+            UsageWorkspace {
+                id: "b".to_string(),
+                name: "B".to_string(),
+                path: PathBuf::from("/tmp/project-b"),
+            },
+        ];
+
+        let snapshot =
+            scan_local_usage(1, None, &[root], &workspaces, &[], None).expect("scan usage");
+
+        let workspace_ids: Vec<&str> = snapshot
+            .top_workspaces
+            .iter()
+            .map(|entry| entry.workspace_id.as_str())
+            .collect();
+        assert_eq!(workspace_ids, vec!["a", "b"]);
+        assert_eq!(snapshot.top_workspaces[0].total_tokens, 900);
+        assert_eq!(snapshot.top_workspaces[0].share_percent, 90.0);
+        assert_eq!(snapshot.top_workspaces[0].thread_count, 2);
+
+        let thread_ids: Vec<&str> = snapshot
+            .top_threads
+            .iter()
+            .map(|entry| entry.thread_id.as_str())
+            .collect();
+        assert_eq!(
+            thread_ids,
+            vec!["thread-big", "thread-small", "thread-other"]
+        );
+        let big = &snapshot.top_threads[0];
+        assert_eq!(big.workspace_id.as_deref(), Some("a"));
+        assert_eq!(big.cwd.as_deref(), Some("/tmp/project-a"));
+        assert_eq!(big.preview.as_deref(), Some("Fix the build"));
+        assert_eq!(big.total_tokens, 700);
+        assert_eq!(big.share_percent, 70.0);
+        assert_eq!(big.updated_at, Some(timestamp_ms));
+    }
+
+    #[test]
+    fn scan_local_usage_parses_only_appended_lines_with_cache() {
+        let day_key = make_day_keys(1)[0].clone();
+        let naive =
+            NaiveDateTime::parse_from_str(&format!("{day_key} 12:00:00"), "%Y-%m-%d %H:%M:%S")
+                .expect("timestamp");
+        let timestamp_ms = Local
+            .from_local_datetime(&naive)
+            .single()
+            .expect("timestamp")
+            .timestamp_millis();
+        let token_line = |input: i64, output: i64| {
+            format!(
+                r#"{{"timestamp":{timestamp_ms},"payload":{{"type":"token_count","info":{{"total_token_usage":{{"input_tokens":{input},"cached_input_tokens":0,"output_tokens":{output}}}}}}}}}"#
+            )
+        };
+        let root = make_temp_sessions_root();
+        let cache = root.join("cache").join(CACHE_FILE);
+        let path = write_session_file(
+            &root,
+            &day_key,
+            &[
+                r#"{"type":"session_meta","payload":{"id":"t1","cwd":"/tmp/project-a"}}"#
+                    .to_string(),
+                token_line(10, 5),
+            ],
+        );
+        let scan = || {
+            scan_local_usage(1, None, std::slice::from_ref(&root), &[], &[], Some(&cache))
+                .expect("scan usage")
+                .totals
+                .last30_days_tokens
+        };
+        let cached_offset = || {
+            open_cache(&cache)
+                .expect("open cache")
+                .query_row(
+                    "SELECT offset FROM files WHERE path = ?1",
+                    params![path.to_string_lossy().to_string()],
+                    |row| row.get::<_, i64>(0),
+                )
+                .expect("cached file") as u64
+        };
+
+        assert_eq!(scan(), 15);
+        assert_eq!(
+            cached_offset(),
+            fs::metadata(&path).expect("metadata").len()
+        );
+
+        // The running total continues from the cached state, and a half-written line waits.
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .expect("open session file");
+        writeln!(file, "{}", token_line(30, 10)).expect("append line");
+        write!(file, "{}", &token_line(50, 20)[..20]).expect("append partial line");
+        drop(file);
+        assert_eq!(scan(), 40);
+        assert!(cached_offset() < fs::metadata(&path).expect("metadata").len());
+
+        // A file that shrank was rewritten and is parsed from the start.
+        fs::write(&path, format!("{}\n", token_line(7, 1))).expect("rewrite session file");
+        assert_eq!(scan(), 8);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn thread_id_from_path_reads_rollout_suffix() {
+        assert_eq!(
+            thread_id_from_path(Path::new(
+                "/x/rollout-2026-01-19T12-00-00-0199a1b2-c3d4-7e5f-8a9b-0c1d2e3f4a5b.jsonl"
+            ))
+            .as_deref(),
+            Some("0199a1b2-c3d4-7e5f-8a9b-0c1d2e3f4a5b")
+        );
+        assert_eq!(thread_id_from_path(Path::new("/x/usage.jsonl")), None);
+    }
+
+    #[test]
+    fn scan_thread_timeline_splits_usage_per_turn() {
+        let path = write_temp_jsonl(&[
+            r#"{"timestamp":"2026-01-19T12:00:00.000Z","type":"session_meta","payload":{"id":"t1","cwd":"/tmp/project-a"}}"#,
+            r#"{"timestamp":"2026-01-19T12:00:00.000Z","type":"turn_context","payload":{"cwd":"/tmp/project-a","model":"gpt-5"}}"#,
+            r#"{"timestamp":"2026-01-19T12:00:01.000Z","type":"event_msg","payload":{"type":"user_message","message":"first"}}"#,
+            r#"{"timestamp":"2026-01-19T12:00:05.000Z","type":"event_msg","payload":{"type":"token_count","info":{"total_token_usage":{"input_tokens":1000,"cached_input_tokens":0,"output_tokens":100}}}}"#,
+            r#"{"timestamp":"2026-01-19T12:01:00.000Z","type":"event_msg","payload":{"type":"user_message","message":"second"}}"#,
+            r#"{"timestamp":"2026-01-19T12:01:02.000Z","type":"turn_context","payload":{"cwd":"/tmp/project-a","model":"gpt-5-mini"}}"#,
+            r#"{"timestamp":"2026-01-19T12:01:09.000Z","type":"event_msg","payload":{"type":"token_count","info":{"total_token_usage":{"input_tokens":3000,"cached_input_tokens":1500,"output_tokens":300}}}}"#,
+        ]);
+        let workspaces = vec![UsageWorkspace {
+            id: "a".to_string(),
+            name: "A".to_string(),
+            path: PathBuf::from("/tmp/project-a"),
+        }];
+        let prices = vec![ModelPrice {
+            model: "gpt-5".to_string(),
+            input_per_million: 1_000.0,
+            cached_input_per_million: 0.0,
+            output_per_million: 10_000.0,
+        }];
+
+        let timeline =
+            scan_thread_timeline(&path, "t1", &workspaces, &prices).expect("scan timeline");
+
+        assert_eq!(timeline.workspace_id.as_deref(), Some("a"));
+        assert_eq!(timeline.turns.len(), 2);
+        let first = &timeline.turns[0];
+        assert_eq!(first.prompt_preview.as_deref(), Some("first"));
+        assert_eq!(first.model.as_deref(), Some("gpt-5"));
+        assert_eq!(first.total_tokens, 1_100);
+        assert_eq!(first.estimated_cost_usd, 2.0);
+        assert_eq!(
+            first
+                .ended_at
+                .zip(first.started_at)
+                .map(|(end, start)| end - start),
+            Some(4_000)
+        );
+        let second = &timeline.turns[1];
+        assert_eq!(second.index, 1);
+        assert_eq!(second.model.as_deref(), Some("gpt-5-mini"));
+        assert_eq!(second.input_tokens, 2_000);
+        assert_eq!(second.cached_input_tokens, 1_500);
+        assert_eq!(second.output_tokens, 200);
+        assert_eq!(second.estimated_cost_usd, 0.0);
+        assert_eq!(timeline.total_tokens, 3_300);
+        assert_eq!(timeline.estimated_cost_usd, 2.0);
+    }
+
     #[test]
     fn resolve_sessions_roots_uses_single_default_root() {
         let mut workspaces = HashMap::new();
@@ -837,4 +2354,35 @@ mod tests {
             .unwrap_or_default();
         assert_eq!(roots, expected);
     }
+
+    #[test]
+    fn usage_watches_are_deduped_capped_and_dropped_per_subscriber() {
+        let watchers = LocalUsageWatchers::default();
+        let window = |days: u32| WatchKey {
+            days,
+            workspace_path: None,
+        };
+        let window_count =
+            |watchers: &LocalUsageWatchers| lock_registry(&watchers.shared).windows.len();
+
+        assert_eq!(watchers.subscribe(&window(7), 1), Ok(true));
+        assert_eq!(watchers.subscribe(&window(7), 1), Ok(false));
+        assert_eq!(watchers.subscribe(&window(7), 2), Ok(true));
+        assert_eq!(window_count(&watchers), 1);
+
+        // Another subscriber cannot cancel a shared window.
+        watchers.unsubscribe(&window(7), 2);
+        assert_eq!(window_count(&watchers), 1);
+
+        for days in 1..MAX_WATCHES_PER_SUBSCRIBER as u32 {
+            assert_eq!(watchers.subscribe(&window(days + 10), 1), Ok(true));
+        }
+        assert!(watchers.subscribe(&window(90), 1).is_err());
+        assert_eq!(watchers.subscribe(&window(90), 2), Ok(true));
+
+        watchers.unsubscribe_all(1);
+        assert_eq!(window_count(&watchers), 1);
+        watchers.unsubscribe(&window(90), 2);
+        assert_eq!(window_count(&watchers), 0);
+    }
 }
@@ -1,5 +1,6 @@
 pub(crate) mod account;
 pub(crate) mod agents_config_core;
+pub(crate) mod approval_policy_core;
 pub(crate) mod codex_aux_core;
 pub(crate) mod codex_core;
 pub(crate) mod codex_update_core;
@@ -11,7 +12,17 @@ pub(crate) mod git_ui_core;
 pub(crate) mod local_usage_core;
 pub(crate) mod process_core;
 pub(crate) mod prompts_core;
+pub(crate) mod rotating_jsonl;
+pub(crate) mod rpc_error;
+pub(crate) mod session_log_tail;
 pub(crate) mod settings_core;
+#[cfg(desktop)]
+pub(crate) mod terminal_core;
+pub(crate) mod thread_export_core;
+pub(crate) mod thread_search_core;
+pub(crate) mod tls_core;
+pub(crate) mod usage_budget_core;
+pub(crate) mod usage_export_core;
 pub(crate) mod workspace_rpc;
 pub(crate) mod workspaces_core;
 pub(crate) mod worktree_core;
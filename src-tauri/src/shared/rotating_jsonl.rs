@@ -0,0 +1,120 @@
+//! Append-only JSONL logs in a data dir, shared by the daemon audit log and the approval
+//! decision log.
+
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+/// `<name>.jsonl`, moved to `<name>.1.jsonl` once it grows past `max_bytes` (a single
+/// previous file is kept).
+pub(crate) struct RotatingJsonlLog {
+    path: PathBuf,
+    rotated_path: PathBuf,
+    max_bytes: u64,
+    write_lock: Mutex<()>,
+}
+
+impl RotatingJsonlLog {
+    pub(crate) fn new(dir: &Path, name: &str, max_bytes: u64) -> Self {
+        Self {
+            path: dir.join(format!("{name}.jsonl")),
+            rotated_path: dir.join(format!("{name}.1.jsonl")),
+            max_bytes,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    pub(crate) async fn append<T: Serialize>(&self, entry: &T) -> Result<(), String> {
+        let mut line = serde_json::to_string(entry).map_err(|err| err.to_string())?;
+        line.push('\n');
+
+        let _guard = self.write_lock.lock().await;
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|err| err.to_string())?;
+        }
+        let size = tokio::fs::metadata(&self.path)
+            .await
+            .map(|metadata| metadata.len())
+            .unwrap_or(0);
+        if size >= self.max_bytes {
+            tokio::fs::rename(&self.path, &self.rotated_path)
+                .await
+                .map_err(|err| err.to_string())?;
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .map_err(|err| err.to_string())?;
+        file.write_all(line.as_bytes())
+            .await
+            .map_err(|err| err.to_string())?;
+        // tokio files write in the background; flush so the entry is on disk on return.
+        file.flush().await.map_err(|err| err.to_string())
+    }
+
+    /// Entries from the previous and the current file, oldest first. Lines that do not
+    /// parse as `T` are skipped.
+    pub(crate) async fn read<T: DeserializeOwned>(
+        &self,
+        keep: impl Fn(&T) -> bool,
+    ) -> Result<Vec<T>, String> {
+        let _guard = self.write_lock.lock().await;
+        let mut entries = Vec::new();
+        for path in [&self.rotated_path, &self.path] {
+            let data = match tokio::fs::read_to_string(path).await {
+                Ok(data) => data,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err.to_string()),
+            };
+            entries.extend(
+                data.lines()
+                    .filter_map(|line| serde_json::from_str::<T>(line).ok())
+                    .filter(|entry| keep(entry)),
+            );
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RotatingJsonlLog;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn rotates_past_the_size_limit_and_reads_both_files() {
+        let dir =
+            std::env::temp_dir().join(format!("codex-monitor-jsonl-{}", uuid::Uuid::new_v4()));
+        let log = RotatingJsonlLog::new(&dir, "events", 8);
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("runtime");
+        runtime.block_on(async {
+            for n in 0..3 {
+                log.append(&json!({ "n": n })).await.expect("append");
+            }
+            let all: Vec<Value> = log.read(|_| true).await.expect("read");
+            // Each entry fills the 8-byte budget, so only the last two survive.
+            assert_eq!(all, vec![json!({ "n": 1 }), json!({ "n": 2 })]);
+            let ones: Vec<Value> = log
+                .read(|entry: &Value| entry["n"] == 1)
+                .await
+                .expect("read");
+            assert_eq!(ones, vec![json!({ "n": 1 })]);
+        });
+
+        let current = std::fs::read_to_string(dir.join("events.jsonl")).expect("current");
+        let rotated = std::fs::read_to_string(dir.join("events.1.jsonl")).expect("rotated");
+        assert_eq!(current, "{\"n\":2}\n");
+        assert_eq!(rotated, "{\"n\":1}\n");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
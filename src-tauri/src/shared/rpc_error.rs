@@ -0,0 +1,214 @@
+use std::fmt;
+
+use serde_json::{json, Value};
+
+/// Messages daemons sent for auth failures before errors carried codes.
+const LEGACY_UNAUTHORIZED_MESSAGES: [&str; 2] = ["unauthorized", "invalid token"];
+
+/// Machine-readable error kinds carried in JSON-RPC 2.0 error objects.
+///
+/// The standard JSON-RPC codes are used where they fit; daemon-specific failures use the
+/// implementation-defined `-32000..=-32099` range.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum RpcErrorCode {
+    InvalidRequest,
+    MethodNotFound,
+    InvalidParams,
+    Internal,
+    Unauthorized,
+    Forbidden,
+    WorkspaceNotFound,
+    WorkspaceNotConnected,
+    GitFailure,
+    Timeout,
+    /// The request no longer matches the current state (a stash that moved, a branch that
+    /// diverged, uncommitted changes in the way).
+    Conflict,
+    /// Client-side only: the connection to the daemon dropped before a response arrived.
+    Disconnected,
+}
+
+impl RpcErrorCode {
+    pub(crate) const ALL: [RpcErrorCode; 12] = [
+        RpcErrorCode::InvalidRequest,
+        RpcErrorCode::MethodNotFound,
+        RpcErrorCode::InvalidParams,
+        RpcErrorCode::Internal,
+        RpcErrorCode::Unauthorized,
+        RpcErrorCode::Forbidden,
+        RpcErrorCode::WorkspaceNotFound,
+        RpcErrorCode::WorkspaceNotConnected,
+        RpcErrorCode::GitFailure,
+        RpcErrorCode::Timeout,
+        RpcErrorCode::Conflict,
+        RpcErrorCode::Disconnected,
+    ];
+
+    pub(crate) fn code(self) -> i64 {
+        match self {
+            RpcErrorCode::InvalidRequest => -32600,
+            RpcErrorCode::MethodNotFound => -32601,
+            RpcErrorCode::InvalidParams => -32602,
+            RpcErrorCode::Internal => -32603,
+            RpcErrorCode::Unauthorized => -32001,
+            RpcErrorCode::Forbidden => -32002,
+            RpcErrorCode::WorkspaceNotFound => -32003,
+            RpcErrorCode::WorkspaceNotConnected => -32004,
+            RpcErrorCode::GitFailure => -32005,
+            RpcErrorCode::Timeout => -32006,
+            RpcErrorCode::Disconnected => -32007,
+            RpcErrorCode::Conflict => -32008,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn from_code(code: i64) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.code() == code)
+    }
+
+    /// Stable snake_case name, also reported in `data.kind`.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            RpcErrorCode::InvalidRequest => "invalid_request",
+            RpcErrorCode::MethodNotFound => "method_not_found",
+            RpcErrorCode::InvalidParams => "invalid_params",
+            RpcErrorCode::Internal => "internal",
+            RpcErrorCode::Unauthorized => "unauthorized",
+            RpcErrorCode::Forbidden => "forbidden",
+            RpcErrorCode::WorkspaceNotFound => "workspace_not_found",
+            RpcErrorCode::WorkspaceNotConnected => "workspace_not_connected",
+            RpcErrorCode::GitFailure => "git_failure",
+            RpcErrorCode::Timeout => "timeout",
+            RpcErrorCode::Conflict => "conflict",
+            RpcErrorCode::Disconnected => "disconnected",
+        }
+    }
+}
+
+/// A JSON-RPC 2.0 error object: `{ "code": <number>, "message": <string>, "data"?: <any> }`.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct RpcError {
+    pub(crate) code: RpcErrorCode,
+    pub(crate) message: String,
+    pub(crate) data: Option<Value>,
+}
+
+impl RpcError {
+    pub(crate) fn new(code: RpcErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn with_data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    pub(crate) fn is(&self, code: RpcErrorCode) -> bool {
+        self.code == code
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn to_value(&self) -> Value {
+        let mut data = match self.data.clone() {
+            Some(Value::Object(map)) => Value::Object(map),
+            Some(other) => json!({ "details": other }),
+            None => json!({}),
+        };
+        data["kind"] = json!(self.code.name());
+        json!({
+            "code": self.code.code(),
+            "message": self.message,
+            "data": data,
+        })
+    }
+
+    /// Parses an `error` member, accepting the legacy `{ "message" }` shape as well. Legacy
+    /// errors carry no kind, except the two fixed auth failures older daemons sent.
+    #[allow(dead_code)]
+    pub(crate) fn from_value(value: &Value) -> Self {
+        let message = value
+            .get("message")
+            .and_then(Value::as_str)
+            .or_else(|| value.as_str())
+            .unwrap_or("remote error")
+            .to_string();
+        let code = value
+            .get("code")
+            .and_then(Value::as_i64)
+            .and_then(RpcErrorCode::from_code)
+            .unwrap_or_else(|| {
+                if LEGACY_UNAUTHORIZED_MESSAGES.contains(&message.as_str()) {
+                    RpcErrorCode::Unauthorized
+                } else {
+                    RpcErrorCode::Internal
+                }
+            });
+        Self {
+            code,
+            message,
+            data: value.get("data").cloned(),
+        }
+    }
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Untyped failures from code that still reports plain strings.
+impl From<String> for RpcError {
+    fn from(message: String) -> Self {
+        Self::new(RpcErrorCode::Internal, message)
+    }
+}
+
+impl From<RpcError> for String {
+    fn from(error: RpcError) -> Self {
+        error.message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RpcError, RpcErrorCode};
+    use serde_json::json;
+
+    #[test]
+    fn error_objects_round_trip_with_numeric_codes() {
+        let error = RpcError::new(RpcErrorCode::Forbidden, "nope")
+            .with_data(json!({ "requiredRole": "admin" }));
+        let value = error.to_value();
+        assert_eq!(value["code"], json!(-32002));
+        assert_eq!(value["data"]["kind"], json!("forbidden"));
+        assert_eq!(value["data"]["requiredRole"], json!("admin"));
+
+        let parsed = RpcError::from_value(&value);
+        assert!(parsed.is(RpcErrorCode::Forbidden));
+        assert_eq!(parsed.message, "nope");
+    }
+
+    #[test]
+    fn legacy_message_only_errors_are_not_guessed_from_text() {
+        let parsed = RpcError::from_value(&json!({ "message": "invalid token" }));
+        assert!(parsed.is(RpcErrorCode::Unauthorized));
+        let parsed = RpcError::from_value(&json!({ "message": "workspace not found" }));
+        assert!(parsed.is(RpcErrorCode::Internal));
+        let parsed = RpcError::from_value(&json!({ "code": -32008, "message": "moved" }));
+        assert!(parsed.is(RpcErrorCode::Conflict));
+        assert!(RpcError::from("missing or invalid `rows`".to_string()).is(RpcErrorCode::Internal));
+    }
+
+    #[test]
+    fn every_code_maps_back_to_its_kind() {
+        for kind in RpcErrorCode::ALL {
+            assert_eq!(RpcErrorCode::from_code(kind.code()), Some(kind));
+        }
+    }
+}
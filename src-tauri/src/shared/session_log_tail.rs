@@ -0,0 +1,133 @@
+//! Incremental reads of Codex session logs, shared by the caches that follow them (local
+//! usage and the thread search index).
+
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+/// Longer lines (huge tool outputs) are skipped rather than parsed.
+pub(crate) const MAX_LINE_BYTES: usize = 512_000;
+
+/// How far a session log was read, with the size and mtime it had at the time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LogCursor {
+    pub(crate) size: u64,
+    pub(crate) modified_ms: i64,
+    pub(crate) offset: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LogResume {
+    /// Same size and mtime as when it was read: nothing new.
+    Unchanged,
+    /// Keep what was parsed and read on from this offset.
+    From(u64),
+    /// New or rewritten file: parse it from the start.
+    Restart,
+}
+
+/// Decides how to bring a cached log up to date given its current size and mtime.
+pub(crate) fn log_resume(previous: Option<&LogCursor>, size: u64, modified_ms: i64) -> LogResume {
+    match previous {
+        Some(previous) if previous.size == size && previous.modified_ms == modified_ms => {
+            LogResume::Unchanged
+        }
+        // Session logs only grow; anything else means the file was rewritten.
+        Some(previous) if size >= previous.offset => LogResume::From(previous.offset),
+        _ => LogResume::Restart,
+    }
+}
+
+pub(crate) fn modified_ms(metadata: &std::fs::Metadata) -> i64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_millis() as i64)
+}
+
+/// Calls `on_line` for every complete JSON line from `offset` on and returns the offset
+/// after the last one, so a line still being written is picked up by the next read.
+pub(crate) fn read_lines_from(
+    path: &Path,
+    offset: u64,
+    mut on_line: impl FnMut(&Value) -> Result<(), String>,
+) -> Result<u64, String> {
+    let Ok(mut file) = File::open(path) else {
+        return Ok(offset);
+    };
+    file.seek(SeekFrom::Start(offset))
+        .map_err(|err| err.to_string())?;
+    let mut reader = BufReader::new(file);
+    let mut consumed = offset;
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        let read = reader
+            .read_until(b'\n', &mut line)
+            .map_err(|err| err.to_string())?;
+        if read == 0 || line.last() != Some(&b'\n') {
+            break;
+        }
+        consumed += read as u64;
+        if line.len() > MAX_LINE_BYTES {
+            continue;
+        }
+        if let Ok(value) = serde_json::from_slice::<Value>(&line) {
+            on_line(&value)?;
+        }
+    }
+    Ok(consumed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn log_resume_reads_appends_and_restarts_rewrites() {
+        let cursor = LogCursor {
+            size: 100,
+            modified_ms: 5,
+            offset: 90,
+        };
+        assert_eq!(log_resume(None, 100, 5), LogResume::Restart);
+        assert_eq!(log_resume(Some(&cursor), 100, 5), LogResume::Unchanged);
+        assert_eq!(log_resume(Some(&cursor), 100, 6), LogResume::From(90));
+        assert_eq!(log_resume(Some(&cursor), 140, 7), LogResume::From(90));
+        assert_eq!(log_resume(Some(&cursor), 40, 7), LogResume::Restart);
+    }
+
+    #[test]
+    fn read_lines_from_stops_before_a_partial_line() {
+        let path = std::env::temp_dir().join(format!(
+            "codex-monitor-log-tail-{}.jsonl",
+            uuid::Uuid::new_v4()
+        ));
+        let mut file = File::create(&path).expect("create log");
+        file.write_all(b"{\"n\":1}\nnot json\n{\"n\":2}\n{\"n\":")
+            .expect("write log");
+
+        let mut seen = Vec::new();
+        let offset = read_lines_from(&path, 0, |value| {
+            seen.push(value["n"].as_i64().unwrap_or_default());
+            Ok(())
+        })
+        .expect("read log");
+        assert_eq!(seen, vec![1, 2]);
+        assert_eq!(offset, 25);
+
+        file.write_all(b"3}\n").expect("finish line");
+        let offset = read_lines_from(&path, offset, |value| {
+            seen.push(value["n"].as_i64().unwrap_or_default());
+            Ok(())
+        })
+        .expect("read appended line");
+        assert_eq!(seen, vec![1, 2, 3]);
+        assert_eq!(offset, 33);
+        std::fs::remove_file(&path).ok();
+    }
+}
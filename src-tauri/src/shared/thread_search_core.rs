@@ -0,0 +1,832 @@
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+use crate::shared::local_usage_core::{extract_cwd, read_timestamp_ms, resolve_sessions_roots};
+use crate::shared::session_log_tail::{
+    log_resume, modified_ms, read_lines_from, LogCursor, LogResume,
+};
+use crate::types::{ThreadSearchMatch, ThreadSearchResponse, ThreadSearchResult, WorkspaceEntry};
+
+const INDEX_FILE: &str = "thread-index.sqlite3";
+/// Bump when the schema or the extraction rules change; the index is then rebuilt.
+const SCHEMA_VERSION: i64 = 1;
+const MAX_ENTRY_CHARS: usize = 16_000;
+const MAX_PREVIEW_CHARS: usize = 200;
+const MAX_MATCHES_PER_THREAD: usize = 3;
+const SNIPPET_TOKENS: i64 = 16;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS files (
+    path TEXT PRIMARY KEY,
+    size INTEGER NOT NULL,
+    modified_ms INTEGER NOT NULL,
+    offset INTEGER NOT NULL,
+    thread_id TEXT
+);
+CREATE TABLE IF NOT EXISTS threads (
+    thread_id TEXT PRIMARY KEY,
+    path TEXT NOT NULL,
+    cwd TEXT,
+    started_at INTEGER,
+    updated_at INTEGER,
+    preview TEXT
+);
+CREATE TABLE IF NOT EXISTS thread_files (
+    thread_id TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    PRIMARY KEY (thread_id, file_path)
+);
+CREATE VIRTUAL TABLE IF NOT EXISTS entries USING fts5(
+    body,
+    kind UNINDEXED,
+    thread_id UNINDEXED,
+    path UNINDEXED,
+    timestamp UNINDEXED
+);
+";
+
+pub(crate) fn index_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(INDEX_FILE)
+}
+
+/// Full-text search over the Codex session logs of every known codex home. The index is
+/// brought up to date before each search, reading only what was appended since last time.
+/// With `allowed_workspaces`, only threads whose cwd maps to one of those workspaces count
+/// towards `limit`.
+pub(crate) async fn search_threads_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    data_dir: &Path,
+    query: String,
+    workspace_id: Option<String>,
+    limit: Option<u32>,
+    allowed_workspaces: Option<HashSet<String>>,
+) -> Result<ThreadSearchResponse, String> {
+    let fts_query = build_fts_query(&query).ok_or_else(|| "query must not be empty".to_string())?;
+    let limit = limit.unwrap_or(20).clamp(1, 100) as usize;
+    let (sessions_roots, workspace_paths) = {
+        let workspaces = workspaces.lock().await;
+        if let Some(workspace_id) = workspace_id.as_deref() {
+            if !workspaces.contains_key(workspace_id) {
+                return Err("workspace not found".to_string());
+            }
+        }
+        let workspace_paths = workspaces
+            .values()
+            .map(|entry| (entry.id.clone(), PathBuf::from(&entry.path)))
+            .collect::<Vec<_>>();
+        (resolve_sessions_roots(&workspaces, None), workspace_paths)
+    };
+    let db_path = index_path(data_dir);
+    tokio::task::spawn_blocking(move || {
+        let mut conn = open_index(&db_path)?;
+        let indexed_files = refresh_index(&mut conn, &session_log_dirs(&sessions_roots))?;
+        let results = search_index(
+            &conn,
+            &fts_query,
+            workspace_id.as_deref(),
+            allowed_workspaces.as_ref(),
+            &workspace_paths,
+            limit,
+        )?;
+        Ok(ThreadSearchResponse {
+            query,
+            results,
+            indexed_files,
+        })
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+fn open_index(db_path: &Path) -> Result<Connection, String> {
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    let conn = Connection::open(db_path).map_err(|err| err.to_string())?;
+    conn.busy_timeout(Duration::from_secs(10))
+        .map_err(|err| err.to_string())?;
+    let version: i64 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|err| err.to_string())?;
+    if version != SCHEMA_VERSION {
+        conn.execute_batch(
+            "DROP TABLE IF EXISTS files;
+             DROP TABLE IF EXISTS threads;
+             DROP TABLE IF EXISTS thread_files;
+             DROP TABLE IF EXISTS entries;",
+        )
+        .map_err(|err| err.to_string())?;
+    }
+    conn.execute_batch(SCHEMA).map_err(|err| err.to_string())?;
+    conn.execute_batch(&format!("PRAGMA user_version = {SCHEMA_VERSION}"))
+        .map_err(|err| err.to_string())?;
+    Ok(conn)
+}
+
+/// `sessions/` trees plus the flat `archived_sessions/` directory next to each of them.
+fn session_log_dirs(sessions_roots: &[PathBuf]) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    for root in sessions_roots {
+        dirs.push(root.clone());
+        if let Some(codex_home) = root.parent() {
+            dirs.push(codex_home.join("archived_sessions"));
+        }
+    }
+    dirs
+}
+
+fn collect_session_files(dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = dirs.to_vec();
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().and_then(|ext| ext.to_str()) == Some("jsonl") {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+struct IndexedFile {
+    cursor: LogCursor,
+    thread_id: Option<String>,
+}
+
+/// Indexes new and grown session files and drops files that disappeared. Returns how many
+/// session files the index covers.
+fn refresh_index(conn: &mut Connection, dirs: &[PathBuf]) -> Result<u32, String> {
+    let files = collect_session_files(dirs);
+    let seen = files
+        .iter()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect::<HashSet<_>>();
+
+    let known = {
+        let mut statement = conn
+            .prepare("SELECT path FROM files")
+            .map_err(|err| err.to_string())?;
+        let rows = statement
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|err| err.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|err| err.to_string())?
+    };
+    for path in known.iter().filter(|path| !seen.contains(*path)) {
+        let tx = conn.transaction().map_err(|err| err.to_string())?;
+        forget_file(&tx, path)?;
+        tx.execute("DELETE FROM files WHERE path = ?1", params![path])
+            .map_err(|err| err.to_string())?;
+        tx.commit().map_err(|err| err.to_string())?;
+    }
+
+    for path in &files {
+        index_file(conn, path)?;
+    }
+    Ok(files.len() as u32)
+}
+
+fn forget_file(tx: &Transaction<'_>, path: &str) -> Result<(), String> {
+    tx.execute("DELETE FROM entries WHERE path = ?1", params![path])
+        .map_err(|err| err.to_string())?;
+    let thread_id: Option<String> = tx
+        .query_row(
+            "SELECT thread_id FROM files WHERE path = ?1",
+            params![path],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?
+        .flatten();
+    if let Some(thread_id) = thread_id {
+        tx.execute(
+            "DELETE FROM threads WHERE thread_id = ?1 AND path = ?2",
+            params![thread_id, path],
+        )
+        .map_err(|err| err.to_string())?;
+        tx.execute(
+            "DELETE FROM thread_files WHERE thread_id = ?1",
+            params![thread_id],
+        )
+        .map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+fn index_file(conn: &mut Connection, path: &Path) -> Result<(), String> {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return Ok(());
+    };
+    let size = metadata.len();
+    let modified_ms = modified_ms(&metadata);
+    let path_key = path.to_string_lossy().to_string();
+
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let previous = tx
+        .query_row(
+            "SELECT size, modified_ms, offset, thread_id FROM files WHERE path = ?1",
+            params![path_key],
+            |row| {
+                Ok(IndexedFile {
+                    cursor: LogCursor {
+                        size: row.get::<_, i64>(0)? as u64,
+                        modified_ms: row.get(1)?,
+                        offset: row.get::<_, i64>(2)? as u64,
+                    },
+                    thread_id: row.get(3)?,
+                })
+            },
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+    let resume = log_resume(
+        previous.as_ref().map(|file| &file.cursor),
+        size,
+        modified_ms,
+    );
+    let (offset, thread_id) = match (resume, previous) {
+        (LogResume::Unchanged, _) => return Ok(()),
+        (LogResume::From(offset), Some(previous)) => (offset, previous.thread_id),
+        (_, previous) => {
+            if previous.is_some() {
+                forget_file(&tx, &path_key)?;
+            }
+            (0, None)
+        }
+    };
+
+    let mut indexer = FileIndexer {
+        tx: &tx,
+        path: &path_key,
+        thread_id: thread_id.unwrap_or_else(|| thread_id_from_file_name(path)),
+    };
+    let offset = read_lines_from(path, offset, |value| indexer.index_line(value))?;
+    tx.execute(
+        "INSERT INTO files (path, size, modified_ms, offset, thread_id)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(path) DO UPDATE SET
+             size = excluded.size,
+             modified_ms = excluded.modified_ms,
+             offset = excluded.offset,
+             thread_id = excluded.thread_id",
+        params![
+            path_key,
+            size as i64,
+            modified_ms,
+            offset as i64,
+            indexer.thread_id
+        ],
+    )
+    .map_err(|err| err.to_string())?;
+    tx.commit().map_err(|err| err.to_string())
+}
+
+/// Codex names logs `rollout-<timestamp>-<uuid>.jsonl`; used until `session_meta` is seen.
+fn thread_id_from_file_name(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if stem.len() >= 36 && stem.is_char_boundary(stem.len() - 36) {
+        stem[stem.len() - 36..].to_string()
+    } else {
+        stem
+    }
+}
+
+struct FileIndexer<'a> {
+    tx: &'a Transaction<'a>,
+    path: &'a str,
+    thread_id: String,
+}
+
+impl FileIndexer<'_> {
+    fn index_line(&mut self, value: &Value) -> Result<(), String> {
+        let timestamp = read_timestamp_ms(value);
+        let entry_type = value.get("type").and_then(Value::as_str).unwrap_or("");
+        let Some(payload) = value.get("payload") else {
+            return Ok(());
+        };
+        let payload_type = payload.get("type").and_then(Value::as_str).unwrap_or("");
+        match (entry_type, payload_type) {
+            ("session_meta", _) => {
+                if let Some(id) = payload.get("id").and_then(Value::as_str) {
+                    self.thread_id = id.to_string();
+                }
+                self.touch_thread(timestamp, extract_cwd(value), None)?;
+            }
+            ("event_msg", "user_message") => {
+                let message = payload.get("message").and_then(Value::as_str).unwrap_or("");
+                self.touch_thread(timestamp, None, Some(message))?;
+                self.add_entry("user", message, timestamp)?;
+            }
+            ("event_msg", "agent_message") => {
+                let message = payload.get("message").and_then(Value::as_str).unwrap_or("");
+                self.touch_thread(timestamp, None, None)?;
+                self.add_entry("assistant", message, timestamp)?;
+            }
+            ("event_msg", "turn_diff") => {
+                let diff = payload
+                    .get("unified_diff")
+                    .and_then(Value::as_str)
+                    .unwrap_or("");
+                self.add_entry("diff", diff, timestamp)?;
+                for file in diff_file_paths(diff) {
+                    self.add_file(&file, timestamp)?;
+                }
+            }
+            ("event_msg", "patch_apply_begin") => {
+                if let Some(changes) = payload.get("changes").and_then(Value::as_object) {
+                    for file in changes.keys() {
+                        self.add_file(file, timestamp)?;
+                    }
+                }
+            }
+            ("response_item", "function_call") | ("response_item", "custom_tool_call") => {
+                let name = payload.get("name").and_then(Value::as_str).unwrap_or("");
+                let input = payload
+                    .get("arguments")
+                    .or_else(|| payload.get("input"))
+                    .and_then(Value::as_str)
+                    .unwrap_or("");
+                self.add_entry("tool_call", &tool_call_text(name, input), timestamp)?;
+                for file in patch_file_paths(input) {
+                    self.add_file(&file, timestamp)?;
+                }
+            }
+            ("response_item", "local_shell_call") => {
+                let command = payload
+                    .get("action")
+                    .and_then(|action| action.get("command"))
+                    .map(command_text)
+                    .unwrap_or_default();
+                self.add_entry("tool_call", &command, timestamp)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn touch_thread(
+        &self,
+        timestamp: Option<i64>,
+        cwd: Option<String>,
+        preview: Option<&str>,
+    ) -> Result<(), String> {
+        let preview = preview
+            .map(|text| truncate_chars(text.trim(), MAX_PREVIEW_CHARS))
+            .filter(|text| !text.is_empty());
+        self.tx
+            .execute(
+                "INSERT INTO threads (thread_id, path, cwd, started_at, updated_at, preview)
+                 VALUES (?1, ?2, ?3, ?4, ?4, ?5)
+                 ON CONFLICT(thread_id) DO UPDATE SET
+                     path = excluded.path,
+                     cwd = COALESCE(excluded.cwd, threads.cwd),
+                     started_at = COALESCE(threads.started_at, excluded.started_at),
+                     updated_at = COALESCE(
+                         MAX(threads.updated_at, excluded.updated_at),
+                         threads.updated_at,
+                         excluded.updated_at
+                     ),
+                     preview = COALESCE(threads.preview, excluded.preview)",
+                params![self.thread_id, self.path, cwd, timestamp, preview],
+            )
+            .map_err(|err| err.to_string())?;
+        Ok(())
+    }
+
+    fn add_entry(&self, kind: &str, body: &str, timestamp: Option<i64>) -> Result<(), String> {
+        let body = body.trim();
+        if body.is_empty() {
+            return Ok(());
+        }
+        self.tx
+            .execute(
+                "INSERT INTO entries (body, kind, thread_id, path, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    truncate_chars(body, MAX_ENTRY_CHARS),
+                    kind,
+                    self.thread_id,
+                    self.path,
+                    timestamp
+                ],
+            )
+            .map_err(|err| err.to_string())?;
+        Ok(())
+    }
+
+    /// Records a touched file once per thread.
+    fn add_file(&self, file: &str, timestamp: Option<i64>) -> Result<(), String> {
+        let inserted = self
+            .tx
+            .execute(
+                "INSERT OR IGNORE INTO thread_files (thread_id, file_path) VALUES (?1, ?2)",
+                params![self.thread_id, file],
+            )
+            .map_err(|err| err.to_string())?;
+        if inserted > 0 {
+            self.add_entry("file", file, timestamp)?;
+        }
+        Ok(())
+    }
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((index, _)) => text[..index].to_string(),
+        None => text.to_string(),
+    }
+}
+
+fn command_text(command: &Value) -> String {
+    match command {
+        Value::Array(items) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" "),
+        Value::String(text) => text.clone(),
+        _ => String::new(),
+    }
+}
+
+/// Tool calls are indexed as `<name> <arguments>`, with shell commands joined into one line.
+fn tool_call_text(name: &str, input: &str) -> String {
+    let command = serde_json::from_str::<Value>(input)
+        .ok()
+        .and_then(|args| args.get("command").map(command_text))
+        .filter(|command| !command.is_empty());
+    match command {
+        Some(command) => format!("{name} {command}"),
+        None => format!("{name} {input}"),
+    }
+}
+
+fn diff_file_paths(diff: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    for line in diff.lines() {
+        let path = line
+            .strip_prefix("+++ b/")
+            .or_else(|| line.strip_prefix("--- a/"));
+        if let Some(path) = path {
+            let path = path.trim().to_string();
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    paths
+}
+
+/// Paths from `apply_patch` envelopes (`*** Update File: src/lib.rs`).
+fn patch_file_paths(input: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    for line in input.lines() {
+        let path = line
+            .strip_prefix("*** Add File: ")
+            .or_else(|| line.strip_prefix("*** Update File: "))
+            .or_else(|| line.strip_prefix("*** Delete File: "))
+            .or_else(|| line.strip_prefix("*** Move to: "));
+        if let Some(path) = path {
+            let path = path.trim().to_string();
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    paths
+}
+
+/// Turns free text into an FTS5 query: every word must appear, and the last word may be a
+/// prefix so results show up while typing.
+fn build_fts_query(query: &str) -> Option<String> {
+    let terms = query
+        .split_whitespace()
+        .map(|term| term.replace('"', ""))
+        .filter(|term| !term.is_empty())
+        .collect::<Vec<_>>();
+    if terms.is_empty() {
+        return None;
+    }
+    let prefix_last = !query.ends_with(char::is_whitespace);
+    let last = terms.len() - 1;
+    Some(
+        terms
+            .iter()
+            .enumerate()
+            .map(|(index, term)| {
+                if index == last && prefix_last {
+                    format!("\"{term}\"*")
+                } else {
+                    format!("\"{term}\"")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+pub(crate) fn workspace_for_cwd(
+    cwd: &str,
+    workspace_paths: &[(String, PathBuf)],
+) -> Option<String> {
+    let cwd = Path::new(cwd);
+    workspace_paths
+        .iter()
+        .filter(|(_, path)| cwd.starts_with(path))
+        .max_by_key(|(_, path)| path.as_os_str().len())
+        .map(|(id, _)| id.clone())
+}
+
+fn search_index(
+    conn: &Connection,
+    fts_query: &str,
+    workspace_id: Option<&str>,
+    allowed_workspaces: Option<&HashSet<String>>,
+    workspace_paths: &[(String, PathBuf)],
+    limit: usize,
+) -> Result<Vec<ThreadSearchResult>, String> {
+    let row_limit = (limit * 20).clamp(200, 2_000) as i64;
+    let mut statement = conn
+        .prepare(
+            "SELECT thread_id, kind, timestamp,
+                    snippet(entries, 0, '<mark>', '</mark>', '…', ?2)
+             FROM entries
+             WHERE entries MATCH ?1
+             ORDER BY bm25(entries)
+             LIMIT ?3",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = statement
+        .query_map(params![fts_query, SNIPPET_TOKENS, row_limit], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                ThreadSearchMatch {
+                    kind: row.get(1)?,
+                    timestamp: row.get(2)?,
+                    snippet: row.get(3)?,
+                },
+            ))
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut thread_statement = conn
+        .prepare("SELECT cwd, started_at, updated_at, preview FROM threads WHERE thread_id = ?1")
+        .map_err(|err| err.to_string())?;
+    let mut results: Vec<ThreadSearchResult> = Vec::new();
+    let mut positions: HashMap<String, Option<usize>> = HashMap::new();
+    for row in rows {
+        let (thread_id, found) = row.map_err(|err| err.to_string())?;
+        let position = match positions.get(&thread_id) {
+            Some(position) => *position,
+            None => {
+                let thread = thread_statement
+                    .query_row(params![thread_id], |row| {
+                        Ok((
+                            row.get::<_, Option<String>>(0)?,
+                            row.get::<_, Option<i64>>(1)?,
+                            row.get::<_, Option<i64>>(2)?,
+                            row.get::<_, Option<String>>(3)?,
+                        ))
+                    })
+                    .optional()
+                    .map_err(|err| err.to_string())?;
+                let (cwd, started_at, updated_at, preview) =
+                    thread.unwrap_or((None, None, None, None));
+                let thread_workspace = cwd
+                    .as_deref()
+                    .and_then(|cwd| workspace_for_cwd(cwd, workspace_paths));
+                let requested = match workspace_id {
+                    Some(id) => thread_workspace.as_deref() == Some(id),
+                    None => true,
+                };
+                let allowed = match allowed_workspaces {
+                    Some(allowed) => thread_workspace
+                        .as_deref()
+                        .is_some_and(|id| allowed.contains(id)),
+                    None => true,
+                };
+                let wanted = requested && allowed;
+                let position = if wanted && results.len() < limit {
+                    results.push(ThreadSearchResult {
+                        thread_id: thread_id.clone(),
+                        workspace_id: thread_workspace,
+                        cwd,
+                        preview,
+                        started_at,
+                        updated_at,
+                        matches: Vec::new(),
+                    });
+                    Some(results.len() - 1)
+                } else {
+                    None
+                };
+                positions.insert(thread_id, position);
+                position
+            }
+        };
+        if let Some(result) = position.and_then(|index| results.get_mut(index)) {
+            if result.matches.len() < MAX_MATCHES_PER_THREAD {
+                result.matches.push(found);
+            }
+        }
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+    use std::io::Write;
+    use uuid::Uuid;
+
+    fn temp_dir(label: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("codexmonitor-{label}-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).expect("create temp dir");
+        dir
+    }
+
+    fn append_lines(path: &Path, lines: &[Value]) {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .expect("open session log");
+        for line in lines {
+            writeln!(file, "{line}").expect("write session line");
+        }
+    }
+
+    fn search(
+        conn: &Connection,
+        query: &str,
+        workspace_id: Option<&str>,
+    ) -> Vec<ThreadSearchResult> {
+        let workspace_paths = vec![("ws-1".to_string(), PathBuf::from("/work/app"))];
+        search_index(
+            conn,
+            &build_fts_query(query).expect("query"),
+            workspace_id,
+            None,
+            &workspace_paths,
+            10,
+        )
+        .expect("search")
+    }
+
+    #[test]
+    fn build_fts_query_quotes_terms_and_prefixes_last() {
+        assert_eq!(
+            build_fts_query("fix \"flaky\" tes").as_deref(),
+            Some("\"fix\" \"flaky\" \"tes\"*")
+        );
+        assert_eq!(build_fts_query("done ").as_deref(), Some("\"done\""));
+        assert_eq!(build_fts_query("  "), None);
+    }
+
+    #[test]
+    fn refresh_indexes_messages_tool_calls_and_files_incrementally() {
+        let root = temp_dir("thread-search-sessions");
+        let data_dir = temp_dir("thread-search-data");
+        let day_dir = root.join("2026").join("01").join("19");
+        fs::create_dir_all(&day_dir).expect("create day dir");
+        let log = day_dir.join("rollout-2026-01-19T10-00-00-thread-a.jsonl");
+        append_lines(
+            &log,
+            &[
+                json!({ "timestamp": "2026-01-19T10:00:00Z", "type": "session_meta", "payload": { "id": "thread-a", "cwd": "/work/app/src" } }),
+                json!({ "timestamp": "2026-01-19T10:00:01Z", "type": "event_msg", "payload": { "type": "user_message", "message": "Why does the websocket reconnect loop spin?" } }),
+                json!({ "timestamp": "2026-01-19T10:00:02Z", "type": "response_item", "payload": { "type": "function_call", "name": "shell", "arguments": "{\"command\":[\"rg\",\"reconnect_backoff\"]}" } }),
+                json!({ "timestamp": "2026-01-19T10:00:03Z", "type": "response_item", "payload": { "type": "custom_tool_call", "name": "apply_patch", "input": "*** Begin Patch\n*** Update File: src/transport.rs\n@@\n-old\n+new\n*** End Patch" } }),
+            ],
+        );
+
+        let mut conn = open_index(&index_path(&data_dir)).expect("open index");
+        assert_eq!(
+            refresh_index(&mut conn, std::slice::from_ref(&root)).expect("refresh"),
+            1
+        );
+
+        let results = search(&conn, "websocket reconn", None);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].thread_id, "thread-a");
+        assert_eq!(results[0].workspace_id.as_deref(), Some("ws-1"));
+        assert_eq!(
+            results[0].preview.as_deref(),
+            Some("Why does the websocket reconnect loop spin?")
+        );
+        assert_eq!(results[0].matches[0].kind, "user");
+        assert!(results[0].matches[0]
+            .snippet
+            .contains("<mark>websocket</mark>"));
+
+        let results = search(&conn, "reconnect_backoff", None);
+        assert_eq!(results[0].matches[0].kind, "tool_call");
+        let results = search(&conn, "src/transport.rs", None);
+        assert!(results[0].matches.iter().any(|found| found.kind == "file"));
+        assert!(search(&conn, "websocket", Some("ws-2")).is_empty());
+
+        append_lines(
+            &log,
+            &[
+                json!({ "timestamp": "2026-01-19T10:05:00Z", "type": "event_msg", "payload": { "type": "agent_message", "message": "The jitter was missing from the backoff." } }),
+            ],
+        );
+        refresh_index(&mut conn, std::slice::from_ref(&root)).expect("refresh again");
+        let results = search(&conn, "jitter", None);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].matches[0].kind, "assistant");
+        assert_eq!(search(&conn, "websocket", None)[0].matches.len(), 1);
+
+        fs::remove_file(&log).expect("remove log");
+        refresh_index(&mut conn, std::slice::from_ref(&root)).expect("refresh after delete");
+        assert!(search(&conn, "jitter", None).is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&data_dir);
+    }
+
+    #[test]
+    fn refresh_leaves_partial_lines_for_later() {
+        let root = temp_dir("thread-search-partial");
+        let data_dir = temp_dir("thread-search-partial-data");
+        let log = root.join("rollout-thread-b.jsonl");
+        fs::write(
+            &log,
+            "{\"type\":\"event_msg\",\"payload\":{\"type\":\"agent_message\",\"message\":\"halfway",
+        )
+        .expect("write partial line");
+
+        let mut conn = open_index(&index_path(&data_dir)).expect("open index");
+        refresh_index(&mut conn, std::slice::from_ref(&root)).expect("refresh");
+        assert!(search(&conn, "halfway", None).is_empty());
+
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(&log)
+            .expect("open log");
+        writeln!(file, " there\"}}}}").expect("finish line");
+        refresh_index(&mut conn, std::slice::from_ref(&root)).expect("refresh again");
+        let results = search(&conn, "halfway", None);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].thread_id, "rollout-thread-b");
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&data_dir);
+    }
+
+    #[test]
+    fn search_applies_the_workspace_scope_before_the_limit() {
+        let root = temp_dir("thread-search-scope");
+        let data_dir = temp_dir("thread-search-scope-data");
+        for (thread, cwd) in [("thread-a", "/work/app"), ("thread-b", "/work/api")] {
+            append_lines(
+                &root.join(format!("rollout-{thread}.jsonl")),
+                &[
+                    json!({ "type": "session_meta", "payload": { "id": thread, "cwd": cwd } }),
+                    json!({ "type": "event_msg", "payload": { "type": "user_message", "message": "rotate the signing keys" } }),
+                ],
+            );
+        }
+        let mut conn = open_index(&index_path(&data_dir)).expect("open index");
+        refresh_index(&mut conn, std::slice::from_ref(&root)).expect("refresh");
+
+        let workspace_paths = vec![
+            ("ws-1".to_string(), PathBuf::from("/work/app")),
+            ("ws-2".to_string(), PathBuf::from("/work/api")),
+        ];
+        let fts_query = build_fts_query("signing").expect("query");
+        for allowed in ["ws-1", "ws-2"] {
+            let allowed_workspaces = HashSet::from([allowed.to_string()]);
+            let results = search_index(
+                &conn,
+                &fts_query,
+                None,
+                Some(&allowed_workspaces),
+                &workspace_paths,
+                1,
+            )
+            .expect("search");
+            assert_eq!(results.len(), 1);
+            assert_eq!(results[0].workspace_id.as_deref(), Some(allowed));
+        }
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&data_dir);
+    }
+}
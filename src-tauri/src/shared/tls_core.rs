@@ -0,0 +1,128 @@
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
+
+const FINGERPRINT_PREFIX: &str = "sha256:";
+
+/// SHA-256 over the DER encoding, formatted as colon-separated uppercase hex.
+pub(crate) fn certificate_fingerprint(der: &[u8]) -> String {
+    let digest = Sha256::digest(der);
+    digest
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Accepts `AB:CD:...`, `abcd...` or `sha256:...` and returns the canonical form.
+#[allow(dead_code)]
+pub(crate) fn normalize_fingerprint(value: &str) -> Option<String> {
+    let trimmed = value.trim();
+    let without_prefix = if trimmed
+        .get(..FINGERPRINT_PREFIX.len())
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(FINGERPRINT_PREFIX))
+    {
+        &trimmed[FINGERPRINT_PREFIX.len()..]
+    } else {
+        trimmed
+    };
+    let hex: String = without_prefix
+        .chars()
+        .filter(|ch| !matches!(ch, ':' | ' ' | '-'))
+        .collect();
+    if hex.len() != 64 || !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return None;
+    }
+    let upper = hex.to_ascii_uppercase();
+    Some(
+        upper
+            .as_bytes()
+            .chunks(2)
+            .map(|pair| String::from_utf8_lossy(pair).into_owned())
+            .collect::<Vec<_>>()
+            .join(":"),
+    )
+}
+
+pub(crate) fn load_certificates(path: &Path) -> Result<Vec<CertificateDer<'static>>, String> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .map_err(|err| format!("Failed to read certificates from {}: {err}", path.display()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| format!("Failed to parse certificates in {}: {err}", path.display()))?;
+    if certs.is_empty() {
+        return Err(format!("No certificates found in {}", path.display()));
+    }
+    Ok(certs)
+}
+
+pub(crate) fn load_private_key(path: &Path) -> Result<PrivateKeyDer<'static>, String> {
+    PrivateKeyDer::from_pem_file(path)
+        .map_err(|err| format!("Failed to read private key from {}: {err}", path.display()))
+}
+
+/// Extracts the name used for SNI and certificate validation from `host:port`.
+#[allow(dead_code)]
+pub(crate) fn server_name_for_host(host: &str) -> Result<ServerName<'static>, String> {
+    let trimmed = host.trim();
+    let name = if let Some(rest) = trimmed.strip_prefix('[') {
+        rest.split(']').next().unwrap_or(rest)
+    } else {
+        match trimmed.rsplit_once(':') {
+            Some((name, port)) if !name.contains(':') && port.parse::<u16>().is_ok() => name,
+            _ => trimmed,
+        }
+    };
+    ServerName::try_from(name.to_string())
+        .map_err(|err| format!("Invalid host for TLS ({host}): {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{certificate_fingerprint, normalize_fingerprint, server_name_for_host};
+    use tokio_rustls::rustls::pki_types::ServerName;
+
+    #[test]
+    fn fingerprint_is_colon_separated_uppercase_hex() {
+        let fingerprint = certificate_fingerprint(b"");
+        assert_eq!(
+            fingerprint,
+            "E3:B0:C4:42:98:FC:1C:14:9A:FB:F4:C8:99:6F:B9:24:27:AE:41:E4:64:9B:93:4C:A4:95:99:1B:78:52:B8:55"
+        );
+        assert_eq!(normalize_fingerprint(&fingerprint), Some(fingerprint));
+    }
+
+    #[test]
+    fn normalize_fingerprint_accepts_common_spellings() {
+        let expected = certificate_fingerprint(b"");
+        assert_eq!(
+            normalize_fingerprint(
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+            ),
+            Some(expected.clone())
+        );
+        assert_eq!(
+            normalize_fingerprint(&format!("SHA256:{}", expected.to_ascii_lowercase())),
+            Some(expected)
+        );
+        assert_eq!(normalize_fingerprint("E3:B0"), None);
+        assert_eq!(normalize_fingerprint(&"zz".repeat(32)), None);
+    }
+
+    #[test]
+    fn server_name_strips_port_and_brackets() {
+        assert_eq!(
+            server_name_for_host("daemon.example:4732").expect("dns name"),
+            ServerName::try_from("daemon.example").expect("name")
+        );
+        assert_eq!(
+            server_name_for_host("[::1]:4732").expect("ipv6"),
+            ServerName::try_from("::1").expect("name")
+        );
+        assert_eq!(
+            server_name_for_host("100.64.0.1:4732").expect("ipv4"),
+            ServerName::try_from("100.64.0.1").expect("name")
+        );
+    }
+}
@@ -0,0 +1,570 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::backend::events::EventSink;
+use crate::shared::local_usage_core::{self, UsageAmount};
+use crate::storage::{read_settings, read_workspaces};
+use crate::types::{
+    AppSettings, UsageBudget, UsageBudgetAlert, UsageBudgetMetric, UsageBudgetPeriod,
+    UsageBudgetStatus, WorkspaceEntry,
+};
+
+const STATE_FILE: &str = "usage-budgets.json";
+/// Days scanned per evaluation; enough to cover a week that started on Monday.
+const SCAN_DAYS: u32 = 7;
+/// How long `ensure_send_allowed` trusts the last evaluation before scanning again.
+const STATUS_MAX_AGE_MS: i64 = 60 * 1000;
+
+/// Evaluates the `usageBudgets` setting against local usage. Budgets are read from
+/// `settings.json` on every evaluation, so edits made by the app or the daemon apply
+/// immediately. Alerted thresholds and overrides are kept per period in `usage-budgets.json`.
+pub(crate) struct UsageBudgetTracker {
+    data_dir: PathBuf,
+    inner: std::sync::Mutex<TrackerInner>,
+}
+
+#[derive(Default)]
+struct TrackerInner {
+    state: Option<TrackerState>,
+    statuses: Vec<UsageBudgetStatus>,
+    evaluated_at_ms: Option<i64>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+struct TrackerState {
+    #[serde(default)]
+    budgets: HashMap<String, BudgetPeriodState>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BudgetPeriodState {
+    period_start: String,
+    #[serde(default)]
+    notified: Vec<u32>,
+    #[serde(default)]
+    overridden: bool,
+}
+
+impl UsageBudgetTracker {
+    pub(crate) fn new(data_dir: &Path) -> Self {
+        Self {
+            data_dir: data_dir.to_path_buf(),
+            inner: std::sync::Mutex::new(TrackerInner::default()),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, TrackerInner> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn state_path(&self) -> PathBuf {
+        self.data_dir.join(STATE_FILE)
+    }
+
+    fn load_state(&self, inner: &mut TrackerInner) {
+        if inner.state.is_none() {
+            let state = std::fs::read_to_string(self.state_path())
+                .ok()
+                .and_then(|data| serde_json::from_str(&data).ok())
+                .unwrap_or_default();
+            inner.state = Some(state);
+        }
+    }
+
+    fn save_state(&self, state: &TrackerState) -> Result<(), String> {
+        std::fs::create_dir_all(&self.data_dir).map_err(|err| err.to_string())?;
+        let data = serde_json::to_string_pretty(state).map_err(|err| err.to_string())?;
+        std::fs::write(self.state_path(), data).map_err(|err| err.to_string())
+    }
+
+    fn settings(&self) -> Result<AppSettings, String> {
+        read_settings(&self.data_dir.join("settings.json"))
+    }
+
+    /// Scans the current periods and returns every budget's status plus the alerts for
+    /// thresholds crossed since the last evaluation. Blocking; run it off the async runtime.
+    pub(crate) fn evaluate(
+        &self,
+    ) -> Result<(Vec<UsageBudgetStatus>, Vec<UsageBudgetAlert>), String> {
+        let settings = self.settings()?;
+        let workspaces = read_workspaces(&self.data_dir.join("workspaces.json"))?;
+        let budgets = settings.usage_budgets;
+        let usage = if budgets.is_empty() {
+            HashMap::new()
+        } else {
+            local_usage_core::scan_daily_workspace_usage(
+                SCAN_DAYS,
+                &workspaces,
+                &settings.model_prices,
+                Some(&local_usage_core::cache_path(&self.data_dir)),
+            )?
+        };
+
+        let mut inner = self.lock();
+        self.load_state(&mut inner);
+        let state = inner.state.get_or_insert_with(TrackerState::default);
+        let before = state.clone();
+        let (statuses, alerts) = apply_budgets(
+            &budgets,
+            &usage,
+            &workspaces,
+            Local::now().date_naive(),
+            state,
+        );
+        if *state != before {
+            let state = state.clone();
+            if let Err(err) = self.save_state(&state) {
+                eprintln!("usage budgets: failed to save state: {err}");
+            }
+        }
+        inner.statuses = statuses.clone();
+        inner.evaluated_at_ms = Some(now_ms());
+        Ok((statuses, alerts))
+    }
+
+    /// Evaluates and emits `usage-budget-alert` for each newly crossed threshold.
+    pub(crate) fn check(
+        &self,
+        event_sink: &impl EventSink,
+    ) -> Result<Vec<UsageBudgetStatus>, String> {
+        let (statuses, alerts) = self.evaluate()?;
+        for alert in alerts {
+            event_sink.emit_usage_budget_alert(alert);
+        }
+        Ok(statuses)
+    }
+
+    fn find_budget(&self, budget_id: &str) -> Result<UsageBudget, String> {
+        self.settings()?
+            .usage_budgets
+            .into_iter()
+            .find(|budget| budget.id == budget_id)
+            .ok_or_else(|| format!("Unknown usage budget: {budget_id}"))
+    }
+
+    /// Lets messages through a blocking budget for the rest of its current period.
+    pub(crate) fn override_budget(&self, budget_id: &str) -> Result<(), String> {
+        let budget = self.find_budget(budget_id)?;
+        let period_start = period_start(budget.period, Local::now().date_naive());
+
+        let mut inner = self.lock();
+        self.load_state(&mut inner);
+        let state = inner.state.get_or_insert_with(TrackerState::default);
+        let entry = state.budgets.entry(budget.id.clone()).or_default();
+        if entry.period_start != period_start {
+            *entry = BudgetPeriodState {
+                period_start,
+                ..Default::default()
+            };
+        }
+        entry.overridden = true;
+        let state = state.clone();
+        for status in inner
+            .statuses
+            .iter_mut()
+            .filter(|status| status.budget_id == budget_id)
+        {
+            status.overridden = true;
+            status.blocking = false;
+        }
+        self.save_state(&state)
+    }
+}
+
+pub(crate) async fn usage_budget_status_core(
+    tracker: &Arc<UsageBudgetTracker>,
+    event_sink: impl EventSink,
+) -> Result<Vec<UsageBudgetStatus>, String> {
+    let tracker = Arc::clone(tracker);
+    tokio::task::spawn_blocking(move || tracker.check(&event_sink))
+        .await
+        .map_err(|err| err.to_string())?
+}
+
+/// The workspace a budget counts (`None` for global budgets), so callers can authorize an
+/// override before applying it.
+pub(crate) async fn usage_budget_workspace_core(
+    tracker: &Arc<UsageBudgetTracker>,
+    budget_id: String,
+) -> Result<Option<String>, String> {
+    let budget_id = budget_id.trim().to_string();
+    let tracker = Arc::clone(tracker);
+    tokio::task::spawn_blocking(move || tracker.find_budget(&budget_id))
+        .await
+        .map_err(|err| err.to_string())?
+        .map(|budget| budget.workspace_id)
+}
+
+pub(crate) async fn override_usage_budget_core(
+    tracker: &Arc<UsageBudgetTracker>,
+    budget_id: String,
+) -> Result<(), String> {
+    let budget_id = budget_id.trim().to_string();
+    if budget_id.is_empty() {
+        return Err("budgetId is required".to_string());
+    }
+    let tracker = Arc::clone(tracker);
+    tokio::task::spawn_blocking(move || tracker.override_budget(&budget_id))
+        .await
+        .map_err(|err| err.to_string())?
+}
+
+/// Re-evaluates budgets in the background, e.g. after a usage scan; errors are only logged.
+pub(crate) fn spawn_usage_budget_check(
+    tracker: &Arc<UsageBudgetTracker>,
+    event_sink: impl EventSink,
+) {
+    let tracker = Arc::clone(tracker);
+    tokio::task::spawn_blocking(move || {
+        if let Err(err) = tracker.check(&event_sink) {
+            eprintln!("usage budgets: evaluation failed: {err}");
+        }
+    });
+}
+
+/// Refuses a new message when a blocking budget covers `workspace_id`. Reuses the last
+/// evaluation while it is recent so sending stays cheap; if usage cannot be read, the
+/// message goes through.
+pub(crate) async fn ensure_send_allowed(
+    tracker: &Arc<UsageBudgetTracker>,
+    workspace_id: &str,
+    event_sink: impl EventSink,
+) -> Result<(), String> {
+    let blocks = tracker.settings().is_ok_and(|settings| {
+        settings
+            .usage_budgets
+            .iter()
+            .any(|budget| budget.block_at_limit)
+    });
+    if !blocks {
+        return Ok(());
+    }
+    let cached = {
+        let inner = tracker.lock();
+        inner
+            .evaluated_at_ms
+            .filter(|evaluated_at| now_ms() - evaluated_at < STATUS_MAX_AGE_MS)
+            .map(|_| inner.statuses.clone())
+    };
+    let statuses = match cached {
+        Some(statuses) => statuses,
+        None => match usage_budget_status_core(tracker, event_sink).await {
+            Ok(statuses) => statuses,
+            Err(err) => {
+                eprintln!("usage budgets: evaluation failed: {err}");
+                return Ok(());
+            }
+        },
+    };
+    match blocking_status(&statuses, workspace_id) {
+        Some(status) => Err(format!(
+            "Usage budget \"{}\" reached {} of its {} limit. Override the budget to keep sending messages.",
+            status.budget_id,
+            format_amount(status.metric, status.used),
+            format_amount(status.metric, status.limit),
+        )),
+        None => Ok(()),
+    }
+}
+
+fn blocking_status<'a>(
+    statuses: &'a [UsageBudgetStatus],
+    workspace_id: &str,
+) -> Option<&'a UsageBudgetStatus> {
+    statuses.iter().find(|status| {
+        status.blocking
+            && status
+                .workspace_id
+                .as_deref()
+                .is_none_or(|id| id == workspace_id)
+    })
+}
+
+/// Computes statuses for `budgets` on `today` and records newly crossed thresholds in
+/// `state`. Each budget raises at most one alert per call, for its highest new threshold.
+fn apply_budgets(
+    budgets: &[UsageBudget],
+    usage: &HashMap<(String, Option<String>), UsageAmount>,
+    workspaces: &HashMap<String, WorkspaceEntry>,
+    today: NaiveDate,
+    state: &mut TrackerState,
+) -> (Vec<UsageBudgetStatus>, Vec<UsageBudgetAlert>) {
+    state
+        .budgets
+        .retain(|id, _| budgets.iter().any(|budget| &budget.id == id));
+    let today_key = today.format("%Y-%m-%d").to_string();
+
+    let mut statuses = Vec::with_capacity(budgets.len());
+    let mut alerts = Vec::new();
+    for budget in budgets {
+        let period_start = period_start(budget.period, today);
+        let used = usage
+            .iter()
+            .filter(|((day, workspace_id), _)| {
+                *day >= period_start
+                    && *day <= today_key
+                    && budget
+                        .workspace_id
+                        .as_ref()
+                        .is_none_or(|id| workspace_id.as_ref() == Some(id))
+            })
+            .map(|(_, amount)| match budget.metric {
+                UsageBudgetMetric::Tokens => amount.tokens as f64,
+                UsageBudgetMetric::Cost => amount.cost_usd,
+            })
+            .sum::<f64>();
+        let percent = if budget.limit > 0.0 {
+            (used / budget.limit * 1000.0).round() / 10.0
+        } else {
+            0.0
+        };
+
+        let entry = state.budgets.entry(budget.id.clone()).or_default();
+        if entry.period_start != period_start {
+            *entry = BudgetPeriodState {
+                period_start: period_start.clone(),
+                ..Default::default()
+            };
+        }
+        let blocking = budget.block_at_limit && percent >= 100.0 && !entry.overridden;
+        let crossed = budget
+            .thresholds
+            .iter()
+            .copied()
+            .filter(|threshold| percent >= f64::from(*threshold))
+            .filter(|threshold| !entry.notified.contains(threshold))
+            .collect::<Vec<_>>();
+        if let Some(threshold) = crossed.iter().copied().max() {
+            entry.notified.extend(crossed);
+            entry.notified.sort_unstable();
+            alerts.push(UsageBudgetAlert {
+                budget_id: budget.id.clone(),
+                workspace_id: budget.workspace_id.clone(),
+                workspace_name: budget
+                    .workspace_id
+                    .as_ref()
+                    .and_then(|id| workspaces.get(id))
+                    .map(|entry| entry.name.clone()),
+                period: budget.period,
+                metric: budget.metric,
+                threshold,
+                limit: budget.limit,
+                used,
+                percent,
+                blocking,
+            });
+        }
+
+        statuses.push(UsageBudgetStatus {
+            budget_id: budget.id.clone(),
+            workspace_id: budget.workspace_id.clone(),
+            period: budget.period,
+            metric: budget.metric,
+            period_start,
+            limit: budget.limit,
+            used,
+            percent,
+            overridden: entry.overridden,
+            blocking,
+        });
+    }
+    (statuses, alerts)
+}
+
+/// Today for daily budgets, the Monday of this week for weekly ones, as `YYYY-MM-DD`.
+fn period_start(period: UsageBudgetPeriod, today: NaiveDate) -> String {
+    let start = match period {
+        UsageBudgetPeriod::Daily => today,
+        UsageBudgetPeriod::Weekly => {
+            today - Duration::days(i64::from(today.weekday().num_days_from_monday()))
+        }
+    };
+    start.format("%Y-%m-%d").to_string()
+}
+
+fn format_amount(metric: UsageBudgetMetric, value: f64) -> String {
+    match metric {
+        UsageBudgetMetric::Tokens => format!("{} tokens", value.round() as i64),
+        UsageBudgetMetric::Cost => format!("${value:.2}"),
+    }
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn budget(id: &str, period: UsageBudgetPeriod, limit: f64) -> UsageBudget {
+        UsageBudget {
+            id: id.to_string(),
+            workspace_id: None,
+            period,
+            metric: UsageBudgetMetric::Tokens,
+            limit,
+            thresholds: vec![80, 100],
+            block_at_limit: false,
+        }
+    }
+
+    fn usage(
+        entries: &[(&str, Option<&str>, i64)],
+    ) -> HashMap<(String, Option<String>), UsageAmount> {
+        entries
+            .iter()
+            .map(|(day, workspace_id, tokens)| {
+                (
+                    (day.to_string(), workspace_id.map(str::to_string)),
+                    UsageAmount {
+                        tokens: *tokens,
+                        cost_usd: *tokens as f64 / 1000.0,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    fn day(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn period_start_uses_monday_for_weekly_budgets() {
+        // 2026-10-18 is a Sunday.
+        assert_eq!(
+            period_start(UsageBudgetPeriod::Weekly, day("2026-10-18")),
+            "2026-10-12"
+        );
+        assert_eq!(
+            period_start(UsageBudgetPeriod::Weekly, day("2026-10-12")),
+            "2026-10-12"
+        );
+        assert_eq!(
+            period_start(UsageBudgetPeriod::Daily, day("2026-10-18")),
+            "2026-10-18"
+        );
+    }
+
+    #[test]
+    fn apply_budgets_alerts_once_per_threshold_and_period() {
+        let budgets = vec![budget("daily", UsageBudgetPeriod::Daily, 1000.0)];
+        let workspaces = HashMap::new();
+        let mut state = TrackerState::default();
+
+        let (statuses, alerts) = apply_budgets(
+            &budgets,
+            &usage(&[("2026-10-17", None, 5000), ("2026-10-18", None, 850)]),
+            &workspaces,
+            day("2026-10-18"),
+            &mut state,
+        );
+        assert_eq!(statuses[0].used, 850.0);
+        assert_eq!(statuses[0].percent, 85.0);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].threshold, 80);
+
+        let (_, alerts) = apply_budgets(
+            &budgets,
+            &usage(&[("2026-10-18", None, 900)]),
+            &workspaces,
+            day("2026-10-18"),
+            &mut state,
+        );
+        assert!(alerts.is_empty());
+
+        let (_, alerts) = apply_budgets(
+            &budgets,
+            &usage(&[("2026-10-18", None, 1200)]),
+            &workspaces,
+            day("2026-10-18"),
+            &mut state,
+        );
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].threshold, 100);
+
+        let (statuses, alerts) = apply_budgets(
+            &budgets,
+            &usage(&[("2026-10-19", None, 1200)]),
+            &workspaces,
+            day("2026-10-19"),
+            &mut state,
+        );
+        assert_eq!(statuses[0].period_start, "2026-10-19");
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].threshold, 100);
+    }
+
+    #[test]
+    fn apply_budgets_scopes_workspace_budgets_and_blocks_until_overridden() {
+        let mut workspace_budget = budget("ws", UsageBudgetPeriod::Weekly, 1.0);
+        workspace_budget.workspace_id = Some("ws-1".to_string());
+        workspace_budget.metric = UsageBudgetMetric::Cost;
+        workspace_budget.block_at_limit = true;
+        let budgets = vec![workspace_budget];
+        let amounts = usage(&[
+            ("2026-10-11", Some("ws-1"), 9000),
+            ("2026-10-12", Some("ws-1"), 600),
+            ("2026-10-14", Some("ws-1"), 600),
+            ("2026-10-14", Some("ws-2"), 9000),
+            ("2026-10-14", None, 9000),
+        ]);
+        let mut state = TrackerState::default();
+
+        let (statuses, alerts) = apply_budgets(
+            &budgets,
+            &amounts,
+            &HashMap::new(),
+            day("2026-10-15"),
+            &mut state,
+        );
+        assert!((statuses[0].used - 1.2).abs() < 1e-9);
+        assert!(statuses[0].blocking);
+        assert_eq!(alerts[0].threshold, 100);
+        assert!(alerts[0].blocking);
+        assert!(blocking_status(&statuses, "ws-1").is_some());
+        assert!(blocking_status(&statuses, "ws-2").is_none());
+
+        state.budgets.get_mut("ws").unwrap().overridden = true;
+        let (statuses, _) = apply_budgets(
+            &budgets,
+            &amounts,
+            &HashMap::new(),
+            day("2026-10-15"),
+            &mut state,
+        );
+        assert!(statuses[0].overridden);
+        assert!(!statuses[0].blocking);
+    }
+
+    #[test]
+    fn apply_budgets_drops_state_for_removed_budgets() {
+        let mut state = TrackerState::default();
+        state
+            .budgets
+            .insert("gone".to_string(), BudgetPeriodState::default());
+
+        let (statuses, alerts) = apply_budgets(
+            &[],
+            &HashMap::new(),
+            &HashMap::new(),
+            day("2026-10-18"),
+            &mut state,
+        );
+        assert!(statuses.is_empty());
+        assert!(alerts.is_empty());
+        assert!(state.budgets.is_empty());
+    }
+}
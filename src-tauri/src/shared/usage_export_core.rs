@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::path::Path;
+use tokio::sync::Mutex;
+
+use crate::shared::local_usage_core::{self, LocalUsageReport};
+use crate::types::{ModelPrice, WorkspaceEntry};
+
+const CSV_HEADER: &str = "day,model,workspace_id,workspace_name,input_tokens,cached_input_tokens,output_tokens,total_tokens,estimated_cost_usd";
+pub(crate) const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum LocalUsageExportFormat {
+    /// One row per day, model and workspace.
+    Csv,
+    /// Prometheus text exposition format.
+    #[serde(alias = "prom")]
+    Prometheus,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LocalUsageExport {
+    pub(crate) format: LocalUsageExportFormat,
+    pub(crate) days: u32,
+    pub(crate) file_name: String,
+    pub(crate) mime_type: String,
+    pub(crate) content: String,
+}
+
+pub(crate) async fn local_usage_export_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    data_dir: &Path,
+    format: LocalUsageExportFormat,
+    days: Option<u32>,
+    workspace_path: Option<String>,
+    model_prices: Vec<ModelPrice>,
+) -> Result<LocalUsageExport, String> {
+    let report = local_usage_core::local_usage_report_core(
+        workspaces,
+        data_dir,
+        days,
+        workspace_path,
+        model_prices,
+    )
+    .await?;
+    Ok(render_local_usage_export(&report, format))
+}
+
+pub(crate) fn render_local_usage_export(
+    report: &LocalUsageReport,
+    format: LocalUsageExportFormat,
+) -> LocalUsageExport {
+    let (content, extension, mime_type) = match format {
+        LocalUsageExportFormat::Csv => (render_csv(report), "csv", "text/csv; charset=utf-8"),
+        LocalUsageExportFormat::Prometheus => {
+            (render_prometheus(report), "prom", PROMETHEUS_CONTENT_TYPE)
+        }
+    };
+    LocalUsageExport {
+        format,
+        days: report.days,
+        file_name: format!("codex-usage-{}d.{extension}", report.days),
+        mime_type: mime_type.to_string(),
+        content,
+    }
+}
+
+fn render_csv(report: &LocalUsageReport) -> String {
+    let mut out = String::from(CSV_HEADER);
+    out.push('\n');
+    for row in &report.rows {
+        let fields = [
+            csv_field(&row.day),
+            csv_field(&row.model),
+            csv_field(row.workspace_id.as_deref().unwrap_or("")),
+            csv_field(row.workspace_name.as_deref().unwrap_or("")),
+            row.input_tokens.to_string(),
+            row.cached_input_tokens.to_string(),
+            row.output_tokens.to_string(),
+            (row.input_tokens + row.output_tokens).to_string(),
+            format!("{:.4}", row.estimated_cost_usd),
+        ];
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Quotes fields with separators or quotes, and defuses values a spreadsheet would run as
+/// a formula.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// Values are sums over the export window, which drop when old days leave it, so they are
+/// gauges rather than counters: `rate` and `increase` would read each drop as a reset.
+fn render_prometheus(report: &LocalUsageReport) -> String {
+    let mut tokens: BTreeMap<(&str, &str, &str), [i64; 3]> = BTreeMap::new();
+    for row in &report.rows {
+        let entry = tokens
+            .entry((
+                row.model.as_str(),
+                row.workspace_id.as_deref().unwrap_or(""),
+                row.workspace_name.as_deref().unwrap_or(""),
+            ))
+            .or_default();
+        entry[0] += row.input_tokens;
+        entry[1] += row.cached_input_tokens;
+        entry[2] += row.output_tokens;
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "# HELP codex_monitor_usage_window_days Days of session logs covered by the gauges below."
+    );
+    let _ = writeln!(out, "# TYPE codex_monitor_usage_window_days gauge");
+    let _ = writeln!(out, "codex_monitor_usage_window_days {}", report.days);
+    let _ = writeln!(
+        out,
+        "# HELP codex_monitor_tokens Tokens recorded in Codex session logs over the window. cached_input is a subset of input."
+    );
+    let _ = writeln!(out, "# TYPE codex_monitor_tokens gauge");
+    for ((model, workspace_id, workspace_name), values) in &tokens {
+        for (kind, value) in ["input", "cached_input", "output"].iter().zip(values) {
+            let _ = writeln!(
+                out,
+                "codex_monitor_tokens{{type=\"{kind}\",model=\"{}\",workspace_id=\"{}\",workspace=\"{}\"}} {value}",
+                label_value(model),
+                label_value(workspace_id),
+                label_value(workspace_name),
+            );
+        }
+    }
+    let _ = writeln!(
+        out,
+        "# HELP codex_monitor_agent_runs Agent runs recorded in Codex session logs over the window."
+    );
+    let _ = writeln!(out, "# TYPE codex_monitor_agent_runs gauge");
+    let _ = writeln!(out, "codex_monitor_agent_runs {}", report.agent_runs);
+    let _ = writeln!(
+        out,
+        "# HELP codex_monitor_agent_time_seconds Time agents spent working over the window, from session log activity."
+    );
+    let _ = writeln!(out, "# TYPE codex_monitor_agent_time_seconds gauge");
+    let _ = writeln!(
+        out,
+        "codex_monitor_agent_time_seconds {}",
+        report.agent_time_ms as f64 / 1000.0
+    );
+    out
+}
+
+fn label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::local_usage_core::LocalUsageRow;
+
+    fn report() -> LocalUsageReport {
+        LocalUsageReport {
+            days: 7,
+            rows: vec![
+                LocalUsageRow {
+                    day: "2026-10-17".to_string(),
+                    model: "gpt-5".to_string(),
+                    workspace_id: Some("ws-1".to_string()),
+                    workspace_name: Some("Acme, \"web\"".to_string()),
+                    input_tokens: 100,
+                    cached_input_tokens: 40,
+                    output_tokens: 10,
+                    estimated_cost_usd: 0.0125,
+                },
+                LocalUsageRow {
+                    day: "2026-10-18".to_string(),
+                    model: "gpt-5".to_string(),
+                    workspace_id: Some("ws-1".to_string()),
+                    workspace_name: Some("Acme, \"web\"".to_string()),
+                    input_tokens: 50,
+                    cached_input_tokens: 0,
+                    output_tokens: 5,
+                    estimated_cost_usd: 0.0,
+                },
+                LocalUsageRow {
+                    day: "2026-10-18".to_string(),
+                    model: "=cmd".to_string(),
+                    workspace_id: None,
+                    workspace_name: None,
+                    input_tokens: 1,
+                    cached_input_tokens: 0,
+                    output_tokens: 2,
+                    estimated_cost_usd: 0.0,
+                },
+            ],
+            agent_runs: 3,
+            agent_time_ms: 90_500,
+        }
+    }
+
+    #[test]
+    fn csv_has_one_escaped_row_per_day_model_and_workspace() {
+        let export = render_local_usage_export(&report(), LocalUsageExportFormat::Csv);
+        let lines: Vec<&str> = export.content.lines().collect();
+
+        assert_eq!(export.file_name, "codex-usage-7d.csv");
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            "2026-10-17,gpt-5,ws-1,\"Acme, \"\"web\"\"\",100,40,10,110,0.0125"
+        );
+        assert_eq!(lines[3], "2026-10-18,'=cmd,,,1,0,2,3,0.0000");
+        assert_eq!(lines.len(), 4);
+    }
+
+    #[test]
+    fn prometheus_sums_tokens_per_model_and_workspace() {
+        let export = render_local_usage_export(&report(), LocalUsageExportFormat::Prometheus);
+
+        assert_eq!(export.mime_type, PROMETHEUS_CONTENT_TYPE);
+        assert!(export
+            .content
+            .contains("codex_monitor_usage_window_days 7\n"));
+        assert!(export.content.contains(
+            "codex_monitor_tokens{type=\"input\",model=\"gpt-5\",workspace_id=\"ws-1\",workspace=\"Acme, \\\"web\\\"\"} 150\n"
+        ));
+        assert!(export.content.contains(
+            "codex_monitor_tokens{type=\"cached_input\",model=\"gpt-5\",workspace_id=\"ws-1\",workspace=\"Acme, \\\"web\\\"\"} 40\n"
+        ));
+        assert!(export.content.contains(
+            "codex_monitor_tokens{type=\"output\",model=\"=cmd\",workspace_id=\"\",workspace=\"\"} 2\n"
+        ));
+        assert!(export.content.contains("codex_monitor_agent_runs 3\n"));
+        assert!(export
+            .content
+            .contains("codex_monitor_agent_time_seconds 90.5\n"));
+        assert_eq!(
+            export
+                .content
+                .matches("# TYPE codex_monitor_tokens gauge")
+                .count(),
+            1
+        );
+        assert!(!export.content.contains(" counter\n"));
+        assert!(!export.content.contains("_total"));
+    }
+}
@@ -2,7 +2,7 @@ use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::types::WorkspaceSettings;
+use crate::types::{WorkspaceSettings, WorktreeLandStrategy};
 
 #[allow(dead_code)]
 pub(crate) fn to_params<T: Serialize>(request: &T) -> Result<Value, String> {
@@ -60,6 +60,15 @@ pub(crate) struct WorkspaceIdRequest {
     pub(crate) workspace_id: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LandWorktreeRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) strategy: WorktreeLandStrategy,
+    pub(crate) message: Option<String>,
+    pub(crate) remove_worktree: Option<bool>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct IdRequest {
     pub(crate) id: String,
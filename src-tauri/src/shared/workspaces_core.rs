@@ -11,7 +11,10 @@ pub(crate) use crud_persistence::{
     add_clone_core, add_workspace_core, add_workspace_from_git_url_core, remove_workspace_core,
     update_workspace_settings_core,
 };
-pub(crate) use git_orchestration::{apply_worktree_changes_core, run_git_command_unit};
+pub(crate) use git_orchestration::{
+    apply_worktree_changes_core, land_worktree_core, preview_land_worktree_core,
+    run_git_command_unit,
+};
 pub(crate) use helpers::{is_workspace_path_dir_core, list_workspaces_core};
 pub(crate) use io::{
     get_open_app_icon_core, list_workspace_files_core, open_workspace_in_core,
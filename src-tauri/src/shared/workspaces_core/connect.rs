@@ -180,6 +180,9 @@ mod tests {
             owner_workspace_id: "test-owner".to_string(),
             workspace_ids: Mutex::new(HashSet::from(["test-owner".to_string()])),
             workspace_roots: Mutex::new(HashMap::new()),
+            approval_policy: None,
+            thread_access_modes: Mutex::new(HashMap::new()),
+            item_file_changes: Mutex::new(HashMap::new()),
         })
     }
 
@@ -3,13 +3,17 @@ use std::future::Future;
 use std::path::PathBuf;
 use std::process::Stdio;
 
+use git2::{Oid, Repository, RepositoryState, Sort};
 use tokio::io::AsyncWriteExt;
 use tokio::sync::Mutex;
 
-use crate::git_utils::resolve_git_root;
+use crate::git_utils::{commit_to_entry, resolve_git_root};
 use crate::shared::process_core::tokio_command;
-use crate::shared::{git_core, worktree_core};
-use crate::types::WorkspaceEntry;
+use crate::shared::rpc_error::{RpcError, RpcErrorCode};
+use crate::shared::{git_core, git_ui_core, worktree_core};
+use crate::types::{
+    AppSettings, WorkspaceEntry, WorktreeLandPreview, WorktreeLandResult, WorktreeLandStrategy,
+};
 
 pub(crate) fn run_git_command_unit<F, Fut>(
     repo_path: &PathBuf,
@@ -32,47 +36,59 @@ where
     }
 }
 
+async fn worktree_and_parent(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: &str,
+) -> Result<(WorkspaceEntry, WorkspaceEntry), String> {
+    let workspaces = workspaces.lock().await;
+    let entry = workspaces
+        .get(workspace_id)
+        .cloned()
+        .ok_or_else(|| "workspace not found".to_string())?;
+    if !entry.kind.is_worktree() {
+        return Err("Not a worktree workspace.".to_string());
+    }
+    let parent_id = entry
+        .parent_id
+        .clone()
+        .ok_or_else(|| "worktree parent not found".to_string())?;
+    let parent = workspaces
+        .get(&parent_id)
+        .cloned()
+        .ok_or_else(|| "worktree parent not found".to_string())?;
+    Ok((entry, parent))
+}
+
+async fn has_uncommitted_changes(root: &PathBuf) -> Result<bool, String> {
+    let status = git_core::run_git_command_bytes(root, &["status", "--porcelain"]).await?;
+    Ok(!String::from_utf8_lossy(&status).trim().is_empty())
+}
+
 pub(crate) async fn apply_worktree_changes_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<(), String> {
-    let (entry, parent) = {
-        let workspaces = workspaces.lock().await;
-        let entry = workspaces
-            .get(&workspace_id)
-            .cloned()
-            .ok_or_else(|| "workspace not found".to_string())?;
-        if !entry.kind.is_worktree() {
-            return Err("Not a worktree workspace.".to_string());
-        }
-        let parent_id = entry
-            .parent_id
-            .clone()
-            .ok_or_else(|| "worktree parent not found".to_string())?;
-        let parent = workspaces
-            .get(&parent_id)
-            .cloned()
-            .ok_or_else(|| "worktree parent not found".to_string())?;
-        (entry, parent)
-    };
-
+) -> Result<(), RpcError> {
+    let (entry, parent) = worktree_and_parent(workspaces, &workspace_id).await?;
     apply_worktree_changes_inner_core(&entry, &parent).await
 }
 
+/// Refusals caused by the state of either checkout (uncommitted changes, nothing to apply,
+/// a patch that conflicts) are `Conflict`; a failing git command is `GitFailure`.
 pub(super) async fn apply_worktree_changes_inner_core(
     entry: &WorkspaceEntry,
     parent: &WorkspaceEntry,
-) -> Result<(), String> {
+) -> Result<(), RpcError> {
     let worktree_root = resolve_git_root(entry)?;
     let parent_root = resolve_git_root(parent)?;
 
-    let parent_status =
-        git_core::run_git_command_bytes(&parent_root, &["status", "--porcelain"]).await?;
-    if !String::from_utf8_lossy(&parent_status).trim().is_empty() {
-        return Err(
-            "Your current branch has uncommitted changes. Please commit, stash, or discard them before applying worktree changes."
-                .to_string(),
-        );
+    if has_uncommitted_changes(&parent_root)
+        .await
+        .map_err(git_failure)?
+    {
+        return Err(RpcError::new(
+            RpcErrorCode::Conflict,
+            "Your current branch has uncommitted changes. Please commit, stash, or discard them before applying worktree changes.",
+        ));
     }
 
     let mut patch: Vec<u8> = Vec::new();
@@ -80,17 +96,21 @@ pub(super) async fn apply_worktree_changes_inner_core(
         &worktree_root,
         &["diff", "--binary", "--no-color", "--cached"],
     )
-    .await?;
+    .await
+    .map_err(git_failure)?;
     patch.extend_from_slice(&staged_patch);
     let unstaged_patch =
-        git_core::run_git_diff(&worktree_root, &["diff", "--binary", "--no-color"]).await?;
+        git_core::run_git_diff(&worktree_root, &["diff", "--binary", "--no-color"])
+            .await
+            .map_err(git_failure)?;
     patch.extend_from_slice(&unstaged_patch);
 
     let untracked_output = git_core::run_git_command_bytes(
         &worktree_root,
         &["ls-files", "--others", "--exclude-standard", "-z"],
     )
-    .await?;
+    .await
+    .map_err(git_failure)?;
     for raw_path in untracked_output.split(|byte| *byte == 0) {
         if raw_path.is_empty() {
             continue;
@@ -108,12 +128,16 @@ pub(super) async fn apply_worktree_changes_inner_core(
                 &path,
             ],
         )
-        .await?;
+        .await
+        .map_err(git_failure)?;
         patch.extend_from_slice(&diff);
     }
 
     if String::from_utf8_lossy(&patch).trim().is_empty() {
-        return Err("No changes to apply.".to_string());
+        return Err(RpcError::new(
+            RpcErrorCode::Conflict,
+            "No changes to apply.",
+        ));
     }
 
     let git_bin =
@@ -152,21 +176,450 @@ pub(super) async fn apply_worktree_changes_inner_core(
         stderr.trim()
     };
     if detail.is_empty() {
-        return Err("Git apply failed.".to_string());
+        return Err(git_failure("Git apply failed.".to_string()));
     }
 
     if detail.contains("Applied patch to") {
         if detail.contains("with conflicts") {
-            return Err(
-                "Applied with conflicts. Resolve conflicts in the parent repo before retrying."
-                    .to_string(),
-            );
+            return Err(RpcError::new(
+                RpcErrorCode::Conflict,
+                "Applied with conflicts. Resolve conflicts in the parent repo before retrying.",
+            ));
+        }
+        return Err(RpcError::new(
+            RpcErrorCode::Conflict,
+            "Patch applied partially. Resolve changes in the parent repo before retrying.",
+        ));
+    }
+
+    Err(git_failure(detail.to_string()))
+}
+
+fn git_failure(message: String) -> RpcError {
+    RpcError::new(RpcErrorCode::GitFailure, message)
+}
+
+struct LandContext {
+    worktree_root: PathBuf,
+    parent_root: PathBuf,
+    worktree_branch: String,
+    parent_branch: String,
+}
+
+fn current_branch(root: &PathBuf, label: &str) -> Result<(String, Oid), String> {
+    let repo = Repository::open(root).map_err(|e| e.to_string())?;
+    let head = repo.head().map_err(|e| e.to_string())?;
+    let branch = head
+        .shorthand()
+        .filter(|_| head.is_branch())
+        .ok_or_else(|| format!("The {label} is not on a branch."))?
+        .to_string();
+    let tip = head
+        .target()
+        .ok_or_else(|| format!("The {label} has no commits."))?;
+    Ok((branch, tip))
+}
+
+fn land_context(entry: &WorkspaceEntry, parent: &WorkspaceEntry) -> Result<LandContext, String> {
+    let worktree_root = resolve_git_root(entry)?;
+    let parent_root = resolve_git_root(parent)?;
+    let (worktree_branch, _) = current_branch(&worktree_root, "worktree")?;
+    let (parent_branch, _) = current_branch(&parent_root, "parent checkout")?;
+    if worktree_branch == parent_branch {
+        return Err("The worktree and its parent are on the same branch.".to_string());
+    }
+    Ok(LandContext {
+        worktree_root,
+        parent_root,
+        worktree_branch,
+        parent_branch,
+    })
+}
+
+/// Both checkouts share one object database, so the parent repository sees the
+/// worktree branch too.
+fn branch_tips(context: &LandContext) -> Result<(Repository, Oid, Oid), String> {
+    let repo = Repository::open(&context.parent_root).map_err(|e| e.to_string())?;
+    let tip = |branch: &str| {
+        repo.revparse_single(&format!("refs/heads/{branch}"))
+            .map(|object| object.id())
+            .map_err(|e| e.to_string())
+    };
+    let worktree_tip = tip(&context.worktree_branch)?;
+    let parent_tip = tip(&context.parent_branch)?;
+    Ok((repo, worktree_tip, parent_tip))
+}
+
+pub(crate) async fn preview_land_worktree_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    app_settings: &Mutex<AppSettings>,
+    workspace_id: String,
+) -> Result<WorktreeLandPreview, String> {
+    let (entry, parent) = worktree_and_parent(workspaces, &workspace_id).await?;
+    let context = land_context(&entry, &parent)?;
+    let ignore_whitespace_changes = {
+        let settings = app_settings.lock().await;
+        settings.git_diff_ignore_whitespace_changes
+    };
+    let worktree_has_uncommitted_changes = has_uncommitted_changes(&context.worktree_root).await?;
+    let parent_has_uncommitted_changes = has_uncommitted_changes(&context.parent_root).await?;
+
+    let (repo, worktree_tip, parent_tip) = branch_tips(&context)?;
+    let mut revwalk = repo.revwalk().map_err(|e| e.to_string())?;
+    revwalk.push(worktree_tip).map_err(|e| e.to_string())?;
+    revwalk.hide(parent_tip).map_err(|e| e.to_string())?;
+    revwalk
+        .set_sorting(Sort::TOPOLOGICAL | Sort::TIME)
+        .map_err(|e| e.to_string())?;
+    let mut commits = Vec::new();
+    for oid in revwalk {
+        let oid = oid.map_err(|e| e.to_string())?;
+        let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+        commits.push(commit_to_entry(commit));
+    }
+    let (_, behind) = repo
+        .graph_ahead_behind(worktree_tip, parent_tip)
+        .map_err(|e| e.to_string())?;
+    let diff = if commits.is_empty() {
+        Vec::new()
+    } else {
+        let merge_base = repo
+            .merge_base(worktree_tip, parent_tip)
+            .map_err(|e| e.to_string())?;
+        git_ui_core::diff_commits_core(&repo, merge_base, worktree_tip, ignore_whitespace_changes)?
+    };
+
+    Ok(WorktreeLandPreview {
+        worktree_branch: context.worktree_branch,
+        parent_branch: context.parent_branch,
+        can_fast_forward: behind == 0 && !commits.is_empty(),
+        commits,
+        behind,
+        worktree_has_uncommitted_changes,
+        parent_has_uncommitted_changes,
+        diff,
+    })
+}
+
+/// Lands the worktree branch's commits on the parent's current branch. A failed squash or
+/// rebase is rolled back so both checkouts are left as they were. Removing the worktree is
+/// up to the caller, which sets `require_clean_worktree` so nothing uncommitted is lost.
+/// Refusals caused by the state of either checkout are `Conflict`.
+pub(crate) async fn land_worktree_core(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: String,
+    strategy: WorktreeLandStrategy,
+    message: Option<String>,
+    require_clean_worktree: bool,
+) -> Result<WorktreeLandResult, RpcError> {
+    let (entry, parent) = worktree_and_parent(workspaces, &workspace_id).await?;
+    let context = land_context(&entry, &parent)?;
+    let worktree_root = &context.worktree_root;
+    let parent_root = &context.parent_root;
+    let worktree_branch = context.worktree_branch.as_str();
+    let parent_branch = context.parent_branch.as_str();
+
+    if has_uncommitted_changes(parent_root)
+        .await
+        .map_err(git_failure)?
+    {
+        return Err(RpcError::new(
+            RpcErrorCode::Conflict,
+            "Your current branch has uncommitted changes. Please commit, stash, or discard them before landing the worktree.",
+        ));
+    }
+    let worktree_dirty = has_uncommitted_changes(worktree_root)
+        .await
+        .map_err(git_failure)?;
+    if worktree_dirty && (require_clean_worktree || strategy == WorktreeLandStrategy::Rebase) {
+        return Err(RpcError::new(
+            RpcErrorCode::Conflict,
+            "The worktree has uncommitted changes. Commit or discard them first.",
+        ));
+    }
+
+    let (behind, ahead) = {
+        let (repo, worktree_tip, parent_tip) = branch_tips(&context)?;
+        let (ahead, behind) = repo
+            .graph_ahead_behind(worktree_tip, parent_tip)
+            .map_err(|e| e.to_string())?;
+        (behind, ahead)
+    };
+    if ahead == 0 {
+        return Err(RpcError::new(
+            RpcErrorCode::Conflict,
+            format!(
+                "Nothing to land: {worktree_branch} has no commits that {parent_branch} lacks."
+            ),
+        ));
+    }
+
+    match strategy {
+        WorktreeLandStrategy::FastForward => {
+            if behind > 0 {
+                return Err(RpcError::new(
+                    RpcErrorCode::Conflict,
+                    format!(
+                        "{parent_branch} has {behind} commit(s) that {worktree_branch} lacks, so it cannot be fast-forwarded. Squash or rebase instead."
+                    ),
+                ));
+            }
         }
-        return Err(
-            "Patch applied partially. Resolve changes in the parent repo before retrying."
-                .to_string(),
+        WorktreeLandStrategy::Squash => {
+            if let Err(error) =
+                git_core::run_git_command(parent_root, &["merge", "--squash", worktree_branch])
+                    .await
+            {
+                // The parent was clean, so a hard reset only drops the failed squash.
+                let _ = git_core::run_git_command(parent_root, &["reset", "--hard", "HEAD"]).await;
+                return Err(git_failure(format!(
+                    "Squash-merging {worktree_branch} into {parent_branch} failed: {error}"
+                )));
+            }
+            let message = message
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty());
+            let commit = match message.as_deref() {
+                Some(message) => {
+                    git_core::run_git_command(parent_root, &["commit", "-m", message]).await
+                }
+                // Keep git's prepared squash message listing the landed commits.
+                None => {
+                    git_core::run_git_command(parent_root, &["-c", "core.editor=true", "commit"])
+                        .await
+                }
+            };
+            if let Err(error) = commit {
+                let _ = git_core::run_git_command(parent_root, &["reset", "--hard", "HEAD"]).await;
+                return Err(git_failure(error));
+            }
+        }
+        WorktreeLandStrategy::Rebase => {
+            if let Err(error) =
+                git_core::run_git_command(worktree_root, &["rebase", parent_branch]).await
+            {
+                let in_progress = Repository::open(worktree_root)
+                    .map(|repo| repo.state() != RepositoryState::Clean)
+                    .unwrap_or(false);
+                if in_progress {
+                    let _ = git_core::run_git_command(worktree_root, &["rebase", "--abort"]).await;
+                }
+                return Err(git_failure(format!(
+                    "Rebasing {worktree_branch} onto {parent_branch} failed and was aborted: {error}"
+                )));
+            }
+        }
+    }
+
+    if strategy != WorktreeLandStrategy::Squash {
+        git_core::run_git_command(parent_root, &["merge", "--ff-only", worktree_branch])
+            .await
+            .map_err(git_failure)?;
+    }
+    let sha = git_core::run_git_command(parent_root, &["rev-parse", "HEAD"])
+        .await
+        .map_err(git_failure)?;
+    Ok(WorktreeLandResult {
+        parent_branch: parent_branch.to_string(),
+        sha,
+        worktree_removed: false,
+        worktree_removal_error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+    use std::path::Path;
+
+    use tokio::runtime::Runtime;
+    use uuid::Uuid;
+
+    use crate::types::{WorkspaceKind, WorkspaceSettings, WorktreeInfo};
+
+    fn git(root: &Path, args: &[&str]) -> String {
+        let output = std::process::Command::new("git")
+            .args(args)
+            .current_dir(root)
+            .output()
+            .expect("run git");
+        assert!(
+            output.status.success(),
+            "git {args:?} failed: {}",
+            String::from_utf8_lossy(&output.stderr)
         );
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn commit(root: &Path, path: &str, content: &str, message: &str) {
+        fs::write(root.join(path), content).expect("write file");
+        git(root, &["add", path]);
+        git(root, &["commit", "-q", "-m", message]);
     }
 
-    Err(detail.to_string())
+    /// Parent repo plus a `feature` worktree with two commits of its own.
+    fn setup() -> (PathBuf, PathBuf, Mutex<HashMap<String, WorkspaceEntry>>) {
+        let base = std::env::temp_dir().join(format!("codex-monitor-land-{}", Uuid::new_v4()));
+        let parent_root = base.join("parent");
+        let worktree_root = base.join("feature");
+        fs::create_dir_all(&parent_root).expect("create parent");
+        git(&parent_root, &["init", "-q"]);
+        git(&parent_root, &["config", "user.name", "Test"]);
+        git(&parent_root, &["config", "user.email", "test@example.com"]);
+        commit(&parent_root, "base.txt", "base\n", "base");
+        git(
+            &parent_root,
+            &[
+                "worktree",
+                "add",
+                "-q",
+                "-b",
+                "feature",
+                worktree_root.to_str().expect("utf8 path"),
+            ],
+        );
+        commit(&worktree_root, "one.txt", "one\n", "add one");
+        commit(&worktree_root, "two.txt", "two\n", "add two");
+
+        let parent = WorkspaceEntry {
+            id: "parent".to_string(),
+            name: "parent".to_string(),
+            path: parent_root.to_string_lossy().to_string(),
+            kind: WorkspaceKind::Main,
+            parent_id: None,
+            worktree: None,
+            settings: WorkspaceSettings::default(),
+        };
+        let worktree = WorkspaceEntry {
+            id: "wt".to_string(),
+            name: "feature".to_string(),
+            path: worktree_root.to_string_lossy().to_string(),
+            kind: WorkspaceKind::Worktree,
+            parent_id: Some("parent".to_string()),
+            worktree: Some(WorktreeInfo {
+                branch: "feature".to_string(),
+            }),
+            settings: WorkspaceSettings::default(),
+        };
+        let entries = HashMap::from([("parent".to_string(), parent), ("wt".to_string(), worktree)]);
+        (parent_root, worktree_root, Mutex::new(entries))
+    }
+
+    fn land(
+        runtime: &Runtime,
+        workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+        strategy: WorktreeLandStrategy,
+    ) -> Result<WorktreeLandResult, RpcError> {
+        runtime.block_on(land_worktree_core(
+            workspaces,
+            "wt".to_string(),
+            strategy,
+            None,
+            false,
+        ))
+    }
+
+    #[test]
+    fn preview_lists_commits_and_combined_diff() {
+        let (_, _, workspaces) = setup();
+        let app_settings = Mutex::new(AppSettings::default());
+        let runtime = Runtime::new().expect("create tokio runtime");
+
+        let preview = runtime
+            .block_on(preview_land_worktree_core(
+                &workspaces,
+                &app_settings,
+                "wt".to_string(),
+            ))
+            .expect("preview");
+        assert_eq!(preview.worktree_branch, "feature");
+        let summaries: Vec<_> = preview.commits.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(summaries, vec!["add two", "add one"]);
+        assert_eq!(preview.behind, 0);
+        assert!(preview.can_fast_forward);
+        assert!(!preview.worktree_has_uncommitted_changes);
+        let paths: Vec<_> = preview.diff.iter().map(|diff| diff.path.as_str()).collect();
+        assert_eq!(paths, vec!["one.txt", "two.txt"]);
+    }
+
+    #[test]
+    fn fast_forward_lands_commits_and_refuses_when_diverged() {
+        let (parent_root, _, workspaces) = setup();
+        let runtime = Runtime::new().expect("create tokio runtime");
+        commit(&parent_root, "main.txt", "main\n", "parent change");
+
+        let error = land(&runtime, &workspaces, WorktreeLandStrategy::FastForward).unwrap_err();
+        assert!(error.is(RpcErrorCode::Conflict), "{error}");
+        assert!(
+            error.message.contains("cannot be fast-forwarded"),
+            "{error}"
+        );
+
+        git(&parent_root, &["reset", "-q", "--hard", "HEAD~1"]);
+        let result =
+            land(&runtime, &workspaces, WorktreeLandStrategy::FastForward).expect("fast-forward");
+        assert_eq!(result.sha, git(&parent_root, &["rev-parse", "feature"]));
+        assert!(!result.worktree_removed);
+
+        let error = land(&runtime, &workspaces, WorktreeLandStrategy::FastForward).unwrap_err();
+        assert!(error.is(RpcErrorCode::Conflict), "{error}");
+        assert!(error.message.starts_with("Nothing to land"), "{error}");
+    }
+
+    #[test]
+    fn squash_makes_one_commit_and_rolls_back_conflicts() {
+        let (parent_root, worktree_root, workspaces) = setup();
+        let runtime = Runtime::new().expect("create tokio runtime");
+        commit(&parent_root, "main.txt", "main\n", "parent change");
+
+        let result = runtime
+            .block_on(land_worktree_core(
+                &workspaces,
+                "wt".to_string(),
+                WorktreeLandStrategy::Squash,
+                Some("Land feature".to_string()),
+                false,
+            ))
+            .expect("squash");
+        assert_eq!(result.sha, git(&parent_root, &["rev-parse", "HEAD"]));
+        assert_eq!(
+            git(&parent_root, &["log", "-1", "--format=%s"]),
+            "Land feature"
+        );
+        assert_eq!(git(&parent_root, &["rev-list", "--count", "HEAD"]), "3");
+        assert!(parent_root.join("two.txt").exists());
+
+        commit(&parent_root, "base.txt", "parent\n", "parent edit");
+        commit(&worktree_root, "base.txt", "feature\n", "feature edit");
+        let head = git(&parent_root, &["rev-parse", "HEAD"]);
+        let error = land(&runtime, &workspaces, WorktreeLandStrategy::Squash).unwrap_err();
+        assert!(error.is(RpcErrorCode::GitFailure), "{error}");
+        assert!(
+            error.message.starts_with("Squash-merging feature"),
+            "{error}"
+        );
+        assert_eq!(git(&parent_root, &["rev-parse", "HEAD"]), head);
+        assert_eq!(git(&parent_root, &["status", "--porcelain"]), "");
+    }
+
+    #[test]
+    fn rebase_replays_worktree_commits_then_fast_forwards() {
+        let (parent_root, worktree_root, workspaces) = setup();
+        let runtime = Runtime::new().expect("create tokio runtime");
+        commit(&parent_root, "main.txt", "main\n", "parent change");
+
+        fs::write(worktree_root.join("one.txt"), "dirty\n").expect("dirty worktree");
+        let error = land(&runtime, &workspaces, WorktreeLandStrategy::Rebase).unwrap_err();
+        assert!(error.is(RpcErrorCode::Conflict), "{error}");
+        assert!(error.message.contains("uncommitted changes"), "{error}");
+        git(&worktree_root, &["checkout", "--", "one.txt"]);
+
+        let result =
+            land(&runtime, &workspaces, WorktreeLandStrategy::Rebase).expect("rebase and land");
+        assert_eq!(git(&parent_root, &["log", "-1", "--format=%s"]), "add two");
+        assert_eq!(git(&parent_root, &["rev-list", "--count", "HEAD"]), "4");
+        assert_eq!(result.sha, git(&worktree_root, &["rev-parse", "HEAD"]));
+    }
 }
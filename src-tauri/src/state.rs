@@ -7,6 +7,7 @@ use tokio::sync::Mutex;
 
 use crate::dictation::DictationState;
 use crate::shared::codex_core::CodexLoginCancelState;
+use crate::shared::usage_budget_core::UsageBudgetTracker;
 use crate::storage::{read_settings, read_workspaces};
 use crate::types::{AppSettings, TcpDaemonState, TcpDaemonStatus, WorkspaceEntry};
 
@@ -31,16 +32,19 @@ impl Default for TcpDaemonRuntime {
 }
 
 pub(crate) struct AppState {
-    pub(crate) workspaces: Mutex<HashMap<String, WorkspaceEntry>>,
+    pub(crate) workspaces: Arc<Mutex<HashMap<String, WorkspaceEntry>>>,
     pub(crate) sessions: Mutex<HashMap<String, Arc<crate::codex::WorkspaceSession>>>,
     pub(crate) terminal_sessions: Mutex<HashMap<String, Arc<crate::terminal::TerminalSession>>>,
     pub(crate) remote_backend: Mutex<Option<crate::remote_backend::RemoteBackend>>,
+    pub(crate) remote_event_cursor: crate::remote_backend::RemoteEventCursor,
     pub(crate) storage_path: PathBuf,
     pub(crate) settings_path: PathBuf,
     pub(crate) app_settings: Mutex<AppSettings>,
     pub(crate) dictation: Mutex<DictationState>,
     pub(crate) codex_login_cancels: Mutex<HashMap<String, CodexLoginCancelState>>,
     pub(crate) tcp_daemon: Mutex<TcpDaemonRuntime>,
+    pub(crate) local_usage_watchers: crate::shared::local_usage_core::LocalUsageWatchers,
+    pub(crate) usage_budgets: Arc<UsageBudgetTracker>,
 }
 
 impl AppState {
@@ -54,16 +58,19 @@ impl AppState {
         let workspaces = read_workspaces(&storage_path).unwrap_or_default();
         let app_settings = read_settings(&settings_path).unwrap_or_default();
         Self {
-            workspaces: Mutex::new(workspaces),
+            workspaces: Arc::new(Mutex::new(workspaces)),
             sessions: Mutex::new(HashMap::new()),
             terminal_sessions: Mutex::new(HashMap::new()),
             remote_backend: Mutex::new(None),
+            remote_event_cursor: Default::default(),
             storage_path,
             settings_path,
             app_settings: Mutex::new(app_settings),
             dictation: Mutex::new(DictationState::default()),
             codex_login_cancels: Mutex::new(HashMap::new()),
             tcp_daemon: Mutex::new(TcpDaemonRuntime::default()),
+            local_usage_watchers: Default::default(),
+            usage_budgets: Arc::new(UsageBudgetTracker::new(&data_dir)),
         }
     }
 }
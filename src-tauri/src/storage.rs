@@ -34,7 +34,7 @@ pub(crate) fn read_settings(path: &PathBuf) -> Result<AppSettings, String> {
     match serde_json::from_value(value.clone()) {
         Ok(settings) => Ok(settings),
         Err(_) => {
-            sanitize_remote_settings_for_tcp_only(&mut value);
+            sanitize_remote_settings(&mut value);
             migrate_follow_up_message_behavior(&mut value);
             serde_json::from_value(value).map_err(|e| e.to_string())
         }
@@ -49,24 +49,38 @@ pub(crate) fn write_settings(path: &PathBuf, settings: &AppSettings) -> Result<(
     std::fs::write(path, data).map_err(|e| e.to_string())
 }
 
-fn sanitize_remote_settings_for_tcp_only(value: &mut Value) {
+fn sanitize_remote_provider(value: Option<&Value>) -> Value {
+    match value.and_then(Value::as_str) {
+        Some("websocket") => Value::String("websocket".to_string()),
+        _ => Value::String("tcp".to_string()),
+    }
+}
+
+fn sanitize_remote_settings(value: &mut Value) {
     let Value::Object(root) = value else {
         return;
     };
-    root.insert(
-        "remoteBackendProvider".to_string(),
-        Value::String("tcp".to_string()),
-    );
+    let provider = sanitize_remote_provider(root.get("remoteBackendProvider"));
+    root.insert("remoteBackendProvider".to_string(), provider);
     if let Some(Value::Array(remote_backends)) = root.get_mut("remoteBackends") {
         for entry in remote_backends {
             let Value::Object(entry_obj) = entry else {
                 continue;
             };
-            entry_obj.insert("provider".to_string(), Value::String("tcp".to_string()));
+            let provider = sanitize_remote_provider(entry_obj.get("provider"));
+            entry_obj.insert("provider".to_string(), provider);
             entry_obj.retain(|key, _| {
                 matches!(
                     key.as_str(),
-                    "id" | "name" | "provider" | "host" | "token" | "lastConnectedAtMs"
+                    "id" | "name"
+                        | "provider"
+                        | "host"
+                        | "token"
+                        | "lastConnectedAtMs"
+                        | "tlsEnabled"
+                        | "tlsFingerprint"
+                        | "tlsClientCertPath"
+                        | "tlsClientKeyPath"
                 )
             });
         }
@@ -148,6 +162,8 @@ mod tests {
       "provider": "legacy-provider",
       "host": "example:4732",
       "token": "token-1",
+      "tlsEnabled": true,
+      "tlsFingerprint": "AA:BB",
       "legacyWsUrl": "wss://example/ws"
     }
   ],
@@ -166,9 +182,57 @@ mod tests {
             settings.remote_backends[0].provider,
             crate::types::RemoteBackendProvider::Tcp
         ));
+        assert!(settings.remote_backends[0].tls_enabled);
+        assert_eq!(
+            settings.remote_backends[0].tls_fingerprint.as_deref(),
+            Some("AA:BB")
+        );
         assert_eq!(settings.theme, "dark");
     }
 
+    #[test]
+    fn read_settings_keeps_websocket_remote_provider() {
+        let temp_dir = std::env::temp_dir().join(format!("codex-monitor-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&temp_dir).expect("create temp dir");
+        let path = temp_dir.join("settings.json");
+
+        std::fs::write(
+            &path,
+            r#"{
+  "remoteBackendProvider": "websocket",
+  "remoteBackends": [
+    {
+      "id": "remote-a",
+      "name": "Remote A",
+      "provider": "websocket",
+      "host": "wss://proxy.example/codex"
+    },
+    {
+      "id": "remote-b",
+      "name": "Remote B",
+      "provider": "legacy-provider",
+      "host": "example:4732"
+    }
+  ]
+}"#,
+        )
+        .expect("write settings");
+
+        let settings = read_settings(&path).expect("read settings");
+        assert!(matches!(
+            settings.remote_backend_provider,
+            crate::types::RemoteBackendProvider::WebSocket
+        ));
+        assert!(matches!(
+            settings.remote_backends[0].provider,
+            crate::types::RemoteBackendProvider::WebSocket
+        ));
+        assert!(matches!(
+            settings.remote_backends[1].provider,
+            crate::types::RemoteBackendProvider::Tcp
+        ));
+    }
+
     #[test]
     fn read_settings_migrates_follow_up_behavior_from_legacy_steer_enabled_true() {
         let temp_dir = std::env::temp_dir().join(format!("codex-monitor-test-{}", Uuid::new_v4()));
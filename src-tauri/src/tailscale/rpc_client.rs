@@ -1,4 +1,5 @@
 use super::*;
+use crate::shared::rpc_error::{RpcError, RpcErrorCode};
 
 const DAEMON_RPC_TIMEOUT: Duration = Duration::from_millis(700);
 
@@ -24,19 +25,6 @@ pub(super) enum DaemonProbe {
 
 type DaemonLines = tokio::io::Lines<BufReader<OwnedReadHalf>>;
 
-fn parse_daemon_error_message(response: &Value) -> Option<String> {
-    response
-        .get("error")
-        .and_then(|error| error.get("message"))
-        .and_then(Value::as_str)
-        .map(str::to_string)
-}
-
-fn is_auth_error_message(message: &str) -> bool {
-    let lower = message.to_ascii_lowercase();
-    lower.contains("unauthorized") || lower.contains("invalid token")
-}
-
 fn parse_daemon_info(value: &Value) -> Result<DaemonInfo, String> {
     let name = value
         .get("name")
@@ -130,16 +118,18 @@ async fn send_and_expect_result(
     id: u64,
     method: &str,
     params: Value,
-) -> Result<Value, String> {
+) -> Result<Value, RpcError> {
     send_rpc_request(writer, id, method, params).await?;
     let response = read_rpc_response(lines, id).await?;
-    if let Some(message) = parse_daemon_error_message(&response) {
-        return Err(message);
+    if let Some(error) = response.get("error") {
+        return Err(RpcError::from_value(error));
     }
-    response
-        .get("result")
-        .cloned()
-        .ok_or_else(|| "daemon response missing result".to_string())
+    response.get("result").cloned().ok_or_else(|| {
+        RpcError::new(
+            RpcErrorCode::InvalidRequest,
+            "daemon response missing result",
+        )
+    })
 }
 
 async fn request_daemon_info(
@@ -171,7 +161,7 @@ pub(super) async fn probe_daemon(listen_addr: &str, token: Option<&str>) -> Daem
             info: request_daemon_info(&mut writer, &mut lines, 2).await.ok(),
         },
         Err(message) => {
-            if !is_auth_error_message(&message) {
+            if !message.is(RpcErrorCode::Unauthorized) {
                 return DaemonProbe::NotDaemon;
             }
 
@@ -214,7 +204,7 @@ pub(super) async fn probe_daemon(listen_addr: &str, token: Option<&str>) -> Daem
                     }
                 }
                 Err(auth_error) => {
-                    if is_auth_error_message(&auth_error) {
+                    if auth_error.is(RpcErrorCode::Unauthorized) {
                         DaemonProbe::Running {
                             auth_ok: false,
                             auth_error: Some(format!(
@@ -249,7 +239,7 @@ pub(super) async fn request_daemon_shutdown(
 
     match send_and_expect_result(&mut writer, &mut lines, 1, "ping", json!({})).await {
         Ok(_) => {}
-        Err(message) if is_auth_error_message(&message) => {
+        Err(message) if message.is(RpcErrorCode::Unauthorized) => {
             let auth_token = token
                 .map(str::trim)
                 .filter(|value| !value.is_empty())
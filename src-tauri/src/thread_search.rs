@@ -0,0 +1,41 @@
+use serde_json::json;
+use tauri::{AppHandle, State};
+
+use crate::remote_backend;
+use crate::shared::thread_search_core;
+use crate::state::AppState;
+use crate::types::ThreadSearchResponse;
+
+#[tauri::command]
+pub(crate) async fn search_threads(
+    query: String,
+    workspace_id: Option<String>,
+    limit: Option<u32>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<ThreadSearchResponse, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "search_threads",
+            json!({ "query": query, "workspaceId": workspace_id, "limit": limit }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    let data_dir = state
+        .storage_path
+        .parent()
+        .ok_or_else(|| "unable to resolve app data dir".to_string())?;
+    thread_search_core::search_threads_core(
+        &state.workspaces,
+        data_dir,
+        query,
+        workspace_id,
+        limit,
+        None,
+    )
+    .await
+}
@@ -26,6 +26,8 @@ const TRAY_USAGE_HEADER_ID: &str = "tray_usage_header";
 const TRAY_USAGE_SESSION_ID: &str = "tray_usage_session";
 #[cfg(target_os = "macos")]
 const TRAY_USAGE_WEEKLY_ID: &str = "tray_usage_weekly";
+#[cfg(target_os = "macos")]
+const TRAY_USAGE_COST_ID: &str = "tray_usage_cost";
 pub(crate) const TRAY_OPEN_THREAD_EVENT: &str = "tray-open-thread";
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -50,6 +52,9 @@ pub(crate) struct TrayOpenThreadPayload {
 pub(crate) struct TraySessionUsage {
     pub(crate) session_label: String,
     pub(crate) weekly_label: Option<String>,
+    /// Estimated local spend, e.g. `$1.20 today · $8.40 last 7 days`.
+    #[serde(default)]
+    pub(crate) cost_label: Option<String>,
 }
 
 #[derive(Default)]
@@ -184,16 +189,17 @@ fn normalize_session_usage(usage: Option<TraySessionUsage>) -> Option<TraySessio
     if session_label.is_empty() {
         return None;
     }
-    let weekly_label = usage
-        .weekly_label
-        .as_ref()
-        .map(|label| label.trim())
-        .filter(|label| !label.is_empty())
-        .map(ToString::to_string);
+    let normalize_label = |label: Option<&String>| {
+        label
+            .map(|label| label.trim())
+            .filter(|label| !label.is_empty())
+            .map(ToString::to_string)
+    };
 
     Some(TraySessionUsage {
         session_label: session_label.to_string(),
-        weekly_label,
+        weekly_label: normalize_label(usage.weekly_label.as_ref()),
+        cost_label: normalize_label(usage.cost_label.as_ref()),
     })
 }
 
@@ -283,7 +289,7 @@ fn build_usage_menu_items<R: Runtime>(
     usage: Option<&TraySessionUsage>,
 ) -> tauri::Result<Vec<tauri::menu::MenuItem<R>>> {
     let labels = build_usage_menu_labels(usage);
-    let mut items = Vec::with_capacity(3);
+    let mut items = Vec::with_capacity(4);
     let header = MenuItemBuilder::with_id(TRAY_USAGE_HEADER_ID, &labels.0)
         .enabled(false)
         .build(app)?;
@@ -298,16 +304,29 @@ fn build_usage_menu_items<R: Runtime>(
             .build(app)?;
         items.push(weekly);
     }
+    if let Some(cost_label) = labels.3 {
+        let cost = MenuItemBuilder::with_id(TRAY_USAGE_COST_ID, &cost_label)
+            .enabled(false)
+            .build(app)?;
+        items.push(cost);
+    }
     Ok(items)
 }
 
-fn build_usage_menu_labels(usage: Option<&TraySessionUsage>) -> (String, String, Option<String>) {
+fn build_usage_menu_labels(
+    usage: Option<&TraySessionUsage>,
+) -> (String, String, Option<String>, Option<String>) {
     (
         "Current Usage".to_string(),
         usage
             .map(|usage| format!("Session: {}", usage.session_label))
             .unwrap_or_else(|| "No active session".to_string()),
-        usage.map(|usage| usage.weekly_label.clone()).unwrap_or(None).map(|label| format!("Weekly: {label}")),
+        usage
+            .and_then(|usage| usage.weekly_label.as_ref())
+            .map(|label| format!("Weekly: {label}")),
+        usage
+            .and_then(|usage| usage.cost_label.as_ref())
+            .map(|label| format!("Est. cost: {label}")),
     )
 }
 
@@ -425,6 +444,7 @@ mod tests {
             normalize_session_usage(Some(TraySessionUsage {
                 session_label: "   ".into(),
                 weekly_label: None,
+                cost_label: None,
             })),
             None
         );
@@ -432,10 +452,12 @@ mod tests {
             normalize_session_usage(Some(TraySessionUsage {
                 session_label: " 12% used ".into(),
                 weekly_label: Some(" 67% used ".into()),
+                cost_label: Some("  ".into()),
             })),
             Some(TraySessionUsage {
                 session_label: "12% used".into(),
                 weekly_label: Some("67% used".into()),
+                cost_label: None,
             })
         );
     }
@@ -446,16 +468,23 @@ mod tests {
             build_usage_menu_labels(Some(&TraySessionUsage {
                 session_label: "12% used · Resets 2 hours".into(),
                 weekly_label: Some("67% used · Resets in 2 days".into()),
+                cost_label: Some("$1.20 today · $8.40 last 7 days".into()),
             })),
             (
                 "Current Usage".into(),
                 "Session: 12% used · Resets 2 hours".into(),
                 Some("Weekly: 67% used · Resets in 2 days".into()),
+                Some("Est. cost: $1.20 today · $8.40 last 7 days".into()),
             )
         );
         assert_eq!(
             build_usage_menu_labels(None),
-            ("Current Usage".into(), "No active session".into(), None)
+            (
+                "Current Usage".into(),
+                "No active session".into(),
+                None,
+                None
+            )
         );
     }
 }
@@ -30,6 +30,33 @@ pub(crate) struct GitFileDiff {
     pub(crate) new_image_mime: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum GitDiffLineSide {
+    /// Line numbers of the HEAD version, for removed lines.
+    Old,
+    /// Line numbers of the working tree version, for added lines.
+    New,
+}
+
+/// Inclusive, 1-based range of line numbers on one side of a `GitFileDiff`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitDiffLineRange {
+    pub(crate) side: GitDiffLineSide,
+    pub(crate) start: u32,
+    pub(crate) end: u32,
+}
+
+/// Part of a `GitFileDiff` to stage, unstage or revert: whole hunks by index plus single
+/// changed lines by range.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub(crate) struct GitDiffSelection {
+    #[serde(default)]
+    pub(crate) hunks: Vec<usize>,
+    #[serde(default)]
+    pub(crate) lines: Vec<GitDiffLineRange>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct GitCommitDiff {
     pub(crate) path: String,
@@ -61,9 +88,83 @@ pub(crate) struct GitLogEntry {
     pub(crate) timestamp: i64,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitStashEntry {
+    /// Position in the stash list, as in `stash@{index}`.
+    pub(crate) index: usize,
+    pub(crate) sha: String,
+    pub(crate) message: String,
+    /// Branch the stash was made on; `None` for a detached HEAD.
+    pub(crate) branch: Option<String>,
+    pub(crate) timestamp: i64,
+}
+
+/// Multi-step git operation left in progress in the repository, usually by conflicts.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum GitOperationKind {
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+}
+
+/// Which sides of a conflicted path exist, named like `git status`. During a rebase
+/// "us" is the branch being rebased onto and "them" the commit being replayed.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum GitConflictState {
+    BothModified,
+    BothAdded,
+    BothDeleted,
+    AddedByUs,
+    AddedByThem,
+    DeletedByUs,
+    DeletedByThem,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitConflictFile {
+    pub(crate) path: String,
+    pub(crate) state: GitConflictState,
+}
+
+/// Result of starting, continuing, skipping or aborting a merge or rebase.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitOperationStatus {
+    /// `None` once the operation has finished or been aborted.
+    pub(crate) operation: Option<GitOperationKind>,
+    pub(crate) conflicts: Vec<GitConflictFile>,
+}
+
+/// The three index stages of a conflicted path plus the working tree file with conflict
+/// markers. Contents are `None` when that side does not exist or the file is binary.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GitConflictDetail {
+    pub(crate) path: String,
+    pub(crate) state: GitConflictState,
+    pub(crate) base: Option<String>,
+    pub(crate) ours: Option<String>,
+    pub(crate) theirs: Option<String>,
+    pub(crate) worktree: Option<String>,
+    pub(crate) is_binary: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum GitConflictResolution {
+    Ours,
+    Theirs,
+    /// Use caller-provided content, typically the edited working tree file.
+    Merged,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct GitLogResponse {
-    pub(crate) total: usize,
+    /// Commits in the log, across all pages. `None` when an author, message, date or
+    /// path filter is set, since counting those means checking every commit in history.
+    pub(crate) total: Option<usize>,
     pub(crate) entries: Vec<GitLogEntry>,
     #[serde(default)]
     pub(crate) ahead: usize,
@@ -75,6 +176,39 @@ pub(crate) struct GitLogResponse {
     pub(crate) behind_entries: Vec<GitLogEntry>,
     #[serde(default)]
     pub(crate) upstream: Option<String>,
+    /// Pass back as `cursor` for the next page; `None` on the last page.
+    #[serde(default, rename = "nextCursor")]
+    pub(crate) next_cursor: Option<String>,
+}
+
+/// Commits relative to the start branch's upstream.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum GitLogRange {
+    /// Commits on the branch that its upstream lacks.
+    Ahead,
+    /// Commits on the upstream that the branch lacks.
+    Behind,
+}
+
+/// Narrows `get_git_log`. Every field is optional and they combine with AND.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GitLogFilter {
+    /// Case-insensitive substring of the author name or email.
+    pub(crate) author: Option<String>,
+    /// Unix seconds, inclusive, compared with the commit time.
+    pub(crate) since: Option<i64>,
+    pub(crate) until: Option<i64>,
+    /// Case-insensitive substring of the full commit message.
+    pub(crate) message: Option<String>,
+    /// File history: commits that changed this path, following renames to older names.
+    pub(crate) path: Option<String>,
+    /// Branch or revision to walk from instead of HEAD.
+    pub(crate) branch: Option<String>,
+    /// Revision whose history is excluded, as in `base..branch`.
+    pub(crate) base: Option<String>,
+    pub(crate) range: Option<GitLogRange>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -156,6 +290,8 @@ pub(crate) struct LocalUsageDay {
     pub(crate) agent_time_ms: i64,
     #[serde(default)]
     pub(crate) agent_runs: i64,
+    #[serde(default)]
+    pub(crate) estimated_cost_usd: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -167,6 +303,13 @@ pub(crate) struct LocalUsageTotals {
     pub(crate) cache_hit_rate_percent: f64,
     pub(crate) peak_day: Option<String>,
     pub(crate) peak_day_tokens: i64,
+    #[serde(default)]
+    pub(crate) last7_days_cost_usd: f64,
+    #[serde(default)]
+    pub(crate) last30_days_cost_usd: f64,
+    /// Tokens from models missing in the price table; they are left out of every cost.
+    #[serde(default)]
+    pub(crate) unpriced_tokens: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -175,6 +318,45 @@ pub(crate) struct LocalUsageModel {
     pub(crate) model: String,
     pub(crate) tokens: i64,
     pub(crate) share_percent: f64,
+    #[serde(default)]
+    pub(crate) estimated_cost_usd: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LocalUsageWorkspaceCost {
+    pub(crate) workspace_id: String,
+    pub(crate) workspace_name: String,
+    pub(crate) total_tokens: i64,
+    pub(crate) estimated_cost_usd: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LocalUsageWorkspace {
+    pub(crate) workspace_id: String,
+    pub(crate) workspace_name: String,
+    pub(crate) total_tokens: i64,
+    pub(crate) share_percent: f64,
+    pub(crate) estimated_cost_usd: f64,
+    pub(crate) thread_count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LocalUsageThread {
+    pub(crate) thread_id: String,
+    pub(crate) workspace_id: Option<String>,
+    pub(crate) cwd: Option<String>,
+    /// First user prompt of the thread, shortened for display.
+    pub(crate) preview: Option<String>,
+    pub(crate) input_tokens: i64,
+    pub(crate) cached_input_tokens: i64,
+    pub(crate) output_tokens: i64,
+    pub(crate) total_tokens: i64,
+    pub(crate) share_percent: f64,
+    pub(crate) estimated_cost_usd: f64,
+    pub(crate) updated_at: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -185,6 +367,148 @@ pub(crate) struct LocalUsageSnapshot {
     pub(crate) totals: LocalUsageTotals,
     #[serde(default)]
     pub(crate) top_models: Vec<LocalUsageModel>,
+    #[serde(default)]
+    pub(crate) workspace_costs: Vec<LocalUsageWorkspaceCost>,
+    #[serde(default)]
+    pub(crate) top_workspaces: Vec<LocalUsageWorkspace>,
+    #[serde(default)]
+    pub(crate) top_threads: Vec<LocalUsageThread>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LocalUsageTurn {
+    pub(crate) index: i64,
+    pub(crate) started_at: Option<i64>,
+    pub(crate) ended_at: Option<i64>,
+    pub(crate) model: Option<String>,
+    pub(crate) prompt_preview: Option<String>,
+    pub(crate) input_tokens: i64,
+    pub(crate) cached_input_tokens: i64,
+    pub(crate) output_tokens: i64,
+    pub(crate) total_tokens: i64,
+    pub(crate) estimated_cost_usd: f64,
+}
+
+/// Token usage of a single thread, one entry per user turn.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LocalUsageThreadTimeline {
+    pub(crate) thread_id: String,
+    pub(crate) workspace_id: Option<String>,
+    pub(crate) cwd: Option<String>,
+    pub(crate) turns: Vec<LocalUsageTurn>,
+    pub(crate) total_tokens: i64,
+    pub(crate) estimated_cost_usd: f64,
+}
+
+/// USD prices per million tokens for one model, used to estimate local usage cost.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ModelPrice {
+    /// Exact model name, or a prefix ending in `*` such as `gpt-5*`.
+    pub(crate) model: String,
+    pub(crate) input_per_million: f64,
+    pub(crate) cached_input_per_million: f64,
+    pub(crate) output_per_million: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum UsageBudgetPeriod {
+    /// Today, local time.
+    Daily,
+    /// Monday through Sunday, local time.
+    Weekly,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum UsageBudgetMetric {
+    Tokens,
+    /// Estimated USD cost from `modelPrices`.
+    Cost,
+}
+
+/// A token or cost limit over a day or week, for all workspaces or a single one.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UsageBudget {
+    pub(crate) id: String,
+    /// `None` counts usage from every workspace, including sessions outside them.
+    #[serde(default)]
+    pub(crate) workspace_id: Option<String>,
+    pub(crate) period: UsageBudgetPeriod,
+    pub(crate) metric: UsageBudgetMetric,
+    pub(crate) limit: f64,
+    /// Percentages of `limit` that raise an alert when first reached in a period.
+    #[serde(default = "default_usage_budget_thresholds")]
+    pub(crate) thresholds: Vec<u32>,
+    /// Refuse `send_user_message` once the limit is reached, until overridden.
+    #[serde(default)]
+    pub(crate) block_at_limit: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UsageBudgetStatus {
+    pub(crate) budget_id: String,
+    pub(crate) workspace_id: Option<String>,
+    pub(crate) period: UsageBudgetPeriod,
+    pub(crate) metric: UsageBudgetMetric,
+    /// First day of the current period, `YYYY-MM-DD`.
+    pub(crate) period_start: String,
+    pub(crate) limit: f64,
+    pub(crate) used: f64,
+    pub(crate) percent: f64,
+    pub(crate) overridden: bool,
+    pub(crate) blocking: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UsageBudgetAlert {
+    pub(crate) budget_id: String,
+    pub(crate) workspace_id: Option<String>,
+    pub(crate) workspace_name: Option<String>,
+    pub(crate) period: UsageBudgetPeriod,
+    pub(crate) metric: UsageBudgetMetric,
+    /// The highest threshold crossed by this update.
+    pub(crate) threshold: u32,
+    pub(crate) limit: f64,
+    pub(crate) used: f64,
+    pub(crate) percent: f64,
+    pub(crate) blocking: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ThreadSearchMatch {
+    /// `user`, `assistant`, `tool_call`, `diff` or `file`.
+    pub(crate) kind: String,
+    /// Matched text with hits wrapped in `<mark>`/`</mark>`.
+    pub(crate) snippet: String,
+    pub(crate) timestamp: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ThreadSearchResult {
+    pub(crate) thread_id: String,
+    pub(crate) workspace_id: Option<String>,
+    pub(crate) cwd: Option<String>,
+    pub(crate) preview: Option<String>,
+    pub(crate) started_at: Option<i64>,
+    pub(crate) updated_at: Option<i64>,
+    pub(crate) matches: Vec<ThreadSearchMatch>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ThreadSearchResponse {
+    pub(crate) query: String,
+    pub(crate) results: Vec<ThreadSearchResult>,
+    pub(crate) indexed_files: u32,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -329,6 +653,41 @@ pub(crate) struct WorkspaceSettings {
     pub(crate) launch_scripts: Option<Vec<LaunchScriptEntry>>,
     #[serde(default, rename = "worktreeSetupScript")]
     pub(crate) worktree_setup_script: Option<String>,
+    #[serde(default, rename = "approvalRules")]
+    pub(crate) approval_rules: Vec<ApprovalPolicyRule>,
+}
+
+/// Monitor-side rule for answering app-server approval requests. Every condition that is
+/// set must match; rules are tried in order and the first match decides.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub(crate) struct ApprovalPolicyRule {
+    pub(crate) id: String,
+    pub(crate) action: ApprovalPolicyAction,
+    #[serde(default)]
+    pub(crate) description: Option<String>,
+    /// Leading command words, e.g. `["cargo", "test"]`.
+    #[serde(default, rename = "commandPrefix")]
+    pub(crate) command_prefix: Option<Vec<String>>,
+    /// Matched against the command joined with single spaces.
+    #[serde(default, rename = "commandRegex")]
+    pub(crate) command_regex: Option<String>,
+    /// Every touched file must match one of these globs (absolute or workspace-relative).
+    #[serde(default, rename = "fileGlobs")]
+    pub(crate) file_globs: Option<Vec<String>>,
+    /// Whether the request asks for network access.
+    #[serde(default)]
+    pub(crate) network: Option<bool>,
+    /// Access modes (`read-only`, `current`, `full-access`) of the turn that asked.
+    #[serde(default, rename = "accessModes")]
+    pub(crate) access_modes: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ApprovalPolicyAction {
+    Approve,
+    Deny,
+    Escalate,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -340,6 +699,46 @@ pub(crate) struct LaunchScriptEntry {
     pub(crate) label: Option<String>,
 }
 
+/// What landing a worktree branch on its parent's current branch would bring in.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WorktreeLandPreview {
+    pub(crate) worktree_branch: String,
+    pub(crate) parent_branch: String,
+    /// Commits on the worktree branch that the parent branch lacks, newest first.
+    pub(crate) commits: Vec<GitLogEntry>,
+    /// Commits on the parent branch that the worktree branch lacks.
+    pub(crate) behind: usize,
+    pub(crate) can_fast_forward: bool,
+    /// Uncommitted worktree changes are not landed; `apply_worktree_changes` covers them.
+    pub(crate) worktree_has_uncommitted_changes: bool,
+    pub(crate) parent_has_uncommitted_changes: bool,
+    /// Combined diff from the merge base to the worktree branch tip.
+    pub(crate) diff: Vec<GitCommitDiff>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum WorktreeLandStrategy {
+    FastForward,
+    /// One commit on the parent branch with all of the worktree branch's changes.
+    Squash,
+    /// Rebase the worktree branch onto the parent branch, then fast-forward.
+    Rebase,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WorktreeLandResult {
+    pub(crate) parent_branch: String,
+    /// The parent branch's new tip.
+    pub(crate) sha: String,
+    pub(crate) worktree_removed: bool,
+    /// Why removing the worktree failed after the branch landed.
+    #[serde(default)]
+    pub(crate) worktree_removal_error: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct WorktreeSetupStatus {
     #[serde(rename = "shouldRun")]
@@ -371,6 +770,15 @@ pub(crate) struct RemoteBackendTarget {
     pub(crate) token: Option<String>,
     #[serde(default, rename = "lastConnectedAtMs")]
     pub(crate) last_connected_at_ms: Option<i64>,
+    #[serde(default, rename = "tlsEnabled")]
+    pub(crate) tls_enabled: bool,
+    /// SHA-256 of the daemon certificate; when set it replaces CA validation.
+    #[serde(default, rename = "tlsFingerprint")]
+    pub(crate) tls_fingerprint: Option<String>,
+    #[serde(default, rename = "tlsClientCertPath")]
+    pub(crate) tls_client_cert_path: Option<String>,
+    #[serde(default, rename = "tlsClientKeyPath")]
+    pub(crate) tls_client_key_path: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -639,6 +1047,10 @@ pub(crate) struct AppSettings {
     pub(crate) open_app_targets: Vec<OpenAppTarget>,
     #[serde(default = "default_selected_open_app_id", rename = "selectedOpenAppId")]
     pub(crate) selected_open_app_id: String,
+    #[serde(default = "default_model_prices", rename = "modelPrices")]
+    pub(crate) model_prices: Vec<ModelPrice>,
+    #[serde(default, rename = "usageBudgets")]
+    pub(crate) usage_budgets: Vec<UsageBudget>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -658,6 +1070,7 @@ impl Default for BackendMode {
 #[serde(rename_all = "lowercase")]
 pub(crate) enum RemoteBackendProvider {
     Tcp,
+    WebSocket,
 }
 
 impl Default for RemoteBackendProvider {
@@ -987,6 +1400,35 @@ fn default_workspace_groups() -> Vec<WorkspaceGroup> {
     Vec::new()
 }
 
+fn model_price(model: &str, input: f64, cached_input: f64, output: f64) -> ModelPrice {
+    ModelPrice {
+        model: model.to_string(),
+        input_per_million: input,
+        cached_input_per_million: cached_input,
+        output_per_million: output,
+    }
+}
+
+fn default_usage_budget_thresholds() -> Vec<u32> {
+    vec![80, 100]
+}
+
+fn default_model_prices() -> Vec<ModelPrice> {
+    vec![
+        model_price("gpt-5*", 1.25, 0.125, 10.0),
+        model_price("gpt-5-mini*", 0.25, 0.025, 2.0),
+        model_price("gpt-5-nano*", 0.05, 0.005, 0.4),
+        model_price("gpt-5-codex-mini*", 0.25, 0.025, 2.0),
+        model_price("gpt-5.1-codex-mini*", 0.25, 0.025, 2.0),
+        model_price("gpt-5.2*", 1.75, 0.175, 14.0),
+        model_price("gpt-4.1*", 2.0, 0.5, 8.0),
+        model_price("gpt-4.1-mini*", 0.4, 0.1, 1.6),
+        model_price("o3*", 2.0, 0.5, 8.0),
+        model_price("o4-mini*", 1.1, 0.275, 4.4),
+        model_price("codex-mini-latest", 1.5, 0.375, 6.0),
+    ]
+}
+
 fn default_open_app_targets() -> Vec<OpenAppTarget> {
     if cfg!(target_os = "macos") {
         return vec![
@@ -1184,6 +1626,8 @@ impl Default for AppSettings {
             workspace_groups: default_workspace_groups(),
             open_app_targets: default_open_app_targets(),
             selected_open_app_id: default_selected_open_app_id(),
+            model_prices: default_model_prices(),
+            usage_budgets: Vec::new(),
         }
     }
 }
@@ -1348,6 +1792,11 @@ mod tests {
             "vscode"
         };
         assert_eq!(settings.selected_open_app_id, expected_open_id);
+        assert!(settings
+            .model_prices
+            .iter()
+            .any(|price| price.model == "gpt-5*"));
+        assert!(settings.usage_budgets.is_empty());
         assert_eq!(settings.open_app_targets.len(), 6);
         assert_eq!(settings.open_app_targets[0].id, "vscode");
     }
@@ -22,7 +22,10 @@ use crate::git_utils::resolve_git_root;
 use crate::remote_backend;
 use crate::shared::{workspace_rpc, workspaces_core};
 use crate::state::AppState;
-use crate::types::{WorkspaceEntry, WorkspaceInfo, WorkspaceSettings, WorktreeSetupStatus};
+use crate::types::{
+    WorkspaceEntry, WorkspaceInfo, WorkspaceSettings, WorktreeLandPreview, WorktreeLandResult,
+    WorktreeLandStrategy, WorktreeSetupStatus,
+};
 
 fn spawn_with_app(
     app: &AppHandle,
@@ -406,6 +409,10 @@ pub(crate) async fn remove_worktree(
         return Ok(());
     }
 
+    remove_worktree_local(id, &state).await
+}
+
+async fn remove_worktree_local(id: String, state: &AppState) -> Result<(), String> {
     workspaces_core::remove_worktree_core(
         id,
         &state.workspaces,
@@ -546,7 +553,80 @@ pub(crate) async fn apply_worktree_changes(
     workspace_id: String,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
-    workspaces_core::apply_worktree_changes_core(&state.workspaces, workspace_id).await
+    workspaces_core::apply_worktree_changes_core(&state.workspaces, workspace_id)
+        .await
+        .map_err(String::from)
+}
+
+#[tauri::command]
+pub(crate) async fn preview_land_worktree(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<WorktreeLandPreview, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let request = workspace_rpc::WorkspaceIdRequest { workspace_id };
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "preview_land_worktree",
+            workspace_remote_params(&request)?,
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    workspaces_core::preview_land_worktree_core(
+        &state.workspaces,
+        &state.app_settings,
+        workspace_id,
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn land_worktree(
+    workspace_id: String,
+    strategy: WorktreeLandStrategy,
+    message: Option<String>,
+    remove_worktree: Option<bool>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<WorktreeLandResult, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let request = workspace_rpc::LandWorktreeRequest {
+            workspace_id,
+            strategy,
+            message,
+            remove_worktree,
+        };
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "land_worktree",
+            workspace_remote_params(&request)?,
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    let remove_worktree = remove_worktree.unwrap_or(false);
+    let mut result = workspaces_core::land_worktree_core(
+        &state.workspaces,
+        workspace_id.clone(),
+        strategy,
+        message,
+        remove_worktree,
+    )
+    .await?;
+    // The branch has landed by now, so a failed removal is reported rather than an error.
+    if remove_worktree {
+        match remove_worktree_local(workspace_id, &state).await {
+            Ok(()) => result.worktree_removed = true,
+            Err(error) => result.worktree_removal_error = Some(error),
+        }
+    }
+    Ok(result)
 }
 
 #[tauri::command]
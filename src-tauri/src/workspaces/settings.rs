@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use crate::shared::approval_policy_core::validate_approval_rules;
 #[cfg(test)]
 use crate::types::WorkspaceInfo;
 use crate::types::{WorkspaceEntry, WorkspaceSettings};
@@ -22,6 +23,7 @@ pub(crate) fn apply_workspace_settings_update(
     id: &str,
     settings: WorkspaceSettings,
 ) -> Result<WorkspaceEntry, String> {
+    validate_approval_rules(&settings.approval_rules)?;
     match workspaces.get_mut(id) {
         Some(entry) => {
             entry.settings = settings.clone();
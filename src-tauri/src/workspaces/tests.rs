@@ -56,6 +56,7 @@ fn workspace_with_id_and_kind(
             launch_script: None,
             launch_scripts: None,
             worktree_setup_script: None,
+            approval_rules: Vec::new(),
         },
     }
 }
@@ -1159,6 +1159,7 @@ export default function MainApp() {
   useTraySessionUsage({
     accountRateLimits: activeRateLimits,
     showRemaining: appSettings.usageShowRemaining,
+    localUsage: localUsageSnapshot,
   });
   const activePlan = activeThreadId
     ? planByThread[activeThreadId] ?? null
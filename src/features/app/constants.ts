@@ -1,4 +1,4 @@
-import type { OpenAppTarget } from "../../types";
+import type { ModelPrice, OpenAppTarget } from "../../types";
 import {
   fileManagerName,
   isMacPlatform,
@@ -97,3 +97,18 @@ export const DEFAULT_OPEN_APP_TARGETS: OpenAppTarget[] = isMacPlatform()
         args: [],
       },
     ];
+
+// Mirrors `default_model_prices` in src-tauri/src/types.rs (USD per million tokens).
+export const DEFAULT_MODEL_PRICES: ModelPrice[] = [
+  { model: "gpt-5*", inputPerMillion: 1.25, cachedInputPerMillion: 0.125, outputPerMillion: 10 },
+  { model: "gpt-5-mini*", inputPerMillion: 0.25, cachedInputPerMillion: 0.025, outputPerMillion: 2 },
+  { model: "gpt-5-nano*", inputPerMillion: 0.05, cachedInputPerMillion: 0.005, outputPerMillion: 0.4 },
+  { model: "gpt-5-codex-mini*", inputPerMillion: 0.25, cachedInputPerMillion: 0.025, outputPerMillion: 2 },
+  { model: "gpt-5.1-codex-mini*", inputPerMillion: 0.25, cachedInputPerMillion: 0.025, outputPerMillion: 2 },
+  { model: "gpt-5.2*", inputPerMillion: 1.75, cachedInputPerMillion: 0.175, outputPerMillion: 14 },
+  { model: "gpt-4.1*", inputPerMillion: 2, cachedInputPerMillion: 0.5, outputPerMillion: 8 },
+  { model: "gpt-4.1-mini*", inputPerMillion: 0.4, cachedInputPerMillion: 0.1, outputPerMillion: 1.6 },
+  { model: "o3*", inputPerMillion: 2, cachedInputPerMillion: 0.5, outputPerMillion: 8 },
+  { model: "o4-mini*", inputPerMillion: 1.1, cachedInputPerMillion: 0.275, outputPerMillion: 4.4 },
+  { model: "codex-mini-latest", inputPerMillion: 1.5, cachedInputPerMillion: 0.375, outputPerMillion: 6 },
+];
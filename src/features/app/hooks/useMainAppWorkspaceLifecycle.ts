@@ -8,6 +8,7 @@ import { useTabActivationGuard } from "@app/hooks/useTabActivationGuard";
 import {
   useRemoteThreadRefreshOnFocus,
 } from "@app/hooks/useRemoteThreadRefreshOnFocus";
+import { useRemoteEventGapRecovery } from "@app/hooks/useRemoteEventGapRecovery";
 import type { WorkspaceInfo } from "@/types";
 
 type UseMainAppWorkspaceLifecycleArgs = {
@@ -17,7 +18,10 @@ type UseMainAppWorkspaceLifecycleArgs = {
   workspaces: WorkspaceInfo[];
   hasLoaded: boolean;
   connectWorkspace: (workspace: WorkspaceInfo) => Promise<void>;
-  listThreadsForWorkspaces: (workspaces: WorkspaceInfo[]) => Promise<void>;
+  listThreadsForWorkspaces: (
+    workspaces: WorkspaceInfo[],
+    options?: { preserveState?: boolean },
+  ) => Promise<void>;
   refreshWorkspaces: () => Promise<void | WorkspaceInfo[]>;
   backendMode: "local" | "remote";
   activeWorkspace: WorkspaceInfo | null;
@@ -78,4 +82,13 @@ export function useMainAppWorkspaceLifecycle({
     reconnectWorkspace: connectWorkspace,
     refreshThread,
   });
+
+  useRemoteEventGapRecovery({
+    backendMode,
+    workspaces,
+    activeWorkspace,
+    activeThreadId,
+    listThreadsForWorkspaces,
+    refreshThread,
+  });
 }
@@ -0,0 +1,76 @@
+// @vitest-environment jsdom
+import { act, renderHook } from "@testing-library/react";
+import { beforeEach, describe, expect, it, vi } from "vitest";
+import type { EventGapEvent } from "@services/events";
+import type { WorkspaceInfo } from "@/types";
+import { useRemoteEventGapRecovery } from "./useRemoteEventGapRecovery";
+
+let gapListener: ((event: EventGapEvent) => void) | null = null;
+const subscribeEventGapMock = vi.fn((listener: (event: EventGapEvent) => void) => {
+  gapListener = listener;
+  return () => {
+    gapListener = null;
+  };
+});
+
+vi.mock("@services/events", () => ({
+  subscribeEventGap: (listener: (event: EventGapEvent) => void) =>
+    subscribeEventGapMock(listener),
+}));
+
+function makeWorkspace(id: string, connected: boolean): WorkspaceInfo {
+  return {
+    id,
+    name: id,
+    path: `/tmp/${id}`,
+    connected,
+    settings: { sidebarCollapsed: false },
+  };
+}
+
+describe("useRemoteEventGapRecovery", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    gapListener = null;
+  });
+
+  it("reloads thread lists and the open thread when the daemon reports a gap", () => {
+    const listThreadsForWorkspaces = vi.fn().mockResolvedValue(undefined);
+    const refreshThread = vi.fn().mockResolvedValue(undefined);
+    const active = makeWorkspace("ws-1", true);
+    renderHook(() =>
+      useRemoteEventGapRecovery({
+        backendMode: "remote",
+        workspaces: [active, makeWorkspace("ws-2", false)],
+        activeWorkspace: active,
+        activeThreadId: "thread-1",
+        listThreadsForWorkspaces,
+        refreshThread,
+      }),
+    );
+
+    act(() => {
+      gapListener?.({ afterSeq: 42, reason: "evicted" });
+    });
+
+    expect(listThreadsForWorkspaces).toHaveBeenCalledWith([active], {
+      preserveState: true,
+    });
+    expect(refreshThread).toHaveBeenCalledWith("ws-1", "thread-1");
+  });
+
+  it("does not listen in local mode", () => {
+    renderHook(() =>
+      useRemoteEventGapRecovery({
+        backendMode: "local",
+        workspaces: [],
+        activeWorkspace: null,
+        activeThreadId: null,
+        listThreadsForWorkspaces: vi.fn(),
+        refreshThread: vi.fn(),
+      }),
+    );
+
+    expect(subscribeEventGapMock).not.toHaveBeenCalled();
+  });
+});
@@ -0,0 +1,45 @@
+import { useCallback } from "react";
+import { subscribeEventGap } from "@services/events";
+import type { WorkspaceInfo } from "@/types";
+import { useTauriEvent } from "./useTauriEvent";
+
+type UseRemoteEventGapRecoveryOptions = {
+  backendMode: string;
+  workspaces: WorkspaceInfo[];
+  activeWorkspace: WorkspaceInfo | null;
+  activeThreadId: string | null;
+  listThreadsForWorkspaces: (
+    workspaces: WorkspaceInfo[],
+    options?: { preserveState?: boolean },
+  ) => Promise<void>;
+  refreshThread: (workspaceId: string, threadId: string) => Promise<unknown> | unknown;
+};
+
+/**
+ * The daemon reports an `event-gap` when events it could not replay were lost, so thread
+ * state built from events may be stale. Reloads the thread lists and the open thread.
+ */
+export function useRemoteEventGapRecovery({
+  backendMode,
+  workspaces,
+  activeWorkspace,
+  activeThreadId,
+  listThreadsForWorkspaces,
+  refreshThread,
+}: UseRemoteEventGapRecoveryOptions) {
+  const handleGap = useCallback(() => {
+    const connected = workspaces.filter((entry) => entry.connected);
+    if (connected.length > 0) {
+      void listThreadsForWorkspaces(connected, { preserveState: true }).catch(() => {
+        // Silent: the next focus or poll refresh retries.
+      });
+    }
+    if (activeWorkspace?.connected && activeThreadId) {
+      void Promise.resolve(refreshThread(activeWorkspace.id, activeThreadId)).catch(() => {
+        // Silent: the next focus or poll refresh retries.
+      });
+    }
+  }, [activeThreadId, activeWorkspace, listThreadsForWorkspaces, refreshThread, workspaces]);
+
+  useTauriEvent(subscribeEventGap, handleGap, { enabled: backendMode === "remote" });
+}
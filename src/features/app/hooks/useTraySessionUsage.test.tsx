@@ -90,6 +90,41 @@ describe("useTraySessionUsage", () => {
     });
   });
 
+  it("adds an estimated cost line from the local usage snapshot", () => {
+    expect(
+      buildTraySessionUsage(makeRateLimits(), false, {
+        updatedAt: 0,
+        days: [
+          {
+            day: "2026-01-01",
+            inputTokens: 10,
+            cachedInputTokens: 0,
+            outputTokens: 5,
+            totalTokens: 15,
+            agentTimeMs: 0,
+            agentRuns: 1,
+            estimatedCostUsd: 1.2,
+          },
+        ],
+        totals: {
+          last7DaysTokens: 15,
+          last30DaysTokens: 15,
+          averageDailyTokens: 15,
+          cacheHitRatePercent: 0,
+          peakDay: "2026-01-01",
+          peakDayTokens: 15,
+          last7DaysCostUsd: 8.4,
+          last30DaysCostUsd: 8.4,
+        },
+        topModels: [],
+      }),
+    ).toEqual({
+      sessionLabel: "12% used · Resets 2 hours",
+      weeklyLabel: null,
+      costLabel: "$1.20 today · $8.40 last 7 days",
+    });
+  });
+
   it("syncs only when the derived usage changes", async () => {
     type HookProps = {
       accountRateLimits: RateLimitSnapshot | null;
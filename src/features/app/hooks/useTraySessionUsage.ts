@@ -1,7 +1,11 @@
 import { isTauri } from "@tauri-apps/api/core";
 import { useEffect, useMemo, useRef } from "react";
 import { setTraySessionUsage } from "@services/tauri";
-import type { RateLimitSnapshot, TraySessionUsage } from "../../../types";
+import type {
+  LocalUsageSnapshot,
+  RateLimitSnapshot,
+  TraySessionUsage,
+} from "../../../types";
 import { getUsageLabels } from "../utils/usageLabels";
 
 const SYNC_DEBOUNCE_MS = 150;
@@ -9,11 +13,23 @@ const SYNC_DEBOUNCE_MS = 150;
 type UseTraySessionUsageParams = {
   accountRateLimits: RateLimitSnapshot | null;
   showRemaining: boolean;
+  localUsage?: LocalUsageSnapshot | null;
 };
 
+function formatCostLabel(localUsage: LocalUsageSnapshot | null | undefined) {
+  const days = localUsage?.days ?? [];
+  const today = days[days.length - 1]?.estimatedCostUsd ?? 0;
+  const lastWeek = localUsage?.totals.last7DaysCostUsd ?? 0;
+  if (lastWeek <= 0) {
+    return null;
+  }
+  return `$${today.toFixed(2)} today · $${lastWeek.toFixed(2)} last 7 days`;
+}
+
 export function buildTraySessionUsage(
   accountRateLimits: RateLimitSnapshot | null,
   showRemaining: boolean,
+  localUsage?: LocalUsageSnapshot | null,
 ): TraySessionUsage | null {
   const {
     sessionPercent,
@@ -38,6 +54,8 @@ export function buildTraySessionUsage(
         : `${weeklyPercent}% used`
       : null;
 
+  const costLabel = formatCostLabel(localUsage);
+
   return {
     sessionLabel:
       sessionResetLabel === null
@@ -49,16 +67,18 @@ export function buildTraySessionUsage(
         : weeklyResetLabel === null
           ? weeklyUsageLabel
           : `${weeklyUsageLabel} · ${weeklyResetLabel}`,
+    ...(costLabel ? { costLabel } : {}),
   };
 }
 
 export function useTraySessionUsage({
   accountRateLimits,
   showRemaining,
+  localUsage = null,
 }: UseTraySessionUsageParams) {
   const usage = useMemo(
-    () => buildTraySessionUsage(accountRateLimits, showRemaining),
-    [accountRateLimits, showRemaining],
+    () => buildTraySessionUsage(accountRateLimits, showRemaining, localUsage),
+    [accountRateLimits, showRemaining, localUsage],
   );
   const lastSyncedUsageRef = useRef<string | null>(null);
 
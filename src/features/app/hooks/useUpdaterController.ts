@@ -2,6 +2,7 @@ import { useCallback, useRef } from "react";
 import { useUpdater } from "../../update/hooks/useUpdater";
 import { useAgentSoundNotifications } from "../../notifications/hooks/useAgentSoundNotifications";
 import { useAgentSystemNotifications } from "../../notifications/hooks/useAgentSystemNotifications";
+import { useUsageBudgetNotifications } from "../../notifications/hooks/useUsageBudgetNotifications";
 import { useWindowFocusState } from "../../layout/hooks/useWindowFocusState";
 import { useTauriEvent } from "./useTauriEvent";
 import { playNotificationSound } from "../../../utils/notificationSounds";
@@ -88,6 +89,11 @@ export function useUpdaterController({
     onDebug,
   });
 
+  useUsageBudgetNotifications({
+    enabled: enabled && systemNotificationsEnabled,
+    onDebug,
+  });
+
   const handleTestNotificationSound = useCallback(() => {
     const useError = nextTestSoundIsError.current;
     nextTestSoundIsError.current = !useError;
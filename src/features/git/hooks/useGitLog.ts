@@ -55,7 +55,7 @@ export function useGitLog(
       }
       setState({
         entries: response.entries,
-        total: response.total,
+        total: response.total ?? 0,
         ahead: response.ahead,
         behind: response.behind,
         aheadEntries: response.aheadEntries,
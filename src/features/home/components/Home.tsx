@@ -71,6 +71,19 @@ export function Home({
     return String(value);
   };
 
+  const formatUsd = (value: number | null | undefined) => {
+    if (value === null || value === undefined) {
+      return "--";
+    }
+    if (value > 0 && value < 0.01) {
+      return "<$0.01";
+    }
+    return `$${value.toFixed(2)}`;
+  };
+
+  const costSuffix = (value: number | null | undefined) =>
+    value && value > 0 ? ` · ${formatUsd(value)}` : "";
+
   const formatCount = (value: number | null | undefined) => {
     if (value === null || value === undefined) {
       return "--";
@@ -376,6 +389,7 @@ export function Home({
                     </div>
                     <div className="home-usage-caption">
                       Avg {formatCompactNumber(usageTotals?.averageDailyTokens)} / day
+                      {costSuffix(usageTotals?.last7DaysCostUsd)}
                     </div>
                   </div>
                   <div className="home-usage-card">
@@ -388,6 +402,7 @@ export function Home({
                     </div>
                     <div className="home-usage-caption">
                       Total {formatCount(usageTotals?.last30DaysTokens)}
+                      {costSuffix(usageTotals?.last30DaysCostUsd)}
                     </div>
                   </div>
                   <div className="home-usage-card">
@@ -474,7 +489,7 @@ export function Home({
                   );
                   const tooltip =
                     usageMetric === "tokens"
-                      ? `${formatDayLabel(day.day)} · ${formatCount(day.totalTokens)} tokens`
+                      ? `${formatDayLabel(day.day)} · ${formatCount(day.totalTokens)} tokens${costSuffix(day.estimatedCostUsd)}`
                       : `${formatDayLabel(day.day)} · ${formatDuration(day.agentTimeMs ?? 0)} agent time`;
                   return (
                     <div
@@ -507,7 +522,7 @@ export function Home({
                     <span
                       className="home-usage-model-chip"
                       key={model.model}
-                      title={`${model.model}: ${formatCount(model.tokens)} tokens`}
+                      title={`${model.model}: ${formatCount(model.tokens)} tokens${costSuffix(model.estimatedCostUsd)}`}
                     >
                       {model.model}
                       <span className="home-usage-model-share">
@@ -523,6 +538,28 @@ export function Home({
                 <div className="home-usage-error">{localUsageError}</div>
               )}
             </div>
+            {usageMetric === "tokens" &&
+              (localUsageSnapshot?.workspaceCosts?.length ?? 0) > 0 && (
+                <div className="home-usage-models">
+                  <div className="home-usage-models-label">
+                    Estimated cost by workspace
+                  </div>
+                  <div className="home-usage-models-list">
+                    {localUsageSnapshot?.workspaceCosts?.map((entry) => (
+                      <span
+                        className="home-usage-model-chip"
+                        key={entry.workspaceId}
+                        title={`${entry.workspaceName}: ${formatCount(entry.totalTokens)} tokens`}
+                      >
+                        {entry.workspaceName}
+                        <span className="home-usage-model-share">
+                          {formatUsd(entry.estimatedCostUsd)}
+                        </span>
+                      </span>
+                    ))}
+                  </div>
+                </div>
+              )}
           </>
         )}
       </div>
@@ -0,0 +1,93 @@
+// @vitest-environment jsdom
+import { act, renderHook, waitFor } from "@testing-library/react";
+import { afterEach, beforeEach, describe, expect, it, vi } from "vitest";
+import type { LocalUsageSnapshot } from "../../../types";
+import type { LocalUsageUpdatedEvent } from "../../../services/events";
+import {
+  localUsageSnapshot,
+  localUsageUnwatch,
+  localUsageWatch,
+} from "../../../services/tauri";
+import { subscribeLocalUsageUpdated } from "../../../services/events";
+import { useLocalUsage } from "./useLocalUsage";
+
+vi.mock("../../../services/tauri", () => ({
+  localUsageSnapshot: vi.fn(),
+  localUsageWatch: vi.fn(),
+  localUsageUnwatch: vi.fn(),
+}));
+
+vi.mock("../../../services/events", () => ({
+  subscribeLocalUsageUpdated: vi.fn(),
+}));
+
+function makeSnapshot(totalTokens: number): LocalUsageSnapshot {
+  return {
+    updatedAt: totalTokens,
+    days: [],
+    totals: {
+      last7DaysTokens: totalTokens,
+      last30DaysTokens: totalTokens,
+      averageDailyTokens: 0,
+      cacheHitRatePercent: 0,
+      peakDay: null,
+      peakDayTokens: 0,
+    },
+    topModels: [],
+  };
+}
+
+let listener: ((event: LocalUsageUpdatedEvent) => void) | null = null;
+const unlisten = vi.fn();
+
+beforeEach(() => {
+  listener = null;
+  unlisten.mockReset();
+  vi.mocked(subscribeLocalUsageUpdated).mockImplementation((cb) => {
+    listener = cb;
+    return unlisten;
+  });
+  vi.mocked(localUsageSnapshot).mockResolvedValue(makeSnapshot(10));
+  vi.mocked(localUsageWatch).mockResolvedValue(undefined);
+  vi.mocked(localUsageUnwatch).mockResolvedValue(undefined);
+});
+
+afterEach(() => {
+  vi.clearAllMocks();
+});
+
+describe("useLocalUsage", () => {
+  it("applies pushed snapshots for the watched window and unwatches on unmount", async () => {
+    const { result, unmount } = renderHook(() => useLocalUsage(true, "/tmp/project"));
+
+    await waitFor(() => {
+      expect(result.current.snapshot?.totals.last30DaysTokens).toBe(10);
+    });
+    expect(localUsageWatch).toHaveBeenCalledWith(30, "/tmp/project");
+
+    act(() => {
+      listener?.({ days: 30, workspacePath: null, snapshot: makeSnapshot(99) });
+    });
+    expect(result.current.snapshot?.totals.last30DaysTokens).toBe(10);
+
+    act(() => {
+      listener?.({
+        days: 30,
+        workspacePath: "/tmp/project",
+        snapshot: makeSnapshot(42),
+      });
+    });
+    expect(result.current.snapshot?.totals.last30DaysTokens).toBe(42);
+
+    unmount();
+    expect(unlisten).toHaveBeenCalledTimes(1);
+    expect(localUsageUnwatch).toHaveBeenCalledWith(30, "/tmp/project");
+  });
+
+  it("does not watch while disabled", () => {
+    renderHook(() => useLocalUsage(false, null));
+
+    expect(localUsageWatch).not.toHaveBeenCalled();
+    expect(subscribeLocalUsageUpdated).not.toHaveBeenCalled();
+  });
+});
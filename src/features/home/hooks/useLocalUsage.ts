@@ -1,6 +1,11 @@
 import { useCallback, useEffect, useRef, useState } from "react";
 import type { LocalUsageSnapshot } from "../../../types";
-import { localUsageSnapshot } from "../../../services/tauri";
+import {
+  localUsageSnapshot,
+  localUsageUnwatch,
+  localUsageWatch,
+} from "../../../services/tauri";
+import { subscribeLocalUsageUpdated } from "../../../services/events";
 
 type LocalUsageState = {
   snapshot: LocalUsageSnapshot | null;
@@ -14,7 +19,7 @@ const emptyState: LocalUsageState = {
   error: null,
 };
 
-const REFRESH_INTERVAL_MS = 5 * 60 * 1000;
+const USAGE_DAYS = 30;
 
 export function useLocalUsage(enabled: boolean, workspacePath: string | null) {
   const [state, setState] = useState<LocalUsageState>(emptyState);
@@ -40,7 +45,7 @@ export function useLocalUsage(enabled: boolean, workspacePath: string | null) {
     const requestId = requestIdRef.current + 1;
     requestIdRef.current = requestId;
     setState((prev) => ({ ...prev, isLoading: true, error: null }));
-    return localUsageSnapshot(30, workspaceRef.current ?? undefined)
+    return localUsageSnapshot(USAGE_DAYS, workspaceRef.current ?? undefined)
       .then((snapshot) => {
         if (requestIdRef.current !== requestId || !enabledRef.current) {
           return;
@@ -61,11 +66,23 @@ export function useLocalUsage(enabled: boolean, workspacePath: string | null) {
       return;
     }
     refresh()?.catch(() => {});
-    const interval = window.setInterval(() => {
-      refresh()?.catch(() => {});
-    }, REFRESH_INTERVAL_MS);
+    // The backend rescans when session logs change and pushes the new snapshot.
+    const watchedPath = workspacePath?.trim() || null;
+    const unsubscribe = subscribeLocalUsageUpdated((event) => {
+      if (
+        event.days !== USAGE_DAYS ||
+        event.workspacePath !== watchedPath ||
+        !enabledRef.current
+      ) {
+        return;
+      }
+      requestIdRef.current += 1;
+      setState({ snapshot: event.snapshot, isLoading: false, error: null });
+    });
+    localUsageWatch(USAGE_DAYS, watchedPath).catch(() => {});
     return () => {
-      window.clearInterval(interval);
+      unsubscribe();
+      localUsageUnwatch(USAGE_DAYS, watchedPath).catch(() => {});
     };
   }, [enabled, refresh, workspacePath]);
 
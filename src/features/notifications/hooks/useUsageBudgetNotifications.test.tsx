@@ -0,0 +1,87 @@
+// @vitest-environment jsdom
+import { act, renderHook } from "@testing-library/react";
+import { beforeEach, describe, expect, it, vi } from "vitest";
+import type { UsageBudgetAlert } from "../../../types";
+import { subscribeUsageBudgetAlert } from "../../../services/events";
+import { sendNotification } from "../../../services/tauri";
+import { useUsageBudgetNotifications } from "./useUsageBudgetNotifications";
+
+vi.mock("../../../services/tauri", () => ({
+  sendNotification: vi.fn(),
+}));
+
+vi.mock("../../../services/events", () => ({
+  subscribeUsageBudgetAlert: vi.fn(),
+}));
+
+function makeAlert(overrides: Partial<UsageBudgetAlert> = {}): UsageBudgetAlert {
+  return {
+    budgetId: "daily-cost",
+    workspaceId: "ws-1",
+    workspaceName: "CodexMonitor",
+    period: "daily",
+    metric: "cost",
+    threshold: 100,
+    limit: 5,
+    used: 5.25,
+    percent: 105,
+    blocking: true,
+    ...overrides,
+  };
+}
+
+let listener: ((alert: UsageBudgetAlert) => void) | null = null;
+
+describe("useUsageBudgetNotifications", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    listener = null;
+    vi.mocked(sendNotification).mockResolvedValue();
+    vi.mocked(subscribeUsageBudgetAlert).mockImplementation((cb) => {
+      listener = cb;
+      return () => {};
+    });
+  });
+
+  it("sends a system notification for each alert", () => {
+    renderHook(() => useUsageBudgetNotifications({ enabled: true }));
+
+    act(() => {
+      listener?.(makeAlert());
+    });
+
+    expect(sendNotification).toHaveBeenCalledWith(
+      "Usage budget reached",
+      "Daily cost budget for CodexMonitor: $5.25 of $5.00 (105%). New messages are paused until you override the budget.",
+      expect.objectContaining({ autoCancel: true }),
+    );
+
+    act(() => {
+      listener?.(
+        makeAlert({
+          workspaceId: null,
+          workspaceName: null,
+          period: "weekly",
+          metric: "tokens",
+          threshold: 80,
+          limit: 1000,
+          used: 850,
+          percent: 85,
+          blocking: false,
+        }),
+      );
+    });
+
+    expect(sendNotification).toHaveBeenLastCalledWith(
+      "Usage budget at 80%",
+      `Weekly token budget for all workspaces: 850 tokens of ${(1000).toLocaleString()} tokens (85%).`,
+      expect.anything(),
+    );
+  });
+
+  it("does not subscribe while disabled", () => {
+    renderHook(() => useUsageBudgetNotifications({ enabled: false }));
+
+    expect(subscribeUsageBudgetAlert).not.toHaveBeenCalled();
+  });
+});
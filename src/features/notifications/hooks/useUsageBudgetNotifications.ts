@@ -0,0 +1,66 @@
+import { useCallback } from "react";
+import type { DebugEntry, UsageBudgetAlert } from "../../../types";
+import { subscribeUsageBudgetAlert } from "../../../services/events";
+import { sendNotification } from "../../../services/tauri";
+import { useTauriEvent } from "../../app/hooks/useTauriEvent";
+
+type UsageBudgetNotificationOptions = {
+  enabled: boolean;
+  onDebug?: (entry: DebugEntry) => void;
+};
+
+function formatAmount(alert: UsageBudgetAlert, value: number) {
+  if (alert.metric === "cost") {
+    return `$${value.toFixed(2)}`;
+  }
+  return `${Math.round(value).toLocaleString()} tokens`;
+}
+
+export function buildUsageBudgetNotification(alert: UsageBudgetAlert) {
+  const title =
+    alert.threshold >= 100
+      ? "Usage budget reached"
+      : `Usage budget at ${alert.threshold}%`;
+  const period = alert.period === "weekly" ? "Weekly" : "Daily";
+  const scope = alert.workspaceId
+    ? (alert.workspaceName ?? alert.workspaceId)
+    : "all workspaces";
+  let body = `${period} ${alert.metric === "cost" ? "cost" : "token"} budget for ${scope}: ${formatAmount(alert, alert.used)} of ${formatAmount(alert, alert.limit)} (${Math.round(alert.percent)}%).`;
+  if (alert.blocking) {
+    body += " New messages are paused until you override the budget.";
+  }
+  return { title, body };
+}
+
+export function useUsageBudgetNotifications({
+  enabled,
+  onDebug,
+}: UsageBudgetNotificationOptions) {
+  const handleAlert = useCallback(
+    (alert: UsageBudgetAlert) => {
+      const { title, body } = buildUsageBudgetNotification(alert);
+      onDebug?.({
+        id: `${Date.now()}-event-usage-budget-alert`,
+        timestamp: Date.now(),
+        source: "event",
+        label: "usage-budget/alert",
+        payload: alert,
+      });
+      void sendNotification(title, body, {
+        autoCancel: true,
+        extra: { kind: "usage_budget", budgetId: alert.budgetId },
+      }).catch((error) => {
+        onDebug?.({
+          id: `${Date.now()}-client-notification-error`,
+          timestamp: Date.now(),
+          source: "error",
+          label: "notification/error",
+          payload: error instanceof Error ? error.message : String(error),
+        });
+      });
+    },
+    [onDebug],
+  );
+
+  useTauriEvent(subscribeUsageBudgetAlert, handleAlert, { enabled });
+}
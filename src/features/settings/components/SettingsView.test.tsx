@@ -151,6 +151,8 @@ const baseSettings: AppSettings = {
     },
   ],
   selectedOpenAppId: "vscode",
+  modelPrices: [],
+  usageBudgets: [],
 };
 
 const createDoctorResult = () => ({
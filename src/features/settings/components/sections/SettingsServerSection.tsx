@@ -51,6 +51,7 @@ type SettingsServerSectionProps = {
   onCommitRemoteName: () => Promise<void>;
   onCommitRemoteHost: () => Promise<void>;
   onCommitRemoteToken: () => Promise<void>;
+  onSetRemoteProvider: (provider: AppSettings["remoteBackendProvider"]) => Promise<void>;
   onSelectRemoteBackend: (id: string) => Promise<void>;
   onAddRemoteBackend: (draft: AddRemoteBackendDraft) => Promise<void>;
   onMoveRemoteBackend: (id: string, direction: "up" | "down") => Promise<void>;
@@ -95,6 +96,7 @@ export function SettingsServerSection({
   onCommitRemoteName,
   onCommitRemoteHost,
   onCommitRemoteToken,
+  onSetRemoteProvider,
   onSelectRemoteBackend,
   onAddRemoteBackend,
   onMoveRemoteBackend,
@@ -124,6 +126,8 @@ export function SettingsServerSection({
         : remoteBackends.find((entry) => entry.id === pendingDeleteRemoteId) ?? null,
     [pendingDeleteRemoteId, remoteBackends],
   );
+  const activeRemoteProvider =
+    remoteBackends.find((entry) => entry.id === activeRemoteBackendId)?.provider ?? "tcp";
   const tcpRunnerStatusText = (() => {
     if (!tcpDaemonStatus) {
       return null;
@@ -395,6 +399,29 @@ export function SettingsServerSection({
           </div>
         </div>
 
+        <div className="settings-field">
+          <label className="settings-field-label" htmlFor="remote-backend-provider">
+            Transport
+          </label>
+          <select
+            id="remote-backend-provider"
+            className="settings-select"
+            value={activeRemoteProvider}
+            onChange={(event) =>
+              void onSetRemoteProvider(
+                event.target.value as AppSettings["remoteBackendProvider"],
+              )
+            }
+          >
+            <option value="tcp">TCP (default)</option>
+            <option value="websocket">WebSocket</option>
+          </select>
+          <div className="settings-help">
+            WebSocket connects to a daemon started with `--ws-listen`, directly or through an
+            HTTPS reverse proxy. The host may be `host:port` or a full `ws://`/`wss://` URL.
+          </div>
+        </div>
+
         {isMobileSimplified && (
           <div className="settings-field">
             <div className="settings-field-label">Connection test</div>
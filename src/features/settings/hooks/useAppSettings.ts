@@ -11,6 +11,7 @@ import {
   normalizeFontFamily,
 } from "@utils/fonts";
 import {
+  DEFAULT_MODEL_PRICES,
   DEFAULT_OPEN_APP_ID,
   DEFAULT_OPEN_APP_TARGETS,
   OPEN_APP_STORAGE_KEY,
@@ -31,14 +32,17 @@ const DEFAULT_REMOTE_PROVIDER: AppSettings["remoteBackendProvider"] = "tcp";
 type RemoteBackendTarget = AppSettings["remoteBackends"][number];
 
 function normalizeRemoteProvider(value: unknown): AppSettings["remoteBackendProvider"] {
-  void value;
-  return "tcp";
+  return value === "websocket" ? "websocket" : "tcp";
 }
 
 function normalizeRemoteToken(value: string | null | undefined): string | null {
   return value?.trim() ? value.trim() : null;
 }
 
+function normalizeRemoteTlsValue(value: string | null | undefined): string | null {
+  return value?.trim() ? value.trim() : null;
+}
+
 function normalizeRemoteHost(value: string | null | undefined): string {
   return value?.trim() ? value.trim() : DEFAULT_REMOTE_BACKEND_HOST;
 }
@@ -78,6 +82,10 @@ function normalizeRemoteBackends(settings: AppSettings): {
         typeof entry.lastConnectedAtMs === "number" && Number.isFinite(entry.lastConnectedAtMs)
           ? entry.lastConnectedAtMs
           : null,
+      tlsEnabled: entry.tlsEnabled === true,
+      tlsFingerprint: normalizeRemoteTlsValue(entry.tlsFingerprint),
+      tlsClientCertPath: normalizeRemoteTlsValue(entry.tlsClientCertPath),
+      tlsClientKeyPath: normalizeRemoteTlsValue(entry.tlsClientKeyPath),
     };
   });
 
@@ -206,6 +214,8 @@ function buildDefaultSettings(): AppSettings {
     workspaceGroups: [],
     openAppTargets: DEFAULT_OPEN_APP_TARGETS,
     selectedOpenAppId: DEFAULT_OPEN_APP_ID,
+    modelPrices: DEFAULT_MODEL_PRICES,
+    usageBudgets: [],
   };
 }
 
@@ -274,6 +284,10 @@ function normalizeAppSettings(settings: AppSettings): AppSettings {
     commitMessagePrompt,
     openAppTargets: normalizedTargets,
     selectedOpenAppId,
+    modelPrices: Array.isArray(settings.modelPrices)
+      ? settings.modelPrices
+      : DEFAULT_MODEL_PRICES,
+    usageBudgets: Array.isArray(settings.usageBudgets) ? settings.usageBudgets : [],
   };
 }
 
@@ -120,6 +120,14 @@ const validateRemoteHost = (value: string): string | null => {
   if (!trimmed) {
     return "Host is required.";
   }
+  if (/^wss?:\/\//i.test(trimmed)) {
+    try {
+      new URL(trimmed);
+      return null;
+    } catch {
+      return "Enter a valid WebSocket URL (for example `wss://proxy.example.com/codex`).";
+    }
+  }
   const match = trimmed.match(/^([^:\s]+|\[[^\]]+\]):([0-9]{1,5})$/);
   if (!match) {
     return "Use host:port (for example `macbook.tailnet.ts.net:4732`).";
@@ -197,13 +205,17 @@ export const useSettingsServerSection = ({
   ): RemoteBackendTarget => ({
     id: entry.id?.trim() || `remote-${index + 1}`,
     name: entry.name?.trim() || `Remote ${index + 1}`,
-    provider: "tcp",
+    provider: entry.provider === "websocket" ? "websocket" : "tcp",
     host: entry.host?.trim() || DEFAULT_REMOTE_HOST,
     token: entry.token?.trim() ? entry.token.trim() : null,
     lastConnectedAtMs:
       typeof entry.lastConnectedAtMs === "number" && Number.isFinite(entry.lastConnectedAtMs)
         ? entry.lastConnectedAtMs
         : null,
+    tlsEnabled: entry.tlsEnabled === true,
+    tlsFingerprint: entry.tlsFingerprint?.trim() ? entry.tlsFingerprint.trim() : null,
+    tlsClientCertPath: entry.tlsClientCertPath?.trim() ? entry.tlsClientCertPath.trim() : null,
+    tlsClientKeyPath: entry.tlsClientKeyPath?.trim() ? entry.tlsClientKeyPath.trim() : null,
   });
 
   const buildSettingsFromRemoteBackends = useCallback(
@@ -223,7 +235,7 @@ export const useSettingsServerSection = ({
         ...latestSettings,
         remoteBackends: normalizedBackends,
         activeRemoteBackendId: active.id,
-        remoteBackendProvider: "tcp",
+        remoteBackendProvider: active.provider,
         remoteBackendHost: active.host,
         remoteBackendToken: active.token,
         ...(mobilePlatform
@@ -270,7 +282,6 @@ export const useSettingsServerSection = ({
       nextBackends[safeIndex] = {
         ...nextBackends[safeIndex],
         ...patch,
-        provider: "tcp",
       };
       await persistRemoteBackends(nextBackends, nextBackends[safeIndex].id);
     },
@@ -329,6 +340,13 @@ export const useSettingsServerSection = ({
     setRemoteStatus("Remote token saved.");
   };
 
+  const handleSetRemoteProvider = async (provider: RemoteBackendTarget["provider"]) => {
+    await updateActiveRemoteBackend({ provider });
+    setRemoteStatus(
+      provider === "websocket" ? "Remote transport set to WebSocket." : "Remote transport set to TCP.",
+    );
+  };
+
   const handleSelectRemoteBackend = async (id: string) => {
     const latestSettings = latestSettingsRef.current;
     const candidates = getConfiguredRemoteBackends(latestSettings);
@@ -669,6 +687,7 @@ export const useSettingsServerSection = ({
     onCommitRemoteName: handleCommitRemoteName,
     onCommitRemoteHost: handleCommitRemoteHost,
     onCommitRemoteToken: handleCommitRemoteToken,
+    onSetRemoteProvider: handleSetRemoteProvider,
     onSelectRemoteBackend: handleSelectRemoteBackend,
     onAddRemoteBackend: handleAddRemoteBackend,
     onMoveRemoteBackend: handleMoveRemoteBackend,
@@ -3,17 +3,31 @@ import type {
   AppServerEvent,
   DictationEvent,
   DictationModelStatus,
+  LocalUsageSnapshot,
   TrayOpenThreadPayload,
+  UsageBudgetAlert,
 } from "../types";
 
 export type Unsubscribe = () => void;
 
+export type LocalUsageUpdatedEvent = {
+  days: number;
+  workspacePath: string | null;
+  snapshot: LocalUsageSnapshot;
+};
+
 export type TerminalOutputEvent = {
   workspaceId: string;
   terminalId: string;
   data: string;
 };
 
+/** Daemon events were lost (`evicted` from its replay buffer, or the daemon `restarted`). */
+export type EventGapEvent = {
+  afterSeq: number;
+  reason: "evicted" | "restarted";
+};
+
 export type TerminalExitEvent = {
   workspaceId: string;
   terminalId: string;
@@ -90,7 +104,10 @@ const appServerHub = createEventHub<AppServerEvent>("app-server-event");
 const dictationDownloadHub = createEventHub<DictationModelStatus>("dictation-download");
 const dictationEventHub = createEventHub<DictationEvent>("dictation-event");
 const terminalOutputHub = createEventHub<TerminalOutputEvent>("terminal-output");
+const localUsageUpdatedHub = createEventHub<LocalUsageUpdatedEvent>("local-usage-updated");
+const usageBudgetAlertHub = createEventHub<UsageBudgetAlert>("usage-budget-alert");
 const terminalExitHub = createEventHub<TerminalExitEvent>("terminal-exit");
+const eventGapHub = createEventHub<EventGapEvent>("event-gap");
 const updaterCheckHub = createEventHub<void>("updater-check");
 const trayOpenThreadHub = createEventHub<TrayOpenThreadPayload>("tray-open-thread");
 const menuNewAgentHub = createEventHub<void>("menu-new-agent");
@@ -139,6 +156,20 @@ export function subscribeDictationEvents(
   return dictationEventHub.subscribe(onEvent, options);
 }
 
+export function subscribeLocalUsageUpdated(
+  onEvent: (event: LocalUsageUpdatedEvent) => void,
+  options?: SubscriptionOptions,
+): Unsubscribe {
+  return localUsageUpdatedHub.subscribe(onEvent, options);
+}
+
+export function subscribeUsageBudgetAlert(
+  onEvent: (event: UsageBudgetAlert) => void,
+  options?: SubscriptionOptions,
+): Unsubscribe {
+  return usageBudgetAlertHub.subscribe(onEvent, options);
+}
+
 export function subscribeTerminalOutput(
   onEvent: (event: TerminalOutputEvent) => void,
   options?: SubscriptionOptions,
@@ -153,6 +184,13 @@ export function subscribeTerminalExit(
   return terminalExitHub.subscribe(onEvent, options);
 }
 
+export function subscribeEventGap(
+  onEvent: (event: EventGapEvent) => void,
+  options?: SubscriptionOptions,
+): Unsubscribe {
+  return eventGapHub.subscribe(onEvent, options);
+}
+
 export function subscribeUpdaterCheck(
   onEvent: () => void,
   options?: SubscriptionOptions,
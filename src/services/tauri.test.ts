@@ -24,6 +24,10 @@ import {
   openWorkspaceIn,
   readAgentMd,
   stageGitAll,
+  stageGitSelection,
+  createGitStash,
+  resolveGitConflict,
+  landWorktree,
   respondToServerRequest,
   respondToUserInputRequest,
   sendUserMessage,
@@ -218,6 +222,34 @@ describe("tauri invoke wrappers", () => {
     expect(invokeMock).toHaveBeenCalledWith("get_git_log", {
       workspaceId: "ws-3",
       limit: 40,
+      cursor: null,
+      filter: null,
+    });
+  });
+
+  it("passes cursor and filters for git log", async () => {
+    const invokeMock = vi.mocked(invoke);
+    invokeMock.mockResolvedValueOnce({
+      total: 0,
+      entries: [],
+      ahead: 0,
+      behind: 0,
+      aheadEntries: [],
+      behindEntries: [],
+      upstream: null,
+      nextCursor: null,
+    });
+
+    await getGitLog("ws-3", 20, {
+      cursor: "20:abc",
+      filter: { author: "agent", path: "src/main.rs", range: "ahead" },
+    });
+
+    expect(invokeMock).toHaveBeenCalledWith("get_git_log", {
+      workspaceId: "ws-3",
+      limit: 20,
+      cursor: "20:abc",
+      filter: { author: "agent", path: "src/main.rs", range: "ahead" },
     });
   });
 
@@ -368,6 +400,76 @@ describe("tauri invoke wrappers", () => {
     });
   });
 
+  it("invokes stage_git_selection with hunks and line ranges", async () => {
+    const invokeMock = vi.mocked(invoke);
+    invokeMock.mockResolvedValueOnce({ ok: true });
+
+    await stageGitSelection("ws-6", "src/main.rs", {
+      hunks: [0],
+      lines: [{ side: "new", start: 12, end: 14 }],
+    });
+
+    expect(invokeMock).toHaveBeenCalledWith("stage_git_selection", {
+      workspaceId: "ws-6",
+      path: "src/main.rs",
+      selection: {
+        hunks: [0],
+        lines: [{ side: "new", start: 12, end: 14 }],
+      },
+    });
+  });
+
+  it("invokes create_git_stash with null defaults", async () => {
+    const invokeMock = vi.mocked(invoke);
+    invokeMock.mockResolvedValueOnce({
+      index: 0,
+      sha: "abc",
+      message: "WIP on main: abc init",
+      branch: "main",
+      timestamp: 1,
+    });
+
+    await createGitStash("ws-6");
+
+    expect(invokeMock).toHaveBeenCalledWith("create_git_stash", {
+      workspaceId: "ws-6",
+      message: null,
+      includeUntracked: null,
+    });
+  });
+
+  it("invokes resolve_git_conflict with null content for a side", async () => {
+    const invokeMock = vi.mocked(invoke);
+    invokeMock.mockResolvedValueOnce({ ok: true });
+
+    await resolveGitConflict("ws-6", "src/main.rs", "theirs");
+
+    expect(invokeMock).toHaveBeenCalledWith("resolve_git_conflict", {
+      workspaceId: "ws-6",
+      path: "src/main.rs",
+      resolution: "theirs",
+      content: null,
+    });
+  });
+
+  it("invokes land_worktree with strategy and removal flag", async () => {
+    const invokeMock = vi.mocked(invoke);
+    invokeMock.mockResolvedValueOnce({
+      parentBranch: "main",
+      sha: "abc",
+      worktreeRemoved: true,
+    });
+
+    await landWorktree("ws-8", "squash", { removeWorktree: true });
+
+    expect(invokeMock).toHaveBeenCalledWith("land_worktree", {
+      workspaceId: "ws-8",
+      strategy: "squash",
+      message: null,
+      removeWorktree: true,
+    });
+  });
+
   it("invokes fetch_git", async () => {
     const invokeMock = vi.mocked(invoke);
     invokeMock.mockResolvedValueOnce({});
@@ -3,21 +3,42 @@ import { open, save } from "@tauri-apps/plugin-dialog";
 import type { Options as NotificationOptions } from "@tauri-apps/plugin-notification";
 import type {
   AppSettings,
+  ApprovalPolicyExplanation,
+  ApprovalPolicyRule,
+  CodexPrefixRule,
+  CodexRuleEntry,
+  CodexRulesListing,
   CodexUpdateResult,
   CodexDoctorResult,
   DictationModelStatus,
   DictationSessionState,
+  LocalUsageExport,
+  LocalUsageExportFormat,
   LocalUsageSnapshot,
+  LocalUsageThreadTimeline,
+  ThreadBundle,
+  ThreadExport,
+  ThreadExportFormat,
+  ThreadImport,
+  ThreadSearchResponse,
   TcpDaemonStatus,
   TailscaleDaemonCommandPreview,
   TailscaleStatus,
   TrayRecentThreadEntry,
   TraySessionUsage,
+  UsageBudgetStatus,
   WorkspaceInfo,
   AppMention,
   WorkspaceSettings,
+  WorktreeLandPreview,
+  WorktreeLandResult,
+  WorktreeLandStrategy,
 } from "../types";
 import type {
+  GitConflictDetail,
+  GitConflictFile,
+  GitConflictResolution,
+  GitDiffSelection,
   GitFileDiff,
   GitFileStatus,
   GitCommitDiff,
@@ -25,7 +46,11 @@ import type {
   GitHubPullRequestComment,
   GitHubPullRequestDiff,
   GitHubPullRequestsResponse,
+  GitLogFilter,
   GitLogResponse,
+  GitOperationKind,
+  GitOperationStatus,
+  GitStashEntry,
   ReviewTarget,
 } from "../types";
 
@@ -338,6 +363,25 @@ export async function applyWorktreeChanges(workspaceId: string): Promise<void> {
   return invoke("apply_worktree_changes", { workspaceId });
 }
 
+export async function previewLandWorktree(
+  workspaceId: string,
+): Promise<WorktreeLandPreview> {
+  return invoke("preview_land_worktree", { workspaceId });
+}
+
+export async function landWorktree(
+  workspaceId: string,
+  strategy: WorktreeLandStrategy,
+  options?: { message?: string | null; removeWorktree?: boolean },
+): Promise<WorktreeLandResult> {
+  return invoke("land_worktree", {
+    workspaceId,
+    strategy,
+    message: options?.message ?? null,
+    removeWorktree: options?.removeWorktree ?? null,
+  });
+}
+
 export async function openWorkspaceIn(
   path: string,
   options: {
@@ -540,11 +584,45 @@ export async function rememberApprovalRule(
   return invoke("remember_approval_rule", { workspaceId, command });
 }
 
+export async function listCodexRules(workspaceId: string): Promise<CodexRulesListing> {
+  return invoke<CodexRulesListing>("list_codex_rules", { workspaceId });
+}
+
+export async function updateCodexRule(
+  workspaceId: string,
+  ruleId: string,
+  rule: CodexPrefixRule,
+): Promise<CodexRuleEntry> {
+  return invoke<CodexRuleEntry>("update_codex_rule", { workspaceId, ruleId, rule });
+}
+
+export async function deleteCodexRule(workspaceId: string, ruleId: string): Promise<void> {
+  return invoke("delete_codex_rule", { workspaceId, ruleId });
+}
+
+export async function explainApprovalPolicy(
+  workspaceId: string,
+  method: string,
+  params: Record<string, unknown>,
+  accessMode?: string | null,
+  rules?: ApprovalPolicyRule[] | null,
+): Promise<ApprovalPolicyExplanation> {
+  return invoke<ApprovalPolicyExplanation>("explain_approval_policy", {
+    workspaceId,
+    method,
+    params,
+    accessMode: accessMode ?? null,
+    rules: rules ?? null,
+  });
+}
+
 export async function getGitStatus(workspace_id: string): Promise<{
   branchName: string;
   files: GitFileStatus[];
   stagedFiles: GitFileStatus[];
   unstagedFiles: GitFileStatus[];
+  conflictedFiles: GitConflictFile[];
+  operation: GitOperationKind | null;
   totalAdditions: number;
   totalDeletions: number;
 }> {
@@ -604,8 +682,14 @@ export async function getGitDiffs(
 export async function getGitLog(
   workspace_id: string,
   limit = 40,
+  options?: { cursor?: string | null; filter?: GitLogFilter | null },
 ): Promise<GitLogResponse> {
-  return invoke("get_git_log", { workspaceId: workspace_id, limit });
+  return invoke("get_git_log", {
+    workspaceId: workspace_id,
+    limit,
+    cursor: options?.cursor ?? null,
+    filter: options?.filter ?? null,
+  });
 }
 
 export async function getGitCommitDiff(
@@ -635,6 +719,30 @@ export async function revertGitFile(workspaceId: string, path: string) {
   return invoke("revert_git_file", { workspaceId, path });
 }
 
+export async function stageGitSelection(
+  workspaceId: string,
+  path: string,
+  selection: GitDiffSelection,
+) {
+  return invoke("stage_git_selection", { workspaceId, path, selection });
+}
+
+export async function unstageGitSelection(
+  workspaceId: string,
+  path: string,
+  selection: GitDiffSelection,
+) {
+  return invoke("unstage_git_selection", { workspaceId, path, selection });
+}
+
+export async function revertGitSelection(
+  workspaceId: string,
+  path: string,
+  selection: GitDiffSelection,
+) {
+  return invoke("revert_git_selection", { workspaceId, path, selection });
+}
+
 export async function revertGitAll(workspaceId: string) {
   return invoke("revert_git_all", { workspaceId });
 }
@@ -662,6 +770,109 @@ export async function syncGit(workspaceId: string): Promise<void> {
   return invoke("sync_git", { workspaceId });
 }
 
+export async function listGitStashes(
+  workspaceId: string,
+): Promise<GitStashEntry[]> {
+  return invoke("list_git_stashes", { workspaceId });
+}
+
+export async function getGitStashDiff(
+  workspaceId: string,
+  index: number,
+  sha: string,
+): Promise<GitFileDiff[]> {
+  return invoke("get_git_stash_diff", { workspaceId, index, sha });
+}
+
+export async function createGitStash(
+  workspaceId: string,
+  message?: string | null,
+  includeUntracked?: boolean,
+): Promise<GitStashEntry> {
+  return invoke("create_git_stash", {
+    workspaceId,
+    message: message ?? null,
+    includeUntracked: includeUntracked ?? null,
+  });
+}
+
+export async function applyGitStash(
+  workspaceId: string,
+  index: number,
+  sha: string,
+): Promise<void> {
+  return invoke("apply_git_stash", { workspaceId, index, sha });
+}
+
+export async function popGitStash(
+  workspaceId: string,
+  index: number,
+  sha: string,
+): Promise<void> {
+  return invoke("pop_git_stash", { workspaceId, index, sha });
+}
+
+export async function dropGitStash(
+  workspaceId: string,
+  index: number,
+  sha: string,
+): Promise<void> {
+  return invoke("drop_git_stash", { workspaceId, index, sha });
+}
+
+export async function mergeGitBranch(
+  workspaceId: string,
+  name: string,
+): Promise<GitOperationStatus> {
+  return invoke("merge_git_branch", { workspaceId, name });
+}
+
+export async function rebaseGitBranch(
+  workspaceId: string,
+  name: string,
+): Promise<GitOperationStatus> {
+  return invoke("rebase_git_branch", { workspaceId, name });
+}
+
+export async function continueGitOperation(
+  workspaceId: string,
+): Promise<GitOperationStatus> {
+  return invoke("continue_git_operation", { workspaceId });
+}
+
+export async function skipGitOperation(
+  workspaceId: string,
+): Promise<GitOperationStatus> {
+  return invoke("skip_git_operation", { workspaceId });
+}
+
+export async function abortGitOperation(
+  workspaceId: string,
+): Promise<GitOperationStatus> {
+  return invoke("abort_git_operation", { workspaceId });
+}
+
+export async function getGitConflict(
+  workspaceId: string,
+  path: string,
+): Promise<GitConflictDetail> {
+  return invoke("get_git_conflict", { workspaceId, path });
+}
+
+export async function resolveGitConflict(
+  workspaceId: string,
+  path: string,
+  resolution: GitConflictResolution,
+  content?: string | null,
+): Promise<void> {
+  return invoke("resolve_git_conflict", {
+    workspaceId,
+    path,
+    resolution,
+    content: content ?? null,
+  });
+}
+
 export async function getGitHubIssues(
   workspace_id: string,
 ): Promise<GitHubIssuesResponse> {
@@ -715,6 +926,79 @@ export async function localUsageSnapshot(
   return invoke("local_usage_snapshot", payload);
 }
 
+export async function localUsageWatch(
+  days: number,
+  workspacePath?: string | null,
+): Promise<void> {
+  return invoke("local_usage_watch", { days, workspacePath: workspacePath ?? null });
+}
+
+export async function localUsageUnwatch(
+  days: number,
+  workspacePath?: string | null,
+): Promise<void> {
+  return invoke("local_usage_unwatch", { days, workspacePath: workspacePath ?? null });
+}
+
+export async function localUsageThreadTimeline(
+  threadId: string,
+): Promise<LocalUsageThreadTimeline> {
+  return invoke("local_usage_thread_timeline", { threadId });
+}
+
+export async function localUsageExport(
+  format: LocalUsageExportFormat,
+  days?: number | null,
+  workspacePath?: string | null,
+): Promise<LocalUsageExport> {
+  return invoke<LocalUsageExport>("local_usage_export", {
+    format,
+    days: days ?? null,
+    workspacePath: workspacePath ?? null,
+  });
+}
+
+export async function usageBudgetStatus(): Promise<UsageBudgetStatus[]> {
+  return invoke<UsageBudgetStatus[]>("usage_budget_status");
+}
+
+export async function overrideUsageBudget(budgetId: string): Promise<void> {
+  return invoke("override_usage_budget", { budgetId });
+}
+
+export async function searchThreads(
+  query: string,
+  workspaceId?: string | null,
+  limit?: number,
+): Promise<ThreadSearchResponse> {
+  return invoke<ThreadSearchResponse>("search_threads", {
+    query,
+    workspaceId: workspaceId ?? null,
+    limit: limit ?? null,
+  });
+}
+
+export async function exportThread(
+  workspaceId: string,
+  threadId: string,
+  format: ThreadExportFormat,
+  redact = false,
+): Promise<ThreadExport> {
+  return invoke<ThreadExport>("export_thread", {
+    workspaceId,
+    threadId,
+    format,
+    redact,
+  });
+}
+
+export async function importThread(
+  workspaceId: string,
+  bundle: ThreadBundle,
+): Promise<ThreadImport> {
+  return invoke<ThreadImport>("import_thread", { workspaceId, bundle });
+}
+
 export async function getModelList(workspaceId: string) {
   return invoke<any>("model_list", { workspaceId });
 }
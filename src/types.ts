@@ -7,6 +7,75 @@ export type WorkspaceSettings = {
   launchScript?: string | null;
   launchScripts?: LaunchScriptEntry[] | null;
   worktreeSetupScript?: string | null;
+  approvalRules?: ApprovalPolicyRule[] | null;
+};
+
+export type ApprovalPolicyAction = "approve" | "deny" | "escalate";
+
+export type ApprovalPolicyRule = {
+  id: string;
+  action: ApprovalPolicyAction;
+  description?: string | null;
+  commandPrefix?: string[] | null;
+  commandRegex?: string | null;
+  fileGlobs?: string[] | null;
+  network?: boolean | null;
+  accessModes?: string[] | null;
+};
+
+export type CodexRuleDecision = "allow" | "prompt" | "forbidden";
+
+export type CodexPrefixRule = {
+  pattern: (string | string[])[];
+  decision: CodexRuleDecision;
+  justification?: string | null;
+  match?: (string | string[])[];
+  notMatch?: (string | string[])[];
+};
+
+export type CodexRuleEntry = {
+  id: string;
+  file: string;
+  index: number;
+  line: number;
+  rule: CodexPrefixRule;
+  comments: string[];
+  source: string;
+};
+
+export type CodexRulesListing = {
+  rulesDir: string;
+  files: {
+    file: string;
+    path: string;
+    rules: CodexRuleEntry[];
+    errors: { line: number; message: string }[];
+  }[];
+  duplicates: { ruleId: string; duplicateOf: string }[];
+  conflicts: {
+    ruleIds: string[];
+    effectiveDecision: CodexRuleDecision;
+    message: string;
+  }[];
+};
+
+export type ApprovalPolicyExplanation = {
+  action: ApprovalPolicyAction;
+  ruleId: string | null;
+  facts: {
+    method: string;
+    threadId: string | null;
+    command: string[] | null;
+    files: string[];
+    network: boolean;
+    accessMode: string | null;
+  };
+  rules: {
+    ruleId: string;
+    action: ApprovalPolicyAction;
+    matched: boolean;
+    details: string[];
+  }[];
 };
 
 export type LaunchScriptIconId =
@@ -73,6 +142,7 @@ export type TrayRecentThreadEntry = {
 export type TraySessionUsage = {
   sessionLabel: string;
   weeklyLabel: string | null;
+  costLabel?: string | null;
 };
 
 export type TrayOpenThreadPayload = {
@@ -192,7 +262,7 @@ export type PullRequestSelectionRange = {
 
 export type AccessMode = "read-only" | "current" | "full-access";
 export type BackendMode = "local" | "remote";
-export type RemoteBackendProvider = "tcp";
+export type RemoteBackendProvider = "tcp" | "websocket";
 export type RemoteBackendTarget = {
   id: string;
   name: string;
@@ -200,6 +270,10 @@ export type RemoteBackendTarget = {
   host: string;
   token: string | null;
   lastConnectedAtMs?: number | null;
+  tlsEnabled?: boolean;
+  tlsFingerprint?: string | null;
+  tlsClientCertPath?: string | null;
+  tlsClientKeyPath?: string | null;
 };
 export type ThemePreference = "system" | "light" | "dark" | "dim";
 export type PersonalityPreference = "friendly" | "pragmatic";
@@ -304,6 +378,8 @@ export type AppSettings = {
   workspaceGroups: WorkspaceGroup[];
   openAppTargets: OpenAppTarget[];
   selectedOpenAppId: string;
+  modelPrices: ModelPrice[];
+  usageBudgets: UsageBudget[];
 };
 
 export type CodexFeatureStage =
@@ -439,6 +515,17 @@ export type GitFileDiff = {
   newImageMime?: string | null;
 };
 
+export type GitDiffLineRange = {
+  side: "old" | "new";
+  start: number;
+  end: number;
+};
+
+export type GitDiffSelection = {
+  hunks?: number[];
+  lines?: GitDiffLineRange[];
+};
+
 export type GitCommitDiff = {
   path: string;
   status: string;
@@ -460,14 +547,91 @@ export type GitLogEntry = {
   timestamp: number;
 };
 
+export type GitStashEntry = {
+  index: number;
+  sha: string;
+  message: string;
+  branch: string | null;
+  timestamp: number;
+};
+
+export type WorktreeLandPreview = {
+  worktreeBranch: string;
+  parentBranch: string;
+  commits: GitLogEntry[];
+  behind: number;
+  canFastForward: boolean;
+  worktreeHasUncommittedChanges: boolean;
+  parentHasUncommittedChanges: boolean;
+  diff: GitCommitDiff[];
+};
+
+export type WorktreeLandStrategy = "fast_forward" | "squash" | "rebase";
+
+export type WorktreeLandResult = {
+  parentBranch: string;
+  sha: string;
+  worktreeRemoved: boolean;
+  worktreeRemovalError?: string | null;
+};
+
+export type GitOperationKind = "merge" | "rebase" | "cherry_pick" | "revert";
+
+export type GitConflictState =
+  | "both_modified"
+  | "both_added"
+  | "both_deleted"
+  | "added_by_us"
+  | "added_by_them"
+  | "deleted_by_us"
+  | "deleted_by_them";
+
+export type GitConflictFile = {
+  path: string;
+  state: GitConflictState;
+};
+
+export type GitOperationStatus = {
+  operation: GitOperationKind | null;
+  conflicts: GitConflictFile[];
+};
+
+export type GitConflictDetail = {
+  path: string;
+  state: GitConflictState;
+  base: string | null;
+  ours: string | null;
+  theirs: string | null;
+  worktree: string | null;
+  isBinary: boolean;
+};
+
+export type GitConflictResolution = "ours" | "theirs" | "merged";
+
 export type GitLogResponse = {
-  total: number;
+  // Null when an author, message, date or path filter is set.
+  total: number | null;
   entries: GitLogEntry[];
   ahead: number;
   behind: number;
   aheadEntries: GitLogEntry[];
   behindEntries: GitLogEntry[];
   upstream: string | null;
+  nextCursor?: string | null;
+};
+
+export type GitLogRange = "ahead" | "behind";
+
+export type GitLogFilter = {
+  author?: string | null;
+  // Unix seconds, inclusive.
+  since?: number | null;
+  until?: number | null;
+  message?: string | null;
+  path?: string | null;
+  branch?: string | null;
+  base?: string | null;
+  range?: GitLogRange | null;
 };
 
 export type GitHubIssue = {
@@ -540,6 +704,7 @@ export type LocalUsageDay = {
   totalTokens: number;
   agentTimeMs: number;
   agentRuns: number;
+  estimatedCostUsd?: number;
 };
 
 export type LocalUsageTotals = {
@@ -549,12 +714,46 @@ export type LocalUsageTotals = {
   cacheHitRatePercent: number;
   peakDay: string | null;
   peakDayTokens: number;
+  last7DaysCostUsd?: number;
+  last30DaysCostUsd?: number;
+  unpricedTokens?: number;
 };
 
 export type LocalUsageModel = {
   model: string;
   tokens: number;
   sharePercent: number;
+  estimatedCostUsd?: number;
+};
+
+export type LocalUsageWorkspaceCost = {
+  workspaceId: string;
+  workspaceName: string;
+  totalTokens: number;
+  estimatedCostUsd: number;
+};
+
+export type LocalUsageWorkspace = {
+  workspaceId: string;
+  workspaceName: string;
+  totalTokens: number;
+  sharePercent: number;
+  estimatedCostUsd: number;
+  threadCount: number;
+};
+
+export type LocalUsageThread = {
+  threadId: string;
+  workspaceId: string | null;
+  cwd: string | null;
+  preview: string | null;
+  inputTokens: number;
+  cachedInputTokens: number;
+  outputTokens: number;
+  totalTokens: number;
+  sharePercent: number;
+  estimatedCostUsd: number;
+  updatedAt: number | null;
 };
 
 export type LocalUsageSnapshot = {
@@ -562,6 +761,175 @@ export type LocalUsageSnapshot = {
   days: LocalUsageDay[];
   totals: LocalUsageTotals;
   topModels: LocalUsageModel[];
+  workspaceCosts?: LocalUsageWorkspaceCost[];
+  topWorkspaces?: LocalUsageWorkspace[];
+  topThreads?: LocalUsageThread[];
+};
+
+export type LocalUsageTurn = {
+  index: number;
+  startedAt: number | null;
+  endedAt: number | null;
+  model: string | null;
+  promptPreview: string | null;
+  inputTokens: number;
+  cachedInputTokens: number;
+  outputTokens: number;
+  totalTokens: number;
+  estimatedCostUsd: number;
+};
+
+export type LocalUsageThreadTimeline = {
+  threadId: string;
+  workspaceId: string | null;
+  cwd: string | null;
+  turns: LocalUsageTurn[];
+  totalTokens: number;
+  estimatedCostUsd: number;
+};
+
+export type ModelPrice = {
+  model: string;
+  inputPerMillion: number;
+  cachedInputPerMillion: number;
+  outputPerMillion: number;
+};
+
+export type LocalUsageExportFormat = "csv" | "prometheus";
+
+export type LocalUsageExport = {
+  format: LocalUsageExportFormat;
+  days: number;
+  fileName: string;
+  mimeType: string;
+  content: string;
+};
+
+export type UsageBudgetPeriod = "daily" | "weekly";
+
+export type UsageBudgetMetric = "tokens" | "cost";
+
+export type UsageBudget = {
+  id: string;
+  workspaceId: string | null;
+  period: UsageBudgetPeriod;
+  metric: UsageBudgetMetric;
+  limit: number;
+  thresholds: number[];
+  blockAtLimit: boolean;
+};
+
+export type UsageBudgetStatus = {
+  budgetId: string;
+  workspaceId: string | null;
+  period: UsageBudgetPeriod;
+  metric: UsageBudgetMetric;
+  periodStart: string;
+  limit: number;
+  used: number;
+  percent: number;
+  overridden: boolean;
+  blocking: boolean;
+};
+
+export type UsageBudgetAlert = {
+  budgetId: string;
+  workspaceId: string | null;
+  workspaceName: string | null;
+  period: UsageBudgetPeriod;
+  metric: UsageBudgetMetric;
+  threshold: number;
+  limit: number;
+  used: number;
+  percent: number;
+  blocking: boolean;
+};
+
+export type ThreadSearchMatchKind = "user" | "assistant" | "tool_call" | "diff" | "file";
+
+export type ThreadSearchMatch = {
+  kind: ThreadSearchMatchKind;
+  snippet: string;
+  timestamp: number | null;
+};
+
+export type ThreadSearchResult = {
+  threadId: string;
+  workspaceId: string | null;
+  cwd: string | null;
+  preview: string | null;
+  startedAt: number | null;
+  updatedAt: number | null;
+  matches: ThreadSearchMatch[];
+};
+
+export type ThreadSearchResponse = {
+  query: string;
+  results: ThreadSearchResult[];
+  indexedFiles: number;
+};
+
+export type ThreadExportFormat = "markdown" | "html" | "json";
+
+export type ThreadExport = {
+  threadId: string;
+  format: ThreadExportFormat;
+  fileName: string;
+  mimeType: string;
+  content: string;
+  redactions: number;
+};
+
+export type ThreadBundleItem =
+  | { type: "userMessage"; text: string; timestamp: string | null }
+  | { type: "agentMessage"; text: string; timestamp: string | null }
+  | { type: "reasoning"; text: string; timestamp: string | null }
+  | {
+      type: "commandExecution";
+      callId: string | null;
+      command: string;
+      cwd: string | null;
+      exitCode: number | null;
+      output: string;
+      timestamp: string | null;
+    }
+  | {
+      type: "patch";
+      callId: string | null;
+      files: string[];
+      patch: string;
+      success: boolean | null;
+      output: string;
+      timestamp: string | null;
+    }
+  | {
+      type: "toolCall";
+      callId: string | null;
+      name: string;
+      arguments: string;
+      output: string;
+      timestamp: string | null;
+    };
+
+export type ThreadBundle = {
+  version: number;
+  threadId: string;
+  cwd: string | null;
+  startedAt: string | null;
+  model: string | null;
+  cliVersion: string | null;
+  exportedAt: string;
+  redacted: boolean;
+  items: ThreadBundleItem[];
+  rollout: Record<string, unknown>[];
+};
+
+export type ThreadImport = {
+  threadId: string;
+  sourceThreadId: string;
+  cwd: string;
+  path: string;
+  remappedPaths: number;
 };
 
 export type TurnPlanStepStatus = "pending" | "inProgress" | "completed";